@@ -0,0 +1,40 @@
+#![no_main]
+
+use bytes::{Bytes, BytesMut};
+use libfuzzer_sys::fuzz_target;
+use std::io::Cursor;
+use tidis::Frame;
+
+// Feed the input to the frame parser the same way the connection does, it
+// must never panic whatever the peer sends.
+fuzz_target!(|data: &[u8]| {
+    let mut buffer = BytesMut::from(data);
+    loop {
+        let inline = match buffer.first() {
+            Some(&b) => b != b'*',
+            None => return,
+        };
+        let mut buf = Cursor::new(&buffer[..]);
+        let checked = if inline {
+            Frame::check_inline(&mut buf)
+        } else {
+            Frame::check(&mut buf)
+        };
+        match checked {
+            Ok(_) => {
+                let len = buf.position() as usize;
+                let data: Bytes = buffer.split_to(len).freeze();
+                let parsed = if inline {
+                    Frame::parse_inline(&mut Cursor::new(&data[..]), &data)
+                } else {
+                    Frame::parse(&mut Cursor::new(&data[..]), &data)
+                };
+                if parsed.is_err() {
+                    return;
+                }
+            }
+            // incomplete or malformed
+            Err(_) => return,
+        }
+    }
+});
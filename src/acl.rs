@@ -0,0 +1,415 @@
+//! Access control list, the users a connection authenticates as and the
+//! commands and keys each of them is allowed to access.
+//!
+//! The `default` user is allowed to run every command on every key and its
+//! password is the `password` of the server, the other users are loaded from
+//! `acl_users` at startup and managed with `ACL SETUSER`. Users are kept in
+//! memory on each instance, they are not shared across the cluster.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+
+use bytes::Bytes;
+use slog::error;
+
+use crate::cmd::spec::{argv_from_frame, category_commands, lookup_category, lookup_command_spec};
+use crate::config::LOGGER;
+use crate::tikv::errors::{RTError, REDIS_NOPERM_KEY_ERR};
+use crate::utils::{glob_match, resp_array, resp_bulk, sha1hex};
+use crate::{acl_users_or_default, Frame};
+
+pub const DEFAULT_USER: &str = "default";
+
+lazy_static! {
+    static ref ACL_USERS: RwLock<HashMap<String, User>> = {
+        let mut users = HashMap::new();
+        users.insert(DEFAULT_USER.to_owned(), User::new_default());
+        for line in acl_users_or_default() {
+            let mut args = line.split_whitespace();
+            let name = match args.next() {
+                Some(name) => name.to_owned(),
+                None => continue,
+            };
+            let rules: Vec<&str> = args.collect();
+            let user = users
+                .entry(name.clone())
+                .or_insert_with(|| User::new(&name));
+            if let Err(e) = user.apply_rules(&rules) {
+                error!(LOGGER, "invalid acl user {}: {}", name, e);
+            }
+        }
+        RwLock::new(users)
+    };
+}
+
+/// A user of the access control list.
+///
+/// Passwords are kept as their sha1 hex digest only.
+#[derive(Debug, Clone)]
+pub struct User {
+    name: String,
+    enabled: bool,
+    nopass: bool,
+    passwords: BTreeSet<String>,
+    all_commands: bool,
+    commands: BTreeSet<&'static str>,
+    key_patterns: Vec<String>,
+}
+
+impl User {
+    /// A new user is disabled and allowed to run nothing, as in redis.
+    fn new(name: &str) -> User {
+        User {
+            name: name.to_owned(),
+            enabled: false,
+            nopass: false,
+            passwords: BTreeSet::new(),
+            all_commands: false,
+            commands: BTreeSet::new(),
+            key_patterns: vec![],
+        }
+    }
+
+    /// The password of the `default` user is checked against the server
+    /// config, the user itself takes none.
+    fn new_default() -> User {
+        User {
+            name: DEFAULT_USER.to_owned(),
+            enabled: true,
+            nopass: true,
+            passwords: BTreeSet::new(),
+            all_commands: true,
+            commands: BTreeSet::new(),
+            key_patterns: vec!["*".to_owned()],
+        }
+    }
+
+    /// Apply the rules in order, stopping at the first invalid one.
+    fn apply_rules(&mut self, rules: &[&str]) -> Result<(), String> {
+        for rule in rules {
+            self.apply_rule(rule)?;
+        }
+        Ok(())
+    }
+
+    fn apply_rule(&mut self, rule: &str) -> Result<(), String> {
+        let is_password_rule = rule.starts_with(|c| matches!(c, '>' | '<' | '#'))
+            || matches!(rule.to_lowercase().as_str(), "nopass" | "resetpass");
+        if self.name == DEFAULT_USER && is_password_rule {
+            return Err(format!(
+                "Error in ACL SETUSER modifier '{}': the password of the default user is set by the server config",
+                rule
+            ));
+        }
+
+        match rule.to_lowercase().as_str() {
+            "on" => self.enabled = true,
+            "off" => self.enabled = false,
+            "nopass" => {
+                self.nopass = true;
+                self.passwords.clear();
+            }
+            "resetpass" => {
+                self.nopass = false;
+                self.passwords.clear();
+            }
+            "allkeys" => self.key_patterns = vec!["*".to_owned()],
+            "resetkeys" => self.key_patterns.clear(),
+            "allcommands" => self.allow_category(u32::MAX),
+            "nocommands" => self.deny_category(u32::MAX),
+            "reset" => {
+                let name = std::mem::take(&mut self.name);
+                *self = User::new(&name);
+            }
+            _ => {
+                let prefix = match rule.chars().next() {
+                    Some(c) => c,
+                    None => return Err("Error in ACL SETUSER modifier '': Syntax error".to_owned()),
+                };
+                let arg = &rule[prefix.len_utf8()..];
+                match prefix {
+                    '>' => {
+                        self.nopass = false;
+                        self.passwords.insert(sha1hex(arg));
+                    }
+                    '<' => {
+                        if !self.passwords.remove(&sha1hex(arg)) {
+                            return Err(format!(
+                                "Error in ACL SETUSER modifier '{}': no such password",
+                                rule
+                            ));
+                        }
+                    }
+                    '#' => {
+                        if arg.len() != 40 || !arg.bytes().all(|c| c.is_ascii_hexdigit()) {
+                            return Err(format!(
+                                "Error in ACL SETUSER modifier '{}': the password hash must be a sha1 hex digest",
+                                rule
+                            ));
+                        }
+                        self.nopass = false;
+                        self.passwords.insert(arg.to_lowercase());
+                    }
+                    '~' => {
+                        if !self.key_patterns.iter().any(|p| p == "*") {
+                            self.key_patterns.push(arg.to_owned());
+                        }
+                    }
+                    '+' | '-' if arg.starts_with('@') => match lookup_category(arg) {
+                        Some(category) if prefix == '+' => self.allow_category(category),
+                        Some(category) => self.deny_category(category),
+                        None => {
+                            return Err(format!(
+                                "Error in ACL SETUSER modifier '{}': Unknown command category",
+                                rule
+                            ))
+                        }
+                    },
+                    '+' | '-' => match lookup_command_spec(&arg.to_lowercase()) {
+                        Some(spec) if prefix == '+' => {
+                            if !self.all_commands {
+                                self.commands.insert(spec.name);
+                            }
+                        }
+                        Some(spec) => {
+                            self.expand_all_commands();
+                            self.commands.remove(spec.name);
+                        }
+                        None => {
+                            return Err(format!(
+                                "Error in ACL SETUSER modifier '{}': Unknown command",
+                                rule
+                            ))
+                        }
+                    },
+                    _ => {
+                        return Err(format!(
+                            "Error in ACL SETUSER modifier '{}': Syntax error",
+                            rule
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn allow_category(&mut self, category: u32) {
+        if category == u32::MAX {
+            self.all_commands = true;
+            self.commands.clear();
+        } else if !self.all_commands {
+            self.commands.extend(category_commands(category));
+        }
+    }
+
+    fn deny_category(&mut self, category: u32) {
+        if category == u32::MAX {
+            self.all_commands = false;
+            self.commands.clear();
+        } else {
+            self.expand_all_commands();
+            for name in category_commands(category) {
+                self.commands.remove(name);
+            }
+        }
+    }
+
+    /// Turn `allcommands` into the explicit set of commands, before removing
+    /// some of them.
+    fn expand_all_commands(&mut self) {
+        if self.all_commands {
+            self.all_commands = false;
+            self.commands = category_commands(u32::MAX).into_iter().collect();
+        }
+    }
+
+    fn command_allowed(&self, name: &str) -> bool {
+        self.all_commands || self.commands.contains(name)
+    }
+
+    fn unrestricted(&self) -> bool {
+        self.all_commands && self.key_patterns.iter().any(|p| p == "*")
+    }
+
+    fn key_allowed(&self, key: &[u8]) -> bool {
+        self.key_patterns
+            .iter()
+            .any(|pattern| glob_match(pattern.as_bytes(), key))
+    }
+
+    fn flags(&self) -> Vec<&'static str> {
+        let mut flags = vec![if self.enabled { "on" } else { "off" }];
+        if self.nopass {
+            flags.push("nopass");
+        }
+        if self.key_patterns.iter().any(|p| p == "*") {
+            flags.push("allkeys");
+        }
+        if self.all_commands {
+            flags.push("allcommands");
+        }
+        flags
+    }
+
+    fn commands_rule(&self) -> String {
+        if self.all_commands {
+            return "+@all".to_owned();
+        }
+        let mut rule = "-@all".to_owned();
+        for name in &self.commands {
+            rule.push_str(" +");
+            rule.push_str(name);
+        }
+        rule
+    }
+
+    fn keys_rule(&self) -> String {
+        self.key_patterns
+            .iter()
+            .map(|pattern| format!("~{}", pattern))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The user described by the rules that recreate it, as listed by
+    /// `ACL LIST`.
+    fn describe(&self) -> String {
+        let mut rules = vec![
+            "user".to_owned(),
+            self.name.clone(),
+            if self.enabled { "on" } else { "off" }.to_owned(),
+        ];
+        if self.nopass {
+            rules.push("nopass".to_owned());
+        }
+        rules.extend(self.passwords.iter().map(|hash| format!("#{}", hash)));
+        let keys = self.keys_rule();
+        if !keys.is_empty() {
+            rules.push(keys);
+        }
+        rules.push(self.commands_rule());
+        rules.join(" ")
+    }
+
+    /// The fields of the user as replied by `ACL GETUSER`.
+    fn to_frame(&self) -> Frame {
+        Frame::Map(vec![
+            (bulk("flags"), strings_frame(self.flags())),
+            (
+                bulk("passwords"),
+                strings_frame(self.passwords.iter().map(String::as_str).collect()),
+            ),
+            (bulk("commands"), bulk(&self.commands_rule())),
+            (bulk("keys"), bulk(&self.keys_rule())),
+        ])
+    }
+}
+
+fn bulk(s: &str) -> Frame {
+    resp_bulk(s.as_bytes().to_vec())
+}
+
+fn strings_frame(items: Vec<&str>) -> Frame {
+    resp_array(items.into_iter().map(bulk).collect())
+}
+
+/// Check the password of a user other than `default`, the user must be
+/// enabled.
+pub fn authenticate(username: &str, password: &str) -> bool {
+    let users = ACL_USERS.read().unwrap();
+    match users.get(username) {
+        Some(user) => user.enabled && (user.nopass || user.passwords.contains(&sha1hex(password))),
+        None => false,
+    }
+}
+
+/// Whether the `default` user may be authenticated as, it is disabled by the
+/// `off` rule.
+pub fn default_user_enabled() -> bool {
+    let users = ACL_USERS.read().unwrap();
+    users.get(DEFAULT_USER).map_or(false, |user| user.enabled)
+}
+
+/// Check the user may run the command of the request frame on its keys.
+///
+/// Unknown commands are left to the dispatcher, which replies its own error.
+pub fn check_permission(username: &str, frame: &Frame) -> Result<(), RTError> {
+    // the request is not decoded for the users allowed everything
+    if ACL_USERS
+        .read()
+        .unwrap()
+        .get(username)
+        .map_or(false, User::unrestricted)
+    {
+        return Ok(());
+    }
+    check_argv_permission(username, &argv_from_frame(frame))
+}
+
+/// Check the user may run the command of the argv on its keys, the command
+/// name included, e.g. for the commands called by scripts.
+pub fn check_argv_permission(username: &str, argv: &[Bytes]) -> Result<(), RTError> {
+    let users = ACL_USERS.read().unwrap();
+    let user = users.get(username);
+    if user.map_or(false, User::unrestricted) {
+        return Ok(());
+    }
+    if argv.is_empty() {
+        return Ok(());
+    }
+    let name = String::from_utf8_lossy(&argv[0]).to_lowercase();
+    let spec = match lookup_command_spec(&name) {
+        Some(spec) => spec,
+        None => return Ok(()),
+    };
+
+    let user = match user {
+        Some(user) if user.command_allowed(spec.name) => user,
+        _ => {
+            return Err(RTError::Owned(format!(
+                "NOPERM User {} has no permissions to run the '{}' command",
+                username, spec.name
+            )))
+        }
+    };
+    if spec
+        .keys(argv)
+        .into_iter()
+        .any(|key| !user.key_allowed(key))
+    {
+        return Err(REDIS_NOPERM_KEY_ERR);
+    }
+    Ok(())
+}
+
+/// Create the user if missing and apply the rules to it.
+pub fn set_user(username: &str, rules: &[&str]) -> Result<(), RTError> {
+    let mut users = ACL_USERS.write().unwrap();
+    let mut user = users
+        .get(username)
+        .cloned()
+        .unwrap_or_else(|| User::new(username));
+    // the user is left untouched if any rule is invalid
+    user.apply_rules(rules)
+        .map_err(|e| RTError::Owned(format!("ERR {}", e)))?;
+    users.insert(username.to_owned(), user);
+    Ok(())
+}
+
+/// The rules of every user, sorted by user name.
+pub fn list_users() -> Vec<String> {
+    let users = ACL_USERS.read().unwrap();
+    let mut names: Vec<&String> = users.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| users[name].describe())
+        .collect()
+}
+
+/// The fields of a user, `None` if the user does not exist.
+pub fn get_user(username: &str) -> Option<Frame> {
+    let users = ACL_USERS.read().unwrap();
+    users.get(username).map(User::to_frame)
+}
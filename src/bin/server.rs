@@ -3,13 +3,14 @@ use tidis::{
     config_port_or_default, config_prometheus_listen_or_default, config_prometheus_port_or_default,
     config_tls_auth_client_or_default, config_tls_ca_cert_file_or_default,
     config_tls_cert_file_or_default, config_tls_key_file_or_default, config_tls_listen_or_default,
-    config_tls_port_or_default, do_async_connect, server, set_global_config, set_instance_id,
-    utils, Config, PrometheusServer,
+    config_tls_port_or_default, config_unixsocket_or_default, do_async_connect, server,
+    set_global_config, set_instance_id, utils, Config, PrometheusServer,
 };
 
 use slog::info;
 
 use async_std::net::TcpListener;
+use async_std::os::unix::net::UnixListener;
 use std::fs;
 use std::process::exit;
 use std::sync::Arc;
@@ -65,6 +66,8 @@ pub async fn main() -> tidis::Result<()> {
         .tls_ca_cert_file
         .as_deref()
         .unwrap_or(&c_tls_ca_cert_file);
+    let c_unixsocket = config_unixsocket_or_default();
+    let unixsocket = cli.unixsocket.as_deref().unwrap_or(&c_unixsocket);
     let c_pd_addrs = config_pd_addrs_or_default();
     let pd_addrs = cli.pd_addrs.as_deref().unwrap_or(&c_pd_addrs);
     let c_instance_id = config_instance_id_or_default();
@@ -98,16 +101,24 @@ pub async fn main() -> tidis::Result<()> {
         server.run().await;
     });
 
-    let mut listener = None;
+    let mut listeners = Vec::new();
     let mut tls_listener = None;
     let mut tls_acceptor = None;
+    let mut unix_listener = None;
     if port != "0" {
-        info!(
-            tidis::config::LOGGER,
-            "TiKV Service Server Listen on: {}:{}", &listen_addr, port
-        );
-        // Bind a TCP listener
-        listener = Some(TcpListener::bind(&format!("{}:{}", &listen_addr, port)).await?);
+        // Bind a TCP listener for each of the comma separated listen addresses
+        for addr in listen_addr
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let addr = utils::addr_with_port(addr, port);
+            info!(
+                tidis::config::LOGGER,
+                "TiKV Service Server Listen on: {}", &addr
+            );
+            listeners.push(TcpListener::bind(&addr).await?);
+        }
     }
 
     if tls_port != "0" && !tls_cert_file.is_empty() && !tls_cert_file.is_empty() {
@@ -126,7 +137,27 @@ pub async fn main() -> tidis::Result<()> {
         tls_acceptor = Some(TlsAcceptor::from(Arc::new(tls_config)));
     }
 
-    server::run(listener, tls_listener, tls_acceptor, signal::ctrl_c()).await;
+    if !unixsocket.is_empty() {
+        info!(
+            tidis::config::LOGGER,
+            "TiKV Service Server Listen on unix socket: {}", unixsocket
+        );
+        // remove the socket file left by a previous run, binding fails otherwise
+        let _ = fs::remove_file(unixsocket);
+        unix_listener = Some((
+            unixsocket.to_string(),
+            UnixListener::bind(unixsocket).await?,
+        ));
+    }
+
+    server::run(
+        listeners,
+        tls_listener,
+        tls_acceptor,
+        unix_listener,
+        signal::ctrl_c(),
+    )
+    .await;
 
     Ok(())
 }
@@ -158,6 +189,9 @@ struct Cli {
     #[structopt(name = "tls_ca_cert_file", long = "--tls_ca_cert_file")]
     tls_ca_cert_file: Option<String>,
 
+    #[structopt(name = "unixsocket", long = "--unixsocket")]
+    unixsocket: Option<String>,
+
     #[structopt(name = "pdaddrs", long = "--pdaddrs")]
     pd_addrs: Option<String>,
 
@@ -1,22 +1,39 @@
+use crate::SocketType;
 use async_std::net::TcpStream;
+use async_std::os::unix::net::UnixStream;
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Formatter;
 use std::os::unix::io::{AsRawFd, RawFd};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::SystemTime;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::Mutex;
 
 // reserve id 0
 static COUNTER: AtomicU64 = AtomicU64::new(1);
 
+/// Registry of the connected clients by id, shared by the accept loops of all
+/// the socket types. A client is registered once its connection is usable and
+/// removed when the connection is closed.
+pub type Clients = Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>;
+
 #[derive(Debug, Clone)]
 pub struct Client {
     id: u64,
     // name here does not constrain to uniqueness
     name: String,
     fd: RawFd,
+    socket_type: SocketType,
     // last command played
     cmd: String,
+    // RESP version of the connection
+    resp: u8,
+    // queued commands of MULTI, -1 if not in MULTI
+    multi: i64,
+    // code of the last error replied to the connection
+    last_error: String,
 
     local_addr: String,
     peer_addr: String,
@@ -28,15 +45,45 @@ pub struct Client {
 }
 
 impl Client {
-    pub fn new(socket: TcpStream, kill_tx: Sender<()>) -> Client {
+    pub fn new(socket: TcpStream, socket_type: SocketType, kill_tx: Sender<()>) -> Client {
+        Client::with_addrs(
+            socket.as_raw_fd(),
+            socket_type,
+            socket.local_addr().unwrap().to_string(),
+            socket.peer_addr().unwrap().to_string(),
+            kill_tx,
+        )
+    }
+
+    pub fn new_unix(path: &str, socket: &UnixStream, kill_tx: Sender<()>) -> Client {
+        Client::with_addrs(
+            socket.as_raw_fd(),
+            SocketType::Unix,
+            path.to_string(),
+            format!("{}:0", path),
+            kill_tx,
+        )
+    }
+
+    fn with_addrs(
+        fd: RawFd,
+        socket_type: SocketType,
+        local_addr: String,
+        peer_addr: String,
+        kill_tx: Sender<()>,
+    ) -> Client {
         let now = SystemTime::now();
         Client {
             id: COUNTER.fetch_add(1, Ordering::Relaxed),
             name: "".to_owned(),
-            fd: socket.as_raw_fd(),
+            fd,
+            socket_type,
             cmd: "".to_owned(),
-            local_addr: socket.local_addr().unwrap().to_string(),
-            peer_addr: socket.peer_addr().unwrap().to_string(),
+            resp: 2,
+            multi: -1,
+            last_error: "".to_owned(),
+            local_addr,
+            peer_addr,
             create_time: now,
             last_interaction: now,
             kill_tx,
@@ -48,8 +95,21 @@ impl Client {
         self.last_interaction = SystemTime::now();
     }
 
-    pub async fn kill(&self) {
-        let _ = self.kill_tx.send(()).await;
+    /// Record the state of the connection before running a command, it is
+    /// reported by CLIENT LIST and CLIENT INFO.
+    pub fn set_conn_state(&mut self, resp3: bool, multi: Option<usize>, last_error: Option<&str>) {
+        self.resp = if resp3 { 3 } else { 2 };
+        self.multi = multi.map_or(-1, |n| n as i64);
+        if let Some(err) = last_error {
+            self.last_error = err.to_owned();
+        }
+    }
+
+    /// Signal the connection to close, it closes once its current command is
+    /// done. The signal is never waited for, a connection killed again before
+    /// it is closed has a signal pending already.
+    pub fn kill(&self) {
+        let _ = self.kill_tx.try_send(());
     }
 
     pub fn id(&self) -> u64 {
@@ -67,6 +127,10 @@ impl Client {
         &self.peer_addr
     }
 
+    pub fn socket_type(&self) -> SocketType {
+        self.socket_type
+    }
+
     pub fn age(&self) -> u64 {
         self.create_time.elapsed().unwrap().as_secs()
     }
@@ -84,9 +148,9 @@ impl fmt::Display for Client {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
-            "id={} addr={} laddr={} fd={} name={} age={} idle={} flags=N \
-            db=0 sub=0 psub=0 multi=-1 qbuf=0 qbuf-free=0 argv-mem=10 obl=0 oll=0 omem=0 \
-            tot-mem=0 events=r cmd={} user=default redir=-1",
+            "id={} addr={} laddr={} fd={} name={} age={} idle={} flags={} \
+            db=0 sub=0 psub=0 multi={} qbuf=0 qbuf-free=0 argv-mem=10 obl=0 oll=0 omem=0 \
+            tot-mem=0 events=r cmd={} user=default redir=-1 resp={} last-err={}",
             self.id,
             self.peer_addr,
             self.local_addr,
@@ -94,7 +158,16 @@ impl fmt::Display for Client {
             self.name,
             self.age(),
             self.idle(),
-            self.cmd
+            // unix socket clients are flagged as U, same as redis
+            if self.socket_type == SocketType::Unix {
+                "U"
+            } else {
+                "N"
+            },
+            self.multi,
+            self.cmd,
+            self.resp,
+            self.last_error
         )
     }
 }
@@ -0,0 +1,74 @@
+use crate::acl::{get_user, list_users, set_user};
+use crate::cmd::Invalid;
+use crate::tikv::errors::REDIS_UNKNOWN_SUBCOMMAND;
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_invalid_arguments, resp_nil, resp_ok};
+use crate::{Frame, Parse};
+
+/// Inspect and manage the users of the access control list.
+///
+/// ```text
+/// ACL WHOAMI
+/// ACL LIST
+/// ACL GETUSER username
+/// ACL SETUSER username [rule [rule ...]]
+/// ```
+///
+/// The command is applied by the connection, which knows the user it is
+/// authenticated as.
+#[derive(Debug, Clone)]
+pub struct Acl {
+    subcommand: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Acl {
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Acl> {
+        let subcommand = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        Ok(Acl {
+            subcommand,
+            args,
+            valid: true,
+        })
+    }
+
+    /// Reply to the subcommand on behalf of the connection authenticated as
+    /// `username`.
+    pub(crate) fn acl(&self, username: &str) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match (self.subcommand.to_uppercase().as_str(), self.args.len()) {
+            ("WHOAMI", 0) => resp_bulk(username.as_bytes().to_vec()),
+            ("LIST", 0) => resp_array(
+                list_users()
+                    .into_iter()
+                    .map(|rules| resp_bulk(rules.into_bytes()))
+                    .collect(),
+            ),
+            ("GETUSER", 1) => get_user(&self.args[0]).unwrap_or_else(resp_nil),
+            ("SETUSER", n) if n > 0 => {
+                let rules: Vec<&str> = self.args[1..].iter().map(String::as_str).collect();
+                match set_user(&self.args[0], &rules) {
+                    Ok(()) => resp_ok(),
+                    Err(e) => resp_err(e),
+                }
+            }
+            _ => resp_err(REDIS_UNKNOWN_SUBCOMMAND),
+        }
+    }
+}
+
+impl Invalid for Acl {
+    fn new_invalid() -> Acl {
+        Acl {
+            subcommand: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -1,8 +1,17 @@
-use crate::cmd::Invalid;
+use crate::cmd::{Invalid, ParseError};
 use crate::Parse;
 
+/// Authenticate the connection.
+///
+/// ```text
+/// AUTH [username] password
+/// ```
+///
+/// There are no users but the `default` one, the username form is accepted
+/// for the clients speaking the ACL style of redis 6.
 #[derive(Debug, Clone)]
 pub struct Auth {
+    username: Option<String>,
     passwd: String,
     valid: bool,
 }
@@ -10,11 +19,16 @@ pub struct Auth {
 impl Auth {
     pub fn new(passwd: String) -> Auth {
         Auth {
+            username: None,
             passwd,
             valid: true,
         }
     }
 
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
     pub fn passwd(&self) -> &str {
         &self.passwd
     }
@@ -24,18 +38,23 @@ impl Auth {
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Auth> {
-        let passwd = parse.next_string()?;
-
-        Ok(Auth {
-            passwd,
-            valid: true,
-        })
+        let first = parse.next_string()?;
+        match parse.next_string() {
+            Ok(passwd) => Ok(Auth {
+                username: Some(first),
+                passwd,
+                valid: true,
+            }),
+            Err(ParseError::EndOfStream) => Ok(Auth::new(first)),
+            Err(e) => Err(e.into()),
+        }
     }
 }
 
 impl Invalid for Auth {
     fn new_invalid() -> Auth {
         Auth {
+            username: None,
             passwd: "".to_owned(),
             valid: false,
         }
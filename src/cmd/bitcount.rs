@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Count the set bits of a string.
+///
+/// ```text
+/// BITCOUNT key [start end [BYTE|BIT]]
+/// ```
+///
+/// `start` and `end` are inclusive byte offsets by default, or bit offsets
+/// with `BIT`. Negative offsets count from the end of the value.
+#[derive(Debug, Clone)]
+pub struct Bitcount {
+    key: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Bitcount {
+    pub fn new(key: &str, args: Vec<String>) -> Bitcount {
+        Bitcount {
+            key: key.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bitcount> {
+        let key = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+
+        Ok(Bitcount::new(&key, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Bitcount> {
+        if argv.is_empty() {
+            return Ok(Bitcount::new_invalid());
+        }
+        let args = argv[1..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Bitcount::new(&String::from_utf8_lossy(&argv[0]), args))
+    }
+
+    /// Parse the arguments to (start, end, in bits).
+    fn parse_range(&self) -> Result<Option<(i64, i64, bool)>, RTError> {
+        match self.args.as_slice() {
+            [] => Ok(None),
+            [start, end] => Ok(Some((start.parse()?, end.parse()?, false))),
+            [start, end, unit] => {
+                let start = start.parse()?;
+                let end = end.parse()?;
+                match unit.to_uppercase().as_str() {
+                    "BYTE" => Ok(Some((start, end, false))),
+                    "BIT" => Ok(Some((start, end, true))),
+                    _ => Err(REDIS_SYNTAX_ERR),
+                }
+            }
+            _ => Err(REDIS_SYNTAX_ERR),
+        }
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitcount(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn bitcount(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let range = match self.parse_range() {
+            Ok(range) => range,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_bitcount(&self.key, range)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Bitcount {
+    fn new_invalid() -> Bitcount {
+        Bitcount {
+            key: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use crate::cmd::getbit::BIT_OFFSET_LIMIT;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_BITFIELD_OVERFLOW_ERR, REDIS_BITFIELD_TYPE_ERR,
+    REDIS_BIT_OFFSET_ERR, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR,
+};
+use crate::tikv::string::{BitfieldOp, BitfieldOverflow, BitfieldType, StringCommandCtx};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Operate on bit fields of a string.
+///
+/// ```text
+/// BITFIELD key [GET type offset] [SET type offset value]
+///     [INCRBY type offset increment] [OVERFLOW WRAP|SAT|FAIL]
+/// ```
+///
+/// The type is `i` or `u` followed by the bit width, i1..i64 or u1..u63. An
+/// offset prefixed by `#` is multiplied by the width. `OVERFLOW` applies to
+/// the SET and INCRBY after it, FAIL replies nil for the overflowing field and
+/// leaves it unchanged. The sub-operations are applied in one transaction and
+/// their results replied in an array.
+#[derive(Debug, Clone)]
+pub struct Bitfield {
+    key: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+fn parse_type(ty: &str) -> Result<BitfieldType, RTError> {
+    let signed = match ty.get(..1) {
+        Some("i") | Some("I") => true,
+        Some("u") | Some("U") => false,
+        _ => return Err(REDIS_BITFIELD_TYPE_ERR),
+    };
+    match ty[1..].parse::<u32>() {
+        Ok(bits) if bits >= 1 && (bits <= 63 || (signed && bits == 64)) => {
+            Ok(BitfieldType { signed, bits })
+        }
+        _ => Err(REDIS_BITFIELD_TYPE_ERR),
+    }
+}
+
+fn parse_offset(offset: &str, ty: BitfieldType) -> Result<u64, RTError> {
+    let (offset, multiplied) = match offset.strip_prefix('#') {
+        Some(offset) => (offset, true),
+        None => (offset, false),
+    };
+    let offset = offset.parse::<u64>().map_err(|_| REDIS_BIT_OFFSET_ERR)?;
+    let offset = if multiplied {
+        offset
+            .checked_mul(ty.bits as u64)
+            .ok_or(REDIS_BIT_OFFSET_ERR)?
+    } else {
+        offset
+    };
+    // the whole field must be below the limit of a bit offset
+    match offset.checked_add(ty.bits as u64) {
+        Some(end) if end <= BIT_OFFSET_LIMIT => Ok(offset),
+        _ => Err(REDIS_BIT_OFFSET_ERR),
+    }
+}
+
+impl Bitfield {
+    pub fn new(key: &str, args: Vec<String>) -> Bitfield {
+        Bitfield {
+            key: key.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bitfield> {
+        let key = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+
+        Ok(Bitfield::new(&key, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Bitfield> {
+        if argv.is_empty() {
+            return Ok(Bitfield::new_invalid());
+        }
+        let args = argv[1..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Bitfield::new(&String::from_utf8_lossy(&argv[0]), args))
+    }
+
+    fn parse_ops(&self) -> Result<Vec<BitfieldOp>, RTError> {
+        let mut ops = vec![];
+        let mut overflow = BitfieldOverflow::Wrap;
+        let mut idx = 0;
+        while idx < self.args.len() {
+            let sub = self.args[idx].to_uppercase();
+            let argc = match sub.as_str() {
+                "GET" => 2,
+                "SET" | "INCRBY" => 3,
+                "OVERFLOW" => 1,
+                _ => return Err(REDIS_SYNTAX_ERR),
+            };
+            if idx + argc >= self.args.len() {
+                return Err(REDIS_SYNTAX_ERR);
+            }
+            let args = &self.args[idx + 1..idx + 1 + argc];
+            match sub.as_str() {
+                "OVERFLOW" => {
+                    overflow = match args[0].to_uppercase().as_str() {
+                        "WRAP" => BitfieldOverflow::Wrap,
+                        "SAT" => BitfieldOverflow::Sat,
+                        "FAIL" => BitfieldOverflow::Fail,
+                        _ => return Err(REDIS_BITFIELD_OVERFLOW_ERR),
+                    };
+                }
+                _ => {
+                    let ty = parse_type(&args[0])?;
+                    let offset = parse_offset(&args[1], ty)?;
+                    ops.push(match sub.as_str() {
+                        "GET" => BitfieldOp::Get(ty, offset),
+                        "SET" => BitfieldOp::Set(ty, offset, args[2].parse::<i64>()?, overflow),
+                        _ => BitfieldOp::Incrby(ty, offset, args[2].parse::<i64>()?, overflow),
+                    });
+                }
+            }
+            idx += argc + 1;
+        }
+        Ok(ops)
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitfield(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn bitfield(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let ops = match self.parse_ops() {
+            Ok(ops) => ops,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_bitfield(&self.key, ops)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Bitfield {
+    fn new_invalid() -> Bitfield {
+        Bitfield {
+            key: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
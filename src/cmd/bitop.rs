@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_BITOP_NOT_ERR, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR,
+};
+use crate::tikv::string::{BitOperation, StringCommandCtx};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Store the result of a bitwise operation across strings.
+///
+/// ```text
+/// BITOP AND|OR|XOR|NOT destkey key [key ...]
+/// ```
+///
+/// The shorter strings are padded with zero bytes to the longest one, NOT
+/// takes a single key. The length of the result is replied, `destkey` is
+/// deleted if the result is empty.
+#[derive(Debug, Clone)]
+pub struct Bitop {
+    op: String,
+    dst: String,
+    srcs: Vec<String>,
+    valid: bool,
+}
+
+impl Bitop {
+    pub fn new(op: &str, dst: &str, srcs: Vec<String>) -> Bitop {
+        Bitop {
+            op: op.to_owned(),
+            dst: dst.to_owned(),
+            srcs,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys = vec![self.dst.clone()];
+        keys.extend(self.srcs.iter().cloned());
+        keys
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bitop> {
+        let op = parse.next_string()?;
+        let dst = parse.next_string()?;
+        let mut srcs = vec![];
+        while let Ok(src) = parse.next_string() {
+            srcs.push(src);
+        }
+        if srcs.is_empty() {
+            return Ok(Bitop::new_invalid());
+        }
+
+        Ok(Bitop::new(&op, &dst, srcs))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Bitop> {
+        if argv.len() < 3 {
+            return Ok(Bitop::new_invalid());
+        }
+        let srcs = argv[2..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Bitop::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+            srcs,
+        ))
+    }
+
+    fn parse_op(&self) -> Result<BitOperation, RTError> {
+        let op = match self.op.to_uppercase().as_str() {
+            "AND" => BitOperation::And,
+            "OR" => BitOperation::Or,
+            "XOR" => BitOperation::Xor,
+            "NOT" => BitOperation::Not,
+            _ => return Err(REDIS_SYNTAX_ERR),
+        };
+        if matches!(op, BitOperation::Not) && self.srcs.len() != 1 {
+            return Err(REDIS_BITOP_NOT_ERR);
+        }
+        Ok(op)
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitop(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn bitop(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let op = match self.parse_op() {
+            Ok(op) => op,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_bitop(op, &self.dst, &self.srcs)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Bitop {
+    fn new_invalid() -> Bitop {
+        Bitop {
+            op: "".to_owned(),
+            dst: "".to_owned(),
+            srcs: vec![],
+            valid: false,
+        }
+    }
+}
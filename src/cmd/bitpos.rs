@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_BIT_ARGUMENT_ERR, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR,
+};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Find the first bit set to 1 or 0 in a string.
+///
+/// ```text
+/// BITPOS key bit [start [end [BYTE|BIT]]]
+/// ```
+///
+/// Without `end`, the value is looked up as if padded with zero bits on the
+/// right, so the bit length is replied if a clear bit is not found. With
+/// `end`, -1 is replied if the bit is not found in the range.
+#[derive(Debug, Clone)]
+pub struct Bitpos {
+    key: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Bitpos {
+    pub fn new(key: &str, args: Vec<String>) -> Bitpos {
+        Bitpos {
+            key: key.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bitpos> {
+        let key = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.is_empty() {
+            return Ok(Bitpos::new_invalid());
+        }
+
+        Ok(Bitpos::new(&key, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Bitpos> {
+        if argv.len() < 2 {
+            return Ok(Bitpos::new_invalid());
+        }
+        let args = argv[1..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Bitpos::new(&String::from_utf8_lossy(&argv[0]), args))
+    }
+
+    /// Parse the arguments to (bit, start, end, in bits).
+    fn parse_args(&self) -> Result<(bool, Option<i64>, Option<i64>, bool), RTError> {
+        let bit = match self.args[0].as_str() {
+            "0" => false,
+            "1" => true,
+            _ => return Err(REDIS_BIT_ARGUMENT_ERR),
+        };
+        let start = match self.args.get(1) {
+            Some(start) => Some(start.parse::<i64>()?),
+            None => None,
+        };
+        let end = match self.args.get(2) {
+            Some(end) => Some(end.parse::<i64>()?),
+            None => None,
+        };
+        let in_bits = match self.args.get(3).map(|unit| unit.to_uppercase()) {
+            Some(unit) if unit == "BIT" => true,
+            Some(unit) if unit == "BYTE" => false,
+            Some(_) => return Err(REDIS_SYNTAX_ERR),
+            None => false,
+        };
+        if self.args.len() > 4 {
+            return Err(REDIS_SYNTAX_ERR);
+        }
+        Ok((bit, start, end, in_bits))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.bitpos(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn bitpos(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (bit, start, end, in_bits) = match self.parse_args() {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_bitpos(&self.key, bit, start, end, in_bits)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Bitpos {
+    fn new_invalid() -> Bitpos {
+        Bitpos {
+            key: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, REDIS_NOT_SUPPORTED_ERR, REDIS_TIMEOUT_IS_NEGATIVE_ERR,
+    REDIS_TIMEOUT_IS_NOT_FLOAT_ERR,
+};
+use crate::tikv::list::ListCommandCtx;
+use crate::utils::{resp_array, resp_bulk, resp_err, resp_invalid_arguments, resp_nil};
+use crate::{Connection, Db, Frame, Shutdown};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::select;
+use tokio::sync::Mutex;
+use tokio::time::{self, Duration, Instant};
+
+/// Interval to check the lists again while blocked, pushes through other
+/// instances of the cluster do not wake the blocked pops of this instance.
+const BLOCKED_POP_RECHECK_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Blocking version of LPOP and RPOP.
+///
+/// ```text
+/// BLPOP key [key ...] timeout
+/// BRPOP key [key ...] timeout
+/// ```
+///
+/// Pops from the first non-empty list of the keys, otherwise blocks until a
+/// push to one of them or `timeout` seconds pass, 0 blocks forever. The blocked
+/// pops of a list are served in FIFO order, a push wakes the first one only.
+#[derive(Debug, Clone)]
+pub struct Bpop {
+    keys: Vec<String>,
+    // NaN if the timeout is not a float
+    timeout: f64,
+    valid: bool,
+}
+
+impl Bpop {
+    pub fn new(keys: Vec<String>, timeout: f64) -> Bpop {
+        Bpop {
+            keys,
+            timeout,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    fn from_timeout(keys: Vec<String>, timeout: &str) -> Bpop {
+        match timeout.parse::<f64>() {
+            Ok(v) if v.is_finite() => Bpop::new(keys, v),
+            _ => Bpop::new(keys, f64::NAN),
+        }
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Bpop> {
+        if argv.len() < 2 {
+            return Ok(Bpop::new_invalid());
+        }
+        let keys = argv[..argv.len() - 1]
+            .iter()
+            .map(|key| String::from_utf8_lossy(key).to_string())
+            .collect();
+        Ok(Bpop::from_timeout(
+            keys,
+            &String::from_utf8_lossy(&argv[argv.len() - 1]),
+        ))
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bpop> {
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 2 {
+            return Ok(Bpop::new_invalid());
+        }
+        let timeout = args.pop().unwrap();
+        Ok(Bpop::from_timeout(args, &timeout))
+    }
+
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        shutdown: &mut Shutdown,
+        op_left: bool,
+    ) -> crate::Result<()> {
+        let response = self.bpop(None, op_left).await?;
+        if !matches!(response, Frame::Null) {
+            return self.reply(dst, response).await;
+        }
+
+        let deadline = if self.timeout > 0.0 {
+            Some(Instant::now() + Duration::from_secs_f64(self.timeout))
+        } else {
+            None
+        };
+
+        // The pop is registered before trying again, a push between the first
+        // try and the registration is not missed.
+        let waiter = db.block_on_lists(&self.keys);
+        loop {
+            let response = self.bpop(None, op_left).await?;
+            if !matches!(response, Frame::Null) {
+                drop(waiter);
+                return self.reply(dst, response).await;
+            }
+
+            let now = Instant::now();
+            let wait = match deadline {
+                Some(deadline) if deadline <= now => {
+                    drop(waiter);
+                    return self.reply(dst, resp_nil()).await;
+                }
+                Some(deadline) => (deadline - now).min(BLOCKED_POP_RECHECK_INTERVAL),
+                None => BLOCKED_POP_RECHECK_INTERVAL,
+            };
+
+            select! {
+                _ = waiter.woken() => {}
+                _ = time::sleep(wait) => {}
+                alive = dst.fill_read_buffer() => {
+                    // a pop woken for a closed connection would lose the element
+                    if !alive? {
+                        return Ok(());
+                    }
+                }
+                _ = shutdown.recv() => {
+                    return Ok(());
+                }
+            }
+            waiter.requeue();
+        }
+    }
+
+    async fn reply(&self, dst: &mut Connection, response: Frame) -> crate::Result<()> {
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// Pop from the first non-empty list without blocking, this is how the
+    /// command runs in transactions and scripts.
+    pub async fn bpop(
+        &self,
+        txn: Option<Arc<Mutex<Transaction>>>,
+        op_left: bool,
+    ) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if self.timeout.is_nan() {
+            return Ok(resp_err(REDIS_TIMEOUT_IS_NOT_FLOAT_ERR));
+        }
+        if self.timeout < 0.0 {
+            return Ok(resp_err(REDIS_TIMEOUT_IS_NEGATIVE_ERR));
+        }
+        if !is_use_txn_api() {
+            return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
+        }
+        for key in &self.keys {
+            match ListCommandCtx::new(txn.clone())
+                .do_async_txnkv_pop(key, op_left, 1)
+                .await?
+            {
+                Frame::Null => continue,
+                Frame::Bulk(value) => {
+                    return Ok(resp_array(vec![
+                        resp_bulk(key.as_bytes().to_vec()),
+                        Frame::Bulk(value),
+                    ]))
+                }
+                response => return Ok(response),
+            }
+        }
+        Ok(resp_nil())
+    }
+}
+
+impl Invalid for Bpop {
+    fn new_invalid() -> Bpop {
+        Bpop {
+            keys: vec![],
+            timeout: 0.0,
+            valid: false,
+        }
+    }
+}
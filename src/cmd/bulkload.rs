@@ -0,0 +1,108 @@
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::tikv::errors::{
+    REDIS_BULK_LOAD_ALREADY_STARTED_ERR, REDIS_BULK_LOAD_NOT_STARTED_ERR, REDIS_UNKNOWN_SUBCOMMAND,
+};
+use crate::tikv::import::BulkImporter;
+use crate::utils::{resp_err, resp_invalid_arguments, resp_ok};
+use crate::{bulk_load_batch_size_or_default, Connection, Parse};
+use slog::debug;
+
+/// Switch the connection into or out of bulk load mode.
+///
+/// ```text
+/// BULKLOAD BEGIN [BATCH size]
+/// BULKLOAD STATUS
+/// BULKLOAD END
+/// ```
+///
+/// Between BEGIN and END all commands of the connection are executed in large
+/// batched transactions, see `BulkImporter` for the consistency tradeoffs.
+#[derive(Debug, Clone)]
+pub struct Bulkload {
+    subcommand: String,
+    batch_size: Option<usize>,
+    valid: bool,
+}
+
+impl Bulkload {
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Bulkload> {
+        let subcommand = parse.next_string()?.to_lowercase();
+        let mut batch_size = None;
+        if subcommand == "begin" {
+            if let Ok(opt) = parse.next_string() {
+                if opt.to_lowercase() != "batch" {
+                    return Ok(Bulkload::new_invalid());
+                }
+                let size = parse.next_int()?;
+                if size <= 0 {
+                    return Ok(Bulkload::new_invalid());
+                }
+                batch_size = Some(size as usize);
+            }
+        }
+
+        Ok(Bulkload {
+            subcommand,
+            batch_size,
+            valid: true,
+        })
+    }
+
+    pub(crate) async fn apply(
+        self,
+        dst: &mut Connection,
+        importer: &mut Option<BulkImporter>,
+    ) -> crate::Result<()> {
+        let response = if !self.valid {
+            resp_invalid_arguments()
+        } else {
+            match self.subcommand.as_str() {
+                "begin" => {
+                    if importer.is_some() {
+                        resp_err(REDIS_BULK_LOAD_ALREADY_STARTED_ERR)
+                    } else {
+                        let batch_size = self
+                            .batch_size
+                            .unwrap_or_else(bulk_load_batch_size_or_default);
+                        importer.replace(BulkImporter::new(batch_size));
+                        resp_ok()
+                    }
+                }
+                "status" => match importer {
+                    Some(im) => im.report(),
+                    None => resp_err(REDIS_BULK_LOAD_NOT_STARTED_ERR),
+                },
+                "end" => match importer.take() {
+                    Some(mut im) => match im.flush().await {
+                        Ok(_) => im.report(),
+                        Err(e) => resp_err(e),
+                    },
+                    None => resp_err(REDIS_BULK_LOAD_NOT_STARTED_ERR),
+                },
+                _ => resp_err(REDIS_UNKNOWN_SUBCOMMAND),
+            }
+        };
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+}
+
+impl Invalid for Bulkload {
+    fn new_invalid() -> Bulkload {
+        Bulkload {
+            subcommand: "".to_owned(),
+            batch_size: None,
+            valid: false,
+        }
+    }
+}
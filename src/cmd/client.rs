@@ -0,0 +1,276 @@
+use slog::debug;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::client::{Client as ClientInfo, Clients};
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::tikv::errors::{
+    REDIS_CLIENT_NAME_INVALID_ERR, REDIS_INVALID_CLIENT_ID_ERR, REDIS_NOT_SUPPORTED_ERR,
+    REDIS_NO_SUCH_CLIENT_ERR, REDIS_UNKNOWN_SUBCOMMAND, REDIS_VALUE_IS_NOT_INTEGER_ERR,
+};
+use crate::utils::{resp_bulk, resp_err, resp_int, resp_invalid_arguments, resp_nil, resp_ok};
+use crate::{Connection, Frame, Parse};
+
+/// Inspect and manage the client connections.
+///
+/// ```text
+/// CLIENT subcommand [argument [argument ...]]
+/// ```
+///
+/// Each subcommand is served by the method of the same name, the arguments
+/// following the subcommand are checked by the method.
+#[derive(Debug, Clone)]
+pub struct Client {
+    subcommand: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Client {
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Client> {
+        let subcommand = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        Ok(Client {
+            subcommand,
+            args,
+            valid: true,
+        })
+    }
+
+    pub(crate) async fn apply(
+        self,
+        dst: &mut Connection,
+        cur_client: Arc<Mutex<ClientInfo>>,
+        clients: Clients,
+    ) -> crate::Result<()> {
+        let response = self.client(cur_client, clients).await;
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    async fn client(self, cur_client: Arc<Mutex<ClientInfo>>, clients: Clients) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match self.subcommand.to_uppercase().as_str() {
+            "ID" => self.id(cur_client).await,
+            "INFO" => self.info(cur_client).await,
+            "LIST" => self.list(clients).await,
+            "KILL" => self.kill(cur_client, clients).await,
+            "SETNAME" => self.setname(cur_client).await,
+            "GETNAME" => self.getname(cur_client).await,
+            _ => resp_err(REDIS_UNKNOWN_SUBCOMMAND),
+        }
+    }
+
+    async fn id(&self, cur_client: Arc<Mutex<ClientInfo>>) -> Frame {
+        if !self.args.is_empty() {
+            return resp_invalid_arguments();
+        }
+        resp_int(cur_client.lock().await.id() as i64)
+    }
+
+    async fn info(&self, cur_client: Arc<Mutex<ClientInfo>>) -> Frame {
+        if !self.args.is_empty() {
+            return resp_invalid_arguments();
+        }
+        Frame::Verbatim(format!("{}\n", cur_client.lock().await))
+    }
+
+    /// ```text
+    /// CLIENT LIST [ID client-id [client-id ...]]
+    /// ```
+    async fn list(&self, clients: Clients) -> Frame {
+        if self.args.is_empty() {
+            return Frame::Verbatim(
+                encode_clients_info(clients.lock().await.clone().into_values().collect()).await,
+            );
+        }
+
+        match self.args[0].to_uppercase().as_str() {
+            "ID" => {
+                let mut match_clients = vec![];
+                for arg in &self.args[1..] {
+                    match arg.parse::<u64>() {
+                        Ok(client_id) => {
+                            if let Some(client) = clients.lock().await.get(&client_id) {
+                                match_clients.push(client.clone());
+                            }
+                        }
+                        Err(_) => return resp_err(REDIS_INVALID_CLIENT_ID_ERR),
+                    }
+                }
+
+                Frame::Verbatim(encode_clients_info(match_clients).await)
+            }
+            _ => resp_err(REDIS_NOT_SUPPORTED_ERR),
+        }
+    }
+
+    /// ```text
+    /// CLIENT KILL ip:port
+    /// CLIENT KILL <ID client-id | ADDR ip:port | LADDR ip:port | SKIPME yes/no> [...]
+    /// ```
+    async fn kill(&self, cur_client: Arc<Mutex<ClientInfo>>, clients: Clients) -> Frame {
+        if self.args.is_empty() {
+            return resp_invalid_arguments();
+        }
+
+        // two arguments format (old format)
+        if self.args.len() == 1 {
+            let mut target_client = None;
+            {
+                let lk_clients = clients.lock().await;
+                for client in lk_clients.values() {
+                    let lk_client = client.lock().await;
+                    if lk_client.peer_addr() == self.args[0] {
+                        target_client = Some(client.clone());
+                        break;
+                    }
+                }
+            }
+
+            return match target_client {
+                Some(client) => {
+                    client.lock().await.kill();
+                    resp_ok()
+                }
+                None => resp_err(REDIS_NO_SUCH_CLIENT_ERR),
+            };
+        }
+
+        // not match <filter> <value> format
+        if self.args.len() & 1 != 0 {
+            return resp_invalid_arguments();
+        }
+
+        let mut filter_peer_addr = "".to_owned();
+        let mut filter_local_addr = "".to_owned();
+        let mut filter_id = 0;
+        // skipme is set to yes by default in redis
+        let mut filter_skipme = true;
+
+        for pair in self.args.chunks(2) {
+            let value = pair[1].clone();
+            match pair[0].to_uppercase().as_str() {
+                "ID" => match value.parse::<u64>() {
+                    Ok(client_id) => filter_id = client_id,
+                    // not REDIS_INVALID_CLIENT_ID_ERR, to be compatible with redis
+                    Err(_) => return resp_err(REDIS_VALUE_IS_NOT_INTEGER_ERR),
+                },
+                "ADDR" => filter_peer_addr = value,
+                "LADDR" => filter_local_addr = value,
+                "SKIPME" => match value.to_uppercase().as_str() {
+                    "YES" => filter_skipme = true,
+                    "NO" => filter_skipme = false,
+                    _ => return resp_invalid_arguments(),
+                },
+                _ => return resp_err(REDIS_NOT_SUPPORTED_ERR),
+            }
+        }
+
+        // retrieve current client id in advance for preventing dead lock during clients traverse
+        let cur_client_id = cur_client.lock().await.id();
+        let mut eligible_clients: Vec<Arc<Mutex<ClientInfo>>> = vec![];
+        {
+            let lk_clients = clients.lock().await;
+            for client in lk_clients.values() {
+                let lk_client = client.lock().await;
+                if !filter_peer_addr.is_empty() && lk_client.peer_addr() != filter_peer_addr {
+                    continue;
+                }
+                if !filter_local_addr.is_empty() && lk_client.local_addr() != filter_local_addr {
+                    continue;
+                }
+                if filter_id != 0 && lk_client.id() != filter_id {
+                    continue;
+                }
+                if cur_client_id == lk_client.id() && filter_skipme {
+                    continue;
+                }
+
+                eligible_clients.push(client.clone());
+            }
+        }
+
+        let killed = eligible_clients.len() as i64;
+        for eligible_client in eligible_clients {
+            eligible_client.lock().await.kill();
+        }
+
+        resp_int(killed)
+    }
+
+    /// ```text
+    /// CLIENT SETNAME connection-name
+    /// ```
+    ///
+    /// An empty name removes the name of the connection.
+    async fn setname(&self, cur_client: Arc<Mutex<ClientInfo>>) -> Frame {
+        if self.args.len() != 1 {
+            return resp_invalid_arguments();
+        }
+        if !valid_client_name(&self.args[0]) {
+            return resp_err(REDIS_CLIENT_NAME_INVALID_ERR);
+        }
+
+        cur_client.lock().await.set_name(&self.args[0]);
+        resp_ok()
+    }
+
+    /// The name of the connection, nil if no name is set as redis does.
+    async fn getname(&self, cur_client: Arc<Mutex<ClientInfo>>) -> Frame {
+        if !self.args.is_empty() {
+            return resp_invalid_arguments();
+        }
+        let name = cur_client.lock().await.name().to_owned();
+        if name.is_empty() {
+            return resp_nil();
+        }
+
+        resp_bulk(name.into_bytes())
+    }
+}
+
+/// Client names are listed in the `field=value` lines of CLIENT LIST, spaces,
+/// newlines and other characters out of the printable ascii range are not
+/// allowed.
+pub(crate) fn valid_client_name(name: &str) -> bool {
+    name.bytes().all(|c| (b'!'..=b'~').contains(&c))
+}
+
+#[inline]
+async fn encode_clients_info(clients: Vec<Arc<Mutex<ClientInfo>>>) -> String {
+    let mut resp_list = String::new();
+    for client in clients {
+        let r_client = client.lock().await;
+        resp_list.push_str(&r_client.to_string());
+        resp_list.push('\n');
+    }
+
+    resp_list
+}
+
+impl Invalid for Client {
+    fn new_invalid() -> Client {
+        Client {
+            subcommand: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
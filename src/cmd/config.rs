@@ -0,0 +1,330 @@
+use slog::debug;
+
+use crate::cmd::Invalid;
+use crate::config::*;
+use crate::tikv::errors::{RTError, REDIS_UNKNOWN_SUBCOMMAND};
+use crate::utils::{glob_match, resp_bulk, resp_err, resp_invalid_arguments, resp_ok};
+use crate::{Connection, Frame, Parse};
+
+/// The parameters reported by `CONFIG GET`, named after the helpers serving
+/// them. Passwords are never reported.
+static CONFIG_PARAMS: &[(&str, fn() -> String)] = &[
+    ("listen", config_listen_or_default),
+    ("port", config_port_or_default),
+    ("tls_listen", config_tls_listen_or_default),
+    ("tls_port", config_tls_port_or_default),
+    ("tls_cert_file", config_tls_cert_file_or_default),
+    ("tls_key_file", config_tls_key_file_or_default),
+    ("tls_auth_client", || {
+        config_tls_auth_client_or_default().to_string()
+    }),
+    ("tls_ca_cert_file", config_tls_ca_cert_file_or_default),
+    ("unixsocket", config_unixsocket_or_default),
+    ("pd_addrs", config_pd_addrs_or_default),
+    ("instance_id", config_instance_id_or_default),
+    ("prometheus_listen", config_prometheus_listen_or_default),
+    ("prometheus_port", config_prometheus_port_or_default),
+    ("log_level", log_level_str),
+    ("log_file", log_file),
+    (
+        "cluster_broadcast_addr",
+        config_cluster_broadcast_addr_or_default,
+    ),
+    ("cluster_topology_interval", || {
+        config_cluster_topology_interval_or_default().to_string()
+    }),
+    ("cluster_topology_expire", || {
+        config_cluster_topology_expire_or_default().to_string()
+    }),
+    ("meta_key_number", || {
+        config_meta_key_number_or_default().to_string()
+    }),
+    ("key_prefix", config_key_prefix_or_default),
+    ("debug_raw_enabled", || {
+        debug_raw_enabled_or_default().to_string()
+    }),
+    ("cmd_size_metrics_enabled", || {
+        cmd_size_metrics_enabled_or_default().to_string()
+    }),
+    ("max_subscriptions_per_client", || {
+        max_subscriptions_per_client_or_default().to_string()
+    }),
+    ("notify_keyspace_events", notify_keyspace_events_or_default),
+    ("slowlog_log_slower_than", || {
+        slowlog_log_slower_than_or_default().to_string()
+    }),
+    ("slowlog_max_len", || {
+        slowlog_max_len_or_default().to_string()
+    }),
+    ("max_inflight_cmds", || {
+        max_inflight_cmds_or_default().to_string()
+    }),
+    ("proto_max_bulk_len", || {
+        proto_max_bulk_len_or_default().to_string()
+    }),
+    ("proto_max_multibulk_len", || {
+        proto_max_multibulk_len_or_default().to_string()
+    }),
+    ("backend_timeout", || {
+        backend_timeout_or_default().to_string()
+    }),
+    ("backend_ca_file", backend_ca_file_or_default),
+    ("backend_cert_file", backend_cert_file_or_default),
+    ("backend_key_file", backend_key_file_or_default),
+    ("backend_health_check_interval", || {
+        backend_health_check_interval_or_default().to_string()
+    }),
+    ("conn_concurrency", || {
+        conn_concurrency_or_default().to_string()
+    }),
+    ("conn_concurrency_max", || {
+        conn_concurrency_max_or_default().to_string()
+    }),
+    ("conn_inflight_threshold", || {
+        conn_inflight_threshold_or_default().to_string()
+    }),
+    ("use_txn_api", || is_use_txn_api().to_string()),
+    ("use_async_commit", || is_use_async_commit().to_string()),
+    ("try_one_pc_commit", || is_try_one_pc_commit().to_string()),
+    ("use_pessimistic_txn", || {
+        is_use_pessimistic_txn().to_string()
+    }),
+    ("local_pool_number", || {
+        config_local_pool_number().to_string()
+    }),
+    ("backend_completion_queue_size", || {
+        backend_completion_queue_size_or_default().to_string()
+    }),
+    ("backend_grpc_keepalive_time", || {
+        backend_grpc_keepalive_time_or_default().to_string()
+    }),
+    ("backend_grpc_keepalive_timeout", || {
+        backend_grpc_keepalive_timeout_or_default().to_string()
+    }),
+    ("backend_allow_batch", || {
+        backend_allow_batch_or_default().to_string()
+    }),
+    ("backend_overload_threshold", || {
+        backend_overload_threshold_or_default().to_string()
+    }),
+    ("backend_max_batch_wait_time", || {
+        backend_max_batch_wait_time_or_default().to_string()
+    }),
+    ("backend_max_batch_size", || {
+        backend_max_batch_size_or_default().to_string()
+    }),
+    ("backend_max_inflight_requests", || {
+        backend_max_inflight_requests_or_default().to_string()
+    }),
+    ("txn_retry_count", || txn_retry_count().to_string()),
+    ("txn_region_backoff_delay_ms", || {
+        txn_region_backoff_delay_ms().to_string()
+    }),
+    ("txn_region_backoff_delay_attemps", || {
+        txn_region_backoff_delay_attemps().to_string()
+    }),
+    ("txn_lock_backoff_delay_ms", || {
+        txn_lock_backoff_delay_ms().to_string()
+    }),
+    ("txn_lock_backoff_delay_attemps", || {
+        txn_lock_backoff_delay_attemps().to_string()
+    }),
+    ("cmd_lrem_length_limit", || {
+        cmd_lrem_length_limit_or_default().to_string()
+    }),
+    ("cmd_linsert_length_limit", || {
+        cmd_linsert_length_limit_or_default().to_string()
+    }),
+    ("cmd_scan_count_limit", || {
+        cmd_scan_count_limit_or_default().to_string()
+    }),
+    ("async_deletion_enabled", || {
+        async_deletion_enabled_or_default().to_string()
+    }),
+    ("async_gc_worker_number", || {
+        async_gc_worker_number_or_default().to_string()
+    }),
+    ("async_gc_worker_queue_size", || {
+        async_gc_worker_queue_size_or_default().to_string()
+    }),
+    ("async_gc_interval", || {
+        async_gc_interval_or_default().to_string()
+    }),
+    ("async_del_list_threshold", || {
+        async_del_list_threshold_or_default().to_string()
+    }),
+    ("async_del_hash_threshold", || {
+        async_del_hash_threshold_or_default().to_string()
+    }),
+    ("async_del_set_threshold", || {
+        async_del_set_threshold_or_default().to_string()
+    }),
+    ("async_del_zset_threshold", || {
+        async_del_zset_threshold_or_default().to_string()
+    }),
+    ("async_expire_list_threshold", || {
+        async_expire_list_threshold_or_default().to_string()
+    }),
+    ("async_expire_hash_threshold", || {
+        async_expire_hash_threshold_or_default().to_string()
+    }),
+    ("async_expire_set_threshold", || {
+        async_expire_set_threshold_or_default().to_string()
+    }),
+    ("async_expire_zset_threshold", || {
+        async_expire_zset_threshold_or_default().to_string()
+    }),
+    ("lazyfree_lazy_server_del", || {
+        lazyfree_lazy_server_del_or_default().to_string()
+    }),
+    ("bulk_load_batch_size", || {
+        bulk_load_batch_size_or_default().to_string()
+    }),
+    ("access_time_sample_rate", || {
+        access_time_sample_rate_or_default().to_string()
+    }),
+    // the timeout of the commands without an override
+    ("cmd_timeout_ms", || {
+        cmd_timeout_ms_or_default("").to_string()
+    }),
+    ("negative_cache_size", || {
+        negative_cache_size_or_default().to_string()
+    }),
+    ("negative_cache_ttl_ms", || {
+        negative_cache_ttl_ms_or_default().to_string()
+    }),
+    ("ttl_sample_interval", || {
+        ttl_sample_interval_or_default().to_string()
+    }),
+    ("ttl_sample_size", || {
+        ttl_sample_size_or_default().to_string()
+    }),
+    ("active_expire_interval", || {
+        active_expire_interval_or_default().to_string()
+    }),
+    ("active_expire_scan_size", || {
+        active_expire_scan_size_or_default().to_string()
+    }),
+    ("active_expire_batch_size", || {
+        active_expire_batch_size_or_default().to_string()
+    }),
+];
+
+/// Inspect and tune the configuration of the server.
+///
+/// ```text
+/// CONFIG GET pattern [pattern ...]
+/// CONFIG SET parameter value [parameter value ...]
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    subcommand: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Config {
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Config> {
+        let subcommand = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        Ok(Config {
+            subcommand,
+            args,
+            valid: true,
+        })
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.config();
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    fn config(&self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match self.subcommand.to_uppercase().as_str() {
+            "GET" if !self.args.is_empty() => self.get(),
+            "SET" if !self.args.is_empty() && self.args.len() % 2 == 0 => self.set(),
+            _ => resp_err(REDIS_UNKNOWN_SUBCOMMAND),
+        }
+    }
+
+    /// The name and value of the parameters matching any of the glob-style
+    /// patterns, case insensitively as in redis. The parameters matching none
+    /// are left out, an unknown parameter is not an error.
+    fn get(&self) -> Frame {
+        let patterns: Vec<String> = self.args.iter().map(|p| param_name(p)).collect();
+        let pairs = CONFIG_PARAMS
+            .iter()
+            .filter(|(name, _)| {
+                patterns
+                    .iter()
+                    .any(|pattern| glob_match(pattern.as_bytes(), name.as_bytes()))
+            })
+            .map(|(name, value)| {
+                (
+                    resp_bulk(name.as_bytes().to_vec()),
+                    resp_bulk(value().into_bytes()),
+                )
+            })
+            .collect();
+        Frame::Map(pairs)
+    }
+
+    /// Override the parameters tunable at runtime, the overrides last until
+    /// the server restarts. Either all the parameters are set or none.
+    fn set(&self) -> Frame {
+        let params: Vec<(String, String)> = self
+            .args
+            .chunks(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect();
+        for (name, _) in &params {
+            let normalized = param_name(name);
+            if !CONFIG_PARAMS.iter().any(|(n, _)| *n == normalized) {
+                return resp_err(RTError::Owned(format!(
+                    "ERR Unknown option or number of arguments for CONFIG SET - '{}'",
+                    name
+                )));
+            }
+        }
+        match set_runtime_params(&params) {
+            Ok(()) => resp_ok(),
+            Err((name, reason)) => resp_err(RTError::Owned(format!(
+                "ERR CONFIG SET failed (possibly related to argument '{}') - {}",
+                name, reason
+            ))),
+        }
+    }
+}
+
+/// Parameters are matched case insensitively, with dashes standing for
+/// underscores as in the redis names, e.g. `slowlog-max-len`.
+fn param_name(name: &str) -> String {
+    name.to_lowercase().replace('-', "_")
+}
+
+impl Invalid for Config {
+    fn new_invalid() -> Config {
+        Config {
+            subcommand: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, REDIS_DB_INDEX_OUT_OF_RANGE_ERR, REDIS_NOT_SUPPORTED_ERR, REDIS_SAME_OBJECT_ERR,
+    REDIS_SYNTAX_ERR,
+};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Copy the value of a key to another key, whatever the type.
+///
+/// ```text
+/// COPY source destination [DB destination-db] [REPLACE]
+/// ```
+///
+/// The ttl is copied along with the value. 0 is replied without changes if
+/// the destination exists, unless `REPLACE` is given, then the old value of
+/// the destination is removed first. Only db 0 exists.
+#[derive(Debug, Clone)]
+pub struct CopyKey {
+    src: String,
+    dst: String,
+    replace: bool,
+    // None if the db option is invalid
+    db: Option<i64>,
+    syntax_ok: bool,
+    valid: bool,
+}
+
+impl CopyKey {
+    pub fn new(src: &str, dst: &str) -> CopyKey {
+        CopyKey {
+            src: src.to_owned(),
+            dst: dst.to_owned(),
+            replace: false,
+            db: Some(0),
+            syntax_ok: true,
+            valid: true,
+        }
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        vec![self.src.clone(), self.dst.clone()]
+    }
+
+    fn parse_options(&mut self, options: &[String]) {
+        let mut idx = 0;
+        while idx < options.len() {
+            match options[idx].to_uppercase().as_str() {
+                "REPLACE" => self.replace = true,
+                "DB" if idx + 1 < options.len() => {
+                    idx += 1;
+                    self.db = options[idx].parse::<i64>().ok();
+                }
+                _ => {
+                    self.syntax_ok = false;
+                    return;
+                }
+            }
+            idx += 1;
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<CopyKey> {
+        let src = parse.next_string()?;
+        let dst = parse.next_string()?;
+        let mut options = vec![];
+        while let Ok(option) = parse.next_string() {
+            options.push(option);
+        }
+
+        let mut cmd = CopyKey::new(&src, &dst);
+        cmd.parse_options(&options);
+        Ok(cmd)
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<CopyKey> {
+        if argv.len() < 2 {
+            return Ok(CopyKey::new_invalid());
+        }
+        let options: Vec<String> = argv[2..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+
+        let mut cmd = CopyKey::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+        );
+        cmd.parse_options(&options);
+        Ok(cmd)
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.copy(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn copy(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if !self.syntax_ok {
+            return Ok(resp_err(REDIS_SYNTAX_ERR));
+        }
+        if self.db != Some(0) {
+            return Ok(resp_err(REDIS_DB_INDEX_OUT_OF_RANGE_ERR));
+        }
+        if self.src == self.dst {
+            return Ok(resp_err(REDIS_SAME_OBJECT_ERR));
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_copy(&self.src, &self.dst, self.replace)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for CopyKey {
+    fn new_invalid() -> CopyKey {
+        CopyKey {
+            src: "".to_owned(),
+            dst: "".to_owned(),
+            replace: false,
+            db: Some(0),
+            syntax_ok: true,
+            valid: false,
+        }
+    }
+}
@@ -1,14 +1,43 @@
 use crate::cmd::Invalid;
 use crate::config::LOGGER;
-use crate::tikv::errors::REDIS_NOT_SUPPORTED_DEBUG_SUB_COMMAND_ERR;
-use crate::tikv::{start_profiler, stop_profiler};
-use crate::utils::{resp_err, resp_invalid_arguments, resp_ok};
-use crate::{Connection, Parse};
+use crate::config::{debug_raw_enabled_or_default, is_use_txn_api};
+use crate::expire::set_active_expire_enabled;
+use crate::metrics::{TIKV_BROKEN_CLIENT_COUNTER, TIKV_CLIENT_RETRIES, TXN_RETRY_COUNTER};
+use crate::tikv::errors::{
+    AsyncResult, REDIS_DEBUG_RAW_DISABLED_ERR, REDIS_NOT_SUPPORTED_DEBUG_SUB_COMMAND_ERR,
+    REDIS_NOT_SUPPORTED_ERR, REDIS_NO_SUCH_KEY_ERR, REDIS_WRONG_PROTOCOL_TYPE_ERR,
+};
+use crate::tikv::string::StringCommandCtx;
+use crate::tikv::{
+    backend_stats, do_async_connect, start_profiler, stop_profiler, DEFAULT_PROFILER_FREQUENCY,
+    DEFAULT_PROFILER_PREFIX,
+};
+use crate::utils::{
+    resp_array, resp_bulk, resp_err, resp_int, resp_invalid_arguments, resp_nil, resp_ok,
+};
+use crate::{Connection, Frame, Parse};
 use slog::debug;
+use tokio::time::{self, Duration};
+
+/// Max entries of each encoded key range dumped by `DEBUG RAW`
+const DEBUG_RAW_LIMIT: u32 = 1000;
 
 #[derive(Debug, Clone)]
 pub struct Debug {
     subcommand: String,
+    key: Option<String>,
+    // seconds to sleep of DEBUG SLEEP
+    sleep: f64,
+    // flag of DEBUG SET-ACTIVE-EXPIRE
+    active_expire: bool,
+    // samples per second of DEBUG PROFILER_START
+    profiler_frequency: i32,
+    // prefix of the files written by DEBUG PROFILER_STOP
+    profiler_prefix: String,
+    // PD addresses of DEBUG RECONNECT
+    pd_addrs: Vec<String>,
+    // reply type of DEBUG PROTOCOL
+    protocol: String,
     valid: bool,
 }
 
@@ -16,14 +45,74 @@ impl Debug {
     pub fn new(subcommand: impl ToString) -> Debug {
         Debug {
             subcommand: subcommand.to_string(),
+            key: None,
+            sleep: 0.0,
+            active_expire: true,
+            profiler_frequency: DEFAULT_PROFILER_FREQUENCY,
+            profiler_prefix: DEFAULT_PROFILER_PREFIX.to_owned(),
+            pd_addrs: vec![],
+            protocol: "".to_owned(),
             valid: true,
         }
     }
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Debug> {
         let subcommand = parse.next_string()?;
+        let mut debug = Debug::new(&subcommand);
+        if matches!(
+            subcommand.to_lowercase().as_str(),
+            "object" | "raw" | "repair_meta"
+        ) {
+            debug.key = Some(parse.next_string()?);
+        } else if subcommand.eq_ignore_ascii_case("sleep") {
+            match parse.next_string()?.parse::<f64>() {
+                Ok(secs) if secs.is_finite() && secs >= 0.0 => debug.sleep = secs,
+                _ => return Ok(Debug::new_invalid()),
+            }
+        } else if subcommand.eq_ignore_ascii_case("set-active-expire") {
+            match parse.next_string()?.as_str() {
+                "0" => debug.active_expire = false,
+                "1" => debug.active_expire = true,
+                _ => return Ok(Debug::new_invalid()),
+            }
+        } else if subcommand.eq_ignore_ascii_case("profiler_start") {
+            if let Ok(frequency) = parse.next_string() {
+                match frequency.parse::<i32>() {
+                    Ok(frequency) if frequency > 0 => debug.profiler_frequency = frequency,
+                    _ => return Ok(Debug::new_invalid()),
+                }
+            }
+        } else if subcommand.eq_ignore_ascii_case("profiler_stop") {
+            if let Ok(prefix) = parse.next_string() {
+                if prefix.is_empty() {
+                    return Ok(Debug::new_invalid());
+                }
+                debug.profiler_prefix = prefix;
+            }
+        } else if subcommand.eq_ignore_ascii_case("reconnect") {
+            // the addresses are separated by spaces or commas as in the config
+            while let Ok(addrs) = parse.next_string() {
+                debug.pd_addrs.extend(
+                    addrs
+                        .split(',')
+                        .filter(|addr| !addr.is_empty())
+                        .map(str::to_owned),
+                );
+            }
+            if debug.pd_addrs.is_empty() {
+                return Ok(Debug::new_invalid());
+            }
+        } else if subcommand.eq_ignore_ascii_case("protocol") {
+            debug.protocol = parse.next_string()?.to_lowercase();
+        }
+
+        Ok(debug)
+    }
 
-        Ok(Debug::new(subcommand))
+    /// `DEBUG SLEEP` only waits, it takes no resources from the other
+    /// connections.
+    pub(crate) fn is_sleep(&self) -> bool {
+        self.valid && self.subcommand.eq_ignore_ascii_case("sleep")
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -33,14 +122,37 @@ impl Debug {
         }
 
         let response = match self.subcommand.to_lowercase().as_str() {
-            "profiler_start" => {
-                start_profiler();
+            "profiler_start" => match start_profiler(self.profiler_frequency) {
+                Ok(()) => resp_ok(),
+                Err(e) => resp_err(e),
+            },
+            "profiler_stop" => match stop_profiler(&self.profiler_prefix) {
+                Ok(paths) => resp_array(
+                    paths
+                        .into_iter()
+                        .map(|path| resp_bulk(path.into_bytes()))
+                        .collect(),
+                ),
+                Err(e) => resp_err(e),
+            },
+            "object" => self.debug_object().await.unwrap_or_else(Into::into),
+            "raw" => self.debug_raw().await.unwrap_or_else(Into::into),
+            "repair_meta" => self.debug_repair_meta().await.unwrap_or_else(Into::into),
+            "backend" => self.debug_backend(),
+            "reconnect" => match do_async_connect(self.pd_addrs.clone()).await {
+                Ok(()) => resp_ok(),
+                Err(e) => resp_err(e),
+            },
+            "sleep" => {
+                // only the connection sleeps, the worker keeps serving others
+                time::sleep(Duration::from_secs_f64(self.sleep)).await;
                 resp_ok()
             }
-            "profiler_stop" => {
-                stop_profiler();
+            "set-active-expire" => {
+                set_active_expire_enabled(self.active_expire);
                 resp_ok()
             }
+            "protocol" => self.debug_protocol(),
             _ => resp_err(REDIS_NOT_SUPPORTED_DEBUG_SUB_COMMAND_ERR),
         };
 
@@ -56,12 +168,155 @@ impl Debug {
 
         Ok(())
     }
+
+    async fn debug_object(&self) -> AsyncResult<Frame> {
+        if !is_use_txn_api() {
+            return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
+        }
+        let key = self.key.as_ref().unwrap();
+        match StringCommandCtx::new(None)
+            .do_async_txnkv_debug_object(key)
+            .await?
+        {
+            Frame::Null => Ok(resp_err(REDIS_NO_SUCH_KEY_ERR)),
+            line => Ok(line),
+        }
+    }
+
+    fn debug_backend(&self) -> Frame {
+        let stats = backend_stats();
+        let field = |name: &str| resp_bulk(name.as_bytes().to_vec());
+        Frame::Map(vec![
+            (
+                field("pd_addrs"),
+                resp_array(
+                    stats
+                        .pd_addrs
+                        .into_iter()
+                        .map(|addr| resp_bulk(addr.into_bytes()))
+                        .collect(),
+                ),
+            ),
+            (
+                field("txn_client_pool_size"),
+                resp_int(stats.txn_client_pool_size as i64),
+            ),
+            (
+                field("txn_client_index"),
+                resp_int(stats.txn_client_index as i64),
+            ),
+            (
+                field("broken_txn_clients"),
+                resp_array(
+                    stats
+                        .broken_txn_clients
+                        .into_iter()
+                        .map(|idx| resp_int(idx as i64))
+                        .collect(),
+                ),
+            ),
+            (
+                field("unhealthy_txn_clients"),
+                resp_array(
+                    stats
+                        .unhealthy_txn_clients
+                        .into_iter()
+                        .map(|idx| resp_int(idx as i64))
+                        .collect(),
+                ),
+            ),
+            (
+                field("txn_client_inflight"),
+                resp_array(
+                    stats
+                        .txn_client_inflight
+                        .into_iter()
+                        .map(resp_int)
+                        .collect(),
+                ),
+            ),
+            (
+                field("tikv_client_retries"),
+                resp_int(TIKV_CLIENT_RETRIES.get()),
+            ),
+            (
+                field("txn_retries"),
+                resp_int(TXN_RETRY_COUNTER.get() as i64),
+            ),
+            (
+                field("broken_client_total"),
+                resp_int(TIKV_BROKEN_CLIENT_COUNTER.get() as i64),
+            ),
+        ])
+    }
+
+    /// A sample reply of the given type, as redis replies it, so that clients
+    /// can test their RESP3 parsing.
+    fn debug_protocol(&self) -> Frame {
+        let int_array = || (0..3).map(resp_int).collect::<Vec<_>>();
+        match self.protocol.as_str() {
+            "string" => resp_bulk(b"Hello World".to_vec()),
+            "integer" => resp_int(12345),
+            "double" => Frame::Double(3.141),
+            "bignum" => Frame::BigNumber("1234567999999999999999999999999999999".to_owned()),
+            "null" => resp_nil(),
+            "array" => resp_array(int_array()),
+            "set" => Frame::Set(int_array()),
+            "map" => Frame::Map(
+                (0..3)
+                    .map(|i| (resp_int(i), Frame::Boolean(i == 1)))
+                    .collect(),
+            ),
+            "verbatim" => Frame::Verbatim("This is a verbatim\nstring".to_owned()),
+            "true" => Frame::Boolean(true),
+            "false" => Frame::Boolean(false),
+            _ => resp_err(REDIS_WRONG_PROTOCOL_TYPE_ERR),
+        }
+    }
+
+    async fn debug_repair_meta(&self) -> AsyncResult<Frame> {
+        if !is_use_txn_api() {
+            return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
+        }
+        let key = self.key.as_ref().unwrap();
+        match StringCommandCtx::new(None)
+            .do_async_txnkv_repair_meta(key)
+            .await?
+        {
+            Frame::Null => Ok(resp_err(REDIS_NO_SUCH_KEY_ERR)),
+            frame => Ok(frame),
+        }
+    }
+
+    async fn debug_raw(&self) -> AsyncResult<Frame> {
+        if !debug_raw_enabled_or_default() {
+            return Ok(resp_err(REDIS_DEBUG_RAW_DISABLED_ERR));
+        }
+        if !is_use_txn_api() {
+            return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
+        }
+        let key = self.key.as_ref().unwrap();
+        match StringCommandCtx::new(None)
+            .do_async_txnkv_raw(key, DEBUG_RAW_LIMIT)
+            .await?
+        {
+            Frame::Null => Ok(resp_err(REDIS_NO_SUCH_KEY_ERR)),
+            frame => Ok(frame),
+        }
+    }
 }
 
 impl Invalid for Debug {
     fn new_invalid() -> Debug {
         Debug {
             subcommand: "".to_owned(),
+            key: None,
+            sleep: 0.0,
+            active_expire: true,
+            profiler_frequency: DEFAULT_PROFILER_FREQUENCY,
+            profiler_prefix: DEFAULT_PROFILER_PREFIX.to_owned(),
+            pd_addrs: vec![],
+            protocol: "".to_owned(),
             valid: false,
         }
     }
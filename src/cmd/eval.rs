@@ -78,8 +78,9 @@ impl Eval {
         is_sha: bool,
         db: &Db,
         lua: &Option<Lua>,
+        user: &str,
     ) -> crate::Result<()> {
-        let response = self.eval(is_sha, db, lua).await?;
+        let response = self.eval(is_sha, db, lua, user).await?;
 
         debug!(
             LOGGER,
@@ -94,7 +95,13 @@ impl Eval {
         Ok(())
     }
 
-    async fn eval(&self, is_sha: bool, db: &Db, lua: &Option<Lua>) -> AsyncResult<Frame> {
+    async fn eval(
+        &self,
+        is_sha: bool,
+        db: &Db,
+        lua: &Option<Lua>,
+        user: &str,
+    ) -> AsyncResult<Frame> {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
@@ -108,7 +115,7 @@ impl Eval {
         let txn = client.begin().await?;
         let txn_rc = Arc::new(Mutex::new(txn));
 
-        let ctx = LuaCommandCtx::new(Some(txn_rc.clone()), lua);
+        let ctx = LuaCommandCtx::new(Some(txn_rc.clone()), lua, user);
 
         let resp = if is_sha {
             ctx.do_async_evalsha(&self.script, db, &self.keys, &self.args)
@@ -5,7 +5,10 @@ use crate::config::is_use_txn_api;
 use crate::config::LOGGER;
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::string::StringCommandCtx;
-use crate::utils::{resp_err, resp_invalid_arguments, timestamp_from_ttl};
+use crate::utils::{
+    expire_timestamp, now_timestamp_in_millis, resp_err, resp_invalid_arguments,
+    resp_invalid_expire_time,
+};
 use crate::{Connection, Frame, Parse};
 use bytes::Bytes;
 use slog::debug;
@@ -91,16 +94,27 @@ impl Expire {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
-        let mut ttl = self.seconds as u64;
         if is_use_txn_api() {
-            if !is_millis {
-                ttl *= 1000;
-            }
-            if !expire_at {
-                ttl = timestamp_from_ttl(ttl);
+            let timestamp = match expire_timestamp(self.seconds, is_millis, expire_at) {
+                Some(timestamp) => timestamp,
+                None => {
+                    let cmd = match (is_millis, expire_at) {
+                        (false, false) => "expire",
+                        (false, true) => "expireat",
+                        (true, false) => "pexpire",
+                        (true, true) => "pexpireat",
+                    };
+                    return Ok(resp_invalid_expire_time(cmd));
+                }
+            };
+            if timestamp <= now_timestamp_in_millis() as i64 {
+                // an expire time in the past deletes the key right away
+                return StringCommandCtx::new(txn)
+                    .do_async_txnkv_del(&vec![self.key])
+                    .await;
             }
             StringCommandCtx::new(txn)
-                .do_async_txnkv_expire(&self.key, ttl)
+                .do_async_txnkv_expire(&self.key, timestamp as u64)
                 .await
         } else {
             Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
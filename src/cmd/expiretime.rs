@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get the absolute unix time a key expires at, also executed as PEXPIRETIME
+/// in milliseconds.
+///
+/// ```text
+/// EXPIRETIME key
+/// ```
+///
+/// -1 is replied if the key has no ttl, -2 if it does not exist.
+#[derive(Debug, Clone)]
+pub struct Expiretime {
+    key: String,
+    valid: bool,
+}
+
+impl Expiretime {
+    pub fn new(key: &str) -> Expiretime {
+        Expiretime {
+            key: key.to_owned(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Expiretime> {
+        let key = parse.next_string()?;
+
+        Ok(Expiretime::new(&key))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Expiretime> {
+        if argv.len() != 1 {
+            return Ok(Expiretime::new_invalid());
+        }
+        Ok(Expiretime::new(&String::from_utf8_lossy(&argv[0])))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection, is_millis: bool) -> crate::Result<()> {
+        let response = self
+            .expiretime(is_millis, None)
+            .await
+            .unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn expiretime(
+        &self,
+        is_millis: bool,
+        txn: Option<Arc<Mutex<Transaction>>>,
+    ) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_expiretime(&self.key, is_millis)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Expiretime {
+    fn new_invalid() -> Expiretime {
+        Expiretime {
+            key: "".to_owned(),
+            valid: false,
+        }
+    }
+}
@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use crate::config::LOGGER;
 use crate::tikv::errors::AsyncResult;
+use crate::tikv::negative_cache::{NegativeCache, NEGATIVE_CACHE};
 use crate::tikv::string::StringCommandCtx;
-use crate::utils::resp_invalid_arguments;
+use crate::utils::{resp_invalid_arguments, resp_nil};
 use crate::{Connection, Frame, Parse};
 use bytes::Bytes;
 use slog::debug;
@@ -103,6 +104,22 @@ impl Get {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
+        // reads in a transaction must see its own writes, skip the cache
+        if txn.is_none() && NegativeCache::enabled() {
+            if NEGATIVE_CACHE.contains(&self.key) {
+                return Ok(resp_nil());
+            }
+            let epoch = NEGATIVE_CACHE.epoch();
+            let resp = self.get_from_backend(None).await?;
+            if let Frame::Null = resp {
+                NEGATIVE_CACHE.insert(&self.key, epoch);
+            }
+            return Ok(resp);
+        }
+        self.get_from_backend(txn).await
+    }
+
+    async fn get_from_backend(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
         if is_use_txn_api() {
             StringCommandCtx::new(txn)
                 .do_async_txnkv_get(&self.key)
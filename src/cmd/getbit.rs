@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, RTError, REDIS_BIT_OFFSET_ERR, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Largest bit offset of a string plus one, values are limited to 512MB
+pub(crate) const BIT_OFFSET_LIMIT: u64 = 1 << 32;
+
+/// Parse a bit offset, it must be less than 2^32.
+pub(crate) fn parse_bit_offset(offset: &str) -> Result<u64, RTError> {
+    match offset.parse::<u64>() {
+        Ok(offset) if offset < BIT_OFFSET_LIMIT => Ok(offset),
+        _ => Err(REDIS_BIT_OFFSET_ERR),
+    }
+}
+
+/// Get the bit at an offset of a string.
+///
+/// ```text
+/// GETBIT key offset
+/// ```
+///
+/// 0 is replied if the offset is beyond the string or the key is missing.
+#[derive(Debug, Clone)]
+pub struct Getbit {
+    key: String,
+    offset: String,
+    valid: bool,
+}
+
+impl Getbit {
+    pub fn new(key: &str, offset: &str) -> Getbit {
+        Getbit {
+            key: key.to_owned(),
+            offset: offset.to_owned(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Getbit> {
+        let key = parse.next_string()?;
+        let offset = parse.next_string()?;
+
+        Ok(Getbit::new(&key, &offset))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Getbit> {
+        if argv.len() != 2 {
+            return Ok(Getbit::new_invalid());
+        }
+        Ok(Getbit::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+        ))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.getbit(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getbit(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let offset = match parse_bit_offset(&self.offset) {
+            Ok(offset) => offset,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_getbit(&self.key, offset)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Getbit {
+    fn new_invalid() -> Getbit {
+        Getbit {
+            key: "".to_owned(),
+            offset: "".to_owned(),
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get the value of key and delete the key.
+///
+/// ```text
+/// GETDEL key
+/// ```
+///
+/// The read and the delete run in a single transaction, nil is replied if the
+/// key does not exist and an error if the value is not a string.
+#[derive(Debug, Clone)]
+pub struct GetDel {
+    key: String,
+    valid: bool,
+}
+
+impl GetDel {
+    pub fn new(key: &str) -> GetDel {
+        GetDel {
+            key: key.to_owned(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<GetDel> {
+        let key = parse.next_string()?;
+
+        Ok(GetDel { key, valid: true })
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<GetDel> {
+        if argv.len() != 1 {
+            return Ok(GetDel::new_invalid());
+        }
+        let key = &String::from_utf8_lossy(&argv[0]);
+        Ok(GetDel::new(key))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.getdel(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getdel(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_getdel(&self.key)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for GetDel {
+    fn new_invalid() -> GetDel {
+        GetDel {
+            key: "".to_owned(),
+            valid: false,
+        }
+    }
+}
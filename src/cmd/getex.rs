@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{
+    resp_err, resp_invalid_arguments, resp_invalid_expire_time, timestamp_from_relative_ttl,
+};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get the value of key and optionally update its expiration.
+///
+/// ```text
+/// GETEX key [EX seconds | PX milliseconds | EXAT unix-time-seconds |
+///     PXAT unix-time-milliseconds | PERSIST]
+/// ```
+///
+/// The read and the update of the expiration run in a single transaction. An
+/// EXAT or PXAT timestamp in the past deletes the key and replies nil.
+#[derive(Debug, Clone)]
+pub struct GetEx {
+    key: String,
+    /// `None` if the options are not valid
+    expiration: Option<Expiration>,
+    valid: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Expiration {
+    /// The ttl of the key is left untouched
+    Keep,
+    /// The ttl of the key is removed
+    Persist,
+    /// Relative time to live in milliseconds
+    After(i64),
+    /// Unix time in milliseconds
+    At(i64),
+}
+
+impl GetEx {
+    pub fn new(key: &str) -> GetEx {
+        GetEx {
+            key: key.to_owned(),
+            expiration: Some(Expiration::Keep),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<GetEx> {
+        let key = parse.next_string()?;
+        let mut options = vec![];
+        while let Ok(option) = parse.next_bytes() {
+            options.push(option);
+        }
+
+        Ok(GetEx::parse_options(&key, &options))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<GetEx> {
+        if argv.is_empty() {
+            return Ok(GetEx::new_invalid());
+        }
+        Ok(GetEx::parse_options(
+            &String::from_utf8_lossy(&argv[0]),
+            &argv[1..],
+        ))
+    }
+
+    /// Parse the options following the key, at most one of them is given.
+    fn parse_options(key: &str, options: &[Bytes]) -> GetEx {
+        let mut getex = GetEx::new(key);
+        getex.expiration = match options {
+            [] => Some(Expiration::Keep),
+            [flag] if flag.eq_ignore_ascii_case(b"persist") => Some(Expiration::Persist),
+            [flag, value] => {
+                let flag = String::from_utf8_lossy(flag).to_uppercase();
+                match String::from_utf8_lossy(value).parse::<i64>() {
+                    Ok(v) => match flag.as_str() {
+                        // an overflow saturates and is rejected as an invalid
+                        // expire time
+                        "EX" => Some(Expiration::After(v.saturating_mul(1000))),
+                        "PX" => Some(Expiration::After(v)),
+                        "EXAT" => Some(Expiration::At(v.checked_mul(1000).unwrap_or(-1))),
+                        "PXAT" => Some(Expiration::At(v)),
+                        _ => None,
+                    },
+                    Err(_) => None,
+                }
+            }
+            _ => None,
+        };
+        getex
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.getex(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getex(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        // the relative time is converted once, a retried transaction applies
+        // the same timestamp
+        let timestamp = match self.expiration {
+            None => return Ok(resp_err(REDIS_SYNTAX_ERR)),
+            Some(Expiration::Keep) => None,
+            Some(Expiration::Persist) => Some(0),
+            Some(Expiration::After(ttl)) => match timestamp_from_relative_ttl(ttl) {
+                Some(ts) => Some(ts),
+                None => return Ok(resp_invalid_expire_time("getex")),
+            },
+            Some(Expiration::At(ms)) if ms > 0 => Some(ms as u64),
+            Some(Expiration::At(_)) => return Ok(resp_invalid_expire_time("getex")),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_getex(&self.key, timestamp)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for GetEx {
+    fn new_invalid() -> GetEx {
+        GetEx {
+            key: "".to_owned(),
+            expiration: None,
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get a substring of a string, also executed as SUBSTR.
+///
+/// ```text
+/// GETRANGE key start end
+/// ```
+///
+/// Negative indexes count from the end, an out of range end is clamped to
+/// the last byte. An empty string is replied for a missing key.
+#[derive(Debug, Clone)]
+pub struct Getrange {
+    key: String,
+    start: String,
+    end: String,
+    valid: bool,
+}
+
+impl Getrange {
+    pub fn new(key: &str, start: &str, end: &str) -> Getrange {
+        Getrange {
+            key: key.to_owned(),
+            start: start.to_owned(),
+            end: end.to_owned(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Getrange> {
+        let key = parse.next_string()?;
+        let start = parse.next_string()?;
+        let end = parse.next_string()?;
+
+        Ok(Getrange::new(&key, &start, &end))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Getrange> {
+        if argv.len() != 3 {
+            return Ok(Getrange::new_invalid());
+        }
+        Ok(Getrange::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+            &String::from_utf8_lossy(&argv[2]),
+        ))
+    }
+
+    fn parse_range(&self) -> Result<(i64, i64), RTError> {
+        Ok((self.start.parse::<i64>()?, self.end.parse::<i64>()?))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.getrange(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn getrange(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (start, end) = match self.parse_range() {
+            Ok(range) => range,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_getrange(&self.key, start, end)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Getrange {
+    fn new_invalid() -> Getrange {
+        Getrange {
+            key: "".to_owned(),
+            start: "".to_owned(),
+            end: "".to_owned(),
+            valid: false,
+        }
+    }
+}
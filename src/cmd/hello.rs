@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use slog::debug;
+use tokio::sync::Mutex;
+
+use crate::client::Client;
+use crate::cmd::{valid_client_name, Invalid};
+use crate::config::LOGGER;
+use crate::tikv::errors::{
+    REDIS_CLIENT_NAME_INVALID_ERR, REDIS_HELLO_OPTION_NOT_SUPPORTED_ERR, REDIS_NOPROTO_ERR,
+};
+use crate::utils::{resp_array, resp_err, resp_int, resp_invalid_arguments};
+use crate::{Connection, Frame, Parse, ParseError};
+
+/// Switch the protocol of the connection and reply the server properties.
+///
+/// ```text
+/// HELLO [protover [AUTH username password] [SETNAME clientname]]
+/// ```
+///
+/// The connection speaks RESP2 until the peer negotiates RESP3 with `HELLO 3`.
+/// The credentials of `AUTH` are checked by the server before the command is
+/// applied, as for the `AUTH` command.
+#[derive(Debug, Clone)]
+pub struct Hello {
+    protover: Option<i64>,
+    auth: Option<(String, String)>,
+    setname: Option<String>,
+    /// an option not supported yet is given
+    unsupported: bool,
+    valid: bool,
+}
+
+impl Hello {
+    pub fn new(protover: Option<i64>) -> Hello {
+        Hello {
+            protover,
+            auth: None,
+            setname: None,
+            unsupported: false,
+            valid: true,
+        }
+    }
+
+    /// The username and password of the `AUTH` option.
+    pub fn auth(&self) -> Option<(&str, &str)> {
+        self.auth
+            .as_ref()
+            .map(|(username, passwd)| (username.as_str(), passwd.as_str()))
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hello> {
+        let protover = match parse.next_int() {
+            Ok(protover) => protover,
+            Err(ParseError::EndOfStream) => return Ok(Hello::new(None)),
+            Err(_) => return Ok(Hello::new_invalid()),
+        };
+        let mut hello = Hello::new(Some(protover));
+        loop {
+            match parse.next_string() {
+                Ok(opt) => match opt.to_lowercase().as_str() {
+                    "auth" => hello.auth = Some((parse.next_string()?, parse.next_string()?)),
+                    "setname" => hello.setname = Some(parse.next_string()?),
+                    _ => {
+                        hello.unsupported = true;
+                        return Ok(hello);
+                    }
+                },
+                Err(ParseError::EndOfStream) => return Ok(hello),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    pub(crate) async fn apply(
+        self,
+        dst: &mut Connection,
+        cur_client: Arc<Mutex<Client>>,
+    ) -> crate::Result<()> {
+        let response = self.hello(dst, cur_client).await;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    async fn hello(&self, dst: &mut Connection, cur_client: Arc<Mutex<Client>>) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        if self.unsupported {
+            return resp_err(REDIS_HELLO_OPTION_NOT_SUPPORTED_ERR);
+        }
+        if let Some(name) = &self.setname {
+            if !valid_client_name(name) {
+                return resp_err(REDIS_CLIENT_NAME_INVALID_ERR);
+            }
+        }
+        match self.protover {
+            Some(2) => dst.set_resp3(false),
+            Some(3) => dst.set_resp3(true),
+            Some(_) => return resp_err(REDIS_NOPROTO_ERR),
+            None => {}
+        }
+
+        let mut client = cur_client.lock().await;
+        if let Some(name) = &self.setname {
+            client.set_name(name);
+        }
+
+        let proto = if dst.is_resp3() { 3 } else { 2 };
+        Frame::Map(vec![
+            (bulk("server"), bulk("redis")),
+            (bulk("version"), bulk(env!("CARGO_PKG_VERSION"))),
+            (bulk("proto"), resp_int(proto)),
+            (bulk("id"), resp_int(client.id() as i64)),
+            (bulk("mode"), bulk("standalone")),
+            (bulk("role"), bulk("master")),
+            (bulk("modules"), resp_array(vec![])),
+        ])
+    }
+}
+
+fn bulk(s: &'static str) -> Frame {
+    Frame::Bulk(Bytes::from_static(s.as_bytes()))
+}
+
+impl Default for Hello {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Invalid for Hello {
+    fn new_invalid() -> Hello {
+        Hello {
+            protover: None,
+            auth: None,
+            setname: None,
+            unsupported: false,
+            valid: false,
+        }
+    }
+}
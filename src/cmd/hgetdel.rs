@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::hash::HashCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get and delete the fields of a hash, the key is deleted with its last field.
+///
+/// ```text
+/// HGETDEL key FIELDS numfields field [field ...]
+/// ```
+#[derive(Debug, Clone)]
+pub struct Hgetdel {
+    key: String,
+    fields: Vec<String>,
+    valid: bool,
+}
+
+impl Hgetdel {
+    pub fn new(key: &str, fields: Vec<String>) -> Hgetdel {
+        Hgetdel {
+            key: key.to_owned(),
+            fields,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hgetdel> {
+        let key = parse.next_string()?;
+        if parse.next_string()?.to_lowercase() != "fields" {
+            return Ok(Hgetdel::new_invalid());
+        }
+        let numfields = parse.next_int()?;
+        if numfields <= 0 {
+            return Ok(Hgetdel::new_invalid());
+        }
+        let mut fields = Vec::with_capacity(numfields as usize);
+        for _ in 0..numfields {
+            fields.push(parse.next_string()?);
+        }
+        Ok(Hgetdel::new(&key, fields))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Hgetdel> {
+        if argv.len() < 4 || String::from_utf8_lossy(&argv[1]).to_lowercase() != "fields" {
+            return Ok(Hgetdel::new_invalid());
+        }
+        match String::from_utf8_lossy(&argv[2]).parse::<usize>() {
+            Ok(numfields) if numfields > 0 && numfields == argv.len() - 3 => {}
+            _ => return Ok(Hgetdel::new_invalid()),
+        }
+        let fields = argv[3..]
+            .iter()
+            .map(|field| String::from_utf8_lossy(field).to_string())
+            .collect();
+        Ok(Hgetdel::new(&String::from_utf8_lossy(&argv[0]), fields))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.hgetdel(None).await?;
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn hgetdel(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            HashCommandCtx::new(txn)
+                .do_async_txnkv_hgetdel(&self.key, &self.fields)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Hgetdel {
+    fn new_invalid() -> Hgetdel {
+        Hgetdel {
+            key: "".to_owned(),
+            fields: vec![],
+            valid: false,
+        }
+    }
+}
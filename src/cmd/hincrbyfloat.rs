@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use crate::cmd::incrbyfloat::parse_float_increment;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::hash::HashCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Increment the float value of a hash field.
+///
+/// ```text
+/// HINCRBYFLOAT key field increment
+/// ```
+///
+/// A missing field is read as 0, the new value is stored and replied as a
+/// string.
+#[derive(Debug, Clone)]
+pub struct Hincrbyfloat {
+    key: String,
+    field: String,
+    increment: String,
+    valid: bool,
+}
+
+impl Hincrbyfloat {
+    pub fn new(key: &str, field: &str, increment: &str) -> Hincrbyfloat {
+        Hincrbyfloat {
+            key: key.to_owned(),
+            field: field.to_owned(),
+            increment: increment.to_owned(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hincrbyfloat> {
+        let key = parse.next_string()?;
+        let field = parse.next_string()?;
+        let increment = parse.next_string()?;
+
+        Ok(Hincrbyfloat::new(&key, &field, &increment))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Hincrbyfloat> {
+        if argv.len() != 3 {
+            return Ok(Hincrbyfloat::new_invalid());
+        }
+        Ok(Hincrbyfloat::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+            &String::from_utf8_lossy(&argv[2]),
+        ))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.hincrbyfloat(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn hincrbyfloat(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let increment = match parse_float_increment(&self.increment) {
+            Ok(increment) => increment,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            HashCommandCtx::new(txn)
+                .do_async_txnkv_hincrbyfloat(&self.key, &self.field, increment)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Hincrbyfloat {
+    fn new_invalid() -> Hincrbyfloat {
+        Hincrbyfloat {
+            key: "".to_owned(),
+            field: "".to_owned(),
+            increment: "".to_owned(),
+            valid: false,
+        }
+    }
+}
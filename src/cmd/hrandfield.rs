@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR};
+use crate::tikv::hash::HashCommandCtx;
+use crate::tikv::sample::Sampling;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get random fields of a hash.
+///
+/// ```text
+/// HRANDFIELD key [count [WITHVALUES]]
+/// ```
+///
+/// A single field is replied without `count`, otherwise an array of distinct
+/// fields for a positive count, or of `-count` fields which may repeat for a
+/// negative one. `WITHVALUES` replies each field followed by its value.
+#[derive(Debug, Clone)]
+pub struct Hrandfield {
+    key: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Hrandfield {
+    pub fn new(key: &str, args: Vec<String>) -> Hrandfield {
+        Hrandfield {
+            key: key.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hrandfield> {
+        let key = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+
+        Ok(Hrandfield::new(&key, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Hrandfield> {
+        if argv.is_empty() {
+            return Ok(Hrandfield::new_invalid());
+        }
+        let args = argv[1..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Hrandfield::new(&String::from_utf8_lossy(&argv[0]), args))
+    }
+
+    /// Parse the arguments to (count, with values).
+    fn parse_args(&self) -> Result<(Option<i64>, bool), RTError> {
+        match self.args.as_slice() {
+            [] => Ok((None, false)),
+            [count] => Ok((Some(count.parse::<i64>()?), false)),
+            [count, with_values] if with_values.to_uppercase() == "WITHVALUES" => {
+                Ok((Some(count.parse::<i64>()?), true))
+            }
+            _ => Err(REDIS_SYNTAX_ERR),
+        }
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.hrandfield(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn hrandfield(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (count, with_values) = match self.parse_args() {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            // called with no count argument returns a single field
+            let sampling = match Sampling::new(count.unwrap_or(1), with_values) {
+                Ok(sampling) => sampling,
+                Err(e) => return Ok(resp_err(e)),
+            };
+            HashCommandCtx::new(txn)
+                .do_async_txnkv_hrandfield(&self.key, sampling, count.is_some(), with_values)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Hrandfield {
+    fn new_invalid() -> Hrandfield {
+        Hrandfield {
+            key: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::{cmd_scan_count_limit_or_default, is_use_txn_api};
+use crate::tikv::errors::{AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR};
+use crate::tikv::hash::HashCommandCtx;
+use crate::utils::{decode_scan_cursor, resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Arguments of HSCAN, SSCAN and ZSCAN, the pattern is None if every element
+/// matches.
+pub(crate) struct CollectionScanArgs {
+    pub start: Vec<u8>,
+    pub count: u32,
+    pub pattern: Option<String>,
+}
+
+/// Parse `cursor [MATCH pattern] [COUNT count]`.
+pub(crate) fn parse_collection_scan_args(
+    cursor: &str,
+    args: &[String],
+) -> Result<CollectionScanArgs, RTError> {
+    let mut parsed = CollectionScanArgs {
+        start: decode_scan_cursor(cursor)?,
+        count: 10,
+        pattern: None,
+    };
+
+    let mut idx = 0;
+    while idx < args.len() {
+        if idx + 1 == args.len() {
+            return Err(REDIS_SYNTAX_ERR);
+        }
+        match args[idx].to_uppercase().as_str() {
+            "MATCH" => {
+                parsed.pattern = match args[idx + 1].as_str() {
+                    "*" => None,
+                    pattern => Some(pattern.to_owned()),
+                };
+            }
+            "COUNT" => {
+                let count = args[idx + 1].parse::<i64>()?;
+                if count < 1 {
+                    return Err(REDIS_SYNTAX_ERR);
+                }
+                parsed.count = count.try_into().unwrap_or(u32::MAX);
+            }
+            _ => return Err(REDIS_SYNTAX_ERR),
+        }
+        idx += 2;
+    }
+
+    // the reply is built in memory, a huge count is clamped to the limit
+    let limit = cmd_scan_count_limit_or_default();
+    if limit > 0 {
+        parsed.count = parsed.count.min(limit);
+    }
+    Ok(parsed)
+}
+
+/// Incrementally iterate the fields and values of a hash.
+///
+/// ```text
+/// HSCAN key cursor [MATCH pattern] [COUNT count]
+/// ```
+///
+/// A scan starts with cursor `0` and ends when `0` is replied. The cursor is
+/// the position of the next field in the hash, so a field present for the
+/// whole scan is returned even if the hash is modified meanwhile. COUNT is the
+/// number of fields read, those not matching the glob pattern are filtered
+/// out after.
+#[derive(Debug, Clone)]
+pub struct Hscan {
+    key: String,
+    cursor: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Hscan {
+    pub fn new(key: &str, cursor: &str, args: Vec<String>) -> Hscan {
+        Hscan {
+            key: key.to_owned(),
+            cursor: cursor.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Hscan> {
+        let key = parse.next_string()?;
+        let cursor = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+
+        Ok(Hscan::new(&key, &cursor, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Hscan> {
+        if argv.len() < 2 {
+            return Ok(Hscan::new_invalid());
+        }
+        let args = argv[2..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Hscan::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+            args,
+        ))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.hscan(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn hscan(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let args = match parse_collection_scan_args(&self.cursor, &self.args) {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            HashCommandCtx::new(txn)
+                .do_async_txnkv_hscan(&self.key, &args.start, args.count, args.pattern.as_deref())
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Hscan {
+    fn new_invalid() -> Hscan {
+        Hscan {
+            key: "".to_owned(),
+            cursor: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
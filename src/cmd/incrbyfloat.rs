@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR, REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR,
+};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Parse the increment of INCRBYFLOAT and HINCRBYFLOAT, NaN and Infinity
+/// are rejected.
+pub(crate) fn parse_float_increment(increment: &str) -> Result<f64, RTError> {
+    match increment.parse::<f64>() {
+        Ok(increment) if increment.is_finite() => Ok(increment),
+        _ => Err(REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR),
+    }
+}
+
+/// Increment the float value of a string.
+///
+/// ```text
+/// INCRBYFLOAT key increment
+/// ```
+///
+/// A missing key is read as 0, the new value is stored and replied as a
+/// string.
+#[derive(Debug, Clone)]
+pub struct Incrbyfloat {
+    key: String,
+    increment: String,
+    valid: bool,
+}
+
+impl Incrbyfloat {
+    pub fn new(key: &str, increment: &str) -> Incrbyfloat {
+        Incrbyfloat {
+            key: key.to_owned(),
+            increment: increment.to_owned(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Incrbyfloat> {
+        let key = parse.next_string()?;
+        let increment = parse.next_string()?;
+
+        Ok(Incrbyfloat::new(&key, &increment))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Incrbyfloat> {
+        if argv.len() != 2 {
+            return Ok(Incrbyfloat::new_invalid());
+        }
+        Ok(Incrbyfloat::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+        ))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.incrbyfloat(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn incrbyfloat(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let increment = match parse_float_increment(&self.increment) {
+            Ok(increment) => increment,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_incrbyfloat(&self.key, increment)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Incrbyfloat {
+    fn new_invalid() -> Incrbyfloat {
+        Incrbyfloat {
+            key: "".to_owned(),
+            increment: "".to_owned(),
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use slog::debug;
+
+use crate::client::Clients;
+use crate::cmd::Invalid;
+use crate::config::{config_port_or_default, LOGGER};
+use crate::metrics::{
+    DATA_TRAFFIC_IN, DATA_TRAFFIC_OUT, INFLIGHT_CMD_GAUGE, REMOVED_EXPIRED_KEY_COUNTER,
+    REQUEST_COUNTER, TIKV_CLIENT_RETRIES, TOTAL_CONNECTION_PROCESSED, TXN_RETRY_COUNTER,
+};
+use crate::tikv::{backend_stats, get_instance_id};
+use crate::utils::resp_invalid_arguments;
+use crate::{Connection, Frame, Parse};
+
+/// The sections replied when none is given, in order.
+const SECTIONS: &[&str] = &["server", "clients", "memory", "stats", "keyspace", "tidis"];
+
+/// Report information and statistics about the server.
+///
+/// ```text
+/// INFO [section [section ...]]
+/// ```
+///
+/// The values are read from the same metrics exported to Prometheus, so both
+/// report the same numbers.
+#[derive(Debug, Clone)]
+pub struct Info {
+    sections: Vec<String>,
+    valid: bool,
+}
+
+impl Info {
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Info> {
+        let mut sections = vec![];
+        while let Ok(section) = parse.next_string() {
+            sections.push(section.to_lowercase());
+        }
+        Ok(Info {
+            sections,
+            valid: true,
+        })
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection, clients: Clients) -> crate::Result<()> {
+        let response = if self.valid {
+            Frame::Verbatim(self.info(clients).await)
+        } else {
+            resp_invalid_arguments()
+        };
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    /// The sections asked for, `all`, `default` and `everything` are all the
+    /// sections and an unknown section is left out as redis does.
+    async fn info(&self, clients: Clients) -> String {
+        let all = self.sections.is_empty()
+            || self
+                .sections
+                .iter()
+                .any(|s| matches!(s.as_str(), "all" | "default" | "everything"));
+        let mut info = String::new();
+        for section in SECTIONS {
+            if !all && !self.sections.iter().any(|s| s == section) {
+                continue;
+            }
+            if !info.is_empty() {
+                info.push_str("\r\n");
+            }
+            let fields = match *section {
+                "server" => server_section(),
+                "clients" => vec![("connected_clients", clients.lock().await.len().to_string())],
+                "memory" => memory_section(),
+                "stats" => stats_section(),
+                // counting the keys would scan the whole keyspace in TiKV
+                "keyspace" => vec![],
+                _ => tidis_section(),
+            };
+            let _ = write!(
+                info,
+                "# {}{}\r\n",
+                section[..1].to_uppercase(),
+                &section[1..]
+            );
+            for (name, value) in fields {
+                let _ = write!(info, "{}:{}\r\n", name, value);
+            }
+        }
+        info
+    }
+}
+
+fn server_section() -> Vec<(&'static str, String)> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let uptime = now.saturating_sub(process_metric("process_start_time_seconds") as u64);
+    vec![
+        // the version reported by HELLO as well
+        ("redis_version", env!("CARGO_PKG_VERSION").to_owned()),
+        ("tidis_version", env!("CARGO_PKG_VERSION").to_owned()),
+        ("redis_mode", "standalone".to_owned()),
+        (
+            "os",
+            format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        ),
+        ("arch_bits", (std::mem::size_of::<usize>() * 8).to_string()),
+        ("process_id", std::process::id().to_string()),
+        ("tcp_port", config_port_or_default()),
+        ("uptime_in_seconds", uptime.to_string()),
+        ("uptime_in_days", (uptime / 86400).to_string()),
+    ]
+}
+
+fn memory_section() -> Vec<(&'static str, String)> {
+    // the data is kept in TiKV, the memory used is the one of the process
+    let rss = process_metric("process_resident_memory_bytes") as u64;
+    vec![
+        ("used_memory", rss.to_string()),
+        ("used_memory_human", human_bytes(rss)),
+        ("used_memory_rss", rss.to_string()),
+        ("maxmemory", "0".to_owned()),
+    ]
+}
+
+fn stats_section() -> Vec<(&'static str, String)> {
+    let expired_keys: u64 = ["string", "hash", "list", "set", "zset"]
+        .iter()
+        .map(|kind| REMOVED_EXPIRED_KEY_COUNTER.with_label_values(&[kind]).get())
+        .sum();
+    vec![
+        (
+            "total_connections_received",
+            TOTAL_CONNECTION_PROCESSED.get().to_string(),
+        ),
+        (
+            "total_commands_processed",
+            REQUEST_COUNTER.get().to_string(),
+        ),
+        ("total_net_input_bytes", DATA_TRAFFIC_IN.get().to_string()),
+        ("total_net_output_bytes", DATA_TRAFFIC_OUT.get().to_string()),
+        ("expired_keys", expired_keys.to_string()),
+    ]
+}
+
+fn tidis_section() -> Vec<(&'static str, String)> {
+    let stats = backend_stats();
+    vec![
+        ("instance_id", get_instance_id().to_string()),
+        ("pd_addrs", stats.pd_addrs.join(",")),
+        ("inflight_cmds", INFLIGHT_CMD_GAUGE.get().to_string()),
+        ("tikv_client_retries", TIKV_CLIENT_RETRIES.get().to_string()),
+        ("txn_retries", TXN_RETRY_COUNTER.get().to_string()),
+        (
+            "txn_client_pool_size",
+            stats.txn_client_pool_size.to_string(),
+        ),
+        (
+            "broken_txn_clients",
+            stats.broken_txn_clients.len().to_string(),
+        ),
+        (
+            "unhealthy_txn_clients",
+            stats.unhealthy_txn_clients.len().to_string(),
+        ),
+    ]
+}
+
+/// The value of a metric of the process collector exported to Prometheus, 0
+/// where it is not supported.
+#[cfg(target_os = "linux")]
+fn process_metric(name: &str) -> f64 {
+    use prometheus::core::Collector;
+    use prometheus::process_collector::ProcessCollector;
+
+    ProcessCollector::for_self()
+        .collect()
+        .iter()
+        .find(|family| family.get_name() == name)
+        .and_then(|family| family.get_metric().first())
+        .map_or(0.0, |metric| {
+            if metric.has_gauge() {
+                metric.get_gauge().get_value()
+            } else {
+                metric.get_counter().get_value()
+            }
+        })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_metric(_name: &str) -> f64 {
+    0.0
+}
+
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "K", "M", "G", "T"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2}{}", value, UNITS[unit])
+}
+
+impl Invalid for Info {
+    fn new_invalid() -> Info {
+        Info {
+            sections: vec![],
+            valid: false,
+        }
+    }
+}
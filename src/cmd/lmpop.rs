@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_COUNT_NOT_POSITIVE_ERR, REDIS_NOT_SUPPORTED_ERR,
+    REDIS_NUMKEYS_NOT_POSITIVE_ERR, REDIS_SYNTAX_ERR,
+};
+use crate::tikv::list::ListCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Parse the arguments of LMPOP and ZMPOP to (keys, from the first side, count).
+///
+/// ```text
+/// numkeys key [key ...] <first|last> [COUNT count]
+/// ```
+pub(crate) fn parse_mpop_args<'a>(
+    args: &'a [String],
+    first: &str,
+    last: &str,
+) -> Result<(&'a [String], bool, u64), RTError> {
+    let numkeys = match args[0].parse::<i64>() {
+        Ok(n) if n > 0 => n as usize,
+        _ => return Err(REDIS_NUMKEYS_NOT_POSITIVE_ERR),
+    };
+    if numkeys + 1 >= args.len() {
+        return Err(REDIS_SYNTAX_ERR);
+    }
+    let keys = &args[1..numkeys + 1];
+    let from_first = match args[numkeys + 1].to_uppercase() {
+        side if side == first => true,
+        side if side == last => false,
+        _ => return Err(REDIS_SYNTAX_ERR),
+    };
+    let count = match &args[numkeys + 2..] {
+        [] => 1,
+        [option, count] if option.to_uppercase() == "COUNT" => match count.parse::<i64>() {
+            Ok(n) if n > 0 => n as u64,
+            _ => return Err(REDIS_COUNT_NOT_POSITIVE_ERR),
+        },
+        _ => return Err(REDIS_SYNTAX_ERR),
+    };
+    Ok((keys, from_first, count))
+}
+
+/// Pop elements from the first non-empty list of the keys.
+///
+/// ```text
+/// LMPOP numkeys key [key ...] LEFT|RIGHT [COUNT count]
+/// ```
+///
+/// The key and an array of the popped elements are replied, at most `count`
+/// of them, nil if all the lists are empty.
+#[derive(Debug, Clone)]
+pub struct Lmpop {
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Lmpop {
+    pub fn new(args: Vec<String>) -> Lmpop {
+        Lmpop { args, valid: true }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lmpop> {
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 3 {
+            return Ok(Lmpop::new_invalid());
+        }
+        Ok(Lmpop::new(args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Lmpop> {
+        if argv.len() < 3 {
+            return Ok(Lmpop::new_invalid());
+        }
+        let args = argv
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Lmpop::new(args))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.lmpop(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn lmpop(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (keys, op_left, count) = match parse_mpop_args(&self.args, "LEFT", "RIGHT") {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            ListCommandCtx::new(txn)
+                .do_async_txnkv_lmpop(keys, op_left, count)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Lmpop {
+    fn new_invalid() -> Lmpop {
+        Lmpop {
+            args: vec![],
+            valid: false,
+        }
+    }
+}
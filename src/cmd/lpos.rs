@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_LPOS_COUNT_NEGATIVE_ERR, REDIS_LPOS_MAXLEN_NEGATIVE_ERR,
+    REDIS_LPOS_RANK_ZERO_ERR, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR,
+};
+use crate::tikv::list::ListCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Find the positions of an element in a list.
+///
+/// ```text
+/// LPOS key element [RANK rank] [COUNT num-matches] [MAXLEN len]
+/// ```
+///
+/// `RANK` skips the first matches, a negative rank searches from the tail.
+/// A single position or nil is replied without `COUNT`, otherwise an array of
+/// at most `COUNT` positions, 0 means all the matches. `MAXLEN` bounds the
+/// number of elements read, 0 means the whole list.
+#[derive(Debug, Clone)]
+pub struct Lpos {
+    key: String,
+    element: Bytes,
+    options: Vec<String>,
+    valid: bool,
+}
+
+impl Lpos {
+    pub fn new(key: &str, element: Bytes, options: Vec<String>) -> Lpos {
+        Lpos {
+            key: key.to_owned(),
+            element,
+            options,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Lpos> {
+        let key = parse.next_string()?;
+        let element = parse.next_bytes()?;
+        let mut options = vec![];
+        while let Ok(option) = parse.next_string() {
+            options.push(option);
+        }
+
+        Ok(Lpos::new(&key, element, options))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Lpos> {
+        if argv.len() < 2 {
+            return Ok(Lpos::new_invalid());
+        }
+        let key = &String::from_utf8_lossy(&argv[0]);
+        let options = argv[2..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Lpos::new(key, argv[1].clone(), options))
+    }
+
+    /// Parse the options to (rank, count, maxlen).
+    fn parse_options(&self) -> Result<(i64, Option<u64>, u64), RTError> {
+        let mut rank = 1;
+        let mut count = None;
+        let mut maxlen = 0;
+        for pair in self.options.chunks(2) {
+            if pair.len() != 2 {
+                return Err(REDIS_SYNTAX_ERR);
+            }
+            let value = pair[1].parse::<i64>();
+            match pair[0].to_uppercase().as_str() {
+                "RANK" => {
+                    let value = value?;
+                    // the rank of i64::MIN can not be negated
+                    if value == 0 || value == i64::MIN {
+                        return Err(REDIS_LPOS_RANK_ZERO_ERR);
+                    }
+                    rank = value;
+                }
+                "COUNT" => {
+                    let value = value?;
+                    if value < 0 {
+                        return Err(REDIS_LPOS_COUNT_NEGATIVE_ERR);
+                    }
+                    count = Some(value as u64);
+                }
+                "MAXLEN" => {
+                    let value = value?;
+                    if value < 0 {
+                        return Err(REDIS_LPOS_MAXLEN_NEGATIVE_ERR);
+                    }
+                    maxlen = value as u64;
+                }
+                _ => return Err(REDIS_SYNTAX_ERR),
+            }
+        }
+        Ok((rank, count, maxlen))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.lpos(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn lpos(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (rank, count, maxlen) = match self.parse_options() {
+            Ok(options) => options,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            ListCommandCtx::new(txn)
+                .do_async_txnkv_lpos(&self.key, &self.element, rank, count, maxlen)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Lpos {
+    fn new_invalid() -> Lpos {
+        Lpos {
+            key: "".to_owned(),
+            element: Bytes::new(),
+            options: vec![],
+            valid: false,
+        }
+    }
+}
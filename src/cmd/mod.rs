@@ -2,7 +2,6 @@ mod get;
 
 use bytes::Bytes;
 pub use get::Get;
-use std::collections::HashMap;
 use std::sync::Arc;
 
 mod del;
@@ -19,6 +18,45 @@ use tokio::sync::Mutex;
 mod strlen;
 pub use strlen::Strlen;
 
+mod bitcount;
+pub use bitcount::Bitcount;
+
+mod bitpos;
+pub use bitpos::Bitpos;
+
+mod bitop;
+pub use bitop::Bitop;
+
+mod setbit;
+pub use setbit::Setbit;
+
+mod getbit;
+pub use getbit::Getbit;
+
+mod bitfield;
+pub use bitfield::Bitfield;
+
+mod setrange;
+pub use setrange::Setrange;
+
+mod getrange;
+pub use getrange::Getrange;
+
+mod append;
+pub use append::Append;
+
+mod incrbyfloat;
+pub use incrbyfloat::Incrbyfloat;
+
+mod getdel;
+pub use getdel::GetDel;
+
+mod getex;
+pub use getex::GetEx;
+
+mod copy;
+pub use copy::CopyKey;
+
 mod publish;
 pub use publish::Publish;
 
@@ -34,12 +72,19 @@ pub use setex::SetEX;
 mod ttl;
 pub use ttl::TTL;
 
+mod expiretime;
+pub use expiretime::Expiretime;
+
 mod cmdtype;
 pub use cmdtype::Type;
 
 mod subscribe;
+pub(crate) use subscribe::{release_subscriptions, Subscriptions};
 pub use subscribe::{Subscribe, Unsubscribe};
 
+mod psubscribe;
+pub use psubscribe::{Psubscribe, Punsubscribe};
+
 mod ping;
 pub use ping::Ping;
 
@@ -82,18 +127,36 @@ pub use hdel::Hdel;
 mod hincrby;
 pub use hincrby::Hincrby;
 
+mod hincrbyfloat;
+pub use hincrbyfloat::Hincrbyfloat;
+
+mod hrandfield;
+pub use hrandfield::Hrandfield;
+
+mod hscan;
+pub use hscan::Hscan;
+
 mod hexists;
 pub use hexists::Hexists;
 
 mod hstrlen;
 pub use hstrlen::Hstrlen;
 
+mod hgetdel;
+pub use hgetdel::Hgetdel;
+
 mod push;
 pub use push::Push;
 
 mod pop;
 pub use pop::Pop;
 
+mod bpop;
+pub use bpop::Bpop;
+
+mod lmpop;
+pub use lmpop::Lmpop;
+
 mod lrange;
 pub use lrange::Lrange;
 
@@ -112,6 +175,9 @@ pub use ltrim::Ltrim;
 mod lrem;
 pub use lrem::Lrem;
 
+mod lpos;
+pub use lpos::Lpos;
+
 mod linsert;
 pub use linsert::Linsert;
 
@@ -124,6 +190,9 @@ pub use sadd::Sadd;
 mod scard;
 pub use scard::Scard;
 
+mod sintercard;
+pub use sintercard::Sintercard;
+
 mod sismember;
 pub use sismember::Sismember;
 
@@ -136,6 +205,9 @@ pub use smembers::Smembers;
 mod srandmember;
 pub use srandmember::Srandmember;
 
+mod sscan;
+pub use sscan::Sscan;
+
 mod spop;
 pub use spop::Spop;
 
@@ -163,6 +235,9 @@ pub use zremrangebyrank::Zremrangebyrank;
 mod zrange;
 pub use zrange::Zrange;
 
+mod zrangestore;
+pub use zrangestore::Zrangestore;
+
 mod zrevrange;
 pub use zrevrange::Zrevrange;
 
@@ -175,9 +250,30 @@ pub use zcount::Zcount;
 mod zpop;
 pub use zpop::Zpop;
 
+mod zmpop;
+pub use zmpop::Zmpop;
+
 mod zrank;
 pub use zrank::Zrank;
 
+mod zrandmember;
+pub use zrandmember::Zrandmember;
+
+mod zscan;
+pub use zscan::Zscan;
+
+mod zdiff;
+pub use zdiff::Zdiff;
+
+mod zdiffstore;
+pub use zdiffstore::Zdiffstore;
+
+mod zunion;
+pub use zunion::Zunion;
+
+mod zinter;
+pub use zinter::Zinter;
+
 mod zincrby;
 pub use zincrby::Zincrby;
 
@@ -193,6 +289,9 @@ pub use unknown::Unknown;
 mod auth;
 pub use auth::Auth;
 
+mod acl;
+pub use acl::Acl;
+
 mod debug;
 pub use debug::Debug;
 
@@ -202,13 +301,54 @@ pub use cluster::Cluster;
 mod fake;
 pub use fake::Fake;
 
+mod info;
+pub use info::Info;
+
+mod client;
+pub(crate) use client::valid_client_name;
+pub use client::Client;
+
 mod multi;
+pub(crate) use multi::exec_cmd_in_txn;
 pub use multi::Multi;
 
+mod watch;
+pub use watch::Watch;
+
+mod unwatch;
+pub use unwatch::Unwatch;
+
+mod bulkload;
+pub use bulkload::Bulkload;
+
 mod scan;
 pub use scan::Scan;
 
-use crate::client::Client;
+mod randomkey;
+pub use randomkey::Randomkey;
+
+mod object;
+pub use object::Object;
+
+mod monitor;
+pub(crate) use monitor::monitor_line;
+pub use monitor::Monitor;
+
+mod hello;
+pub use hello::Hello;
+
+mod wait;
+pub use wait::Wait;
+
+mod config;
+pub use config::Config;
+
+mod slowlog;
+pub use slowlog::Slowlog;
+
+pub mod spec;
+
+use crate::client::{Client as ClientInfo, Clients};
 use crate::{cluster::Cluster as Topo, Connection, Db, Frame, Parse, ParseError, Shutdown};
 
 /// All commands should be implement new_invalid() for invalid check
@@ -244,21 +384,39 @@ pub enum Command {
     Mset(Mset),
     Subscribe(Subscribe),
     Unsubscribe(Unsubscribe),
+    Psubscribe(Psubscribe),
+    Punsubscribe(Punsubscribe),
     Ping(Ping),
     Type(Type),
     TTL(TTL),
     PTTL(TTL),
+    Expiretime(Expiretime),
+    Pexpiretime(Expiretime),
     Expire(Expire),
     ExpireAt(Expire),
     Pexpire(Expire),
     PexpireAt(Expire),
     Persist(Persist),
     Exists(Exists),
+    Object(Object),
     Incr(IncrDecr),
     Decr(IncrDecr),
     IncrBy(IncrDecr),
     DecrBy(IncrDecr),
     Strlen(Strlen),
+    Bitcount(Bitcount),
+    Bitpos(Bitpos),
+    Bitop(Bitop),
+    Setbit(Setbit),
+    Getbit(Getbit),
+    Bitfield(Bitfield),
+    Setrange(Setrange),
+    Getrange(Getrange),
+    Append(Append),
+    Incrbyfloat(Incrbyfloat),
+    GetDel(GetDel),
+    GetEx(GetEx),
+    Copy(CopyKey),
 
     // hash
     Hset(Hset),
@@ -272,27 +430,37 @@ pub enum Command {
     Hkeys(Hkeys),
     Hvals(Hvals),
     Hincrby(Hincrby),
+    Hincrbyfloat(Hincrbyfloat),
+    Hrandfield(Hrandfield),
+    Hscan(Hscan),
     Hexists(Hexists),
     Hstrlen(Hstrlen),
+    Hgetdel(Hgetdel),
     // list
     Lpush(Push),
     Rpush(Push),
     Lpop(Pop),
     Rpop(Pop),
+    Blpop(Bpop),
+    Brpop(Bpop),
+    Lmpop(Lmpop),
     Lrange(Lrange),
     Llen(Llen),
     Lindex(Lindex),
     Lset(Lset),
     Ltrim(Ltrim),
     Lrem(Lrem),
+    Lpos(Lpos),
     Linsert(Linsert),
     // set
     Sadd(Sadd),
     Scard(Scard),
+    Sintercard(Sintercard),
     Sismember(Sismember),
     Smismember(Smismember),
     Smembers(Smembers),
     Srandmember(Srandmember),
+    Sscan(Sscan),
     Spop(Spop),
     Srem(Srem),
     // sorted set
@@ -303,13 +471,21 @@ pub enum Command {
     Zremrangebyscore(Zremrangebyscore),
     Zremrangebyrank(Zremrangebyrank),
     Zrange(Zrange),
+    Zrangestore(Zrangestore),
     Zrevrange(Zrevrange),
     Zrangebyscore(Zrangebyscore),
     Zrevrangebyscore(Zrangebyscore),
     Zcount(Zcount),
     Zpopmin(Zpop),
     Zpopmax(Zpop),
+    Zmpop(Zmpop),
     Zrank(Zrank),
+    Zrandmember(Zrandmember),
+    Zscan(Zscan),
+    Zdiff(Zdiff),
+    Zdiffstore(Zdiffstore),
+    Zunion(Zunion),
+    Zinter(Zinter),
     Zincryby(Zincrby),
 
     // scripts
@@ -318,20 +494,35 @@ pub enum Command {
     Script(Script),
 
     Auth(Auth),
+    Acl(Acl),
     Debug(Debug),
 
     Cluster(Cluster),
     ReadWrite(Fake),
     ReadOnly(Fake),
-    Client(Fake),
-    Info(Fake),
+    Client(Client),
+    Info(Info),
 
     // multi/exec/abort
     Multi(Multi),
     Exec(Multi),
     Discard(Multi),
+    Reset(Multi),
+    Quit(Multi),
+    Watch(Watch),
+    Unwatch(Unwatch),
+
+    Bulkload(Bulkload),
+
+    Monitor(Monitor),
+
+    Hello(Hello),
+    Wait(Wait),
+    Config(Config),
+    Slowlog(Slowlog),
 
     Scan(Scan),
+    Randomkey(Randomkey),
     // Xscan command is same as scan, for testing purpose, avoid some client decoding the response
     Xscan(Scan),
 
@@ -358,7 +549,8 @@ impl Command {
         // All redis commands begin with the command name as a string. The name
         // is read and converted to lower cases in order to do case sensitive
         // matching.
-        let command_name = parse.next_string()?.to_lowercase();
+        let name = parse.next_string()?;
+        let command_name = name.to_lowercase();
         // Match the command name, delegating the rest of the parsing to the
         // specific command.
         let command = match &command_name[..] {
@@ -379,12 +571,28 @@ impl Command {
                 Unsubscribe::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "psubscribe" => Command::Psubscribe(transform_parse(
+                Psubscribe::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "punsubscribe" => Command::Punsubscribe(transform_parse(
+                Punsubscribe::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "ping" => Command::Ping(transform_parse(Ping::parse_frames(&mut parse), &mut parse)),
             "type" => Command::Type(transform_parse(Type::parse_frames(&mut parse), &mut parse)),
             "mget" => Command::Mget(transform_parse(Mget::parse_frames(&mut parse), &mut parse)),
             "mset" => Command::Mset(transform_parse(Mset::parse_frames(&mut parse), &mut parse)),
             "ttl" => Command::TTL(transform_parse(TTL::parse_frames(&mut parse), &mut parse)),
             "pttl" => Command::PTTL(transform_parse(TTL::parse_frames(&mut parse), &mut parse)),
+            "expiretime" => Command::Expiretime(transform_parse(
+                Expiretime::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "pexpiretime" => Command::Pexpiretime(transform_parse(
+                Expiretime::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "expire" => Command::Expire(transform_parse(
                 Expire::parse_frames(&mut parse),
                 &mut parse,
@@ -409,6 +617,10 @@ impl Command {
                 Exists::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "object" => Command::Object(transform_parse(
+                Object::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "incr" => Command::Incr(transform_parse(
                 IncrDecr::parse_frames(&mut parse, true),
                 &mut parse,
@@ -429,6 +641,56 @@ impl Command {
                 Strlen::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "bitcount" => Command::Bitcount(transform_parse(
+                Bitcount::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "bitpos" => Command::Bitpos(transform_parse(
+                Bitpos::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "bitop" => Command::Bitop(transform_parse(Bitop::parse_frames(&mut parse), &mut parse)),
+            "setbit" => Command::Setbit(transform_parse(
+                Setbit::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "getbit" => Command::Getbit(transform_parse(
+                Getbit::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "bitfield" => Command::Bitfield(transform_parse(
+                Bitfield::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "setrange" => Command::Setrange(transform_parse(
+                Setrange::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "getrange" => Command::Getrange(transform_parse(
+                Getrange::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "append" => Command::Append(transform_parse(
+                Append::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "incrbyfloat" => Command::Incrbyfloat(transform_parse(
+                Incrbyfloat::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "substr" => Command::Getrange(transform_parse(
+                Getrange::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "getdel" => Command::GetDel(transform_parse(
+                GetDel::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "getex" => Command::GetEx(transform_parse(GetEx::parse_frames(&mut parse), &mut parse)),
+            "copy" => Command::Copy(transform_parse(
+                CopyKey::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "hset" => Command::Hset(transform_parse(Hset::parse_frames(&mut parse), &mut parse)),
             "hsetnx" => {
                 Command::Hsetnx(transform_parse(Hset::parse_frames(&mut parse), &mut parse))
@@ -448,6 +710,15 @@ impl Command {
                 Hincrby::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "hincrbyfloat" => Command::Hincrbyfloat(transform_parse(
+                Hincrbyfloat::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "hrandfield" => Command::Hrandfield(transform_parse(
+                Hrandfield::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "hscan" => Command::Hscan(transform_parse(Hscan::parse_frames(&mut parse), &mut parse)),
             "hexists" => Command::Hexists(transform_parse(
                 Hexists::parse_frames(&mut parse),
                 &mut parse,
@@ -456,10 +727,17 @@ impl Command {
                 Hstrlen::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "hgetdel" => Command::Hgetdel(transform_parse(
+                Hgetdel::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "lpush" => Command::Lpush(transform_parse(Push::parse_frames(&mut parse), &mut parse)),
             "rpush" => Command::Rpush(transform_parse(Push::parse_frames(&mut parse), &mut parse)),
             "lpop" => Command::Lpop(transform_parse(Pop::parse_frames(&mut parse), &mut parse)),
             "rpop" => Command::Rpop(transform_parse(Pop::parse_frames(&mut parse), &mut parse)),
+            "blpop" => Command::Blpop(transform_parse(Bpop::parse_frames(&mut parse), &mut parse)),
+            "brpop" => Command::Brpop(transform_parse(Bpop::parse_frames(&mut parse), &mut parse)),
+            "lmpop" => Command::Lmpop(transform_parse(Lmpop::parse_frames(&mut parse), &mut parse)),
             "lrange" => Command::Lrange(transform_parse(
                 Lrange::parse_frames(&mut parse),
                 &mut parse,
@@ -472,6 +750,7 @@ impl Command {
             "lset" => Command::Lset(transform_parse(Lset::parse_frames(&mut parse), &mut parse)),
             "ltrim" => Command::Ltrim(transform_parse(Ltrim::parse_frames(&mut parse), &mut parse)),
             "lrem" => Command::Lrem(transform_parse(Lrem::parse_frames(&mut parse), &mut parse)),
+            "lpos" => Command::Lpos(transform_parse(Lpos::parse_frames(&mut parse), &mut parse)),
             "linsert" => Command::Linsert(transform_parse(
                 Linsert::parse_frames(&mut parse),
                 &mut parse,
@@ -486,6 +765,10 @@ impl Command {
             )),
             "sadd" => Command::Sadd(transform_parse(Sadd::parse_frames(&mut parse), &mut parse)),
             "scard" => Command::Scard(transform_parse(Scard::parse_frames(&mut parse), &mut parse)),
+            "sintercard" => Command::Sintercard(transform_parse(
+                Sintercard::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "sismember" => Command::Sismember(transform_parse(
                 Sismember::parse_frames(&mut parse),
                 &mut parse,
@@ -502,6 +785,7 @@ impl Command {
                 Srandmember::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "sscan" => Command::Sscan(transform_parse(Sscan::parse_frames(&mut parse), &mut parse)),
             "spop" => Command::Spop(transform_parse(Spop::parse_frames(&mut parse), &mut parse)),
             "srem" => Command::Srem(transform_parse(Srem::parse_frames(&mut parse), &mut parse)),
             "zadd" => Command::Zadd(transform_parse(Zadd::parse_frames(&mut parse), &mut parse)),
@@ -523,6 +807,10 @@ impl Command {
                 Zrange::parse_frames(&mut parse),
                 &mut parse,
             )),
+            "zrangestore" => Command::Zrangestore(transform_parse(
+                Zrangestore::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "zrevrange" => Command::Zrevrange(transform_parse(
                 Zrevrange::parse_frames(&mut parse),
                 &mut parse,
@@ -545,46 +833,94 @@ impl Command {
             "zpopmax" => {
                 Command::Zpopmax(transform_parse(Zpop::parse_frames(&mut parse), &mut parse))
             }
+            "zmpop" => Command::Zmpop(transform_parse(Zmpop::parse_frames(&mut parse), &mut parse)),
             "zrank" => Command::Zrank(transform_parse(Zrank::parse_frames(&mut parse), &mut parse)),
+            "zrandmember" => Command::Zrandmember(transform_parse(
+                Zrandmember::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "zscan" => Command::Zscan(transform_parse(Zscan::parse_frames(&mut parse), &mut parse)),
+            "zdiff" => Command::Zdiff(transform_parse(Zdiff::parse_frames(&mut parse), &mut parse)),
+            "zdiffstore" => Command::Zdiffstore(transform_parse(
+                Zdiffstore::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "zunion" => Command::Zunion(transform_parse(
+                Zunion::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "zinter" => Command::Zinter(transform_parse(
+                Zinter::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "zincrby" => Command::Zincryby(transform_parse(
                 Zincrby::parse_frames(&mut parse),
                 &mut parse,
             )),
             "auth" => Command::Auth(transform_parse(Auth::parse_frames(&mut parse), &mut parse)),
+            "acl" => Command::Acl(transform_parse(Acl::parse_frames(&mut parse), &mut parse)),
             "debug" => Command::Debug(transform_parse(Debug::parse_frames(&mut parse), &mut parse)),
             "cluster" => Command::Cluster(transform_parse(
                 Cluster::parse_frames(&mut parse),
                 &mut parse,
             )),
-            "readwrite" => Command::ReadWrite(transform_parse(
-                Fake::parse_frames(&mut parse, "readwrite"),
+            "readwrite" => {
+                Command::ReadWrite(transform_parse(Fake::parse_frames(&mut parse), &mut parse))
+            }
+            "readonly" => {
+                Command::ReadOnly(transform_parse(Fake::parse_frames(&mut parse), &mut parse))
+            }
+            "client" => Command::Client(transform_parse(
+                Client::parse_frames(&mut parse),
                 &mut parse,
             )),
-            "readonly" => Command::ReadOnly(transform_parse(
-                Fake::parse_frames(&mut parse, "readonly"),
+            "info" => Command::Info(transform_parse(Info::parse_frames(&mut parse), &mut parse)),
+            "multi" => Command::Multi(Multi::new()),
+            "exec" => Command::Exec(Multi::new()),
+            "discard" => Command::Discard(Multi::new()),
+            "reset" => Command::Reset(Multi::new()),
+            "quit" => Command::Quit(Multi::new()),
+            "watch" => Command::Watch(transform_parse(Watch::parse_frames(&mut parse), &mut parse)),
+            "unwatch" => Command::Unwatch(transform_parse(
+                Unwatch::parse_frames(&mut parse),
                 &mut parse,
             )),
-            "client" => Command::Client(transform_parse(
-                Fake::parse_frames(&mut parse, "client"),
+            "bulkload" => Command::Bulkload(transform_parse(
+                Bulkload::parse_frames(&mut parse),
                 &mut parse,
             )),
-            "info" => Command::Info(transform_parse(
-                Fake::parse_frames(&mut parse, "info"),
+            "monitor" => Command::Monitor(transform_parse(
+                Monitor::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "hello" => Command::Hello(transform_parse(Hello::parse_frames(&mut parse), &mut parse)),
+            "wait" => Command::Wait(transform_parse(Wait::parse_frames(&mut parse), &mut parse)),
+            "config" => Command::Config(transform_parse(
+                Config::parse_frames(&mut parse),
+                &mut parse,
+            )),
+            "slowlog" => Command::Slowlog(transform_parse(
+                Slowlog::parse_frames(&mut parse),
                 &mut parse,
             )),
-            "multi" => Command::Multi(Multi::new()),
-            "exec" => Command::Exec(Multi::new()),
-            "discard" => Command::Discard(Multi::new()),
             "scan" => Command::Scan(transform_parse(Scan::parse_frames(&mut parse), &mut parse)),
+            "randomkey" => Command::Randomkey(transform_parse(
+                Randomkey::parse_frames(&mut parse),
+                &mut parse,
+            )),
             "xscan" => Command::Scan(transform_parse(Scan::parse_frames(&mut parse), &mut parse)),
             _ => {
                 // The command is not recognized and an Unknown command is
                 // returned.
                 //
                 // `return` is called here to skip the `finish()` call below. As
-                // the command is not recognized, there is most likely
-                // unconsumed fields remaining in the `Parse` instance.
-                return Ok(Command::Unknown(Unknown::new(command_name)));
+                // the command is not recognized, the remaining fields of the
+                // `Parse` instance are taken as its arguments.
+                let mut args = vec![];
+                while let Ok(arg) = parse.next_bytes() {
+                    args.push(arg);
+                }
+                return Ok(Command::Unknown(Unknown::new(name, args)));
             }
         };
 
@@ -604,6 +940,20 @@ impl Command {
             "incrby" => Command::IncrBy(IncrDecr::parse_argv(argv, false)?),
             "decrby" => Command::DecrBy(IncrDecr::parse_argv(argv, false)?),
             "strlen" => Command::Strlen(Strlen::parse_argv(argv)?),
+            "bitcount" => Command::Bitcount(Bitcount::parse_argv(argv)?),
+            "bitpos" => Command::Bitpos(Bitpos::parse_argv(argv)?),
+            "bitop" => Command::Bitop(Bitop::parse_argv(argv)?),
+            "setbit" => Command::Setbit(Setbit::parse_argv(argv)?),
+            "getbit" => Command::Getbit(Getbit::parse_argv(argv)?),
+            "bitfield" => Command::Bitfield(Bitfield::parse_argv(argv)?),
+            "setrange" => Command::Setrange(Setrange::parse_argv(argv)?),
+            "getrange" => Command::Getrange(Getrange::parse_argv(argv)?),
+            "append" => Command::Append(Append::parse_argv(argv)?),
+            "incrbyfloat" => Command::Incrbyfloat(Incrbyfloat::parse_argv(argv)?),
+            "substr" => Command::Getrange(Getrange::parse_argv(argv)?),
+            "getdel" => Command::GetDel(GetDel::parse_argv(argv)?),
+            "getex" => Command::GetEx(GetEx::parse_argv(argv)?),
+            "copy" => Command::Copy(CopyKey::parse_argv(argv)?),
             "del" => Command::Del(Del::parse_argv(argv)?),
             "type" => Command::Type(Type::parse_argv(argv)?),
             "exists" => Command::Exists(Exists::parse_argv(argv)?),
@@ -615,6 +965,8 @@ impl Command {
             "mset" => Command::Mset(Mset::parse_argv(argv)?),
             "ttl" => Command::TTL(TTL::parse_argv(argv)?),
             "pttl" => Command::PTTL(TTL::parse_argv(argv)?),
+            "expiretime" => Command::Expiretime(Expiretime::parse_argv(argv)?),
+            "pexpiretime" => Command::Pexpiretime(Expiretime::parse_argv(argv)?),
             "expire" => Command::Expire(Expire::parse_argv(argv)?),
             "expireat" => Command::ExpireAt(Expire::parse_argv(argv)?),
             "pexpire" => Command::Pexpire(Expire::parse_argv(argv)?),
@@ -631,25 +983,35 @@ impl Command {
             "hkeys" => Command::Hkeys(Hkeys::parse_argv(argv)?),
             "hvals" => Command::Hvals(Hvals::parse_argv(argv)?),
             "hincrby" => Command::Hincrby(Hincrby::parse_argv(argv)?),
+            "hincrbyfloat" => Command::Hincrbyfloat(Hincrbyfloat::parse_argv(argv)?),
+            "hrandfield" => Command::Hrandfield(Hrandfield::parse_argv(argv)?),
+            "hscan" => Command::Hscan(Hscan::parse_argv(argv)?),
             "hexists" => Command::Hexists(Hexists::parse_argv(argv)?),
             "hstrlen" => Command::Hstrlen(Hstrlen::parse_argv(argv)?),
+            "hgetdel" => Command::Hgetdel(Hgetdel::parse_argv(argv)?),
             "lpush" => Command::Lpush(Push::parse_argv(argv)?),
             "rpush" => Command::Rpush(Push::parse_argv(argv)?),
             "lpop" => Command::Lpop(Pop::parse_argv(argv)?),
             "rpop" => Command::Rpop(Pop::parse_argv(argv)?),
+            "blpop" => Command::Blpop(Bpop::parse_argv(argv)?),
+            "brpop" => Command::Brpop(Bpop::parse_argv(argv)?),
+            "lmpop" => Command::Lmpop(Lmpop::parse_argv(argv)?),
             "lrange" => Command::Lrange(Lrange::parse_argv(argv)?),
             "llen" => Command::Llen(Llen::parse_argv(argv)?),
             "lindex" => Command::Lindex(Lindex::parse_argv(argv)?),
             "lset" => Command::Lset(Lset::parse_argv(argv)?),
             "ltrim" => Command::Ltrim(Ltrim::parse_argv(argv)?),
             "lrem" => Command::Lrem(Lrem::parse_argv(argv)?),
+            "lpos" => Command::Lpos(Lpos::parse_argv(argv)?),
             "linsert" => Command::Linsert(Linsert::parse_argv(argv)?),
             "sadd" => Command::Sadd(Sadd::parse_argv(argv)?),
             "scard" => Command::Scard(Scard::parse_argv(argv)?),
+            "sintercard" => Command::Sintercard(Sintercard::parse_argv(argv)?),
             "sismember" => Command::Sismember(Sismember::parse_argv(argv)?),
             "smismember" => Command::Smismember(Smismember::parse_argv(argv)?),
             "smembers" => Command::Smembers(Smembers::parse_argv(argv)?),
             "srandmember" => Command::Srandmember(Srandmember::parse_argv(argv)?),
+            "sscan" => Command::Sscan(Sscan::parse_argv(argv)?),
             "spop" => Command::Spop(Spop::parse_argv(argv)?),
             "srem" => Command::Srem(Srem::parse_argv(argv)?),
             "zadd" => Command::Zadd(Zadd::parse_argv(argv)?),
@@ -659,20 +1021,29 @@ impl Command {
             "zremrangebyscore" => Command::Zremrangebyscore(Zremrangebyscore::parse_argv(argv)?),
             "zremrangebyrank" => Command::Zremrangebyrank(Zremrangebyrank::parse_argv(argv)?),
             "zrange" => Command::Zrange(Zrange::parse_argv(argv)?),
+            "zrangestore" => Command::Zrangestore(Zrangestore::parse_argv(argv)?),
             "zrevrange" => Command::Zrevrange(Zrevrange::parse_argv(argv)?),
             "zrangebyscore" => Command::Zrangebyscore(Zrangebyscore::parse_argv(argv)?),
             "zrevrangebyscore" => Command::Zrevrangebyscore(Zrangebyscore::parse_argv(argv)?),
             "zcount" => Command::Zcount(Zcount::parse_argv(argv)?),
             "zpopmin" => Command::Zpopmin(Zpop::parse_argv(argv)?),
             "zpopmax" => Command::Zpopmax(Zpop::parse_argv(argv)?),
+            "zmpop" => Command::Zmpop(Zmpop::parse_argv(argv)?),
             "zrank" => Command::Zrank(Zrank::parse_argv(argv)?),
+            "zrandmember" => Command::Zrandmember(Zrandmember::parse_argv(argv)?),
+            "zscan" => Command::Zscan(Zscan::parse_argv(argv)?),
+            "zdiff" => Command::Zdiff(Zdiff::parse_argv(argv)?),
+            "zdiffstore" => Command::Zdiffstore(Zdiffstore::parse_argv(argv)?),
+            "zunion" => Command::Zunion(Zunion::parse_argv(argv)?),
+            "zinter" => Command::Zinter(Zinter::parse_argv(argv)?),
             "zincrby" => Command::Zincryby(Zincrby::parse_argv(argv)?),
             "scan" => Command::Scan(Scan::parse_argv(argv)?),
+            "randomkey" => Command::Randomkey(Randomkey::parse_argv(argv)?),
             "xscan" => Command::Scan(Scan::parse_argv(argv)?),
             _ => {
                 // The command is not recognized and an Unknown command is
                 // returned.
-                return Ok(Command::Unknown(Unknown::new(command_name)));
+                return Ok(Command::Unknown(Unknown::new(cmd_name, argv.clone())));
             }
         };
 
@@ -689,9 +1060,10 @@ impl Command {
         db: &Db,
         topo: &Topo,
         dst: &mut Connection,
-        cur_client: Arc<Mutex<Client>>,
-        clients: Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>,
+        cur_client: Arc<Mutex<ClientInfo>>,
+        clients: Clients,
         lua: &mut Option<Lua>,
+        user: &str,
         shutdown: &mut Shutdown,
     ) -> crate::Result<()> {
         use Command::*;
@@ -703,24 +1075,39 @@ impl Command {
             Set(cmd) => cmd.apply(dst).await,
             SetNX(cmd) => cmd.apply(dst).await,
             SetEX(cmd) => cmd.apply(dst).await,
-            Subscribe(cmd) => cmd.apply(db, dst, shutdown).await,
             Ping(cmd) => cmd.apply(dst).await,
             Type(cmd) => cmd.apply(dst).await,
             Mget(cmd) => cmd.apply(dst).await,
             Mset(cmd) => cmd.apply(dst).await,
             TTL(cmd) => cmd.apply(dst, false).await,
             PTTL(cmd) => cmd.apply(dst, true).await,
+            Expiretime(cmd) => cmd.apply(dst, false).await,
+            Pexpiretime(cmd) => cmd.apply(dst, true).await,
             Expire(cmd) => cmd.apply(dst, false, false).await,
             ExpireAt(cmd) => cmd.apply(dst, false, true).await,
             Pexpire(cmd) => cmd.apply(dst, true, false).await,
             PexpireAt(cmd) => cmd.apply(dst, true, true).await,
             Persist(cmd) => cmd.apply(dst).await,
             Exists(cmd) => cmd.apply(dst).await,
+            Object(cmd) => cmd.apply(dst).await,
             Incr(cmd) => cmd.apply(dst, true).await,
             Decr(cmd) => cmd.apply(dst, false).await,
             IncrBy(cmd) => cmd.apply(dst, true).await,
             DecrBy(cmd) => cmd.apply(dst, false).await,
             Strlen(cmd) => cmd.apply(dst).await,
+            Bitcount(cmd) => cmd.apply(dst).await,
+            Bitpos(cmd) => cmd.apply(dst).await,
+            Bitop(cmd) => cmd.apply(dst).await,
+            Setbit(cmd) => cmd.apply(dst).await,
+            Getbit(cmd) => cmd.apply(dst).await,
+            Bitfield(cmd) => cmd.apply(dst).await,
+            Setrange(cmd) => cmd.apply(dst).await,
+            Getrange(cmd) => cmd.apply(dst).await,
+            Append(cmd) => cmd.apply(dst).await,
+            Incrbyfloat(cmd) => cmd.apply(dst).await,
+            GetDel(cmd) => cmd.apply(dst).await,
+            GetEx(cmd) => cmd.apply(dst).await,
+            Copy(cmd) => cmd.apply(dst).await,
             Hset(cmd) => cmd.apply(dst, false, false).await,
             Hmset(cmd) => cmd.apply(dst, true, false).await,
             Hsetnx(cmd) => cmd.apply(dst, false, true).await,
@@ -732,28 +1119,37 @@ impl Command {
             Hkeys(cmd) => cmd.apply(dst).await,
             Hvals(cmd) => cmd.apply(dst).await,
             Hincrby(cmd) => cmd.apply(dst).await,
+            Hincrbyfloat(cmd) => cmd.apply(dst).await,
+            Hrandfield(cmd) => cmd.apply(dst).await,
+            Hscan(cmd) => cmd.apply(dst).await,
             Hexists(cmd) => cmd.apply(dst).await,
             Hstrlen(cmd) => cmd.apply(dst).await,
+            Hgetdel(cmd) => cmd.apply(dst).await,
             Lpush(cmd) => cmd.apply(dst, true).await,
             Rpush(cmd) => cmd.apply(dst, false).await,
             Lpop(cmd) => cmd.apply(dst, true).await,
             Rpop(cmd) => cmd.apply(dst, false).await,
+            Blpop(cmd) => cmd.apply(db, dst, shutdown, true).await,
+            Brpop(cmd) => cmd.apply(db, dst, shutdown, false).await,
             Lrange(cmd) => cmd.apply(dst).await,
             Llen(cmd) => cmd.apply(dst).await,
             Lindex(cmd) => cmd.apply(dst).await,
             Lset(cmd) => cmd.apply(dst).await,
             Ltrim(cmd) => cmd.apply(dst).await,
             Lrem(cmd) => cmd.apply(dst).await,
+            Lpos(cmd) => cmd.apply(dst).await,
             Linsert(cmd) => cmd.apply(dst).await,
-            Eval(cmd) => cmd.apply(dst, false, db, lua).await,
-            Evalsha(cmd) => cmd.apply(dst, true, db, lua).await,
+            Eval(cmd) => cmd.apply(dst, false, db, lua, user).await,
+            Evalsha(cmd) => cmd.apply(dst, true, db, lua, user).await,
             Script(cmd) => cmd.apply(dst, db).await,
             Sadd(cmd) => cmd.apply(dst).await,
             Scard(cmd) => cmd.apply(dst).await,
+            Sintercard(cmd) => cmd.apply(dst).await,
             Sismember(cmd) => cmd.apply(dst).await,
             Smismember(cmd) => cmd.apply(dst).await,
             Smembers(cmd) => cmd.apply(dst).await,
             Srandmember(cmd) => cmd.apply(dst).await,
+            Sscan(cmd) => cmd.apply(dst).await,
             Spop(cmd) => cmd.apply(dst).await,
             Srem(cmd) => cmd.apply(dst).await,
             Zadd(cmd) => cmd.apply(dst).await,
@@ -763,30 +1159,44 @@ impl Command {
             Zremrangebyscore(cmd) => cmd.apply(dst).await,
             Zremrangebyrank(cmd) => cmd.apply(dst).await,
             Zrange(cmd) => cmd.apply(dst).await,
+            Zrangestore(cmd) => cmd.apply(dst).await,
             Zrevrange(cmd) => cmd.apply(dst).await,
             Zrangebyscore(cmd) => cmd.apply(dst, false).await,
             Zrevrangebyscore(cmd) => cmd.apply(dst, true).await,
             Zcount(cmd) => cmd.apply(dst).await,
             Zpopmin(cmd) => cmd.apply(dst, true).await,
             Zpopmax(cmd) => cmd.apply(dst, false).await,
+            Zmpop(cmd) => cmd.apply(dst).await,
+            Lmpop(cmd) => cmd.apply(dst).await,
             Zrank(cmd) => cmd.apply(dst).await,
+            Zrandmember(cmd) => cmd.apply(dst).await,
+            Zscan(cmd) => cmd.apply(dst).await,
+            Zdiff(cmd) => cmd.apply(dst).await,
+            Zdiffstore(cmd) => cmd.apply(dst).await,
+            Zunion(cmd) => cmd.apply(dst).await,
+            Zinter(cmd) => cmd.apply(dst).await,
             Zincryby(cmd) => cmd.apply(dst).await,
 
             Debug(cmd) => cmd.apply(dst).await,
 
             Cluster(cmd) => cmd.apply(topo, dst).await,
-            ReadWrite(cmd) => cmd.apply("readwrite", dst, cur_client, clients).await,
-            ReadOnly(cmd) => cmd.apply("readonly", dst, cur_client, clients).await,
-            Client(cmd) => cmd.apply("client", dst, cur_client, clients).await,
-            Info(cmd) => cmd.apply("info", dst, cur_client, clients).await,
+            ReadWrite(cmd) => cmd.apply("readwrite", dst).await,
+            ReadOnly(cmd) => cmd.apply("readonly", dst).await,
+            Client(cmd) => cmd.apply(dst, cur_client, clients).await,
+            Info(cmd) => cmd.apply(dst, clients).await,
+
+            Monitor(cmd) => cmd.apply(db, dst, shutdown).await,
+
+            Hello(cmd) => cmd.apply(dst, cur_client).await,
+            Wait(cmd) => cmd.apply(dst).await,
+            Config(cmd) => cmd.apply(dst).await,
+            Slowlog(cmd) => cmd.apply(dst).await,
 
             Scan(cmd) => cmd.apply(dst).await,
+            Randomkey(cmd) => cmd.apply(dst).await,
             Xscan(cmd) => cmd.apply(dst).await,
 
             Unknown(cmd) => cmd.apply(dst).await,
-            // `Unsubscribe` cannot be applied. It may only be received from the
-            // context of a `Subscribe` command.
-            Unsubscribe(_) => Err("`Unsubscribe` is unsupported in this context".into()),
 
             _ => Ok(()),
         }
@@ -803,23 +1213,41 @@ impl Command {
             Command::SetEX(_) => "setex",
             Command::Subscribe(_) => "subscribe",
             Command::Unsubscribe(_) => "unsubscribe",
+            Command::Psubscribe(_) => "psubscribe",
+            Command::Punsubscribe(_) => "punsubscribe",
             Command::Ping(_) => "ping",
             Command::Type(_) => "type",
             Command::Mget(_) => "mget",
             Command::Mset(_) => "mset",
             Command::TTL(_) => "ttl",
             Command::PTTL(_) => "pttl",
+            Command::Expiretime(_) => "expiretime",
+            Command::Pexpiretime(_) => "pexpiretime",
             Command::Expire(_) => "expire",
             Command::ExpireAt(_) => "expireat",
             Command::Pexpire(_) => "pexpire",
             Command::PexpireAt(_) => "pexpireat",
             Command::Persist(_) => "persist",
             Command::Exists(_) => "exists",
+            Command::Object(_) => "object",
             Command::Incr(_) => "incr",
             Command::Decr(_) => "decr",
             Command::IncrBy(_) => "incrby",
             Command::DecrBy(_) => "decrby",
             Command::Strlen(_) => "strlen",
+            Command::Bitcount(_) => "bitcount",
+            Command::Bitpos(_) => "bitpos",
+            Command::Bitop(_) => "bitop",
+            Command::Setbit(_) => "setbit",
+            Command::Getbit(_) => "getbit",
+            Command::Bitfield(_) => "bitfield",
+            Command::Setrange(_) => "setrange",
+            Command::Getrange(_) => "getrange",
+            Command::Append(_) => "append",
+            Command::Incrbyfloat(_) => "incrbyfloat",
+            Command::GetDel(_) => "getdel",
+            Command::GetEx(_) => "getex",
+            Command::Copy(_) => "copy",
             Command::Hset(_) => "hset",
             Command::Hmset(_) => "hmset",
             Command::Hsetnx(_) => "hsetnx",
@@ -831,28 +1259,38 @@ impl Command {
             Command::Hkeys(_) => "hkeys",
             Command::Hvals(_) => "hvals",
             Command::Hincrby(_) => "hincrby",
+            Command::Hincrbyfloat(_) => "hincrbyfloat",
+            Command::Hrandfield(_) => "hrandfield",
+            Command::Hscan(_) => "hscan",
             Command::Hexists(_) => "hexists",
             Command::Hstrlen(_) => "hstrlen",
+            Command::Hgetdel(_) => "hgetdel",
             Command::Lpush(_) => "lpush",
             Command::Rpush(_) => "rpush",
             Command::Lpop(_) => "lpop",
             Command::Rpop(_) => "rpop",
+            Command::Blpop(_) => "blpop",
+            Command::Brpop(_) => "brpop",
+            Command::Lmpop(_) => "lmpop",
             Command::Lrange(_) => "lrange",
             Command::Llen(_) => "llen",
             Command::Lindex(_) => "lindex",
             Command::Lset(_) => "lset",
             Command::Ltrim(_) => "ltrim",
             Command::Lrem(_) => "lrem",
+            Command::Lpos(_) => "lpos",
             Command::Linsert(_) => "linsert",
             Command::Eval(_) => "eval",
             Command::Evalsha(_) => "evalsha",
             Command::Script(_) => "script",
             Command::Sadd(_) => "sadd",
             Command::Scard(_) => "scard",
+            Command::Sintercard(_) => "sintercard",
             Command::Sismember(_) => "sismember",
             Command::Smismember(_) => "smismember",
             Command::Smembers(_) => "smembers",
             Command::Srandmember(_) => "srandmember",
+            Command::Sscan(_) => "sscan",
             Command::Spop(_) => "spop",
             Command::Srem(_) => "srem",
             Command::Zadd(_) => "zadd",
@@ -862,15 +1300,24 @@ impl Command {
             Command::Zremrangebyscore(_) => "zremrangebyscore",
             Command::Zremrangebyrank(_) => "zremrangebyrank",
             Command::Zrange(_) => "zrange",
+            Command::Zrangestore(_) => "zrangestore",
             Command::Zrevrange(_) => "zrevrange",
             Command::Zrangebyscore(_) => "zrangebyscore",
             Command::Zrevrangebyscore(_) => "zrevrangebyscore",
             Command::Zcount(_) => "zcount",
             Command::Zpopmin(_) => "zpopmin",
             Command::Zpopmax(_) => "zpopmax",
+            Command::Zmpop(_) => "zmpop",
             Command::Zrank(_) => "zrank",
+            Command::Zrandmember(_) => "zrandmember",
+            Command::Zscan(_) => "zscan",
+            Command::Zdiff(_) => "zdiff",
+            Command::Zdiffstore(_) => "zdiffstore",
+            Command::Zunion(_) => "zunion",
+            Command::Zinter(_) => "zinter",
             Command::Zincryby(_) => "zincrby",
             Command::Auth(_) => "auth",
+            Command::Acl(_) => "acl",
             Command::Debug(_) => "debug",
             Command::Cluster(_) => "cluster",
             Command::ReadWrite(_) => "readwrite",
@@ -880,7 +1327,18 @@ impl Command {
             Command::Multi(_) => "multi",
             Command::Exec(_) => "exec",
             Command::Discard(_) => "discard",
+            Command::Reset(_) => "reset",
+            Command::Quit(_) => "quit",
+            Command::Watch(_) => "watch",
+            Command::Unwatch(_) => "unwatch",
+            Command::Bulkload(_) => "bulkload",
+            Command::Monitor(_) => "monitor",
+            Command::Hello(_) => "hello",
+            Command::Wait(_) => "wait",
+            Command::Config(_) => "config",
+            Command::Slowlog(_) => "slowlog",
             Command::Scan(_) => "scan",
+            Command::Randomkey(_) => "randomkey",
             Command::Xscan(_) => "xscan",
             Command::Unknown(cmd) => cmd.get_name(),
         }
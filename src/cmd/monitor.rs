@@ -0,0 +1,118 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::LOGGER;
+use crate::utils::{resp_invalid_arguments, resp_ok};
+use crate::{Connection, Db, Frame, Shutdown};
+use bytes::Bytes;
+use slog::debug;
+use tokio::select;
+
+/// Stream every command processed by the server to this connection until
+/// it disconnects.
+///
+/// ```text
+/// MONITOR
+/// ```
+///
+/// Each command is sent as a status reply in the redis format, e.g.
+/// `1339518083.107412 [0 127.0.0.1:60866] "set" "key" "value"`.
+#[derive(Debug, Clone)]
+pub struct Monitor {
+    valid: bool,
+}
+
+impl Monitor {
+    pub fn new() -> Monitor {
+        Monitor { valid: true }
+    }
+
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> crate::Result<Monitor> {
+        Ok(Monitor::new())
+    }
+
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        shutdown: &mut Shutdown,
+    ) -> crate::Result<()> {
+        if !self.valid {
+            dst.write_frame(&resp_invalid_arguments()).await?;
+            return Ok(());
+        }
+
+        let mut receiver = db.monitor();
+        dst.write_frame(&resp_ok()).await?;
+        debug!(LOGGER, "monitor attached, {}", dst.peer_addr());
+
+        loop {
+            select! {
+                line = receiver.recv() => {
+                    match line {
+                        Some(line) => {
+                            let line = String::from_utf8_lossy(&line).to_string();
+                            dst.write_frame(&Frame::Simple(line)).await?;
+                        }
+                        None => return Ok(()),
+                    }
+                }
+                res = dst.read_frame() => {
+                    // commands of the monitor connection are ignored, the
+                    // monitor stops when the peer disconnects
+                    if res?.is_none() {
+                        return Ok(());
+                    }
+                }
+                _ = shutdown.recv() => {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Invalid for Monitor {
+    fn new_invalid() -> Monitor {
+        Monitor { valid: false }
+    }
+}
+
+/// Format a processed command for the monitors, arguments are quoted and
+/// escaped the same way as redis does.
+pub(crate) fn monitor_line(peer_addr: &str, argv: &[Bytes]) -> Bytes {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let mut line = format!(
+        "{}.{:06} [0 {}]",
+        now.as_secs(),
+        now.subsec_micros(),
+        peer_addr
+    );
+    for arg in argv {
+        line.push(' ');
+        line.push('"');
+        for &b in arg.iter() {
+            match b {
+                b'\\' => line.push_str("\\\\"),
+                b'"' => line.push_str("\\\""),
+                b'\n' => line.push_str("\\n"),
+                b'\r' => line.push_str("\\r"),
+                b'\t' => line.push_str("\\t"),
+                0x07 => line.push_str("\\a"),
+                0x08 => line.push_str("\\b"),
+                0x20..=0x7e => line.push(b as char),
+                _ => line.push_str(&format!("\\x{:02x}", b)),
+            }
+        }
+        line.push('"');
+    }
+    Bytes::from(line)
+}
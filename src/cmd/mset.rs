@@ -39,6 +39,10 @@ impl Mset {
         &self.vals
     }
 
+    pub fn valid(&self) -> bool {
+        self.valid
+    }
+
     pub fn add_key(&mut self, key: String) {
         self.keys.push(key);
     }
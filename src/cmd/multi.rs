@@ -1,11 +1,18 @@
 use std::sync::Arc;
 
 use slog::{debug, error};
+use tikv_client::{Error, Timestamp, TimestampExt, Transaction};
 use tokio::sync::Mutex;
 
 use crate::{
     config::LOGGER,
-    tikv::{errors::REDIS_EXEC_ERR, get_txn_client},
+    tikv::{
+        client::TxnClientWrapper,
+        errors::{AsyncResult, REDIS_EXEC_ERR},
+        get_txn_client,
+        string::read_key_pairs,
+        KEY_ENCODER,
+    },
     utils::{resp_array, resp_err, resp_invalid_arguments, resp_nil},
     Command, Connection, Frame,
 };
@@ -18,91 +25,38 @@ impl Multi {
         Multi {}
     }
 
-    pub async fn exec(self, dst: &mut Connection, cmds: Vec<Command>) -> crate::Result<()> {
+    /// Run the queued commands in one transaction. If any of the `watched`
+    /// keys changed since it was watched, nothing is run and a nil reply is
+    /// written.
+    pub async fn exec(
+        self,
+        dst: &mut Connection,
+        cmds: Vec<Command>,
+        watched: Vec<(String, u64)>,
+    ) -> crate::Result<()> {
         let mut resp_arr = Vec::with_capacity(cmds.len());
 
         // create new txn
         let client = get_txn_client()?;
-        let txn = client.begin().await?;
+        let mut txn = client.begin().await?;
+        if watched_keys_changed(&client, &mut txn, &watched).await? {
+            txn.rollback().await?;
+            debug!(
+                LOGGER,
+                "res, {} -> {}, watched keys changed",
+                dst.local_addr(),
+                dst.peer_addr()
+            );
+            dst.write_frame(&resp_nil()).await?;
+            return Ok(());
+        }
         let txn_rc = Some(Arc::new(Mutex::new(txn)));
 
         let mut response = resp_nil();
         let mut abort_on_error = false;
 
         for cmd in cmds {
-            let result = match cmd {
-                Command::Incr(mut cmd) => cmd.incr_by(txn_rc.clone(), true).await,
-                Command::IncrBy(mut cmd) => cmd.incr_by(txn_rc.clone(), true).await,
-                Command::Decr(mut cmd) => cmd.incr_by(txn_rc.clone(), false).await,
-                Command::DecrBy(mut cmd) => cmd.incr_by(txn_rc.clone(), false).await,
-                Command::Strlen(cmd) => cmd.strlen(txn_rc.clone()).await,
-                Command::Del(cmd) => cmd.del(txn_rc.clone()).await,
-                Command::Exists(cmd) => cmd.exists(txn_rc.clone()).await,
-                Command::Get(cmd) => cmd.get(txn_rc.clone()).await,
-                Command::Set(cmd) => cmd.set(txn_rc.clone()).await,
-                Command::SetNX(cmd) => cmd.put_not_exists(txn_rc.clone()).await,
-                Command::SetEX(cmd) => cmd.setex(txn_rc.clone()).await,
-                Command::Mget(cmd) => cmd.batch_get(txn_rc.clone()).await,
-                Command::Mset(cmd) => cmd.batch_put(txn_rc.clone()).await,
-                Command::Type(cmd) => cmd.cmd_type(txn_rc.clone()).await,
-                Command::TTL(cmd) => cmd.ttl(false, txn_rc.clone()).await,
-                Command::PTTL(cmd) => cmd.ttl(true, txn_rc.clone()).await,
-                Command::Expire(cmd) => cmd.expire(false, false, txn_rc.clone()).await,
-                Command::ExpireAt(cmd) => cmd.expire(false, true, txn_rc.clone()).await,
-                Command::Pexpire(cmd) => cmd.expire(true, false, txn_rc.clone()).await,
-                Command::PexpireAt(cmd) => cmd.expire(true, true, txn_rc.clone()).await,
-                Command::Persist(cmd) => cmd.persist(txn_rc.clone()).await,
-                Command::Hset(cmd) => cmd.hset(txn_rc.clone(), false, false).await,
-                Command::Hmset(cmd) => cmd.hset(txn_rc.clone(), true, false).await,
-                Command::Hsetnx(cmd) => cmd.hset(txn_rc.clone(), false, true).await,
-                Command::Hget(cmd) => cmd.hget(txn_rc.clone()).await,
-                Command::Hmget(cmd) => cmd.hmget(txn_rc.clone()).await,
-                Command::Hlen(cmd) => cmd.hlen(txn_rc.clone()).await,
-                Command::Hgetall(cmd) => cmd.hgetall(txn_rc.clone()).await,
-                Command::Hdel(cmd) => cmd.hdel(txn_rc.clone()).await,
-                Command::Hkeys(cmd) => cmd.hkeys(txn_rc.clone()).await,
-                Command::Hvals(cmd) => cmd.hvals(txn_rc.clone()).await,
-                Command::Hincrby(cmd) => cmd.hincrby(txn_rc.clone()).await,
-                Command::Hexists(cmd) => cmd.hexists(txn_rc.clone()).await,
-                Command::Hstrlen(cmd) => cmd.hstrlen(txn_rc.clone()).await,
-                Command::Lpush(cmd) => cmd.push(txn_rc.clone(), true).await,
-                Command::Rpush(cmd) => cmd.push(txn_rc.clone(), false).await,
-                Command::Lpop(cmd) => cmd.pop(txn_rc.clone(), true).await,
-                Command::Rpop(cmd) => cmd.pop(txn_rc.clone(), false).await,
-                Command::Lrange(cmd) => cmd.lrange(txn_rc.clone()).await,
-                Command::Llen(cmd) => cmd.llen(txn_rc.clone()).await,
-                Command::Lindex(cmd) => cmd.lindex(txn_rc.clone()).await,
-                Command::Lset(cmd) => cmd.lset(txn_rc.clone()).await,
-                Command::Ltrim(cmd) => cmd.ltrim(txn_rc.clone()).await,
-                Command::Lrem(cmd) => cmd.lrem(txn_rc.clone()).await,
-                Command::Linsert(cmd) => cmd.linsert(txn_rc.clone()).await,
-                Command::Sadd(cmd) => cmd.sadd(txn_rc.clone()).await,
-                Command::Scard(cmd) => cmd.scard(txn_rc.clone()).await,
-                Command::Sismember(cmd) => cmd.sismember(txn_rc.clone()).await,
-                Command::Smismember(cmd) => cmd.smismember(txn_rc.clone()).await,
-                Command::Smembers(cmd) => cmd.smembers(txn_rc.clone()).await,
-                Command::Srandmember(cmd) => cmd.srandmember(txn_rc.clone()).await,
-                Command::Spop(cmd) => cmd.spop(txn_rc.clone()).await,
-                Command::Srem(cmd) => cmd.srem(txn_rc.clone()).await,
-                Command::Zadd(cmd) => cmd.zadd(txn_rc.clone()).await,
-                Command::Zcard(cmd) => cmd.zcard(txn_rc.clone()).await,
-                Command::Zscore(cmd) => cmd.zscore(txn_rc.clone()).await,
-                Command::Zrem(cmd) => cmd.zrem(txn_rc.clone()).await,
-                Command::Zremrangebyscore(cmd) => cmd.zremrangebyscore(txn_rc.clone()).await,
-                Command::Zremrangebyrank(cmd) => cmd.zremrangebyrank(txn_rc.clone()).await,
-                Command::Zrange(cmd) => cmd.zrange(txn_rc.clone()).await,
-                Command::Zrevrange(cmd) => cmd.zrevrange(txn_rc.clone()).await,
-                Command::Zrangebyscore(cmd) => cmd.zrangebyscore(txn_rc.clone(), false).await,
-                Command::Zrevrangebyscore(cmd) => cmd.zrangebyscore(txn_rc.clone(), true).await,
-                Command::Zcount(cmd) => cmd.zcount(txn_rc.clone()).await,
-                Command::Zpopmin(cmd) => cmd.zpop(txn_rc.clone(), true).await,
-                Command::Zpopmax(cmd) => cmd.zpop(txn_rc.clone(), false).await,
-                Command::Zrank(cmd) => cmd.zrank(txn_rc.clone()).await,
-                Command::Zincryby(cmd) => cmd.zincrby(txn_rc.clone()).await,
-                Command::Scan(cmd) => cmd.scan(txn_rc.clone()).await,
-                Command::Xscan(cmd) => cmd.scan(txn_rc.clone()).await,
-                _ => Ok(resp_invalid_arguments()),
-            };
+            let result = exec_cmd_in_txn(cmd, txn_rc.clone()).await;
             match result {
                 Ok(resp) => {
                     // check response error
@@ -125,8 +79,15 @@ impl Multi {
         }
 
         if !abort_on_error {
-            response = resp_array(resp_arr);
-            txn_rc.unwrap().lock().await.commit().await?;
+            match txn_rc.unwrap().lock().await.commit().await {
+                Ok(_) => response = resp_array(resp_arr),
+                // a watched key written after it was compared conflicts with
+                // its lock, nothing is applied
+                Err(e) if !watched.is_empty() && is_commit_conflict(&e) => {
+                    error!(LOGGER, "EXEC of watched keys aborted {}", e);
+                }
+                Err(e) => return Err(e.into()),
+            }
         } else {
             txn_rc.unwrap().lock().await.rollback().await?;
         }
@@ -142,3 +103,155 @@ impl Multi {
         Ok(())
     }
 }
+
+/// Whether the commit failed because another transaction wrote or locked one
+/// of its keys, rather than for an error of the store itself.
+fn is_commit_conflict(err: &Error) -> bool {
+    match err {
+        Error::KeyError(e) => e.conflict.is_some() || e.locked.is_some(),
+        Error::MultipleKeyErrors(errs) => errs.iter().any(is_commit_conflict),
+        _ => false,
+    }
+}
+
+/// A watched key changed if what the transaction reads of it differs from its
+/// snapshot at the timestamp of `WATCH`. The keys compared are locked, so that
+/// a write committed to them before the transaction fails its commit.
+async fn watched_keys_changed(
+    client: &TxnClientWrapper,
+    txn: &mut Transaction,
+    watched: &[(String, u64)],
+) -> AsyncResult<bool> {
+    for (key, version) in watched {
+        let mut snapshot = client.begin_at(Timestamp::from_version(*version));
+        let before = read_key_pairs(&mut snapshot, key, u32::MAX).await?;
+        let now = read_key_pairs(txn, key, u32::MAX).await?;
+        if before != now {
+            return Ok(true);
+        }
+        // the meta key is locked even if the key does not exist, so that the
+        // creation of the key conflicts
+        let mut locked = vec![KEY_ENCODER.encode_txnkv_meta_key(key)];
+        locked.extend(now.into_iter().skip(1).map(|kv| kv.0));
+        txn.lock_keys(locked).await?;
+    }
+    Ok(false)
+}
+
+/// Execute a single command within the given transaction and return its response frame.
+///
+/// Commands which can not be executed inside a transaction return invalid arguments.
+pub(crate) async fn exec_cmd_in_txn(
+    cmd: Command,
+    txn_rc: Option<Arc<Mutex<Transaction>>>,
+) -> AsyncResult<Frame> {
+    match cmd {
+        Command::Incr(mut cmd) => cmd.incr_by(txn_rc, true).await,
+        Command::IncrBy(mut cmd) => cmd.incr_by(txn_rc, true).await,
+        Command::Decr(mut cmd) => cmd.incr_by(txn_rc, false).await,
+        Command::DecrBy(mut cmd) => cmd.incr_by(txn_rc, false).await,
+        Command::Strlen(cmd) => cmd.strlen(txn_rc).await,
+        Command::Bitcount(cmd) => cmd.bitcount(txn_rc).await,
+        Command::Bitpos(cmd) => cmd.bitpos(txn_rc).await,
+        Command::Bitop(cmd) => cmd.bitop(txn_rc).await,
+        Command::Setbit(cmd) => cmd.setbit(txn_rc).await,
+        Command::Getbit(cmd) => cmd.getbit(txn_rc).await,
+        Command::Bitfield(cmd) => cmd.bitfield(txn_rc).await,
+        Command::Setrange(cmd) => cmd.setrange(txn_rc).await,
+        Command::Getrange(cmd) => cmd.getrange(txn_rc).await,
+        Command::Append(cmd) => cmd.append(txn_rc).await,
+        Command::Incrbyfloat(cmd) => cmd.incrbyfloat(txn_rc).await,
+        Command::GetDel(cmd) => cmd.getdel(txn_rc).await,
+        Command::GetEx(cmd) => cmd.getex(txn_rc).await,
+        Command::Copy(cmd) => cmd.copy(txn_rc).await,
+        Command::Del(cmd) => cmd.del(txn_rc).await,
+        Command::Exists(cmd) => cmd.exists(txn_rc).await,
+        Command::Object(cmd) => cmd.object(txn_rc).await,
+        Command::Get(cmd) => cmd.get(txn_rc).await,
+        Command::Set(cmd) => cmd.set(txn_rc).await,
+        Command::SetNX(cmd) => cmd.put_not_exists(txn_rc).await,
+        Command::SetEX(cmd) => cmd.setex(txn_rc).await,
+        Command::Mget(cmd) => cmd.batch_get(txn_rc).await,
+        Command::Mset(cmd) => cmd.batch_put(txn_rc).await,
+        Command::Type(cmd) => cmd.cmd_type(txn_rc).await,
+        Command::TTL(cmd) => cmd.ttl(false, txn_rc).await,
+        Command::PTTL(cmd) => cmd.ttl(true, txn_rc).await,
+        Command::Expiretime(cmd) => cmd.expiretime(false, txn_rc).await,
+        Command::Pexpiretime(cmd) => cmd.expiretime(true, txn_rc).await,
+        Command::Expire(cmd) => cmd.expire(false, false, txn_rc).await,
+        Command::ExpireAt(cmd) => cmd.expire(false, true, txn_rc).await,
+        Command::Pexpire(cmd) => cmd.expire(true, false, txn_rc).await,
+        Command::PexpireAt(cmd) => cmd.expire(true, true, txn_rc).await,
+        Command::Persist(cmd) => cmd.persist(txn_rc).await,
+        Command::Hset(cmd) => cmd.hset(txn_rc, false, false).await,
+        Command::Hmset(cmd) => cmd.hset(txn_rc, true, false).await,
+        Command::Hsetnx(cmd) => cmd.hset(txn_rc, false, true).await,
+        Command::Hget(cmd) => cmd.hget(txn_rc).await,
+        Command::Hmget(cmd) => cmd.hmget(txn_rc).await,
+        Command::Hlen(cmd) => cmd.hlen(txn_rc).await,
+        Command::Hgetall(cmd) => cmd.hgetall(txn_rc).await,
+        Command::Hdel(cmd) => cmd.hdel(txn_rc).await,
+        Command::Hkeys(cmd) => cmd.hkeys(txn_rc).await,
+        Command::Hvals(cmd) => cmd.hvals(txn_rc).await,
+        Command::Hincrby(cmd) => cmd.hincrby(txn_rc).await,
+        Command::Hincrbyfloat(cmd) => cmd.hincrbyfloat(txn_rc).await,
+        Command::Hrandfield(cmd) => cmd.hrandfield(txn_rc).await,
+        Command::Hscan(cmd) => cmd.hscan(txn_rc).await,
+        Command::Hexists(cmd) => cmd.hexists(txn_rc).await,
+        Command::Hstrlen(cmd) => cmd.hstrlen(txn_rc).await,
+        Command::Hgetdel(cmd) => cmd.hgetdel(txn_rc).await,
+        Command::Lpush(cmd) => cmd.push(txn_rc, true).await,
+        Command::Rpush(cmd) => cmd.push(txn_rc, false).await,
+        Command::Lpop(cmd) => cmd.pop(txn_rc, true).await,
+        Command::Rpop(cmd) => cmd.pop(txn_rc, false).await,
+        Command::Blpop(cmd) => cmd.bpop(txn_rc, true).await,
+        Command::Brpop(cmd) => cmd.bpop(txn_rc, false).await,
+        Command::Lmpop(cmd) => cmd.lmpop(txn_rc).await,
+        Command::Lrange(cmd) => cmd.lrange(txn_rc).await,
+        Command::Llen(cmd) => cmd.llen(txn_rc).await,
+        Command::Lindex(cmd) => cmd.lindex(txn_rc).await,
+        Command::Lset(cmd) => cmd.lset(txn_rc).await,
+        Command::Ltrim(cmd) => cmd.ltrim(txn_rc).await,
+        Command::Lrem(cmd) => cmd.lrem(txn_rc).await,
+        Command::Lpos(cmd) => cmd.lpos(txn_rc).await,
+        Command::Linsert(cmd) => cmd.linsert(txn_rc).await,
+        Command::Sadd(cmd) => cmd.sadd(txn_rc).await,
+        Command::Scard(cmd) => cmd.scard(txn_rc).await,
+        Command::Sintercard(cmd) => cmd.sintercard(txn_rc).await,
+        Command::Sismember(cmd) => cmd.sismember(txn_rc).await,
+        Command::Smismember(cmd) => cmd.smismember(txn_rc).await,
+        Command::Smembers(cmd) => cmd.smembers(txn_rc).await,
+        Command::Srandmember(cmd) => cmd.srandmember(txn_rc).await,
+        Command::Sscan(cmd) => cmd.sscan(txn_rc).await,
+        Command::Spop(cmd) => cmd.spop(txn_rc).await,
+        Command::Srem(cmd) => cmd.srem(txn_rc).await,
+        Command::Zadd(cmd) => cmd.zadd(txn_rc).await,
+        Command::Zcard(cmd) => cmd.zcard(txn_rc).await,
+        Command::Zscore(cmd) => cmd.zscore(txn_rc).await,
+        Command::Zrem(cmd) => cmd.zrem(txn_rc).await,
+        Command::Zremrangebyscore(cmd) => cmd.zremrangebyscore(txn_rc).await,
+        Command::Zremrangebyrank(cmd) => cmd.zremrangebyrank(txn_rc).await,
+        Command::Zrange(cmd) => cmd.zrange(txn_rc).await,
+        Command::Zrangestore(cmd) => cmd.zrangestore(txn_rc).await,
+        Command::Zrevrange(cmd) => cmd.zrevrange(txn_rc).await,
+        Command::Zrangebyscore(cmd) => cmd.zrangebyscore(txn_rc, false).await,
+        Command::Zrevrangebyscore(cmd) => cmd.zrangebyscore(txn_rc, true).await,
+        Command::Zcount(cmd) => cmd.zcount(txn_rc).await,
+        Command::Zpopmin(cmd) => cmd.zpop(txn_rc, true).await,
+        Command::Zpopmax(cmd) => cmd.zpop(txn_rc, false).await,
+        Command::Zmpop(cmd) => cmd.zmpop(txn_rc).await,
+        Command::Zrank(cmd) => cmd.zrank(txn_rc).await,
+        Command::Zrandmember(cmd) => cmd.zrandmember(txn_rc).await,
+        Command::Zscan(cmd) => cmd.zscan(txn_rc).await,
+        Command::Zdiff(cmd) => cmd.zdiff(txn_rc).await,
+        Command::Zdiffstore(cmd) => cmd.zdiffstore(txn_rc).await,
+        Command::Zunion(cmd) => cmd.zunion(txn_rc).await,
+        Command::Zinter(cmd) => cmd.zinter(txn_rc).await,
+        Command::Zincryby(cmd) => cmd.zincrby(txn_rc).await,
+        Command::Scan(cmd) => cmd.scan(txn_rc).await,
+        Command::Randomkey(cmd) => cmd.randomkey(txn_rc).await,
+        Command::Xscan(cmd) => cmd.scan(txn_rc).await,
+        Command::Unwatch(cmd) => Ok(cmd.unwatch(&mut vec![])),
+        _ => Ok(resp_invalid_arguments()),
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR, REDIS_UNKNOWN_SUBCOMMAND};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_int, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Inspect the internals of the value stored at key.
+///
+/// ```text
+/// OBJECT IDLETIME key
+/// OBJECT ENCODING key
+/// OBJECT REFCOUNT key
+/// ```
+///
+/// The values are not shared, the refcount of an existing key is always 1.
+/// Nil is replied if the key does not exist.
+#[derive(Debug, Clone)]
+pub struct Object {
+    subcommand: String,
+    key: String,
+    valid: bool,
+}
+
+impl Object {
+    pub fn new(subcommand: &str, key: &str) -> Object {
+        Object {
+            subcommand: subcommand.to_lowercase(),
+            key: key.to_string(),
+            valid: true,
+        }
+    }
+
+    /// Get the key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Object> {
+        let subcommand = parse.next_string()?;
+        let key = parse.next_string()?;
+        Ok(Object::new(&subcommand, &key))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.object(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn object(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if !is_use_txn_api() {
+            return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
+        }
+
+        match self.subcommand.as_str() {
+            "idletime" => {
+                StringCommandCtx::new(txn)
+                    .do_async_txnkv_idletime(&self.key)
+                    .await
+            }
+            "encoding" => {
+                StringCommandCtx::new(txn)
+                    .do_async_txnkv_encoding(&self.key)
+                    .await
+            }
+            "refcount" => {
+                match StringCommandCtx::new(txn)
+                    .do_async_txnkv_encoding(&self.key)
+                    .await?
+                {
+                    Frame::Bulk(_) => Ok(resp_int(1)),
+                    response => Ok(response),
+                }
+            }
+            _ => Ok(resp_err(REDIS_UNKNOWN_SUBCOMMAND)),
+        }
+    }
+}
+
+impl Invalid for Object {
+    fn new_invalid() -> Object {
+        Object {
+            subcommand: "".to_string(),
+            key: "".to_string(),
+            valid: false,
+        }
+    }
+}
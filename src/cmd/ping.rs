@@ -79,6 +79,25 @@ impl Ping {
 
         Ok(())
     }
+
+    /// Apply the `Ping` command of a RESP2 client in the subscribed state.
+    ///
+    /// The reply has the shape of the messages, `pong` and the message or an
+    /// empty string, so the client can tell it from the published messages.
+    pub(crate) async fn apply_subscribed(self, dst: &mut Connection) -> crate::Result<()> {
+        if !self.valid {
+            dst.write_frame(&resp_invalid_arguments()).await?;
+            return Ok(());
+        }
+
+        let response = Frame::Array(vec![
+            Frame::Bulk(Bytes::from_static(b"pong")),
+            Frame::Bulk(Bytes::from(self.msg.unwrap_or_default())),
+        ]);
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
 }
 
 impl Default for Ping {
@@ -0,0 +1,219 @@
+use crate::cmd::subscribe::{subscription_limit_reached, Subscription, Subscriptions};
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::tikv::errors::REDIS_MAX_SUBSCRIPTIONS_ERR;
+use crate::{Connection, Db, Frame};
+
+use crate::utils::{resp_err, resp_invalid_arguments};
+use bytes::Bytes;
+use tokio::sync::broadcast;
+
+/// Subscribes the client to one or more glob-style patterns.
+///
+/// The client receives the messages published to every channel matching a
+/// pattern as `pmessage`, along with the pattern and the channel. A client
+/// subscribed to a channel and to a pattern matching it receives a message
+/// for each of them.
+#[derive(Debug, Clone)]
+pub struct Psubscribe {
+    patterns: Vec<String>,
+    valid: bool,
+}
+
+/// Unsubscribes the client from one or more patterns.
+///
+/// When no patterns are specified, the client is unsubscribed from all the
+/// previously subscribed patterns.
+#[derive(Debug, Clone)]
+pub struct Punsubscribe {
+    patterns: Vec<String>,
+    valid: bool,
+}
+
+impl Psubscribe {
+    /// Parse a `Psubscribe` instance from a received frame.
+    ///
+    /// # Format
+    ///
+    /// ```text
+    /// PSUBSCRIBE pattern [pattern ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Psubscribe> {
+        use ParseError::EndOfStream;
+
+        let mut patterns = vec![parse.next_string()?];
+        loop {
+            match parse.next_string() {
+                Ok(s) => patterns.push(s),
+                Err(EndOfStream) => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(Psubscribe {
+            patterns,
+            valid: true,
+        })
+    }
+
+    /// Apply the `Psubscribe` command to the specified `Db` instance.
+    pub(crate) async fn apply(
+        self,
+        db: &Db,
+        dst: &mut Connection,
+        subscriptions: &mut Subscriptions,
+    ) -> crate::Result<()> {
+        if !self.valid {
+            dst.write_frame(&resp_invalid_arguments()).await?;
+            return Ok(());
+        }
+
+        for pattern in self.patterns {
+            subscribe_to_pattern(pattern, subscriptions, db, dst).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn subscribe_to_pattern(
+    pattern: String,
+    subscriptions: &mut Subscriptions,
+    db: &Db,
+    dst: &mut Connection,
+) -> crate::Result<()> {
+    let subscription = Subscription::Pattern(pattern.clone());
+    if subscription_limit_reached(subscriptions, &subscription) {
+        dst.write_frame(&resp_err(REDIS_MAX_SUBSCRIPTIONS_ERR))
+            .await?;
+        return Ok(());
+    }
+
+    let mut rx = db.psubscribe(pattern.clone());
+    let subscribed = pattern.clone();
+    let rx = Box::pin(async_stream::stream! {
+        loop {
+            match rx.recv().await {
+                Ok((channel_name, msg)) => {
+                    yield make_pmessage_frame(subscribed.clone(), channel_name, msg)
+                }
+                // If we lagged in consuming messages, just resume.
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    subscriptions.insert(subscription, rx);
+
+    let response = make_pattern_frame(b"psubscribe", Some(pattern), subscriptions.len());
+    dst.write_frame(&response).await?;
+
+    Ok(())
+}
+
+impl Punsubscribe {
+    /// Parse a `Punsubscribe` instance from a received frame.
+    ///
+    /// # Format
+    ///
+    /// ```text
+    /// PUNSUBSCRIBE [pattern [pattern ...]]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Punsubscribe> {
+        use ParseError::EndOfStream;
+
+        let mut patterns = vec![];
+        loop {
+            match parse.next_string() {
+                Ok(s) => patterns.push(s),
+                Err(EndOfStream) => break,
+                Err(_) => return Ok(Punsubscribe::new_invalid()),
+            }
+        }
+
+        Ok(Punsubscribe {
+            patterns,
+            valid: true,
+        })
+    }
+
+    /// Apply the `Punsubscribe` command to the subscriptions of the connection.
+    ///
+    /// When no patterns are specified, the client is unsubscribed from all the
+    /// patterns, a client not subscribed to any pattern gets a nil pattern. The
+    /// channels subscribed are kept.
+    pub(crate) async fn apply(
+        mut self,
+        db: &Db,
+        dst: &mut Connection,
+        subscriptions: &mut Subscriptions,
+    ) -> crate::Result<()> {
+        if !self.valid {
+            dst.write_frame(&resp_invalid_arguments()).await?;
+            return Ok(());
+        }
+
+        if self.patterns.is_empty() {
+            self.patterns = subscriptions
+                .keys()
+                .filter_map(|subscription| match subscription {
+                    Subscription::Pattern(pattern) => Some(pattern.clone()),
+                    Subscription::Channel(_) => None,
+                })
+                .collect();
+            if self.patterns.is_empty() {
+                let response = make_pattern_frame(b"punsubscribe", None, subscriptions.len());
+                dst.write_frame(&response).await?;
+            }
+        }
+        for pattern in self.patterns {
+            // the receiver is dropped before the channel is released
+            if subscriptions
+                .remove(&Subscription::Pattern(pattern.clone()))
+                .is_some()
+            {
+                db.punsubscribe(&pattern);
+            }
+
+            let response = make_pattern_frame(b"punsubscribe", Some(pattern), subscriptions.len());
+            dst.write_frame(&response).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Creates the response to a psubscribe or punsubscribe request.
+fn make_pattern_frame(kind: &'static [u8], pattern: Option<String>, num_subs: usize) -> Frame {
+    Frame::Push(vec![
+        Frame::Bulk(Bytes::from_static(kind)),
+        pattern.map_or(Frame::Null, |pattern| Frame::Bulk(Bytes::from(pattern))),
+        Frame::Integer(num_subs as i64),
+    ])
+}
+
+/// Creates a message informing the client about a new message on a channel
+/// matching a pattern that the client subscribes to.
+fn make_pmessage_frame(pattern: String, channel_name: String, msg: Bytes) -> Frame {
+    let mut response = Frame::Push(vec![]);
+    response.push_bulk(Bytes::from_static(b"pmessage"));
+    response.push_bulk(Bytes::from(pattern));
+    response.push_bulk(Bytes::from(channel_name));
+    response.push_bulk(msg);
+    response
+}
+
+impl Invalid for Psubscribe {
+    fn new_invalid() -> Psubscribe {
+        Psubscribe {
+            patterns: vec![],
+            valid: false,
+        }
+    }
+}
+
+impl Invalid for Punsubscribe {
+    fn new_invalid() -> Punsubscribe {
+        Punsubscribe {
+            patterns: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Return a random key of the keyspace, nil if it is empty.
+///
+/// ```text
+/// RANDOMKEY
+/// ```
+///
+/// The keys are not picked uniformly, see `do_async_txnkv_randomkey`.
+#[derive(Debug, Clone)]
+pub struct Randomkey {
+    valid: bool,
+}
+
+impl Randomkey {
+    pub fn new() -> Randomkey {
+        Randomkey { valid: true }
+    }
+
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> crate::Result<Randomkey> {
+        Ok(Randomkey::new())
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Randomkey> {
+        if !argv.is_empty() {
+            return Ok(Randomkey::new_invalid());
+        }
+        Ok(Randomkey::new())
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.randomkey(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn randomkey(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn).do_async_txnkv_randomkey().await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Default for Randomkey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Invalid for Randomkey {
+    fn new_invalid() -> Randomkey {
+        Randomkey { valid: false }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::cmd::{Invalid, Parse};
-use crate::config::is_use_txn_api;
+use crate::config::{cmd_scan_count_limit_or_default, is_use_txn_api};
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::string::StringCommandCtx;
 use crate::utils::{resp_err, resp_invalid_arguments};
@@ -18,15 +18,17 @@ pub struct Scan {
     start: String,
     count: i64,
     regex: String,
+    key_type: Option<String>,
     valid: bool,
 }
 
 impl Scan {
-    pub fn new(start: String, count: i64, regex: String) -> Scan {
+    pub fn new(start: String, count: i64, regex: String, key_type: Option<String>) -> Scan {
         Scan {
             start,
             count,
             regex,
+            key_type,
             valid: true,
         }
     }
@@ -39,6 +41,7 @@ impl Scan {
         let start = parse.next_string()?;
         let mut count = 10;
         let mut regex = ".*?".to_owned();
+        let mut key_type = None;
         while let Ok(flag) = parse.next_string() {
             if flag.to_uppercase().as_str() == "COUNT" {
                 if let Ok(c) = parse.next_int() {
@@ -46,6 +49,8 @@ impl Scan {
                 };
             } else if flag.to_uppercase().as_str() == "MATCH" {
                 regex = parse.next_string()?;
+            } else if flag.to_uppercase().as_str() == "TYPE" {
+                key_type = Some(parse.next_string()?);
             }
         }
 
@@ -53,49 +58,44 @@ impl Scan {
             start,
             count,
             regex,
+            key_type,
             valid: true,
         })
     }
 
     pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Scan> {
-        if argv.is_empty() || argv.len() > 5 {
+        if argv.is_empty() || argv.len() > 7 {
             return Ok(Scan::new_invalid());
         }
 
         let mut count = 10;
         let mut regex = ".*?".to_owned();
+        let mut key_type = None;
         let start = String::from_utf8_lossy(&argv[0]);
-        if argv.len() >= 3 {
-            if argv[1].to_ascii_uppercase() == b"COUNT" {
-                if let Ok(c) = String::from_utf8_lossy(&argv[2]).parse::<i64>() {
+        let mut idx = 1;
+        while idx + 1 < argv.len() {
+            let value = String::from_utf8_lossy(&argv[idx + 1]).to_string();
+            if argv[idx].to_ascii_uppercase() == b"COUNT" {
+                if let Ok(c) = value.parse::<i64>() {
                     count = c;
                 } else {
                     return Ok(Scan::new_invalid());
                 }
-            } else if argv[1].to_ascii_uppercase() == b"MATCH" {
-                regex = String::from_utf8_lossy(&argv[2]).to_string();
+            } else if argv[idx].to_ascii_uppercase() == b"MATCH" {
+                regex = value;
+            } else if argv[idx].to_ascii_uppercase() == b"TYPE" {
+                key_type = Some(value);
             } else {
                 return Ok(Scan::new_invalid());
             }
-            if argv.len() == 5 {
-                if argv[3].to_ascii_uppercase() == b"COUNT" {
-                    if let Ok(c) = String::from_utf8_lossy(&argv[4]).parse::<i64>() {
-                        count = c;
-                    } else {
-                        return Ok(Scan::new_invalid());
-                    }
-                } else if argv[3].to_ascii_uppercase() == b"MATCH" {
-                    regex = String::from_utf8_lossy(&argv[4]).to_string();
-                } else {
-                    return Ok(Scan::new_invalid());
-                }
-            }
+            idx += 2;
         }
 
         Ok(Scan {
             start: start.to_string(),
             count,
             regex,
+            key_type,
             valid: true,
         })
     }
@@ -115,12 +115,19 @@ impl Scan {
     }
 
     pub async fn scan(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
-        if !self.valid {
+        if !self.valid || self.count < 1 {
             return Ok(resp_invalid_arguments());
         }
+        // The reply of a scan is built in memory, a huge count is clamped to
+        // the limit and the scan continues from the returned cursor.
+        let mut count: u32 = self.count.try_into().unwrap_or(u32::MAX);
+        let limit = cmd_scan_count_limit_or_default();
+        if limit > 0 {
+            count = count.min(limit);
+        }
         if is_use_txn_api() {
             StringCommandCtx::new(txn)
-                .do_async_txnkv_scan(&self.start, self.count.try_into().unwrap(), &self.regex)
+                .do_async_txnkv_scan(&self.start, count, &self.regex, self.key_type.as_deref())
                 .await
         } else {
             Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
@@ -134,6 +141,7 @@ impl Invalid for Scan {
             start: "".to_owned(),
             count: 0,
             regex: "".to_owned(),
+            key_type: None,
             valid: false,
         }
     }
@@ -3,8 +3,10 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use crate::cmd::Invalid;
 use crate::config::LOGGER;
 use crate::db::Db;
-use crate::tikv::errors::AsyncResult;
-use crate::utils::{resp_array, resp_bulk, resp_int, resp_invalid_arguments, resp_ok, sha1hex};
+use crate::tikv::errors::{AsyncResult, REDIS_UNKNOWN_SUBCOMMAND};
+use crate::utils::{
+    resp_array, resp_bulk, resp_err, resp_int, resp_invalid_arguments, resp_ok, sha1hex,
+};
 use crate::{Connection, Frame, Parse};
 use bytes::Bytes;
 use slog::debug;
@@ -83,11 +85,19 @@ impl Script {
                 cmd.set_script(&script);
             }
             "EXISTS" => {
+                cmd.add_sha1(&parse.next_string()?);
                 while let Ok(sha1) = parse.next_string() {
                     cmd.add_sha1(&sha1);
                 }
             }
-            "FLUSH" => {}
+            "FLUSH" => {
+                // the cache is dropped at once either way
+                if let Ok(mode) = parse.next_string() {
+                    if !matches!(mode.to_uppercase().as_str(), "ASYNC" | "SYNC") {
+                        return Ok(Script::new_invalid());
+                    }
+                }
+            }
             _ => {}
         }
 
@@ -124,15 +134,18 @@ impl Script {
             db.flush_script();
             return Ok(resp_ok());
         } else if self.is_exists {
+            // the digests are looked up case insensitively, as EVALSHA does
             let mut resp = vec![];
             for sha1 in &self.sha1_vec {
-                resp.push(resp_int(if db.get_script(sha1).is_some() { 1 } else { 0 }));
+                let exists = db.get_script(&sha1.to_lowercase()).is_some();
+                resp.push(resp_int(if exists { 1 } else { 0 }));
             }
             return Ok(resp_array(resp));
         } else if self.is_kill {
             script_set_killed();
+            return Ok(resp_ok());
         }
-        Ok(resp_ok())
+        Ok(resp_err(REDIS_UNKNOWN_SUBCOMMAND))
     }
 }
 
@@ -145,7 +158,7 @@ impl Invalid for Script {
             is_exists: false,
             is_flush: false,
             is_kill: false,
-            valid: true,
+            valid: false,
         }
     }
 }
@@ -1,8 +1,10 @@
-use crate::cmd::{Parse, ParseError};
+use crate::cmd::Parse;
 use crate::config::is_use_txn_api;
-use crate::tikv::errors::AsyncResult;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::string::StringCommandCtx;
-use crate::utils::{resp_invalid_arguments, timestamp_from_ttl};
+use crate::utils::{
+    resp_err, resp_invalid_arguments, resp_invalid_expire_time, timestamp_from_relative_ttl,
+};
 use crate::{Connection, Frame};
 
 use crate::config::LOGGER;
@@ -26,6 +28,9 @@ use super::Invalid;
 ///
 /// * EX `seconds` -- Set the specified expire time, in seconds.
 /// * PX `milliseconds` -- Set the specified expire time, in milliseconds.
+/// * NX -- Only set the key if it does not already exist.
+/// * IFEQ `value` -- Only set the key if its current value equals `value`.
+/// * GET -- Reply the old value of the key, nil if it did not exist.
 #[derive(Debug, Clone)]
 pub struct Set {
     /// the lookup key
@@ -40,6 +45,12 @@ pub struct Set {
     /// Set if key is not present
     nx: Option<bool>,
 
+    /// Set if the current value equals it
+    ifeq: Option<Bytes>,
+
+    /// Reply the old value
+    get: bool,
+
     valid: bool,
 }
 
@@ -54,6 +65,8 @@ impl Set {
             value,
             expire,
             nx: None,
+            ifeq: None,
+            get: false,
             valid: true,
         }
     }
@@ -73,6 +86,16 @@ impl Set {
         self.expire
     }
 
+    /// Set only if the key does not exist
+    /// No NX, IFEQ or GET option, the value is written without reading the key.
+    pub fn is_plain(&self) -> bool {
+        self.nx.is_none() && self.ifeq.is_none() && !self.get
+    }
+
+    pub fn valid(&self) -> bool {
+        self.valid
+    }
+
     /// Parse a `Set` instance from a received frame.
     ///
     /// The `Parse` argument provides a cursor-like API to read fields from the
@@ -91,58 +114,23 @@ impl Set {
     /// Expects an array frame containing at least 3 entries.
     ///
     /// ```text
-    /// SET key value [EX seconds|PX milliseconds]
+    /// SET key value [EX seconds|PX milliseconds] [NX|IFEQ value] [GET]
     /// ```
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Set> {
-        use ParseError::EndOfStream;
-
         // Read the key to set. This is a required field
         let key = parse.next_string()?;
 
         // Read the value to set. This is a required field.
         let value = parse.next_bytes()?;
 
-        // The expiration is optional. If nothing else follows, then it is
-        // `None`.
-        let mut expire = None;
-
-        let mut nx = None;
-
-        // Attempt to parse another string.
-        match parse.next_string() {
-            Ok(s) if s.to_uppercase() == "EX" => {
-                // An expiration is specified in seconds. The next value is an
-                // integer.
-                let secs = parse.next_int()?;
-                expire = Some(secs * 1000);
-            }
-            Ok(s) if s.to_uppercase() == "PX" => {
-                // An expiration is specified in milliseconds. The next value is
-                // an integer.
-                let ms = parse.next_int()?;
-                expire = Some(ms);
-            }
-            Ok(s) if s.to_uppercase() == "NX" => {
-                // Only set if key not present
-                nx = Some(true);
-            }
-            Ok(_) => return Err("currently `SET` only supports the expiration option".into()),
-            // The `EndOfStream` error indicates there is no further data to
-            // parse. In this case, it is a normal run time situation and
-            // indicates there are no specified `SET` options.
-            Err(EndOfStream) => {}
-            // All other errors are bubbled up, resulting in the connection
-            // being terminated.
-            Err(err) => return Err(err.into()),
+        // The options are optional, they are parsed the same way as the
+        // arguments of scripts.
+        let mut options = vec![];
+        while let Ok(option) = parse.next_bytes() {
+            options.push(option);
         }
 
-        Ok(Set {
-            key,
-            value,
-            expire,
-            nx,
-            valid: true,
-        })
+        Ok(Set::parse_options(key, value, &options))
     }
 
     pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Set> {
@@ -151,43 +139,41 @@ impl Set {
         }
         let key = String::from_utf8_lossy(&argv[0]).to_string();
         let value = argv[1].clone();
-        let mut expire = None;
-        let mut nx = None;
-        let mut idx = 2;
-        loop {
-            if idx >= argv.len() {
-                break;
-            }
-            let flag = String::from_utf8_lossy(&argv[idx]).to_uppercase();
-            if flag == "EX" {
-                idx += 1;
-                let secs = String::from_utf8_lossy(&argv[idx]).parse::<i64>();
-                if let Ok(v) = secs {
-                    expire = Some(v * 1000);
-                } else {
-                    return Ok(Set::new_invalid());
+        Ok(Set::parse_options(key, value, &argv[2..]))
+    }
+
+    /// Parse the options following the value. EX and PX conflict, so do NX
+    /// and IFEQ.
+    fn parse_options(key: String, value: Bytes, options: &[Bytes]) -> Set {
+        let mut set = Set::new(key, value, None);
+        let mut idx = 0;
+        while idx < options.len() {
+            let flag = String::from_utf8_lossy(&options[idx]).to_uppercase();
+            match flag.as_str() {
+                "EX" | "PX" if set.expire.is_none() && idx + 1 < options.len() => {
+                    idx += 1;
+                    // an overflow saturates and is rejected as an invalid
+                    // expire time
+                    match String::from_utf8_lossy(&options[idx]).parse::<i64>() {
+                        Ok(v) if flag == "EX" => set.expire = Some(v.saturating_mul(1000)),
+                        Ok(v) => set.expire = Some(v),
+                        Err(_) => return Set::new_invalid(),
+                    }
                 }
-            } else if flag == "PX" {
-                idx += 1;
-                let ms = String::from_utf8_lossy(&argv[idx]).parse::<i64>();
-                if let Ok(v) = ms {
-                    expire = Some(v);
+                // Only set if key not present
+                "NX" if set.ifeq.is_none() => set.nx = Some(true),
+                // Only set if the current value equals the given one
+                "IFEQ" if set.nx.is_none() && idx + 1 < options.len() => {
+                    idx += 1;
+                    set.ifeq = Some(options[idx].clone());
                 }
-            } else if flag == "NX" {
-                nx = Some(true);
-            } else {
-                return Ok(Set::new_invalid());
+                // Reply the old value
+                "GET" => set.get = true,
+                _ => return Set::new_invalid(),
             }
-
             idx += 1;
         }
-        Ok(Set {
-            key,
-            value,
-            expire,
-            nx,
-            valid: true,
-        })
+        set
     }
 
     /// Apply the `Set` command to the specified `Db` instance.
@@ -213,13 +199,41 @@ impl Set {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
-        Ok(match self.nx {
-            Some(_) => self.put_not_exists(txn).await,
-            None => self.put(txn).await,
+        if let Some(ttl) = self.expire {
+            if timestamp_from_relative_ttl(ttl).is_none() {
+                return Ok(resp_invalid_expire_time("set"));
+            }
+        }
+        Ok(if self.ifeq.is_some() || self.get {
+            self.put_cond(txn).await
+        } else if self.nx.is_some() {
+            self.put_not_exists(txn).await
+        } else {
+            self.put(txn).await
         }
         .unwrap_or_else(Into::into))
     }
 
+    async fn put_cond(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !is_use_txn_api() {
+            return Ok(resp_err(REDIS_NOT_SUPPORTED_ERR));
+        }
+        let ts = match self.expire {
+            Some(ttl) => timestamp_from_relative_ttl(ttl).unwrap_or_default(),
+            None => 0,
+        };
+        StringCommandCtx::new(txn)
+            .do_async_txnkv_put_cond(
+                &self.key,
+                &self.value,
+                ts,
+                self.nx.is_some(),
+                self.ifeq.clone(),
+                self.get,
+            )
+            .await
+    }
+
     async fn put_not_exists(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
         if is_use_txn_api() {
             StringCommandCtx::new(txn)
@@ -235,8 +249,8 @@ impl Set {
     async fn put(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
         let mut ts = 0;
         if is_use_txn_api() {
-            if self.expire.is_some() {
-                ts = timestamp_from_ttl(self.expire.unwrap() as u64);
+            if let Some(ttl) = self.expire {
+                ts = timestamp_from_relative_ttl(ttl).unwrap_or_default();
             }
             StringCommandCtx::new(txn)
                 .do_async_txnkv_put(&self.key, &self.value, ts)
@@ -256,6 +270,8 @@ impl Invalid for Set {
             value: Bytes::new(),
             expire: None,
             nx: None,
+            ifeq: None,
+            get: false,
             valid: false,
         }
     }
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use crate::cmd::getbit::parse_bit_offset;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_BIT_VALUE_ERR, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Set or clear the bit at an offset of a string.
+///
+/// ```text
+/// SETBIT key offset value
+/// ```
+///
+/// The string is grown with zero bytes when the offset is beyond it, the old
+/// bit is replied.
+#[derive(Debug, Clone)]
+pub struct Setbit {
+    key: String,
+    offset: String,
+    value: String,
+    valid: bool,
+}
+
+impl Setbit {
+    pub fn new(key: &str, offset: &str, value: &str) -> Setbit {
+        Setbit {
+            key: key.to_owned(),
+            offset: offset.to_owned(),
+            value: value.to_owned(),
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Setbit> {
+        let key = parse.next_string()?;
+        let offset = parse.next_string()?;
+        let value = parse.next_string()?;
+
+        Ok(Setbit::new(&key, &offset, &value))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Setbit> {
+        if argv.len() != 3 {
+            return Ok(Setbit::new_invalid());
+        }
+        Ok(Setbit::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+            &String::from_utf8_lossy(&argv[2]),
+        ))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.setbit(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn setbit(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let offset = match parse_bit_offset(&self.offset) {
+            Ok(offset) => offset,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        let bit = match self.value.as_str() {
+            "0" => false,
+            "1" => true,
+            _ => return Ok(resp_err(REDIS_BIT_VALUE_ERR)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_setbit(&self.key, offset, bit)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Setbit {
+    fn new_invalid() -> Setbit {
+        Setbit {
+            key: "".to_owned(),
+            offset: "".to_owned(),
+            value: "".to_owned(),
+            valid: false,
+        }
+    }
+}
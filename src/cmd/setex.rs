@@ -3,7 +3,9 @@ use std::sync::Arc;
 use crate::cmd::{Invalid, Parse};
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::string::StringCommandCtx;
-use crate::utils::{resp_err, resp_invalid_arguments, timestamp_from_ttl};
+use crate::utils::{
+    resp_err, resp_invalid_arguments, resp_invalid_expire_time, timestamp_from_relative_ttl,
+};
 use crate::{is_use_txn_api, Connection, Frame};
 
 use crate::config::LOGGER;
@@ -71,7 +73,8 @@ impl SetEX {
         // Read the ttl to set.
         let uexpire = parse.next_int()?;
 
-        let expire = uexpire as i64 * 1000;
+        // an overflow saturates and is rejected as an invalid expire time
+        let expire = uexpire.saturating_mul(1000);
 
         // Read the value to set. This is a required field.
         let value = parse.next_bytes()?;
@@ -93,7 +96,7 @@ impl SetEX {
         let value = argv[2].clone();
 
         if let Ok(v) = expire {
-            return Ok(SetEX::new(key, value, v * 1000));
+            return Ok(SetEX::new(key, value, v.saturating_mul(1000)));
         }
         Ok(SetEX::new_invalid())
     }
@@ -117,7 +120,10 @@ impl SetEX {
             return Ok(resp_invalid_arguments());
         }
         if is_use_txn_api() {
-            let ts = timestamp_from_ttl(self.expire as u64);
+            let ts = match timestamp_from_relative_ttl(self.expire) {
+                Some(ts) => ts,
+                None => return Ok(resp_invalid_expire_time("setex")),
+            };
             StringCommandCtx::new(txn)
                 .do_async_txnkv_put(&self.key, &self.value, ts)
                 .await
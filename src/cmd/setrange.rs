@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::{is_use_txn_api, proto_max_bulk_len_or_default};
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR, REDIS_OFFSET_OUT_OF_RANGE_ERR,
+    REDIS_STRING_EXCEEDS_MAX_SIZE_ERR,
+};
+use crate::tikv::string::StringCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Overwrite part of a string.
+///
+/// ```text
+/// SETRANGE key offset value
+/// ```
+///
+/// The string is padded with zero bytes if it is shorter than offset, the new
+/// length is replied. The string can not grow beyond `proto-max-bulk-len`.
+#[derive(Debug, Clone)]
+pub struct Setrange {
+    key: String,
+    offset: String,
+    value: Bytes,
+    valid: bool,
+}
+
+impl Setrange {
+    pub fn new(key: &str, offset: &str, value: Bytes) -> Setrange {
+        Setrange {
+            key: key.to_owned(),
+            offset: offset.to_owned(),
+            value,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Setrange> {
+        let key = parse.next_string()?;
+        let offset = parse.next_string()?;
+        let value = parse.next_bytes()?;
+
+        Ok(Setrange::new(&key, &offset, value))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Setrange> {
+        if argv.len() != 3 {
+            return Ok(Setrange::new_invalid());
+        }
+        Ok(Setrange::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+            argv[2].clone(),
+        ))
+    }
+
+    fn parse_offset(&self) -> Result<usize, RTError> {
+        let offset = self.offset.parse::<i64>()?;
+        if offset < 0 {
+            return Err(REDIS_OFFSET_OUT_OF_RANGE_ERR);
+        }
+        // checked before reading the key, so a huge offset is never allocated,
+        // an empty value writes nothing whatever the offset
+        if !self.value.is_empty()
+            && offset as u64 + self.value.len() as u64 > proto_max_bulk_len_or_default()
+        {
+            return Err(REDIS_STRING_EXCEEDS_MAX_SIZE_ERR);
+        }
+        Ok(offset as usize)
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.setrange(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn setrange(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let offset = match self.parse_offset() {
+            Ok(offset) => offset,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            StringCommandCtx::new(txn)
+                .do_async_txnkv_setrange(&self.key, offset, &self.value)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Setrange {
+    fn new_invalid() -> Setrange {
+        Setrange {
+            key: "".to_owned(),
+            offset: "".to_owned(),
+            value: Bytes::new(),
+            valid: false,
+        }
+    }
+}
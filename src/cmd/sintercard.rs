@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_LIMIT_NEGATIVE_ERR, REDIS_NOT_SUPPORTED_ERR,
+    REDIS_NUMKEYS_GREATER_THAN_ARGS_ERR, REDIS_NUMKEYS_NOT_POSITIVE_ERR, REDIS_SYNTAX_ERR,
+};
+use crate::tikv::set::SetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Count the members of the intersection of sets.
+///
+/// ```text
+/// SINTERCARD numkeys key [key ...] [LIMIT limit]
+/// ```
+///
+/// The intersection is not materialized, counting stops once `limit` members
+/// are found, 0 means no limit.
+#[derive(Debug, Clone)]
+pub struct Sintercard {
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Sintercard {
+    pub fn new(args: Vec<String>) -> Sintercard {
+        Sintercard { args, valid: true }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Sintercard> {
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 2 {
+            return Ok(Sintercard::new_invalid());
+        }
+        Ok(Sintercard::new(args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Sintercard> {
+        if argv.len() < 2 {
+            return Ok(Sintercard::new_invalid());
+        }
+        let args = argv
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Sintercard::new(args))
+    }
+
+    /// Parse the arguments to (keys, limit).
+    fn parse_args(&self) -> Result<(&[String], u64), RTError> {
+        let numkeys = match self.args[0].parse::<i64>() {
+            Ok(n) if n > 0 => n as usize,
+            _ => return Err(REDIS_NUMKEYS_NOT_POSITIVE_ERR),
+        };
+        if numkeys > self.args.len() - 1 {
+            return Err(REDIS_NUMKEYS_GREATER_THAN_ARGS_ERR);
+        }
+        let keys = &self.args[1..numkeys + 1];
+        let mut limit = 0;
+        let options = &self.args[numkeys + 1..];
+        for pair in options.chunks(2) {
+            if pair.len() != 2 || pair[0].to_uppercase() != "LIMIT" {
+                return Err(REDIS_SYNTAX_ERR);
+            }
+            match pair[1].parse::<i64>()? {
+                n if n < 0 => return Err(REDIS_LIMIT_NEGATIVE_ERR),
+                n => limit = n as u64,
+            }
+        }
+        Ok((keys, limit))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.sintercard(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn sintercard(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (keys, limit) = match self.parse_args() {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            SetCommandCtx::new(txn)
+                .do_async_txnkv_sintercard(keys, limit)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Sintercard {
+    fn new_invalid() -> Sintercard {
+        Sintercard {
+            args: vec![],
+            valid: false,
+        }
+    }
+}
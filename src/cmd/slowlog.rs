@@ -0,0 +1,89 @@
+use slog::debug;
+
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::slowlog::{slowlog_entries, slowlog_len, slowlog_reset};
+use crate::tikv::errors::{RTError, REDIS_UNKNOWN_SUBCOMMAND};
+use crate::utils::{resp_err, resp_int, resp_invalid_arguments, resp_ok};
+use crate::{Connection, Frame, Parse};
+
+/// The entries replied by `SLOWLOG GET` without a count, as in redis.
+const SLOWLOG_DEFAULT_GET_COUNT: usize = 10;
+
+/// Inspect and reset the slow log.
+///
+/// ```text
+/// SLOWLOG GET [count]
+/// SLOWLOG LEN
+/// SLOWLOG RESET
+/// ```
+#[derive(Debug, Clone)]
+pub struct Slowlog {
+    subcommand: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Slowlog {
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Slowlog> {
+        let subcommand = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        Ok(Slowlog {
+            subcommand,
+            args,
+            valid: true,
+        })
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.slowlog();
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    fn slowlog(&self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        match (self.subcommand.to_uppercase().as_str(), self.args.len()) {
+            ("GET", 0) => slowlog_entries(Some(SLOWLOG_DEFAULT_GET_COUNT)),
+            // -1 replies all the entries
+            ("GET", 1) => match self.args[0].parse::<i64>() {
+                Ok(-1) => slowlog_entries(None),
+                Ok(count) if count >= 0 => slowlog_entries(Some(count as usize)),
+                _ => resp_err(RTError::String(
+                    "ERR count should be greater than or equal to -1",
+                )),
+            },
+            ("LEN", 0) => resp_int(slowlog_len() as i64),
+            ("RESET", 0) => {
+                slowlog_reset();
+                resp_ok()
+            }
+            _ => resp_err(REDIS_UNKNOWN_SUBCOMMAND),
+        }
+    }
+}
+
+impl Invalid for Slowlog {
+    fn new_invalid() -> Slowlog {
+        Slowlog {
+            subcommand: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
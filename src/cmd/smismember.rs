@@ -48,6 +48,9 @@ impl Smismember {
         while let Ok(member) = parse.next_string() {
             smismember.add_member(&member);
         }
+        if smismember.members.is_empty() {
+            return Ok(Smismember::new_invalid());
+        }
         Ok(smismember)
     }
 
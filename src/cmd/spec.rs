@@ -0,0 +1,365 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use crate::Frame;
+
+/// The command may modify the keyspace
+pub const CMD_WRITE: u32 = 1;
+/// The command only reads the keyspace
+pub const CMD_READONLY: u32 = 1 << 1;
+/// Administrative or connection level command
+pub const CMD_ADMIN: u32 = 1 << 2;
+/// Pub/Sub related command
+pub const CMD_PUBSUB: u32 = 1 << 3;
+
+// Categories of the commands, named after the redis ACL categories, e.g. a
+// `+@read` rule covers the commands tagged with `CAT_READ`.
+pub const CAT_KEYSPACE: u32 = 1;
+pub const CAT_READ: u32 = 1 << 1;
+pub const CAT_WRITE: u32 = 1 << 2;
+pub const CAT_STRING: u32 = 1 << 3;
+pub const CAT_HASH: u32 = 1 << 4;
+pub const CAT_LIST: u32 = 1 << 5;
+pub const CAT_SET: u32 = 1 << 6;
+pub const CAT_SORTEDSET: u32 = 1 << 7;
+pub const CAT_PUBSUB: u32 = 1 << 8;
+pub const CAT_ADMIN: u32 = 1 << 9;
+pub const CAT_FAST: u32 = 1 << 10;
+pub const CAT_SLOW: u32 = 1 << 11;
+pub const CAT_BLOCKING: u32 = 1 << 12;
+pub const CAT_DANGEROUS: u32 = 1 << 13;
+pub const CAT_CONNECTION: u32 = 1 << 14;
+pub const CAT_TRANSACTION: u32 = 1 << 15;
+pub const CAT_SCRIPTING: u32 = 1 << 16;
+
+static CATEGORY_NAMES: &[(&str, u32)] = &[
+    ("keyspace", CAT_KEYSPACE),
+    ("read", CAT_READ),
+    ("write", CAT_WRITE),
+    ("string", CAT_STRING),
+    ("hash", CAT_HASH),
+    ("list", CAT_LIST),
+    ("set", CAT_SET),
+    ("sortedset", CAT_SORTEDSET),
+    ("pubsub", CAT_PUBSUB),
+    ("admin", CAT_ADMIN),
+    ("fast", CAT_FAST),
+    ("slow", CAT_SLOW),
+    ("blocking", CAT_BLOCKING),
+    ("dangerous", CAT_DANGEROUS),
+    ("connection", CAT_CONNECTION),
+    ("transaction", CAT_TRANSACTION),
+    ("scripting", CAT_SCRIPTING),
+];
+
+/// Static properties of a command, looked up by name from the raw request frame
+/// where the parsed `Command` is not at hand, e.g. to extract the keys of a request.
+///
+/// Key positions follow the redis command table, argv[0] is the command name,
+/// `first_key` is 0 for commands without keys and a negative `last_key` counts
+/// from the end of argv. The commands taking a number of keys followed by the
+/// keys, e.g. `ZUNION numkeys key [key ...]`, have the position of the number
+/// in `numkeys`, 0 for the other commands. `categories` is the set of `CAT_*`
+/// categories of the command.
+#[derive(Debug)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub flags: u32,
+    pub first_key: usize,
+    pub last_key: i32,
+    pub step: usize,
+    pub numkeys: usize,
+    pub categories: u32,
+}
+
+const fn spec(
+    name: &'static str,
+    flags: u32,
+    first_key: usize,
+    last_key: i32,
+    step: usize,
+    categories: u32,
+) -> CommandSpec {
+    CommandSpec {
+        name,
+        flags,
+        first_key,
+        last_key,
+        step,
+        numkeys: 0,
+        categories,
+    }
+}
+
+#[rustfmt::skip]
+static COMMAND_TABLE: &[CommandSpec] = &[
+    // keys
+    spec("del", CMD_WRITE, 1, -1, 1, CAT_KEYSPACE | CAT_WRITE | CAT_SLOW),
+    spec("copy", CMD_WRITE, 1, 2, 1, CAT_KEYSPACE | CAT_WRITE | CAT_SLOW),
+    spec("exists", CMD_READONLY, 1, -1, 1, CAT_KEYSPACE | CAT_READ | CAT_FAST),
+    spec("type", CMD_READONLY, 1, 1, 1, CAT_KEYSPACE | CAT_READ | CAT_FAST),
+    spec("ttl", CMD_READONLY, 1, 1, 1, CAT_KEYSPACE | CAT_READ | CAT_FAST),
+    spec("pttl", CMD_READONLY, 1, 1, 1, CAT_KEYSPACE | CAT_READ | CAT_FAST),
+    spec("expiretime", CMD_READONLY, 1, 1, 1, CAT_KEYSPACE | CAT_READ | CAT_FAST),
+    spec("pexpiretime", CMD_READONLY, 1, 1, 1, CAT_KEYSPACE | CAT_READ | CAT_FAST),
+    spec("expire", CMD_WRITE, 1, 1, 1, CAT_KEYSPACE | CAT_WRITE | CAT_FAST),
+    spec("expireat", CMD_WRITE, 1, 1, 1, CAT_KEYSPACE | CAT_WRITE | CAT_FAST),
+    spec("pexpire", CMD_WRITE, 1, 1, 1, CAT_KEYSPACE | CAT_WRITE | CAT_FAST),
+    spec("pexpireat", CMD_WRITE, 1, 1, 1, CAT_KEYSPACE | CAT_WRITE | CAT_FAST),
+    spec("persist", CMD_WRITE, 1, 1, 1, CAT_KEYSPACE | CAT_WRITE | CAT_FAST),
+    spec("object", CMD_READONLY, 2, 2, 1, CAT_KEYSPACE | CAT_READ | CAT_SLOW),
+    spec("scan", CMD_READONLY, 0, 0, 0, CAT_KEYSPACE | CAT_READ | CAT_SLOW),
+    spec("randomkey", CMD_READONLY, 0, 0, 0, CAT_KEYSPACE | CAT_READ | CAT_SLOW),
+    spec("xscan", CMD_READONLY, 0, 0, 0, CAT_KEYSPACE | CAT_READ | CAT_SLOW),
+    // string
+    spec("get", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_STRING | CAT_FAST),
+    spec("set", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_SLOW),
+    spec("setnx", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    spec("setex", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_SLOW),
+    spec("mget", CMD_READONLY, 1, -1, 1, CAT_READ | CAT_STRING | CAT_FAST),
+    spec("mset", CMD_WRITE, 1, -1, 2, CAT_WRITE | CAT_STRING | CAT_SLOW),
+    spec("strlen", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_STRING | CAT_FAST),
+    spec("bitcount", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_STRING | CAT_SLOW),
+    spec("bitpos", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_STRING | CAT_SLOW),
+    spec("bitop", CMD_WRITE, 2, -1, 1, CAT_WRITE | CAT_STRING | CAT_SLOW),
+    spec("setbit", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_SLOW),
+    spec("getbit", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_STRING | CAT_FAST),
+    spec("bitfield", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_SLOW),
+    spec("setrange", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_SLOW),
+    spec("getrange", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_STRING | CAT_SLOW),
+    spec("append", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    spec("incrbyfloat", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    spec("substr", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_STRING | CAT_SLOW),
+    spec("getdel", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    spec("getex", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    spec("incr", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    spec("decr", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    spec("incrby", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    spec("decrby", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_STRING | CAT_FAST),
+    // hash
+    spec("hset", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_HASH | CAT_FAST),
+    spec("hmset", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_HASH | CAT_FAST),
+    spec("hsetnx", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_HASH | CAT_FAST),
+    spec("hget", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_FAST),
+    spec("hmget", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_FAST),
+    spec("hlen", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_FAST),
+    spec("hgetall", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_SLOW),
+    spec("hdel", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_HASH | CAT_FAST),
+    spec("hkeys", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_SLOW),
+    spec("hvals", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_SLOW),
+    spec("hincrby", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_HASH | CAT_FAST),
+    spec("hincrbyfloat", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_HASH | CAT_FAST),
+    spec("hrandfield", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_SLOW),
+    spec("hscan", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_SLOW),
+    spec("hexists", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_FAST),
+    spec("hstrlen", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_HASH | CAT_FAST),
+    spec("hgetdel", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_HASH | CAT_FAST),
+    // list
+    spec("lpush", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_LIST | CAT_FAST),
+    spec("rpush", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_LIST | CAT_FAST),
+    spec("lpop", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_LIST | CAT_FAST),
+    spec("rpop", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_LIST | CAT_FAST),
+    spec("blpop", CMD_WRITE, 1, -2, 1, CAT_WRITE | CAT_LIST | CAT_SLOW | CAT_BLOCKING),
+    spec("brpop", CMD_WRITE, 1, -2, 1, CAT_WRITE | CAT_LIST | CAT_SLOW | CAT_BLOCKING),
+    spec("lmpop", CMD_WRITE, 0, 0, 0, CAT_WRITE | CAT_LIST | CAT_SLOW).with_numkeys(1),
+    spec("lrange", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_LIST | CAT_SLOW),
+    spec("llen", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_LIST | CAT_FAST),
+    spec("lindex", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_LIST | CAT_SLOW),
+    spec("lset", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_LIST | CAT_SLOW),
+    spec("ltrim", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_LIST | CAT_SLOW),
+    spec("lrem", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_LIST | CAT_SLOW),
+    spec("lpos", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_LIST | CAT_SLOW),
+    spec("linsert", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_LIST | CAT_SLOW),
+    // set
+    spec("sadd", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SET | CAT_FAST),
+    spec("scard", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SET | CAT_FAST),
+    spec("sintercard", CMD_READONLY, 0, 0, 0, CAT_READ | CAT_SET | CAT_SLOW).with_numkeys(1),
+    spec("sismember", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SET | CAT_FAST),
+    spec("smismember", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SET | CAT_FAST),
+    spec("smembers", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SET | CAT_SLOW),
+    spec("srandmember", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SET | CAT_SLOW),
+    spec("sscan", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SET | CAT_SLOW),
+    spec("spop", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SET | CAT_FAST),
+    spec("srem", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SET | CAT_FAST),
+    // sorted set
+    spec("zadd", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SORTEDSET | CAT_FAST),
+    spec("zcard", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_FAST),
+    spec("zscore", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_FAST),
+    spec("zrem", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SORTEDSET | CAT_FAST),
+    spec("zremrangebyscore", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SORTEDSET | CAT_SLOW),
+    spec("zremrangebyrank", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SORTEDSET | CAT_SLOW),
+    spec("zrange", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_SLOW),
+    spec("zrangestore", CMD_WRITE, 1, 2, 1, CAT_WRITE | CAT_SORTEDSET | CAT_SLOW),
+    spec("zrevrange", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_SLOW),
+    spec("zrangebyscore", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_SLOW),
+    spec("zrevrangebyscore", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_SLOW),
+    spec("zcount", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_FAST),
+    spec("zpopmin", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SORTEDSET | CAT_FAST),
+    spec("zpopmax", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SORTEDSET | CAT_FAST),
+    spec("zmpop", CMD_WRITE, 0, 0, 0, CAT_WRITE | CAT_SORTEDSET | CAT_SLOW).with_numkeys(1),
+    spec("zrank", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_FAST),
+    spec("zrandmember", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_SLOW),
+    spec("zscan", CMD_READONLY, 1, 1, 1, CAT_READ | CAT_SORTEDSET | CAT_SLOW),
+    spec("zdiff", CMD_READONLY, 0, 0, 0, CAT_READ | CAT_SORTEDSET | CAT_SLOW).with_numkeys(1),
+    // the destination is followed by numkeys and the source keys
+    spec("zdiffstore", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SORTEDSET | CAT_SLOW).with_numkeys(2),
+    spec("zunion", CMD_READONLY, 0, 0, 0, CAT_READ | CAT_SORTEDSET | CAT_SLOW).with_numkeys(1),
+    spec("zinter", CMD_READONLY, 0, 0, 0, CAT_READ | CAT_SORTEDSET | CAT_SLOW).with_numkeys(1),
+    spec("zincrby", CMD_WRITE, 1, 1, 1, CAT_WRITE | CAT_SORTEDSET | CAT_FAST),
+    // scripts, keys are declared by numkeys
+    spec("eval", CMD_WRITE, 0, 0, 0, CAT_SCRIPTING | CAT_SLOW).with_numkeys(2),
+    spec("evalsha", CMD_WRITE, 0, 0, 0, CAT_SCRIPTING | CAT_SLOW).with_numkeys(2),
+    spec("script", CMD_ADMIN, 0, 0, 0, CAT_SCRIPTING | CAT_SLOW),
+    // pub/sub
+    spec("publish", CMD_PUBSUB, 0, 0, 0, CAT_PUBSUB | CAT_FAST),
+    spec("subscribe", CMD_PUBSUB, 0, 0, 0, CAT_PUBSUB | CAT_SLOW),
+    spec("unsubscribe", CMD_PUBSUB, 0, 0, 0, CAT_PUBSUB | CAT_SLOW),
+    spec("psubscribe", CMD_PUBSUB, 0, 0, 0, CAT_PUBSUB | CAT_SLOW),
+    spec("punsubscribe", CMD_PUBSUB, 0, 0, 0, CAT_PUBSUB | CAT_SLOW),
+    // connection and server
+    spec("ping", 0, 0, 0, 0, CAT_CONNECTION | CAT_FAST),
+    spec("auth", 0, 0, 0, 0, CAT_CONNECTION | CAT_FAST),
+    spec("acl", CMD_ADMIN, 0, 0, 0, CAT_ADMIN | CAT_SLOW | CAT_DANGEROUS),
+    spec("multi", 0, 0, 0, 0, CAT_TRANSACTION | CAT_FAST),
+    spec("exec", 0, 0, 0, 0, CAT_TRANSACTION | CAT_SLOW),
+    spec("discard", 0, 0, 0, 0, CAT_TRANSACTION | CAT_FAST),
+    spec("watch", 0, 1, -1, 1, CAT_TRANSACTION | CAT_FAST),
+    spec("unwatch", 0, 0, 0, 0, CAT_TRANSACTION | CAT_FAST),
+    spec("reset", 0, 0, 0, 0, CAT_CONNECTION | CAT_FAST),
+    spec("quit", 0, 0, 0, 0, CAT_CONNECTION | CAT_FAST),
+    spec("debug", CMD_ADMIN, 0, 0, 0, CAT_ADMIN | CAT_SLOW | CAT_DANGEROUS),
+    spec("cluster", CMD_ADMIN, 0, 0, 0, CAT_SLOW),
+    spec("readwrite", 0, 0, 0, 0, CAT_CONNECTION | CAT_FAST),
+    spec("readonly", 0, 0, 0, 0, CAT_CONNECTION | CAT_FAST),
+    spec("client", CMD_ADMIN, 0, 0, 0, CAT_ADMIN | CAT_SLOW | CAT_DANGEROUS | CAT_CONNECTION),
+    spec("info", CMD_ADMIN, 0, 0, 0, CAT_SLOW | CAT_DANGEROUS),
+    spec("bulkload", CMD_ADMIN, 0, 0, 0, CAT_ADMIN | CAT_SLOW | CAT_DANGEROUS),
+    spec("monitor", CMD_ADMIN, 0, 0, 0, CAT_ADMIN | CAT_SLOW | CAT_DANGEROUS),
+    spec("hello", 0, 0, 0, 0, CAT_CONNECTION | CAT_FAST),
+    spec("wait", 0, 0, 0, 0, CAT_CONNECTION | CAT_SLOW),
+    spec("config", CMD_ADMIN, 0, 0, 0, CAT_ADMIN | CAT_SLOW | CAT_DANGEROUS),
+    spec("slowlog", CMD_ADMIN, 0, 0, 0, CAT_ADMIN | CAT_SLOW | CAT_DANGEROUS),
+];
+
+lazy_static! {
+    static ref COMMAND_SPECS: HashMap<&'static str, &'static CommandSpec> =
+        COMMAND_TABLE.iter().map(|spec| (spec.name, spec)).collect();
+}
+
+/// Lookup the spec of a command by its lowercase name.
+pub fn lookup_command_spec(name: &str) -> Option<&'static CommandSpec> {
+    COMMAND_SPECS.get(name).copied()
+}
+
+/// Lookup a category by its name, with or without the `@` prefix, `all` covers
+/// every category.
+pub fn lookup_category(name: &str) -> Option<u32> {
+    let name = name.strip_prefix('@').unwrap_or(name).to_lowercase();
+    if name == "all" {
+        return Some(u32::MAX);
+    }
+    CATEGORY_NAMES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, category)| *category)
+}
+
+/// Expand a category to the names of the commands in it, so a rule such as
+/// `-@dangerous` resolves to the commands it covers.
+pub fn category_commands(category: u32) -> Vec<&'static str> {
+    COMMAND_TABLE
+        .iter()
+        .filter(|spec| spec.in_category(category))
+        .map(|spec| spec.name)
+        .collect()
+}
+
+impl CommandSpec {
+    /// The keys of the command follow the number of keys at `numkeys`, after
+    /// the keys at fixed positions if any.
+    const fn with_numkeys(self, numkeys: usize) -> CommandSpec {
+        CommandSpec { numkeys, ..self }
+    }
+
+    pub fn is_write(&self) -> bool {
+        self.flags & CMD_WRITE > 0
+    }
+
+    pub fn is_readonly(&self) -> bool {
+        self.flags & CMD_READONLY > 0
+    }
+
+    pub fn in_category(&self, category: u32) -> bool {
+        self.categories & category > 0
+    }
+
+    /// Names of the categories of the command, without the `@` prefix.
+    pub fn category_names(&self) -> Vec<&'static str> {
+        CATEGORY_NAMES
+            .iter()
+            .filter(|(_, category)| self.in_category(*category))
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    /// Extract the keys from the full argv of a request. A number of keys
+    /// which is not a number or exceeds the arguments is bounded by the
+    /// arguments, the command itself replies the error.
+    pub fn keys<'a>(&self, argv: &'a [Bytes]) -> Vec<&'a Bytes> {
+        let mut keys = vec![];
+        if self.first_key > 0 && argv.len() > self.first_key {
+            let last_key = if self.last_key < 0 {
+                argv.len() as i32 + self.last_key
+            } else {
+                self.last_key
+            };
+            let mut idx = self.first_key;
+            while idx as i32 <= last_key && idx < argv.len() {
+                keys.push(&argv[idx]);
+                idx += self.step;
+            }
+        }
+        if self.numkeys > 0 && argv.len() > self.numkeys {
+            let numkeys = std::str::from_utf8(&argv[self.numkeys])
+                .ok()
+                .and_then(|n| n.parse::<usize>().ok())
+                .unwrap_or(0);
+            let start = self.numkeys + 1;
+            keys.extend(argv[start..].iter().take(numkeys));
+        }
+        keys
+    }
+}
+
+/// Collect the argv of a request frame, the command name included.
+pub fn argv_from_frame(frame: &Frame) -> Vec<Bytes> {
+    match frame {
+        Frame::Array(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                Frame::Bulk(data) => Some(data.clone()),
+                Frame::Simple(s) => Some(Bytes::from(s.clone())),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Extract the keys of the request frame according to the command table.
+pub fn command_keys(frame: &Frame) -> Vec<String> {
+    let argv = argv_from_frame(frame);
+    if argv.is_empty() {
+        return vec![];
+    }
+    let name = String::from_utf8_lossy(&argv[0]).to_lowercase();
+    match lookup_command_spec(&name) {
+        Some(spec) => spec
+            .keys(&argv)
+            .into_iter()
+            .map(|key| String::from_utf8_lossy(key).to_string())
+            .collect(),
+        None => vec![],
+    }
+}
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use crate::cmd::{Invalid, Parse};
 use crate::config::is_use_txn_api;
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::sample::Sampling;
 use crate::tikv::set::SetCommandCtx;
 use crate::utils::{resp_err, resp_invalid_arguments};
 use crate::{Connection, Frame};
@@ -76,25 +77,13 @@ impl Srandmember {
             return Ok(resp_invalid_arguments());
         }
         if is_use_txn_api() {
-            let mut count;
-            let repeatable;
-            let array_resp;
-            if self.count.is_none() {
-                repeatable = false;
-                count = 1;
-                array_resp = false;
-            } else {
-                array_resp = true;
-                count = self.count.unwrap();
-                if count > 0 {
-                    repeatable = false;
-                } else {
-                    repeatable = true;
-                    count = -count;
-                }
-            }
+            // called with no count argument returns a single member
+            let sampling = match Sampling::new(self.count.unwrap_or(1), false) {
+                Ok(sampling) => sampling,
+                Err(e) => return Ok(resp_err(e)),
+            };
             SetCommandCtx::new(txn)
-                .do_async_txnkv_srandmemeber(&self.key, count, repeatable, array_resp)
+                .do_async_txnkv_srandmemeber(&self.key, sampling, self.count.is_some())
                 .await
         } else {
             Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
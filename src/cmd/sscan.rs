@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use crate::cmd::hscan::parse_collection_scan_args;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::set::SetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Incrementally iterate the members of a set.
+///
+/// ```text
+/// SSCAN key cursor [MATCH pattern] [COUNT count]
+/// ```
+///
+/// The cursor and the options are the same as HSCAN.
+#[derive(Debug, Clone)]
+pub struct Sscan {
+    key: String,
+    cursor: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Sscan {
+    pub fn new(key: &str, cursor: &str, args: Vec<String>) -> Sscan {
+        Sscan {
+            key: key.to_owned(),
+            cursor: cursor.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Sscan> {
+        let key = parse.next_string()?;
+        let cursor = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+
+        Ok(Sscan::new(&key, &cursor, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Sscan> {
+        if argv.len() < 2 {
+            return Ok(Sscan::new_invalid());
+        }
+        let args = argv[2..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Sscan::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+            args,
+        ))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.sscan(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn sscan(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let args = match parse_collection_scan_args(&self.cursor, &self.args) {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            SetCommandCtx::new(txn)
+                .do_async_txnkv_sscan(&self.key, &args.start, args.count, args.pattern.as_deref())
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Sscan {
+    fn new_invalid() -> Sscan {
+        Sscan {
+            key: "".to_owned(),
+            cursor: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -1,18 +1,22 @@
-use crate::cmd::{Invalid, Parse, ParseError, Unknown};
-use crate::{Command, Connection, Db, Frame, Shutdown};
+use crate::cmd::{Invalid, Parse, ParseError};
+use crate::config::max_subscriptions_per_client_or_default;
+use crate::tikv::errors::REDIS_MAX_SUBSCRIPTIONS_ERR;
+use crate::{Connection, Db, Frame};
 
-use crate::utils::resp_invalid_arguments;
+use crate::utils::{resp_err, resp_invalid_arguments};
 use bytes::Bytes;
 use std::pin::Pin;
-use tokio::select;
 use tokio::sync::broadcast;
-use tokio_stream::{Stream, StreamExt, StreamMap};
+use tokio_stream::{Stream, StreamMap};
 
 /// Subscribes the client to one or more channels.
 ///
-/// Once the client enters the subscribed state, it is not supposed to issue any
-/// other commands, except for additional SUBSCRIBE, PSUBSCRIBE, UNSUBSCRIBE,
-/// PUNSUBSCRIBE, PING and QUIT commands.
+/// Once a RESP2 client enters the subscribed state, it is not supposed to issue
+/// any other commands, except for additional SUBSCRIBE, PSUBSCRIBE, UNSUBSCRIBE,
+/// PUNSUBSCRIBE, PING, RESET and QUIT commands. RESP3 clients may issue any
+/// command, the messages are pushes which never mix with the replies. The
+/// client leaves the subscribed state when it is unsubscribed from all the
+/// channels or sends RESET.
 #[derive(Debug, Clone)]
 pub struct Subscribe {
     channels: Vec<String>,
@@ -30,10 +34,26 @@ pub struct Unsubscribe {
 }
 
 /// Stream of messages. The stream receives messages from the
-/// `broadcast::Receiver`. We use `stream!` to create a `Stream` that consumes
-/// messages. Because `stream!` values cannot be named, we box the stream using
-/// a trait object.
-type Messages = Pin<Box<dyn Stream<Item = Bytes> + Send>>;
+/// `broadcast::Receiver` and turns them into message frames. We use `stream!`
+/// to create a `Stream` that consumes messages. Because `stream!` values cannot
+/// be named, we box the stream using a trait object.
+pub(crate) type Messages = Pin<Box<dyn Stream<Item = Frame> + Send>>;
+
+/// A subscription of a connection, a channel and a pattern with the same name
+/// are distinct subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Subscription {
+    Channel(String),
+    Pattern(String),
+}
+
+/// Channels and patterns subscribed by a connection.
+///
+/// An individual client may subscribe to multiple channels and may dynamically
+/// add and remove channels from its subscription set. The `StreamMap` merges
+/// the messages of the individual broadcast channels as they are received, the
+/// connection handler writes them between the replies of the commands.
+pub(crate) type Subscriptions = StreamMap<Subscription, Messages>;
 
 impl Subscribe {
     #[allow(dead_code)]
@@ -101,71 +121,23 @@ impl Subscribe {
 
     /// Apply the `Subscribe` command to the specified `Db` instance.
     ///
-    /// This function is the entry point and includes the initial list of
-    /// channels to subscribe to. Additional `subscribe` and `unsubscribe`
-    /// commands may be received from the client and the list of subscriptions
-    /// are updated accordingly.
-    ///
-    /// [here]: https://redis.io/topics/pubsub
+    /// The channels are added to the subscriptions of the connection, the
+    /// connection handler delivers their messages from then on.
     pub(crate) async fn apply(
-        mut self,
+        self,
         db: &Db,
         dst: &mut Connection,
-        shutdown: &mut Shutdown,
+        subscriptions: &mut Subscriptions,
     ) -> crate::Result<()> {
         if !self.valid {
             dst.write_frame(&resp_invalid_arguments()).await?;
             return Ok(());
         }
 
-        // Each individual channel subscription is handled using a
-        // `sync::broadcast` channel. Messages are then fanned out to all
-        // clients currently subscribed to the channels.
-        //
-        // An individual client may subscribe to multiple channels and may
-        // dynamically add and remove channels from its subscription set. To
-        // handle this, a `StreamMap` is used to track active subscriptions. The
-        // `StreamMap` merges messages from individual broadcast channels as
-        // they are received.
-        let mut subscriptions = StreamMap::new();
-
-        loop {
-            // `self.channels` is used to track additional channels to subscribe
-            // to. When new `SUBSCRIBE` commands are received during the
-            // execution of `apply`, the new channels are pushed onto this vec.
-            for channel_name in self.channels.drain(..) {
-                subscribe_to_channel(channel_name, &mut subscriptions, db, dst).await?;
-            }
-
-            // Wait for one of the following to happen:
-            //
-            // - Receive a message from one of the subscribed channels.
-            // - Receive a subscribe or unsubscribe command from the client.
-            // - A server shutdown signal.
-            select! {
-                // Receive messages from subscribed channels
-                Some((channel_name, msg)) = subscriptions.next() => {
-                    dst.write_frame(&make_message_frame(channel_name, msg)).await?;
-                }
-                res = dst.read_frame() => {
-                    let frame = match res? {
-                        Some(frame) => frame,
-                        // This happens if the remote client has disconnected.
-                        None => return Ok(())
-                    };
-
-                    handle_command(
-                        frame,
-                        &mut self.channels,
-                        &mut subscriptions,
-                        dst,
-                    ).await?;
-                }
-                _ = shutdown.recv() => {
-                    return Ok(());
-                }
-            };
+        for channel_name in self.channels {
+            subscribe_to_channel(channel_name, subscriptions, db, dst).await?;
         }
+        Ok(())
     }
 
     #[allow(dead_code)]
@@ -185,17 +157,25 @@ impl Subscribe {
 
 async fn subscribe_to_channel(
     channel_name: String,
-    subscriptions: &mut StreamMap<String, Messages>,
+    subscriptions: &mut Subscriptions,
     db: &Db,
     dst: &mut Connection,
 ) -> crate::Result<()> {
+    let subscription = Subscription::Channel(channel_name.clone());
+    if subscription_limit_reached(subscriptions, &subscription) {
+        dst.write_frame(&resp_err(REDIS_MAX_SUBSCRIPTIONS_ERR))
+            .await?;
+        return Ok(());
+    }
+
     let mut rx = db.subscribe(channel_name.clone());
 
     // Subscribe to the channel.
+    let channel = channel_name.clone();
     let rx = Box::pin(async_stream::stream! {
         loop {
             match rx.recv().await {
-                Ok(msg) => yield msg,
+                Ok(msg) => yield make_message_frame(channel.clone(), msg),
                 // If we lagged in consuming messages, just resume.
                 Err(broadcast::error::RecvError::Lagged(_)) => {}
                 Err(_) => break,
@@ -204,7 +184,7 @@ async fn subscribe_to_channel(
     });
 
     // Track subscription in this client's subscription set.
-    subscriptions.insert(channel_name.clone(), rx);
+    subscriptions.insert(subscription, rx);
 
     // Respond with the successful subscription
     let response = make_subscribe_frame(channel_name, subscriptions.len());
@@ -213,52 +193,30 @@ async fn subscribe_to_channel(
     Ok(())
 }
 
-/// Handle a command received while inside `Subscribe::apply`. Only subscribe
-/// and unsubscribe commands are permitted in this context.
-///
-/// Any new subscriptions are appended to `subscribe_to` instead of modifying
-/// `subscriptions`.
-async fn handle_command(
-    frame: Frame,
-    subscribe_to: &mut Vec<String>,
-    subscriptions: &mut StreamMap<String, Messages>,
-    dst: &mut Connection,
-) -> crate::Result<()> {
-    // A command has been received from the client.
-    //
-    // Only `SUBSCRIBE` and `UNSUBSCRIBE` commands are permitted
-    // in this context.
-    match Command::from_frame(frame)? {
-        Command::Subscribe(subscribe) => {
-            // The `apply` method will subscribe to the channels we add to this
-            // vector.
-            subscribe_to.extend(subscribe.channels.into_iter());
-        }
-        Command::Unsubscribe(mut unsubscribe) => {
-            // If no channels are specified, this requests unsubscribing from
-            // **all** channels. To implement this, the `unsubscribe.channels`
-            // vec is populated with the list of channels currently subscribed
-            // to.
-            if unsubscribe.channels.is_empty() {
-                unsubscribe.channels = subscriptions
-                    .keys()
-                    .map(|channel_name| channel_name.to_string())
-                    .collect();
-            }
-
-            for channel_name in unsubscribe.channels {
-                subscriptions.remove(&channel_name);
+/// Whether a new subscription is beyond the subscriptions cap of a client,
+/// subscribing to a channel or a pattern again does not count against the cap.
+pub(crate) fn subscription_limit_reached(
+    subscriptions: &Subscriptions,
+    subscription: &Subscription,
+) -> bool {
+    let max_subscriptions = max_subscriptions_per_client_or_default();
+    max_subscriptions > 0
+        && subscriptions.len() >= max_subscriptions
+        && !subscriptions.contains_key(subscription)
+}
 
-                let response = make_unsubscribe_frame(channel_name, subscriptions.len());
-                dst.write_frame(&response).await?;
-            }
-        }
-        command => {
-            let cmd = Unknown::new(command.get_name());
-            cmd.apply(dst).await?;
+/// Drop all the subscriptions of a connection and release the broadcast
+/// channels left without subscribers, on RESET or when the connection closes.
+pub(crate) fn release_subscriptions(db: &Db, subscriptions: &mut Subscriptions) {
+    let subscribed: Vec<Subscription> = subscriptions.keys().cloned().collect();
+    for subscription in subscribed {
+        // the receiver is dropped before the channel is released
+        subscriptions.remove(&subscription);
+        match subscription {
+            Subscription::Channel(channel_name) => db.unsubscribe(&channel_name),
+            Subscription::Pattern(pattern) => db.punsubscribe(&pattern),
         }
     }
-    Ok(())
 }
 
 /// Creates the response to a subcribe request.
@@ -267,8 +225,10 @@ async fn handle_command(
 /// a `&str` since `Bytes::from` can reuse the allocation in the `String`, and
 /// taking a `&str` would require copying the data. This allows the caller to
 /// decide whether to clone the channel name or not.
+///
+/// The replies are push frames, RESP2 peers receive them as arrays.
 fn make_subscribe_frame(channel_name: String, num_subs: usize) -> Frame {
-    let mut response = Frame::array();
+    let mut response = Frame::Push(vec![]);
     response.push_bulk(Bytes::from_static(b"subscribe"));
     response.push_bulk(Bytes::from(channel_name));
     response.push_int(num_subs as i64);
@@ -277,7 +237,7 @@ fn make_subscribe_frame(channel_name: String, num_subs: usize) -> Frame {
 
 /// Creates the response to an unsubcribe request.
 fn make_unsubscribe_frame(channel_name: String, num_subs: usize) -> Frame {
-    let mut response = Frame::array();
+    let mut response = Frame::Push(vec![]);
     response.push_bulk(Bytes::from_static(b"unsubscribe"));
     response.push_bulk(Bytes::from(channel_name));
     response.push_int(num_subs as i64);
@@ -287,7 +247,7 @@ fn make_unsubscribe_frame(channel_name: String, num_subs: usize) -> Frame {
 /// Creates a message informing the client about a new message on a channel that
 /// the client subscribes to.
 fn make_message_frame(channel_name: String, msg: Bytes) -> Frame {
-    let mut response = Frame::array();
+    let mut response = Frame::Push(vec![]);
     response.push_bulk(Bytes::from_static(b"message"));
     response.push_bulk(Bytes::from(channel_name));
     response.push_bulk(msg);
@@ -353,6 +313,54 @@ impl Unsubscribe {
         })
     }
 
+    /// Apply the `Unsubscribe` command to the subscriptions of the connection.
+    ///
+    /// When no channels are specified, the client is unsubscribed from all the
+    /// channels, a client not subscribed to any channel gets a nil channel. The
+    /// patterns subscribed are kept.
+    pub(crate) async fn apply(
+        mut self,
+        db: &Db,
+        dst: &mut Connection,
+        subscriptions: &mut Subscriptions,
+    ) -> crate::Result<()> {
+        if !self.valid {
+            dst.write_frame(&resp_invalid_arguments()).await?;
+            return Ok(());
+        }
+
+        if self.channels.is_empty() {
+            self.channels = subscriptions
+                .keys()
+                .filter_map(|subscription| match subscription {
+                    Subscription::Channel(channel_name) => Some(channel_name.clone()),
+                    Subscription::Pattern(_) => None,
+                })
+                .collect();
+        }
+        if self.channels.is_empty() {
+            let response = Frame::Push(vec![
+                Frame::Bulk(Bytes::from_static(b"unsubscribe")),
+                Frame::Null,
+                Frame::Integer(subscriptions.len() as i64),
+            ]);
+            dst.write_frame(&response).await?;
+        }
+        for channel_name in self.channels {
+            // the receiver is dropped before the channel is released
+            if subscriptions
+                .remove(&Subscription::Channel(channel_name.clone()))
+                .is_some()
+            {
+                db.unsubscribe(&channel_name);
+            }
+
+            let response = make_unsubscribe_frame(channel_name, subscriptions.len());
+            dst.write_frame(&response).await?;
+        }
+        Ok(())
+    }
+
     #[allow(dead_code)]
     /// Converts the command into an equivalent `Frame`.
     ///
@@ -1,20 +1,26 @@
 use crate::{Connection, Frame};
 
 use crate::config::LOGGER;
+use bytes::Bytes;
 use slog::debug;
 
+/// Length limit of the command name and the arguments quoted in the error
+const UNKNOWN_PREVIEW_LEN: usize = 128;
+
 /// Represents an "unknown" command. This is not a real `Redis` command.
 #[derive(Debug, Clone)]
 pub struct Unknown {
     command_name: String,
+    args: Vec<Bytes>,
 }
 
 impl Unknown {
     /// Create a new `Unknown` command which responds to unknown commands
     /// issued by clients
-    pub(crate) fn new(key: impl ToString) -> Unknown {
+    pub(crate) fn new(key: impl ToString, args: Vec<Bytes>) -> Unknown {
         Unknown {
             command_name: key.to_string(),
+            args,
         }
     }
 
@@ -25,9 +31,10 @@ impl Unknown {
 
     /// Responds to the client, indicating the command is not recognized.
     ///
-    /// This usually means the command is not yet implemented.
+    /// This usually means the command is not yet implemented. The error is
+    /// formatted the same as redis, with a preview of the arguments.
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
-        let response = Frame::ErrorOwned(format!("ERR unknown command '{}'", self.command_name));
+        let response = Frame::ErrorOwned(self.error_message());
 
         debug!(
             LOGGER,
@@ -40,4 +47,26 @@ impl Unknown {
         dst.write_frame(&response).await?;
         Ok(())
     }
+
+    /// `ERR unknown command 'x', with args beginning with: 'a' 'b' `, each
+    /// argument is cut to fit the preview and newlines are replaced by spaces
+    /// to keep the reply a valid simple error.
+    fn error_message(&self) -> String {
+        let mut args = String::new();
+        for arg in &self.args {
+            if args.len() >= UNKNOWN_PREVIEW_LEN {
+                break;
+            }
+            let arg = &arg[..arg.len().min(UNKNOWN_PREVIEW_LEN - args.len())];
+            args.push_str(&format!("'{}' ", String::from_utf8_lossy(arg)));
+        }
+        let name = self.command_name.as_bytes();
+        let name = &name[..name.len().min(UNKNOWN_PREVIEW_LEN)];
+        format!(
+            "ERR unknown command '{}', with args beginning with: {}",
+            String::from_utf8_lossy(name),
+            args
+        )
+        .replace(|c| c == '\r' || c == '\n', " ")
+    }
 }
@@ -0,0 +1,40 @@
+use crate::cmd::Invalid;
+use crate::utils::{resp_invalid_arguments, resp_ok};
+use crate::{Frame, Parse};
+
+/// Flushes all the previously watched keys of the connection.
+///
+/// `EXEC`, `DISCARD` and `RESET` flush the watched keys as well. Queued in
+/// `MULTI`, the command does nothing as the keys are flushed by `EXEC` anyway.
+#[derive(Debug, Clone)]
+pub struct Unwatch {
+    valid: bool,
+}
+
+impl Unwatch {
+    /// Parse an `Unwatch` instance from a received frame.
+    ///
+    /// # Format
+    ///
+    /// ```text
+    /// UNWATCH
+    /// ```
+    pub(crate) fn parse_frames(_parse: &mut Parse) -> crate::Result<Unwatch> {
+        Ok(Unwatch { valid: true })
+    }
+
+    /// Forget the keys watched by the connection.
+    pub(crate) fn unwatch(self, watched: &mut Vec<(String, u64)>) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        watched.clear();
+        resp_ok()
+    }
+}
+
+impl Invalid for Unwatch {
+    fn new_invalid() -> Unwatch {
+        Unwatch { valid: false }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::cmd::Invalid;
+use crate::config::LOGGER;
+use crate::tikv::errors::REDIS_TIMEOUT_IS_NEGATIVE_ERR;
+use crate::utils::{resp_err, resp_int, resp_invalid_arguments};
+use crate::{Connection, Frame, Parse};
+use slog::debug;
+
+/// Wait for the writes of the connection to be acknowledged by replicas.
+///
+/// ```text
+/// WAIT numreplicas timeout
+/// ```
+///
+/// A write is replied once its transaction is committed, which TiKV does after
+/// the raft log of the write is replicated to a majority of the replicas. The
+/// writes of the connection are replicated already, the command replies
+/// `numreplicas` at once without waiting for the timeout.
+#[derive(Debug, Clone)]
+pub struct Wait {
+    numreplicas: i64,
+    timeout: i64,
+    valid: bool,
+}
+
+impl Wait {
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Wait> {
+        let numreplicas = parse.next_int()?;
+        let timeout = parse.next_int()?;
+        Ok(Wait {
+            numreplicas,
+            timeout,
+            valid: true,
+        })
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.wait();
+
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    fn wait(&self) -> Frame {
+        if !self.valid {
+            return resp_invalid_arguments();
+        }
+        if self.timeout < 0 {
+            return resp_err(REDIS_TIMEOUT_IS_NEGATIVE_ERR);
+        }
+        resp_int(self.numreplicas.max(0))
+    }
+}
+
+impl Invalid for Wait {
+    fn new_invalid() -> Wait {
+        Wait {
+            numreplicas: 0,
+            timeout: 0,
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::cmd::Invalid;
+use crate::tikv::errors::AsyncResult;
+use crate::tikv::get_txn_client;
+use crate::utils::{resp_invalid_arguments, resp_ok};
+use crate::{Frame, Parse};
+use tikv_client::TimestampExt;
+
+/// Marks the keys to be watched for the conditional execution of a
+/// transaction.
+///
+/// A timestamp is taken from PD when the keys are watched, `EXEC` compares
+/// the contents of each key in the snapshot at that timestamp with the ones it
+/// reads, and aborts the transaction if any of them changed.
+#[derive(Debug, Clone)]
+pub struct Watch {
+    keys: Vec<String>,
+    valid: bool,
+}
+
+impl Watch {
+    /// Get the keys
+    pub fn keys(&self) -> &Vec<String> {
+        &self.keys
+    }
+
+    /// Parse a `Watch` instance from a received frame.
+    ///
+    /// # Format
+    ///
+    /// ```text
+    /// WATCH key [key ...]
+    /// ```
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Watch> {
+        let mut keys = vec![parse.next_string()?];
+        while let Ok(key) = parse.next_string() {
+            keys.push(key);
+        }
+
+        Ok(Watch { keys, valid: true })
+    }
+
+    /// Add the keys to the keys watched by the connection along with the
+    /// timestamp of the snapshot they are compared with. A key watched
+    /// already keeps its former timestamp.
+    pub(crate) async fn watch(self, watched: &mut Vec<(String, u64)>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+
+        let client = get_txn_client()?;
+        let version = client.current_timestamp().await?.version();
+        for key in self.keys {
+            if !watched.iter().any(|(k, _)| *k == key) {
+                watched.push((key, version));
+            }
+        }
+        Ok(resp_ok())
+    }
+}
+
+impl Invalid for Watch {
+    fn new_invalid() -> Watch {
+        Watch {
+            keys: vec![],
+            valid: false,
+        }
+    }
+}
@@ -2,13 +2,13 @@ use std::sync::Arc;
 
 use crate::cmd::{Invalid, Parse};
 use crate::config::is_use_txn_api;
-use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::errors::{AsyncResult, REDIS_MIN_MAX_NOT_FLOAT_ERR, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::zset::ZsetCommandCtx;
 use crate::utils::{resp_err, resp_invalid_arguments};
 use crate::{Connection, Frame};
 
 use crate::config::LOGGER;
-use bytes::{Buf, Bytes};
+use bytes::Bytes;
 use slog::debug;
 use tikv_client::Transaction;
 use tokio::sync::Mutex;
@@ -37,63 +37,28 @@ impl Zcount {
 
     pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zcount> {
         let key = parse.next_string()?;
-        let mut min_inclusive = true;
-        let mut max_inclusive = true;
+        let bmin = parse.next_bytes()?;
+        let bmax = parse.next_bytes()?;
 
-        // parse score range as bytes, to handle exclusive bounder
-        let mut bmin = parse.next_bytes()?;
-        // check first byte
-        if bmin[0] == b'(' {
-            // drain the first byte
-            bmin.advance(1);
-            min_inclusive = false;
-        }
-        let min = String::from_utf8_lossy(&bmin).parse::<f64>().unwrap();
-
-        let mut bmax = parse.next_bytes()?;
-        if bmax[0] == b'(' {
-            bmax.advance(1);
-            max_inclusive = false;
-        }
-        let max = String::from_utf8_lossy(&bmax).parse::<f64>().unwrap();
-
-        let z = Zcount::new(&key, min, min_inclusive, max, max_inclusive);
-
-        Ok(z)
+        Ok(Zcount::from_bounds(&key, &bmin, &bmax))
     }
 
     pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Zcount> {
-        if argv.len() < 3 {
+        if argv.len() != 3 {
             return Ok(Zcount::new_invalid());
         }
-        let mut min_inclusive = true;
-        let mut max_inclusive = true;
 
-        // parse score range as bytes, to handle exclusive bounder
-        let mut bmin = argv[1].clone();
-        // check first byte
-        if bmin[0] == b'(' {
-            // drain the first byte
-            bmin.advance(1);
-            min_inclusive = false;
-        }
-        let min = String::from_utf8_lossy(&bmin).parse::<f64>().unwrap();
-
-        let mut bmax = argv[2].clone();
-        if bmax[0] == b'(' {
-            bmax.advance(1);
-            max_inclusive = false;
-        }
-        let max = String::from_utf8_lossy(&bmax).parse::<f64>().unwrap();
-
-        let z = Zcount::new(
+        Ok(Zcount::from_bounds(
             &String::from_utf8_lossy(&argv[0]),
-            min,
-            min_inclusive,
-            max,
-            max_inclusive,
-        );
-        Ok(z)
+            &argv[1],
+            &argv[2],
+        ))
+    }
+
+    fn from_bounds(key: &str, bmin: &[u8], bmax: &[u8]) -> Zcount {
+        let (min, min_inclusive) = parse_score_bound(bmin);
+        let (max, max_inclusive) = parse_score_bound(bmax);
+        Zcount::new(key, min, min_inclusive, max, max_inclusive)
     }
 
     pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
@@ -114,6 +79,9 @@ impl Zcount {
         if !self.valid {
             return Ok(resp_invalid_arguments());
         }
+        if self.min.is_nan() || self.max.is_nan() {
+            return Ok(resp_err(REDIS_MIN_MAX_NOT_FLOAT_ERR));
+        }
         if is_use_txn_api() {
             ZsetCommandCtx::new(txn)
                 .do_async_txnkv_zcount(
@@ -142,3 +110,16 @@ impl Invalid for Zcount {
         }
     }
 }
+
+/// Parse a score bound, a leading `(` makes it exclusive, `-inf` and `+inf`
+/// are accepted. The score is NaN if it is not a float.
+fn parse_score_bound(bound: &[u8]) -> (f64, bool) {
+    let (score, inclusive) = match bound.first() {
+        Some(b'(') => (&bound[1..], false),
+        _ => (bound, true),
+    };
+    match String::from_utf8_lossy(score).parse::<f64>() {
+        Ok(score) => (score, inclusive),
+        Err(_) => (f64::NAN, inclusive),
+    }
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR, REDIS_NUMKEYS_GREATER_THAN_ARGS_ERR,
+    REDIS_NUMKEYS_NOT_POSITIVE_ERR, REDIS_SYNTAX_ERR,
+};
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Split `numkeys key [key ...] [option ...]` to the keys and the options.
+pub(crate) fn parse_numkeys(args: &[String]) -> Result<(&[String], &[String]), RTError> {
+    let numkeys = match args.first() {
+        Some(numkeys) => numkeys.parse::<i64>()?,
+        None => return Err(REDIS_SYNTAX_ERR),
+    };
+    if numkeys <= 0 {
+        return Err(REDIS_NUMKEYS_NOT_POSITIVE_ERR);
+    }
+    let numkeys = numkeys as usize;
+    if numkeys > args.len() - 1 {
+        return Err(REDIS_NUMKEYS_GREATER_THAN_ARGS_ERR);
+    }
+    Ok((&args[1..numkeys + 1], &args[numkeys + 1..]))
+}
+
+/// Difference of sorted sets.
+///
+/// ```text
+/// ZDIFF numkeys key [key ...] [WITHSCORES]
+/// ```
+///
+/// The members of the first sorted set that are in none of the others are
+/// replied, ordered by the scores they have in the first one.
+#[derive(Debug, Clone)]
+pub struct Zdiff {
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Zdiff {
+    pub fn new(args: Vec<String>) -> Zdiff {
+        Zdiff { args, valid: true }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zdiff> {
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 2 {
+            return Ok(Zdiff::new_invalid());
+        }
+        Ok(Zdiff::new(args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Zdiff> {
+        if argv.len() < 2 {
+            return Ok(Zdiff::new_invalid());
+        }
+        let args = argv
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Zdiff::new(args))
+    }
+
+    /// Parse the arguments to (keys, with scores).
+    fn parse_args(&self) -> Result<(&[String], bool), RTError> {
+        let (keys, options) = parse_numkeys(&self.args)?;
+        match options {
+            [] => Ok((keys, false)),
+            [with_scores] if with_scores.to_uppercase() == "WITHSCORES" => Ok((keys, true)),
+            _ => Err(REDIS_SYNTAX_ERR),
+        }
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zdiff(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zdiff(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (keys, with_scores) = match self.parse_args() {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zdiff(keys, with_scores)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zdiff {
+    fn new_invalid() -> Zdiff {
+        Zdiff {
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use crate::cmd::zdiff::parse_numkeys;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR};
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Store the difference of sorted sets.
+///
+/// ```text
+/// ZDIFFSTORE destination numkeys key [key ...]
+/// ```
+///
+/// `destination` is replaced by the difference computed as ZDIFF does, or
+/// deleted if it is empty. The number of members stored is replied.
+#[derive(Debug, Clone)]
+pub struct Zdiffstore {
+    dst: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Zdiffstore {
+    pub fn new(dst: &str, args: Vec<String>) -> Zdiffstore {
+        Zdiffstore {
+            dst: dst.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zdiffstore> {
+        let dst = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 2 {
+            return Ok(Zdiffstore::new_invalid());
+        }
+        Ok(Zdiffstore::new(&dst, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Zdiffstore> {
+        if argv.len() < 3 {
+            return Ok(Zdiffstore::new_invalid());
+        }
+        let args = argv[1..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Zdiffstore::new(&String::from_utf8_lossy(&argv[0]), args))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zdiffstore(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zdiffstore(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        // WITHSCORES is a syntax error here, nothing is replied but a count
+        let keys = match parse_numkeys(&self.args) {
+            Ok((keys, [])) => keys,
+            Ok(_) => return Ok(resp_err(REDIS_SYNTAX_ERR)),
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zdiffstore(&self.dst, keys)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zdiffstore {
+    fn new_invalid() -> Zdiffstore {
+        Zdiffstore {
+            dst: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
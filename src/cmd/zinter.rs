@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use crate::cmd::zunion::parse_zunion_inter_args;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Intersection of sorted sets.
+///
+/// ```text
+/// ZINTER numkeys key [key ...] [WEIGHTS weight [weight ...]]
+///     [AGGREGATE SUM|MIN|MAX] [WITHSCORES]
+/// ```
+///
+/// The members in all the sets are replied, scored as ZUNION does. Nothing is
+/// stored, the keys are read from one snapshot.
+#[derive(Debug, Clone)]
+pub struct Zinter {
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Zinter {
+    pub fn new(args: Vec<String>) -> Zinter {
+        Zinter { args, valid: true }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zinter> {
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 2 {
+            return Ok(Zinter::new_invalid());
+        }
+        Ok(Zinter::new(args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Zinter> {
+        if argv.len() < 2 {
+            return Ok(Zinter::new_invalid());
+        }
+        let args = argv
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Zinter::new(args))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zinter(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zinter(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let args = match parse_zunion_inter_args(&self.args) {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zunion_inter(
+                    args.keys,
+                    &args.weights,
+                    args.aggregate,
+                    true,
+                    args.with_scores,
+                )
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zinter {
+    fn new_invalid() -> Zinter {
+        Zinter {
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use crate::cmd::lmpop::parse_mpop_args;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Pop members from the first non-empty sorted set of the keys.
+///
+/// ```text
+/// ZMPOP numkeys key [key ...] MIN|MAX [COUNT count]
+/// ```
+///
+/// The key and an array of the popped [member, score] pairs are replied, at
+/// most `count` of them, nil if all the sorted sets are empty.
+#[derive(Debug, Clone)]
+pub struct Zmpop {
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Zmpop {
+    pub fn new(args: Vec<String>) -> Zmpop {
+        Zmpop { args, valid: true }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zmpop> {
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 3 {
+            return Ok(Zmpop::new_invalid());
+        }
+        Ok(Zmpop::new(args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Zmpop> {
+        if argv.len() < 3 {
+            return Ok(Zmpop::new_invalid());
+        }
+        let args = argv
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Zmpop::new(args))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zmpop(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zmpop(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (keys, from_min, count) = match parse_mpop_args(&self.args, "MIN", "MAX") {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zmpop(keys, from_min, count)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zmpop {
+    fn new_invalid() -> Zmpop {
+        Zmpop {
+            args: vec![],
+            valid: false,
+        }
+    }
+}
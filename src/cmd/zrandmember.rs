@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR};
+use crate::tikv::sample::Sampling;
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Get random members of a sorted set.
+///
+/// ```text
+/// ZRANDMEMBER key [count [WITHSCORES]]
+/// ```
+///
+/// A single member is replied without `count`, otherwise an array of distinct
+/// members for a positive count, or of `-count` members which may repeat for
+/// a negative one. `WITHSCORES` replies each member followed by its score.
+#[derive(Debug, Clone)]
+pub struct Zrandmember {
+    key: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Zrandmember {
+    pub fn new(key: &str, args: Vec<String>) -> Zrandmember {
+        Zrandmember {
+            key: key.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zrandmember> {
+        let key = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+
+        Ok(Zrandmember::new(&key, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Zrandmember> {
+        if argv.is_empty() {
+            return Ok(Zrandmember::new_invalid());
+        }
+        let args = argv[1..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Zrandmember::new(&String::from_utf8_lossy(&argv[0]), args))
+    }
+
+    /// Parse the arguments to (count, with scores).
+    fn parse_args(&self) -> Result<(Option<i64>, bool), RTError> {
+        match self.args.as_slice() {
+            [] => Ok((None, false)),
+            [count] => Ok((Some(count.parse::<i64>()?), false)),
+            [count, with_scores] if with_scores.to_uppercase() == "WITHSCORES" => {
+                Ok((Some(count.parse::<i64>()?), true))
+            }
+            _ => Err(REDIS_SYNTAX_ERR),
+        }
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zrandmember(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zrandmember(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (count, with_scores) = match self.parse_args() {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            // called with no count argument returns a single member
+            let sampling = match Sampling::new(count.unwrap_or(1), with_scores) {
+                Ok(sampling) => sampling,
+                Err(e) => return Ok(resp_err(e)),
+            };
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zrandmember(&self.key, sampling, count.is_some(), with_scores)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zrandmember {
+    fn new_invalid() -> Zrandmember {
+        Zrandmember {
+            key: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_MIN_MAX_NOT_FLOAT_ERR, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR,
+    REDIS_ZRANGE_LIMIT_WITHOUT_BY_ERR,
+};
+use crate::tikv::zset::{ZrangeBy, ZsetCommandCtx};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Store a range of a sorted set into another key.
+///
+/// ```text
+/// ZRANGESTORE dst src min max [BYSCORE|BYLEX] [REV] [LIMIT offset count]
+/// ```
+///
+/// The range is selected the same way as ZRANGE, `dst` is replaced by a sorted
+/// set of the members in the range, or deleted if the range is empty. The
+/// number of members stored is replied. `BYLEX` is not supported.
+#[derive(Debug, Clone)]
+pub struct Zrangestore {
+    dst: String,
+    src: String,
+    args: Vec<String>,
+    valid: bool,
+}
+
+/// Parse a score bound of a range, `(` prefixes an exclusive bound.
+fn parse_score_bound(bound: &str) -> Result<(f64, bool), RTError> {
+    let (bound, inclusive) = match bound.strip_prefix('(') {
+        Some(bound) => (bound, false),
+        None => (bound, true),
+    };
+    match bound.parse::<f64>() {
+        Ok(score) if !score.is_nan() => Ok((score, inclusive)),
+        _ => Err(REDIS_MIN_MAX_NOT_FLOAT_ERR),
+    }
+}
+
+impl Zrangestore {
+    pub fn new(dst: &str, src: &str, args: Vec<String>) -> Zrangestore {
+        Zrangestore {
+            dst: dst.to_owned(),
+            src: src.to_owned(),
+            args,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zrangestore> {
+        let dst = parse.next_string()?;
+        let src = parse.next_string()?;
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 2 {
+            return Ok(Zrangestore::new_invalid());
+        }
+
+        Ok(Zrangestore::new(&dst, &src, args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Zrangestore> {
+        if argv.len() < 4 {
+            return Ok(Zrangestore::new_invalid());
+        }
+        let args = argv[2..]
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Zrangestore::new(
+            &String::from_utf8_lossy(&argv[0]),
+            &String::from_utf8_lossy(&argv[1]),
+            args,
+        ))
+    }
+
+    /// Parse the arguments to (range, reverse, offset, count).
+    fn parse_args(&self) -> Result<(ZrangeBy, bool, usize, Option<usize>), RTError> {
+        let mut by_score = false;
+        let mut by_lex = false;
+        let mut reverse = false;
+        let mut limit = None;
+
+        let mut idx = 2;
+        while idx < self.args.len() {
+            match self.args[idx].to_uppercase().as_str() {
+                "BYSCORE" => by_score = true,
+                "BYLEX" => by_lex = true,
+                "REV" => reverse = true,
+                "LIMIT" if idx + 2 < self.args.len() => {
+                    let offset = self.args[idx + 1].parse::<i64>()?;
+                    let count = self.args[idx + 2].parse::<i64>()?;
+                    limit = Some((offset, count));
+                    idx += 2;
+                }
+                _ => return Err(REDIS_SYNTAX_ERR),
+            }
+            idx += 1;
+        }
+        if by_score && by_lex {
+            return Err(REDIS_SYNTAX_ERR);
+        }
+        if limit.is_some() && !by_score && !by_lex {
+            return Err(REDIS_ZRANGE_LIMIT_WITHOUT_BY_ERR);
+        }
+        if by_lex {
+            return Err(REDIS_NOT_SUPPORTED_ERR);
+        }
+
+        let (min, max) = (&self.args[0], &self.args[1]);
+        let range = if by_score {
+            let (min, min_inclusive) = parse_score_bound(min)?;
+            let (max, max_inclusive) = parse_score_bound(max)?;
+            ZrangeBy::Score(min, min_inclusive, max, max_inclusive)
+        } else {
+            ZrangeBy::Rank(min.parse::<i64>()?, max.parse::<i64>()?)
+        };
+
+        // a negative offset selects nothing and a negative count everything
+        let (offset, count) = match limit {
+            Some((offset, _)) if offset < 0 => (0, Some(0)),
+            Some((offset, count)) if count >= 0 => (offset as usize, Some(count as usize)),
+            Some((offset, _)) => (offset as usize, None),
+            None => (0, None),
+        };
+        Ok((range, reverse, offset, count))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zrangestore(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zrangestore(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let (range, reverse, offset, count) = match self.parse_args() {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zrangestore(&self.dst, &self.src, range, reverse, offset, count)
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zrangestore {
+    fn new_invalid() -> Zrangestore {
+        Zrangestore {
+            dst: "".to_owned(),
+            src: "".to_owned(),
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -4,7 +4,7 @@ use crate::cmd::{Invalid, Parse};
 use crate::config::is_use_txn_api;
 use crate::tikv::errors::{AsyncResult, REDIS_NOT_SUPPORTED_ERR};
 use crate::tikv::zset::ZsetCommandCtx;
-use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::utils::{resp_err, resp_invalid_arguments, resp_scores_map};
 use crate::{Connection, Frame};
 
 use crate::config::LOGGER;
@@ -100,6 +100,13 @@ impl Zrevrange {
             ZsetCommandCtx::new(txn)
                 .do_async_txnkv_zrange(&self.key, self.min, self.max, self.withscores, true)
                 .await
+                .map(|resp| {
+                    if self.withscores {
+                        resp_scores_map(resp)
+                    } else {
+                        resp
+                    }
+                })
         } else {
             Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
         }
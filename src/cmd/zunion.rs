@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use crate::cmd::zdiff::parse_numkeys;
+use crate::cmd::{Invalid, Parse};
+use crate::config::is_use_txn_api;
+use crate::tikv::errors::{
+    AsyncResult, RTError, REDIS_NOT_SUPPORTED_ERR, REDIS_SYNTAX_ERR, REDIS_WEIGHT_NOT_FLOAT_ERR,
+};
+use crate::tikv::zset::{ZsetAggregate, ZsetCommandCtx};
+use crate::utils::{resp_err, resp_invalid_arguments};
+use crate::{Connection, Frame};
+
+use crate::config::LOGGER;
+use bytes::Bytes;
+use slog::debug;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+
+/// Arguments of ZUNION and ZINTER, the weights are empty if not given.
+pub(crate) struct ZunionInterArgs<'a> {
+    pub keys: &'a [String],
+    pub weights: Vec<f64>,
+    pub aggregate: ZsetAggregate,
+    pub with_scores: bool,
+}
+
+/// Parse `numkeys key [key ...] [WEIGHTS weight [weight ...]]
+/// [AGGREGATE SUM|MIN|MAX] [WITHSCORES]`.
+pub(crate) fn parse_zunion_inter_args(args: &[String]) -> Result<ZunionInterArgs<'_>, RTError> {
+    let (keys, options) = parse_numkeys(args)?;
+    let mut parsed = ZunionInterArgs {
+        keys,
+        weights: vec![],
+        aggregate: ZsetAggregate::Sum,
+        with_scores: false,
+    };
+
+    let mut idx = 0;
+    while idx < options.len() {
+        match options[idx].to_uppercase().as_str() {
+            "WEIGHTS" if idx + keys.len() < options.len() => {
+                parsed.weights = options[idx + 1..idx + 1 + keys.len()]
+                    .iter()
+                    .map(|w| match w.parse::<f64>() {
+                        Ok(w) if !w.is_nan() => Ok(w),
+                        _ => Err(REDIS_WEIGHT_NOT_FLOAT_ERR),
+                    })
+                    .collect::<Result<_, _>>()?;
+                idx += keys.len();
+            }
+            "AGGREGATE" if idx + 1 < options.len() => {
+                parsed.aggregate = match options[idx + 1].to_uppercase().as_str() {
+                    "SUM" => ZsetAggregate::Sum,
+                    "MIN" => ZsetAggregate::Min,
+                    "MAX" => ZsetAggregate::Max,
+                    _ => return Err(REDIS_SYNTAX_ERR),
+                };
+                idx += 1;
+            }
+            "WITHSCORES" => parsed.with_scores = true,
+            _ => return Err(REDIS_SYNTAX_ERR),
+        }
+        idx += 1;
+    }
+    Ok(parsed)
+}
+
+/// Union of sorted sets.
+///
+/// ```text
+/// ZUNION numkeys key [key ...] [WEIGHTS weight [weight ...]]
+///     [AGGREGATE SUM|MIN|MAX] [WITHSCORES]
+/// ```
+///
+/// The score of a member is the aggregation of its weighted scores in the
+/// sets it is in. Nothing is stored, the keys are read from one snapshot.
+#[derive(Debug, Clone)]
+pub struct Zunion {
+    args: Vec<String>,
+    valid: bool,
+}
+
+impl Zunion {
+    pub fn new(args: Vec<String>) -> Zunion {
+        Zunion { args, valid: true }
+    }
+
+    pub(crate) fn parse_frames(parse: &mut Parse) -> crate::Result<Zunion> {
+        let mut args = vec![];
+        while let Ok(arg) = parse.next_string() {
+            args.push(arg);
+        }
+        if args.len() < 2 {
+            return Ok(Zunion::new_invalid());
+        }
+        Ok(Zunion::new(args))
+    }
+
+    pub(crate) fn parse_argv(argv: &Vec<Bytes>) -> crate::Result<Zunion> {
+        if argv.len() < 2 {
+            return Ok(Zunion::new_invalid());
+        }
+        let args = argv
+            .iter()
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+        Ok(Zunion::new(args))
+    }
+
+    pub(crate) async fn apply(self, dst: &mut Connection) -> crate::Result<()> {
+        let response = self.zunion(None).await.unwrap_or_else(Into::into);
+        debug!(
+            LOGGER,
+            "res, {} -> {}, {:?}",
+            dst.local_addr(),
+            dst.peer_addr(),
+            response
+        );
+        dst.write_frame(&response).await?;
+
+        Ok(())
+    }
+
+    pub async fn zunion(&self, txn: Option<Arc<Mutex<Transaction>>>) -> AsyncResult<Frame> {
+        if !self.valid {
+            return Ok(resp_invalid_arguments());
+        }
+        let args = match parse_zunion_inter_args(&self.args) {
+            Ok(args) => args,
+            Err(e) => return Ok(resp_err(e)),
+        };
+        if is_use_txn_api() {
+            ZsetCommandCtx::new(txn)
+                .do_async_txnkv_zunion_inter(
+                    args.keys,
+                    &args.weights,
+                    args.aggregate,
+                    false,
+                    args.with_scores,
+                )
+                .await
+        } else {
+            Ok(resp_err(REDIS_NOT_SUPPORTED_ERR))
+        }
+    }
+}
+
+impl Invalid for Zunion {
+    fn new_invalid() -> Zunion {
+        Zunion {
+            args: vec![],
+            valid: false,
+        }
+    }
+}
@@ -4,7 +4,11 @@ use crate::{DEFAULT_PORT, DEFAULT_TLS_PORT};
 
 use slog::{self, Drain};
 use slog_term;
+use std::collections::HashMap;
 use std::fs::OpenOptions;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 
 lazy_static! {
     pub static ref LOGGER: slog::Logger = slog::Logger::root(
@@ -22,6 +26,116 @@ lazy_static! {
         .fuse(),
         slog::o!()
     );
+
+    /// Values set by `CONFIG SET`, the helpers of the parameters tunable at
+    /// runtime consult them before the config file.
+    static ref CONFIG_OVERRIDES: Arc<RwLock<HashMap<&'static str, String>>> =
+        Arc::new(RwLock::new(HashMap::new()));
+}
+
+// set once any parameter is overridden, the helpers skip the lock until then
+static CONFIG_OVERRIDDEN: AtomicBool = AtomicBool::new(false);
+
+enum ParamType {
+    Bool,
+    U32,
+    U64,
+    I64,
+    Usize,
+    Ratio,
+}
+
+/// The parameters `CONFIG SET` changes at runtime, the helpers of the others
+/// are read once at startup or on a hot path.
+static RUNTIME_PARAMS: &[(&str, ParamType)] = &[
+    ("debug_raw_enabled", ParamType::Bool),
+    ("cmd_size_metrics_enabled", ParamType::Bool),
+    ("max_subscriptions_per_client", ParamType::Usize),
+    ("txn_retry_count", ParamType::U32),
+    ("txn_region_backoff_delay_ms", ParamType::U64),
+    ("txn_region_backoff_delay_attemps", ParamType::U32),
+    ("txn_lock_backoff_delay_ms", ParamType::U64),
+    ("txn_lock_backoff_delay_attemps", ParamType::U32),
+    ("cmd_lrem_length_limit", ParamType::U32),
+    ("cmd_linsert_length_limit", ParamType::U32),
+    ("cmd_scan_count_limit", ParamType::U32),
+    ("async_del_list_threshold", ParamType::U32),
+    ("async_del_hash_threshold", ParamType::U32),
+    ("async_del_set_threshold", ParamType::U32),
+    ("async_del_zset_threshold", ParamType::U32),
+    ("async_expire_list_threshold", ParamType::U32),
+    ("async_expire_hash_threshold", ParamType::U32),
+    ("async_expire_set_threshold", ParamType::U32),
+    ("async_expire_zset_threshold", ParamType::U32),
+    ("lazyfree_lazy_server_del", ParamType::Bool),
+    ("access_time_sample_rate", ParamType::Ratio),
+    ("cmd_timeout_ms", ParamType::U64),
+    ("slowlog_log_slower_than", ParamType::I64),
+    ("slowlog_max_len", ParamType::Usize),
+];
+
+fn config_override<T: FromStr>(name: &str) -> Option<T> {
+    if !CONFIG_OVERRIDDEN.load(Ordering::Relaxed) {
+        return None;
+    }
+    CONFIG_OVERRIDES
+        .read()
+        .unwrap()
+        .get(name)
+        .and_then(|value| value.parse().ok())
+}
+
+/// Check the value of a parameter and normalize it to the form the helpers
+/// parse.
+fn parse_param_value(kind: &ParamType, value: &str) -> Result<String, &'static str> {
+    const NOT_INTEGER: &str = "argument couldn't be parsed into an integer";
+    match kind {
+        ParamType::Bool => match value.to_lowercase().as_str() {
+            "yes" | "true" => Ok("true".to_owned()),
+            "no" | "false" => Ok("false".to_owned()),
+            _ => Err("argument must be 'yes' or 'no'"),
+        },
+        ParamType::U32 => value
+            .parse::<u32>()
+            .map(|n| n.to_string())
+            .map_err(|_| NOT_INTEGER),
+        ParamType::U64 => value
+            .parse::<u64>()
+            .map(|n| n.to_string())
+            .map_err(|_| NOT_INTEGER),
+        ParamType::I64 => value
+            .parse::<i64>()
+            .map(|n| n.to_string())
+            .map_err(|_| NOT_INTEGER),
+        ParamType::Usize => value
+            .parse::<usize>()
+            .map(|n| n.to_string())
+            .map_err(|_| NOT_INTEGER),
+        ParamType::Ratio => match value.parse::<f64>() {
+            Ok(n) if (0.0..=1.0).contains(&n) => Ok(n.to_string()),
+            _ => Err("argument must be a number between 0 and 1"),
+        },
+    }
+}
+
+/// Override the parameters with the values of `CONFIG SET`, either all of
+/// them or none if any is not tunable at runtime or its value is malformed.
+/// The error carries the parameter at fault and the reason.
+pub fn set_runtime_params(params: &[(String, String)]) -> Result<(), (String, &'static str)> {
+    let mut values = vec![];
+    for (name, value) in params {
+        let lowercase = name.to_lowercase().replace('-', "_");
+        let (name, kind) = RUNTIME_PARAMS
+            .iter()
+            .find(|(n, _)| *n == lowercase)
+            .ok_or_else(|| (name.clone(), "can't set immutable config"))?;
+        let value = parse_param_value(kind, value).map_err(|e| (name.to_string(), e))?;
+        values.push((*name, value));
+    }
+
+    CONFIG_OVERRIDES.write().unwrap().extend(values);
+    CONFIG_OVERRIDDEN.store(true, Ordering::Relaxed);
+    Ok(())
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -39,11 +153,14 @@ struct Server {
     tls_cert_file: Option<String>,
     tls_auth_client: Option<bool>,
     tls_ca_cert_file: Option<String>,
+    unixsocket: Option<String>,
+    unixsocket_password: Option<String>,
     pd_addrs: Option<String>,
     instance_id: Option<String>,
     prometheus_listen: Option<String>,
     prometheus_port: Option<u16>,
     // username: Option<String>,
+    #[serde(alias = "requirepass")]
     password: Option<String>,
     log_level: Option<String>,
     log_file: Option<String>,
@@ -51,6 +168,17 @@ struct Server {
     cluster_topology_interval: Option<u64>,
     cluster_topology_expire: Option<u64>,
     meta_key_number: Option<u16>,
+    key_prefix: Option<String>,
+    debug_raw_enabled: Option<bool>,
+    cmd_size_metrics_enabled: Option<bool>,
+    max_subscriptions_per_client: Option<usize>,
+    notify_keyspace_events: Option<String>,
+    max_inflight_cmds: Option<usize>,
+    proto_max_bulk_len: Option<u64>,
+    proto_max_multibulk_len: Option<u64>,
+    acl_users: Option<Vec<String>>,
+    slowlog_log_slower_than: Option<i64>,
+    slowlog_max_len: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -60,6 +188,8 @@ struct Backend {
     cert_file: Option<String>,
     key_file: Option<String>,
     conn_concurrency: Option<usize>,
+    conn_concurrency_max: Option<usize>,
+    conn_inflight_threshold: Option<i64>,
     use_txn_api: Option<bool>,
     use_async_commit: Option<bool>,
     try_one_pc_commit: Option<bool>,
@@ -84,6 +214,7 @@ struct Backend {
 
     cmd_lrem_length_limit: Option<u32>,
     cmd_linsert_length_limit: Option<u32>,
+    cmd_scan_count_limit: Option<u32>,
 
     async_deletion_enabled: Option<bool>,
 
@@ -100,6 +231,24 @@ struct Backend {
     async_expire_hash_threshold: Option<u32>,
     async_expire_set_threshold: Option<u32>,
     async_expire_zset_threshold: Option<u32>,
+    lazyfree_lazy_server_del: Option<bool>,
+    bulk_load_batch_size: Option<usize>,
+    access_time_sample_rate: Option<f64>,
+
+    cmd_timeout_ms: Option<u64>,
+    cmd_timeout_overrides: Option<HashMap<String, u64>>,
+
+    negative_cache_size: Option<usize>,
+    negative_cache_ttl_ms: Option<u64>,
+
+    ttl_sample_interval: Option<u64>,
+    ttl_sample_size: Option<u32>,
+
+    active_expire_interval: Option<u64>,
+    active_expire_scan_size: Option<u32>,
+    active_expire_batch_size: Option<usize>,
+
+    health_check_interval: Option<u64>,
 }
 
 // Config
@@ -128,7 +277,34 @@ pub fn is_auth_matched(password: &str) -> bool {
     true
 }
 
+pub fn is_unixsocket_auth_enabled() -> bool {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            // an empty unix socket password skips auth for local clients
+            if let Some(s) = c.server.unixsocket_password.clone() {
+                return !s.is_empty();
+            }
+        }
+    }
+    is_auth_enabled()
+}
+
+// unix socket clients use their own password if set, otherwise the server password
+pub fn is_unixsocket_auth_matched(password: &str) -> bool {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(s) = c.server.unixsocket_password.clone() {
+                return s.is_empty() || s == password;
+            }
+        }
+    }
+    is_auth_matched(password)
+}
+
 pub fn txn_retry_count() -> u32 {
+    if let Some(value) = config_override("txn_retry_count") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(s) = c.backend.txn_retry_count {
@@ -236,6 +412,18 @@ pub fn config_tls_ca_cert_file_or_default() -> String {
     "".to_owned()
 }
 
+pub fn config_unixsocket_or_default() -> String {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(s) = c.server.unixsocket.clone() {
+                return s;
+            }
+        }
+    }
+
+    "".to_owned()
+}
+
 pub fn config_pd_addrs_or_default() -> String {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
@@ -300,11 +488,11 @@ pub fn config_cluster_broadcast_addr_or_default() -> String {
             }
         }
     }
-    // use listen addr if broadcast address not set
-    format!(
-        "{}:{}",
-        config_listen_or_default(),
-        config_port_or_default()
+    // use the first listen addr if broadcast address not set
+    let listen = config_listen_or_default();
+    crate::utils::addr_with_port(
+        listen.split(',').next().unwrap_or_default().trim(),
+        &config_port_or_default(),
     )
 }
 
@@ -347,7 +535,20 @@ pub fn config_meta_key_number_or_default() -> u16 {
     100
 }
 
-fn log_level_str() -> String {
+pub fn config_key_prefix_or_default() -> String {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(s) = c.server.key_prefix.clone() {
+                return s;
+            }
+        }
+    }
+
+    // no prefix, the instance sees the whole keyspace of its instance id
+    "".to_owned()
+}
+
+pub fn log_level_str() -> String {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(l) = c.server.log_level.clone() {
@@ -439,6 +640,9 @@ pub fn is_use_pessimistic_txn() -> bool {
 }
 
 pub fn txn_region_backoff_delay_ms() -> u64 {
+    if let Some(value) = config_override("txn_region_backoff_delay_ms") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.txn_region_backoff_delay_ms {
@@ -450,6 +654,9 @@ pub fn txn_region_backoff_delay_ms() -> u64 {
 }
 
 pub fn txn_region_backoff_delay_attemps() -> u32 {
+    if let Some(value) = config_override("txn_region_backoff_delay_attemps") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.txn_region_backoff_delay_attemps {
@@ -461,6 +668,9 @@ pub fn txn_region_backoff_delay_attemps() -> u32 {
 }
 
 pub fn txn_lock_backoff_delay_ms() -> u64 {
+    if let Some(value) = config_override("txn_lock_backoff_delay_ms") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.txn_lock_backoff_delay_ms {
@@ -472,6 +682,9 @@ pub fn txn_lock_backoff_delay_ms() -> u64 {
 }
 
 pub fn txn_lock_backoff_delay_attemps() -> u32 {
+    if let Some(value) = config_override("txn_lock_backoff_delay_attemps") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.txn_lock_backoff_delay_attemps {
@@ -483,6 +696,9 @@ pub fn txn_lock_backoff_delay_attemps() -> u32 {
 }
 
 pub fn cmd_lrem_length_limit_or_default() -> u32 {
+    if let Some(value) = config_override("cmd_lrem_length_limit") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.cmd_lrem_length_limit {
@@ -495,6 +711,9 @@ pub fn cmd_lrem_length_limit_or_default() -> u32 {
 }
 
 pub fn cmd_linsert_length_limit_or_default() -> u32 {
+    if let Some(value) = config_override("cmd_linsert_length_limit") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.cmd_linsert_length_limit {
@@ -506,7 +725,25 @@ pub fn cmd_linsert_length_limit_or_default() -> u32 {
     0
 }
 
+pub fn cmd_scan_count_limit_or_default() -> u32 {
+    if let Some(value) = config_override("cmd_scan_count_limit") {
+        return value;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.cmd_scan_count_limit {
+                return b;
+            }
+        }
+    }
+    // default scan count no limit
+    0
+}
+
 pub fn async_del_list_threshold_or_default() -> u32 {
+    if let Some(value) = config_override("async_del_list_threshold") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.async_del_list_threshold {
@@ -522,6 +759,9 @@ pub fn async_del_list_threshold_or_default() -> u32 {
 }
 
 pub fn async_del_hash_threshold_or_default() -> u32 {
+    if let Some(value) = config_override("async_del_hash_threshold") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.async_del_hash_threshold {
@@ -537,6 +777,9 @@ pub fn async_del_hash_threshold_or_default() -> u32 {
 }
 
 pub fn async_del_set_threshold_or_default() -> u32 {
+    if let Some(value) = config_override("async_del_set_threshold") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.async_del_set_threshold {
@@ -552,6 +795,9 @@ pub fn async_del_set_threshold_or_default() -> u32 {
 }
 
 pub fn async_del_zset_threshold_or_default() -> u32 {
+    if let Some(value) = config_override("async_del_zset_threshold") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.async_del_zset_threshold {
@@ -567,6 +813,9 @@ pub fn async_del_zset_threshold_or_default() -> u32 {
 }
 
 pub fn async_expire_list_threshold_or_default() -> u32 {
+    if let Some(value) = config_override("async_expire_list_threshold") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.async_expire_list_threshold {
@@ -582,6 +831,9 @@ pub fn async_expire_list_threshold_or_default() -> u32 {
 }
 
 pub fn async_expire_hash_threshold_or_default() -> u32 {
+    if let Some(value) = config_override("async_expire_hash_threshold") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.async_expire_hash_threshold {
@@ -597,6 +849,9 @@ pub fn async_expire_hash_threshold_or_default() -> u32 {
 }
 
 pub fn async_expire_set_threshold_or_default() -> u32 {
+    if let Some(value) = config_override("async_expire_set_threshold") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.async_expire_set_threshold {
@@ -612,6 +867,9 @@ pub fn async_expire_set_threshold_or_default() -> u32 {
 }
 
 pub fn async_expire_zset_threshold_or_default() -> u32 {
+    if let Some(value) = config_override("async_expire_zset_threshold") {
+        return value;
+    }
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
             if let Some(b) = c.backend.async_expire_zset_threshold {
@@ -638,6 +896,22 @@ pub fn async_deletion_enabled_or_default() -> bool {
     true
 }
 
+pub fn lazyfree_lazy_server_del_or_default() -> bool {
+    if let Some(value) = config_override("lazyfree_lazy_server_del") {
+        return value;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.lazyfree_lazy_server_del {
+                // the old members are swept by the gc workers
+                return b && async_deletion_enabled_or_default();
+            }
+        }
+    }
+    // default free the members of an overwritten collection the same way as DEL
+    false
+}
+
 pub fn async_gc_interval_or_default() -> u64 {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
@@ -686,6 +960,18 @@ pub fn backend_timeout_or_default() -> u64 {
     10000
 }
 
+pub fn backend_health_check_interval_or_default() -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(ms) = c.backend.health_check_interval {
+                return ms;
+            }
+        }
+    }
+    // default check the pooled clients every 10s
+    10000
+}
+
 pub fn backend_ca_file_or_default() -> String {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
@@ -734,6 +1020,33 @@ pub fn conn_concurrency_or_default() -> usize {
     5
 }
 
+/// The most txn clients the pool grows to under load, never less than
+/// `conn_concurrency` which is the size the pool starts and shrinks back to.
+pub fn conn_concurrency_max_or_default() -> usize {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.conn_concurrency_max {
+                return b.max(conn_concurrency_or_default());
+            }
+        }
+    }
+    // default the pool size is fixed
+    conn_concurrency_or_default()
+}
+
+/// The requests in flight on every txn client above which the pool grows.
+pub fn conn_inflight_threshold_or_default() -> i64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.backend.conn_inflight_threshold {
+                return n.max(1);
+            }
+        }
+    }
+    // default grow when each client has 32 requests in flight
+    32
+}
+
 pub fn backend_completion_queue_size_or_default() -> usize {
     unsafe {
         if let Some(c) = &SERVER_CONFIG {
@@ -828,3 +1141,282 @@ pub fn backend_max_inflight_requests_or_default() -> usize {
     // default backend max inflight requests
     100
 }
+
+pub fn bulk_load_batch_size_or_default() -> usize {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.bulk_load_batch_size {
+                return b;
+            }
+        }
+    }
+    // default writes committed in one bulk load transaction
+    1000
+}
+
+pub fn access_time_sample_rate_or_default() -> f64 {
+    if let Some(value) = config_override("access_time_sample_rate") {
+        return value;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.backend.access_time_sample_rate {
+                return b;
+            }
+        }
+    }
+    // default sample 1% of requests to record key access time
+    0.01
+}
+
+/// Execution timeout of the command in milliseconds, 0 means no timeout.
+/// `cmd_timeout_overrides` maps lowercase command names to their own timeout,
+/// `CONFIG SET cmd_timeout_ms` changes the timeout of the others.
+pub fn cmd_timeout_ms_or_default(cmd: &str) -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(ms) = c
+                .backend
+                .cmd_timeout_overrides
+                .as_ref()
+                .and_then(|overrides| overrides.get(cmd))
+            {
+                return *ms;
+            }
+        }
+    }
+    if let Some(ms) = config_override("cmd_timeout_ms") {
+        return ms;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(ms) = c.backend.cmd_timeout_ms {
+                return ms;
+            }
+        }
+    }
+    // default no timeout
+    0
+}
+
+pub fn debug_raw_enabled_or_default() -> bool {
+    if let Some(value) = config_override("debug_raw_enabled") {
+        return value;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.server.debug_raw_enabled {
+                return b;
+            }
+        }
+    }
+    // default disabled, the dump exposes the internal encoding
+    false
+}
+
+pub fn cmd_size_metrics_enabled_or_default() -> bool {
+    if let Some(value) = config_override("cmd_size_metrics_enabled") {
+        return value;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(b) = c.server.cmd_size_metrics_enabled {
+                return b;
+            }
+        }
+    }
+    // default disabled, the size class multiplies the series of the handle time
+    false
+}
+
+pub fn max_subscriptions_per_client_or_default() -> usize {
+    if let Some(value) = config_override("max_subscriptions_per_client") {
+        return value;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.server.max_subscriptions_per_client {
+                return n;
+            }
+        }
+    }
+    // default 0, no limit of the channels a client subscribes to
+    0
+}
+
+pub fn notify_keyspace_events_or_default() -> String {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(s) = &c.server.notify_keyspace_events {
+                return s.clone();
+            }
+        }
+    }
+    // default empty, keyspace notifications are disabled
+    "".to_owned()
+}
+
+pub fn max_inflight_cmds_or_default() -> usize {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.server.max_inflight_cmds {
+                return n;
+            }
+        }
+    }
+    // default 0, no limit of the commands running at the same time
+    0
+}
+
+pub fn proto_max_bulk_len_or_default() -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.server.proto_max_bulk_len {
+                return n;
+            }
+        }
+    }
+    // default 512MB, same as redis
+    512 * 1024 * 1024
+}
+
+pub fn proto_max_multibulk_len_or_default() -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.server.proto_max_multibulk_len {
+                return n;
+            }
+        }
+    }
+    // default 1M elements, same as the redis limit of a request
+    1024 * 1024
+}
+
+/// The users of the access control list, each one a user name followed by its
+/// rules as in `ACL SETUSER`, e.g. `alice on >secret ~cache:* +@read`.
+pub fn acl_users_or_default() -> Vec<String> {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(users) = &c.server.acl_users {
+                return users.clone();
+            }
+        }
+    }
+    // default only the `default` user, allowed to run everything
+    vec![]
+}
+
+pub fn slowlog_log_slower_than_or_default() -> i64 {
+    if let Some(value) = config_override("slowlog_log_slower_than") {
+        return value;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(us) = c.server.slowlog_log_slower_than {
+                return us;
+            }
+        }
+    }
+    // default log the commands slower than 10ms, a negative value disables
+    // the slow log
+    10000
+}
+
+pub fn slowlog_max_len_or_default() -> usize {
+    if let Some(value) = config_override("slowlog_max_len") {
+        return value;
+    }
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.server.slowlog_max_len {
+                return n;
+            }
+        }
+    }
+    // default keep the last 128 slow commands
+    128
+}
+
+pub fn negative_cache_size_or_default() -> usize {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(s) = c.backend.negative_cache_size {
+                return s;
+            }
+        }
+    }
+    // default disable the cache of missing keys
+    0
+}
+
+pub fn negative_cache_ttl_ms_or_default() -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(ttl) = c.backend.negative_cache_ttl_ms {
+                return ttl;
+            }
+        }
+    }
+    // default cache a missing key for 1s
+    1000
+}
+
+pub fn ttl_sample_interval_or_default() -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(ms) = c.backend.ttl_sample_interval {
+                return ms;
+            }
+        }
+    }
+    // default disable the ttl sampler
+    0
+}
+
+pub fn ttl_sample_size_or_default() -> u32 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.backend.ttl_sample_size {
+                return n;
+            }
+        }
+    }
+    // default scan 1000 entries each round
+    1000
+}
+
+pub fn active_expire_interval_or_default() -> u64 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(ms) = c.backend.active_expire_interval {
+                return ms;
+            }
+        }
+    }
+    // default run an active expire cycle every second
+    1000
+}
+
+pub fn active_expire_scan_size_or_default() -> u32 {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.backend.active_expire_scan_size {
+                return n;
+            }
+        }
+    }
+    // default scan 1000 entries each cycle
+    1000
+}
+
+pub fn active_expire_batch_size_or_default() -> usize {
+    unsafe {
+        if let Some(c) = &SERVER_CONFIG {
+            if let Some(n) = c.backend.active_expire_batch_size {
+                return n;
+            }
+        }
+    }
+    // default delete up to 100 expired keys each cycle
+    100
+}
@@ -3,11 +3,37 @@ use crate::metrics::{DATA_TRAFFIC_IN, DATA_TRAFFIC_OUT};
 
 use async_std::io::{BufReader, BufWriter, WriteExt};
 use async_std::net::TcpStream;
+use async_std::os::unix::net::UnixStream;
 use async_tls::server::TlsStream;
-use bytes::{Buf, BytesMut};
-use futures::AsyncReadExt;
+use bytes::BytesMut;
+use futures::{future, AsyncReadExt};
 use std::io::{self, Cursor};
 
+/// Max capacity of the encode buffer kept by the connection between writes
+const MAX_REUSED_WRITE_BUFFER: usize = 64 * 1024;
+
+/// Data buffered ahead by `fill_read_buffer` while a command is blocked, the
+/// peer is not read any further until the command is done.
+const MAX_READ_AHEAD_BUFFER: usize = 1024 * 1024;
+
+/// The kind of socket a client connected through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketType {
+    Tcp,
+    Tls,
+    Unix,
+}
+
+impl SocketType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SocketType::Tcp => "tcp",
+            SocketType::Tls => "tls",
+            SocketType::Unix => "unix",
+        }
+    }
+}
+
 /// Send and receive `Frame` values from a remote peer.
 ///
 /// When implementing networking protocols, a message on that protocol is
@@ -22,7 +48,7 @@ use std::io::{self, Cursor};
 /// The contents of the write buffer are then written to the socket.
 #[derive(Debug)]
 pub struct Connection {
-    tls: bool,
+    socket_type: SocketType,
 
     // The `TcpStream`. It is decorated with a `BufWriter`, which provides write
     // level buffering. The `BufWriter` implementation provided by Tokio is
@@ -33,11 +59,24 @@ pub struct Connection {
     tls_w: Option<BufWriter<futures::io::WriteHalf<TlsStream<TcpStream>>>>,
     tls_r: Option<BufReader<futures::io::ReadHalf<TlsStream<TcpStream>>>>,
 
+    unix_w: Option<BufWriter<UnixStream>>,
+    unix_r: Option<BufReader<UnixStream>>,
+
     local_addr: String,
     peer_addr: String,
 
     // The buffer for reading frames.
     buffer: BytesMut,
+    // The buffer for encoding frames.
+    write_buffer: Vec<u8>,
+
+    // The peer negotiated RESP3 with HELLO
+    resp3: bool,
+
+    // Code of the last error replied, e.g. `ERR` or `WRONGTYPE`
+    last_error: Option<String>,
+    // Number of the error replies written
+    error_replies: u64,
 }
 
 impl Connection {
@@ -45,7 +84,7 @@ impl Connection {
     /// are initialized.
     pub fn new(socket: TcpStream) -> Connection {
         Connection {
-            tls: false,
+            socket_type: SocketType::Tcp,
             local_addr: socket.local_addr().unwrap().to_string(),
             peer_addr: socket.peer_addr().unwrap().to_string(),
 
@@ -54,11 +93,17 @@ impl Connection {
 
             tls_w: None,
             tls_r: None,
+            unix_w: None,
+            unix_r: None,
             // Default to a 4KB read buffer. For the use case of mini redis,
             // this is fine. However, real applications will want to tune this
             // value to their specific use case. There is a high likelihood that
             // a larger read buffer will work better.
             buffer: BytesMut::with_capacity(32 * 1024),
+            write_buffer: Vec::new(),
+            resp3: false,
+            last_error: None,
+            error_replies: 0,
         }
     }
 
@@ -69,7 +114,7 @@ impl Connection {
     ) -> Connection {
         let (tls_r, tls_w) = tls_stream.split();
         Connection {
-            tls: true,
+            socket_type: SocketType::Tls,
             local_addr: local_addr.to_string(),
             peer_addr: peer_addr.to_string(),
 
@@ -78,10 +123,43 @@ impl Connection {
 
             tls_w: Some(BufWriter::new(tls_w)),
             tls_r: Some(BufReader::new(tls_r)),
+            unix_w: None,
+            unix_r: None,
+            buffer: BytesMut::with_capacity(32 * 1024),
+            write_buffer: Vec::new(),
+            resp3: false,
+            last_error: None,
+            error_replies: 0,
+        }
+    }
+
+    /// Create a `Connection` backed by a unix domain socket, the peer of a
+    /// unix socket has no address so both ends are named after the socket path.
+    pub fn new_unix(path: &str, socket: UnixStream) -> Connection {
+        Connection {
+            socket_type: SocketType::Unix,
+            local_addr: path.to_string(),
+            peer_addr: format!("{}:0", path),
+
+            w: None,
+            r: None,
+
+            tls_w: None,
+            tls_r: None,
+            unix_w: Some(BufWriter::new(socket.clone())),
+            unix_r: Some(BufReader::new(socket)),
             buffer: BytesMut::with_capacity(32 * 1024),
+            write_buffer: Vec::new(),
+            resp3: false,
+            last_error: None,
+            error_replies: 0,
         }
     }
 
+    pub fn socket_type(&self) -> SocketType {
+        self.socket_type
+    }
+
     pub fn local_addr(&self) -> &str {
         &self.local_addr
     }
@@ -90,30 +168,51 @@ impl Connection {
         &self.peer_addr
     }
 
+    pub fn is_resp3(&self) -> bool {
+        self.resp3
+    }
+
+    pub fn set_resp3(&mut self, resp3: bool) {
+        self.resp3 = resp3;
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    pub fn error_replies(&self) -> u64 {
+        self.error_replies
+    }
+
+    fn error_replied(&mut self, err: &str) {
+        self.last_error = error_code(err);
+        self.error_replies += 1;
+    }
+
     async fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
-        if self.tls {
-            self.tls_w.as_mut().unwrap().write_all(buf).await?;
-        } else {
-            self.w.as_mut().unwrap().write_all(buf).await?;
+        match self.socket_type {
+            SocketType::Tcp => self.w.as_mut().unwrap().write_all(buf).await?,
+            SocketType::Tls => self.tls_w.as_mut().unwrap().write_all(buf).await?,
+            SocketType::Unix => self.unix_w.as_mut().unwrap().write_all(buf).await?,
         }
         DATA_TRAFFIC_OUT.inc_by(buf.len() as u64);
         Ok(())
     }
 
     async fn flush(&mut self) -> io::Result<()> {
-        if self.tls {
-            self.tls_w.as_mut().unwrap().flush().await?;
-        } else {
-            self.w.as_mut().unwrap().flush().await?;
+        match self.socket_type {
+            SocketType::Tcp => self.w.as_mut().unwrap().flush().await?,
+            SocketType::Tls => self.tls_w.as_mut().unwrap().flush().await?,
+            SocketType::Unix => self.unix_w.as_mut().unwrap().flush().await?,
         }
         Ok(())
     }
 
     async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        Ok(if self.tls {
-            self.tls_r.as_mut().unwrap().read(buf).await?
-        } else {
-            self.r.as_mut().unwrap().read(buf).await?
+        Ok(match self.socket_type {
+            SocketType::Tcp => self.r.as_mut().unwrap().read(buf).await?,
+            SocketType::Tls => self.tls_r.as_mut().unwrap().read(buf).await?,
+            SocketType::Unix => self.unix_r.as_mut().unwrap().read(buf).await?,
         })
     }
 
@@ -132,9 +231,26 @@ impl Connection {
         loop {
             // Attempt to parse a frame from the buffered data. If enough data
             // has been buffered, the frame is returned.
-            if let (Some(frame), len) = self.parse_frame()? {
-                DATA_TRAFFIC_IN.inc_by(len as u64);
-                return Ok(Some(frame));
+            match self.parse_frame() {
+                Ok((Some(frame), len)) => {
+                    DATA_TRAFFIC_IN.inc_by(len as u64);
+                    return Ok(Some(frame));
+                }
+                Ok((None, 0)) => {}
+                // an empty request was skipped, try the rest of the buffer
+                Ok((None, len)) => {
+                    DATA_TRAFFIC_IN.inc_by(len as u64);
+                    continue;
+                }
+                Err(e) => {
+                    // tell the peer why the connection is closed, same as redis
+                    if let Some(err @ frame::Error::Protocol(_)) = e.downcast_ref::<frame::Error>()
+                    {
+                        let reply = Frame::ErrorOwned(format!("ERR {}", err));
+                        let _ = self.write_frame(&reply).await;
+                    }
+                    return Err(e);
+                }
             }
 
             // There is not enough buffered data to read a frame. Attempt to
@@ -142,7 +258,7 @@ impl Connection {
             //
             // On success, the number of bytes is returned. `0` indicates "end
             // of stream".
-            let mut buf = vec![0; 1024];
+            let mut buf = [0; 4096];
             let len = self.read(&mut buf).await?;
             if 0 == len {
                 // The remote closed the connection. For this to be a clean
@@ -161,10 +277,30 @@ impl Connection {
         }
     }
 
+    /// Wait for more data from the peer without parsing it, the data is kept
+    /// in the read buffer for the next `read_frame`. This lets a blocked
+    /// command notice the peer going away.
+    ///
+    /// Returns `false` once the peer closed the connection. Once enough data
+    /// is buffered ahead it never returns, the peer keeps pipelining while the
+    /// command is blocked and TCP flow control pushes back on it.
+    pub async fn fill_read_buffer(&mut self) -> crate::Result<bool> {
+        if self.buffer.len() >= MAX_READ_AHEAD_BUFFER {
+            return future::pending().await;
+        }
+        let mut buf = [0; 4096];
+        let len = self.read(&mut buf).await?;
+        self.buffer.extend_from_slice(&buf[..len]);
+        Ok(len > 0)
+    }
+
     /// Tries to parse a frame from the buffer. If the buffer contains enough
     /// data, the frame is returned and the data removed from the buffer. If not
     /// enough data has been buffered yet, `Ok(None)` is returned. If the
     /// buffered data does not represent a valid frame, `Err` is returned.
+    ///
+    /// An empty request is removed from the buffer and `Ok(None)` is returned
+    /// with its length.
     fn parse_frame(&mut self) -> crate::Result<(Option<Frame>, usize)> {
         use frame::Error::Incomplete;
 
@@ -174,12 +310,24 @@ impl Connection {
         // with bytes.
         let mut buf = Cursor::new(&self.buffer[..]);
 
+        // Requests not starting with an array are inline commands, as sent by
+        // humans over telnet.
+        let inline = match self.buffer.first() {
+            Some(&b) => b != b'*',
+            None => return Ok((None, 0)),
+        };
         // The first step is to check if enough data has been buffered to parse
         // a single frame. This step is usually much faster than doing a full
         // parse of the frame, and allows us to skip allocating data structures
         // to hold the frame data unless we know the full frame has been
         // received.
-        match Frame::check(&mut buf) {
+        let checked = if inline {
+            Frame::check_inline(&mut buf)
+        } else {
+            Frame::check(&mut buf)
+        };
+
+        match checked {
             Ok(_) => {
                 // The `check` function will have advanced the cursor until the
                 // end of the frame. Since the cursor had position set to zero
@@ -187,26 +335,28 @@ impl Connection {
                 // frame by checking the cursor position.
                 let len = buf.position() as usize;
 
-                // Reset the position to zero before passing the cursor to
-                // `Frame::parse`.
-                buf.set_position(0);
+                // Split the frame off the read buffer. The frame shares the
+                // allocation with the read buffer, `BytesMut` only allocates
+                // a new buffer on the next fill if a parsed frame still holds
+                // a reference to the old one.
+                let data = self.buffer.split_to(len).freeze();
 
-                // Parse the frame from the buffer. This allocates the necessary
-                // structures to represent the frame and returns the frame
-                // value.
+                // Parse the frame from the split data. Bulk strings are
+                // slices of `data`, only the array of arguments is allocated.
                 //
                 // If the encoded frame representation is invalid, an error is
                 // returned. This should terminate the **current** connection
                 // but should not impact any other connected client.
-                let frame = Frame::parse(&mut buf)?;
+                let frame = if inline {
+                    Frame::parse_inline(&mut Cursor::new(&data[..]), &data)?
+                } else {
+                    Frame::parse(&mut Cursor::new(&data[..]), &data)?
+                };
 
-                // Discard the parsed data from the read buffer.
-                //
-                // When `advance` is called on the read buffer, all of the data
-                // up to `len` is discarded. The details of how this works is
-                // left to `BytesMut`. This is often done by moving an internal
-                // cursor, but it may be done by reallocating and copying data.
-                self.buffer.advance(len);
+                // Empty lines are skipped, same as redis.
+                if matches!(&frame, Frame::Array(args) if args.is_empty()) {
+                    return Ok((None, len));
+                }
 
                 // Return the parsed frame to the caller.
                 Ok((Some(frame), len))
@@ -235,108 +385,33 @@ impl Connection {
     /// write stream. The data will be written to the buffer. Once the buffer is
     /// full, it is flushed to the underlying socket.
     pub async fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
-        // Arrays are encoded by encoding each entry. All other frame types are
-        // considered literals. For now, this is not able to encode
-        // recursive frame structures. See below for more details.
         match frame {
-            Frame::Array(val) => {
-                // Encode the frame type prefix. For an array, it is `*`.
-                self.write_all(b"*").await?;
-
-                // Encode the length of the array.
-                self.write_decimal(val.len() as i64).await?;
-
-                // Iterate and encode each entry in the array.
-                for entry in &**val {
-                    // TODO make this to be recursive
-                    // we need nested array response only for `cluster slots` command for now
-                    match entry {
-                        Frame::Array(val) => {
-                            self.write_all(b"*").await?;
-                            self.write_decimal(val.len() as i64).await?;
-                            for entry in &**val {
-                                match entry {
-                                    Frame::Array(val) => {
-                                        self.write_all(b"*").await?;
-                                        self.write_decimal(val.len() as i64).await?;
-                                        for entry in &**val {
-                                            self.write_value(entry).await?;
-                                        }
-                                    }
-                                    _ => self.write_value(entry).await?,
-                                }
-                            }
-                        }
-                        _ => self.write_value(entry).await?,
-                    }
-                }
-            }
-            // The frame type is a literal. Encode the value directly.
-            _ => self.write_value(frame).await?,
+            Frame::ErrorOwned(e) => self.error_replied(e),
+            Frame::ErrorString(e) => self.error_replied(e),
+            _ => {}
+        }
+
+        // Encode the whole frame first, nested frames are encoded recursively
+        // which async fns do not support. The encode buffer is reused across
+        // writes of the connection.
+        let mut buf = std::mem::take(&mut self.write_buffer);
+        buf.clear();
+        frame.encode(&mut buf, self.resp3);
+        let res = self.write_all(&buf).await;
+        // do not hold the memory of a huge reply for the connection lifetime
+        if buf.capacity() <= MAX_REUSED_WRITE_BUFFER {
+            self.write_buffer = buf;
         }
+        res?;
 
         // Ensure the encoded frame is written to the socket. The calls above
         // are to the buffered stream and writes. Calling `flush` writes the
         // remaining contents of the buffer to the socket.
         self.flush().await
     }
+}
 
-    /// Write a frame literal to the stream
-    async fn write_value(&mut self, frame: &Frame) -> io::Result<()> {
-        match frame {
-            Frame::Simple(val) => {
-                self.write_all(b"+").await?;
-                self.write_all(val.as_bytes()).await?;
-                self.write_all(b"\r\n").await?;
-            }
-            Frame::ErrorString(val) => {
-                self.write_all(b"-").await?;
-                self.write_all(val.as_bytes()).await?;
-                self.write_all(b"\r\n").await?;
-            }
-            Frame::ErrorOwned(val) => {
-                self.write_all(b"-").await?;
-                self.write_all(val.as_bytes()).await?;
-                self.write_all(b"\r\n").await?;
-            }
-            Frame::Integer(val) => {
-                self.write_all(b":").await?;
-                self.write_decimal(*val).await?;
-            }
-            Frame::Null => {
-                self.write_all(b"$-1\r\n").await?;
-            }
-            Frame::Bulk(val) => {
-                let len = val.len();
-
-                self.write_all(b"$").await?;
-                self.write_decimal(len as i64).await?;
-                self.write_all(val).await?;
-                self.write_all(b"\r\n").await?;
-            }
-            // Encoding an `Array` from within a value cannot be done using a
-            // recursive strategy. In general, async fns do not support
-            // recursion.
-            // We do support at most 3 nested array response in up-level for now.
-            Frame::Array(_val) => unreachable!(),
-        }
-
-        Ok(())
-    }
-
-    /// Write a decimal frame to the stream
-    async fn write_decimal(&mut self, val: i64) -> io::Result<()> {
-        use std::io::Write;
-
-        // Convert the value to a string
-        let mut buf = [0u8; 20];
-        let mut buf = Cursor::new(&mut buf[..]);
-        write!(&mut buf, "{}", val)?;
-
-        let pos = buf.position() as usize;
-        self.write_all(&buf.get_ref()[..pos]).await?;
-        self.write_all(b"\r\n").await?;
-
-        Ok(())
-    }
+/// The code of an error reply is its first word, by convention in upper case.
+fn error_code(err: &str) -> Option<String> {
+    err.split_whitespace().next().map(|code| code.to_owned())
 }
@@ -2,9 +2,11 @@ use tokio::sync::{broadcast, Notify};
 use tokio::time::{self, Duration, Instant};
 
 use crate::config::LOGGER;
+use crate::utils::glob_match;
 use bytes::Bytes;
 use slog::debug;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
 /// A wrapper around a `Db` instance. This exists to allow orderly cleanup
@@ -56,6 +58,31 @@ struct Shared {
     /// task waits on this to be notified, then checks for expired values or the
     /// shutdown signal.
     background_task: Notify,
+
+    /// Broadcasts the commands processed by the server to `MONITOR` clients.
+    ///
+    /// Kept out of `state` with an atomic count of attached monitors, so
+    /// connections check for monitors on every command without taking the lock.
+    monitor: broadcast::Sender<Bytes>,
+    monitors: AtomicUsize,
+
+    /// Number of blocked list pops, checked on every write without the lock.
+    list_waiters: AtomicUsize,
+}
+
+/// Receiver of the monitored commands, detaches the monitor when dropped.
+#[derive(Debug)]
+pub(crate) struct MonitorReceiver {
+    rx: broadcast::Receiver<Bytes>,
+    shared: Arc<Shared>,
+}
+
+/// A blocked list pop registered on its keys, unregistered when dropped.
+#[derive(Debug)]
+pub(crate) struct ListWaiter {
+    keys: Vec<String>,
+    notify: Arc<Notify>,
+    shared: Arc<Shared>,
 }
 
 #[derive(Debug)]
@@ -68,6 +95,14 @@ struct State {
 
     pub_sub: HashMap<String, broadcast::Sender<Bytes>>,
 
+    /// Broadcast channels of the patterns subscribed, a message carries the
+    /// channel it was published to.
+    pattern_sub: HashMap<String, broadcast::Sender<(String, Bytes)>>,
+
+    /// Blocked list pops of each list in FIFO order, a write to the list wakes
+    /// the first one only.
+    blocked_lists: HashMap<String, VecDeque<Arc<Notify>>>,
+
     /// Tracks key TTLs.
     ///
     /// A `BTreeMap` is used to maintain expirations sorted by when they expire.
@@ -138,11 +173,16 @@ impl Db {
                 entries: HashMap::new(),
                 scripts: HashMap::new(),
                 pub_sub: HashMap::new(),
+                pattern_sub: HashMap::new(),
+                blocked_lists: HashMap::new(),
                 expirations: BTreeMap::new(),
                 next_id: 0,
                 shutdown: false,
             }),
             background_task: Notify::new(),
+            monitor: broadcast::channel(1024).0,
+            monitors: AtomicUsize::new(0),
+            list_waiters: AtomicUsize::new(0),
         });
 
         // Start the background task.
@@ -284,21 +324,145 @@ impl Db {
         }
     }
 
-    /// Publish a message to the channel. Returns the number of subscribers
-    /// listening on the channel.
-    pub(crate) fn publish(&self, key: &str, value: Bytes) -> usize {
-        let state = self.shared.state.lock().unwrap();
+    /// Release the broadcast channel of `key` once its last subscriber is
+    /// gone, must be called after dropping the `Receiver` of the channel.
+    pub(crate) fn unsubscribe(&self, key: &str) {
+        let mut state = self.shared.state.lock().unwrap();
 
-        state
+        let unused = state
             .pub_sub
             .get(key)
-            // On a successful message send on the broadcast channel, the number
-            // of subscribers is returned. An error indicates there are no
-            // receivers, in which case, `0` should be returned.
-            .map(|tx| tx.send(value).unwrap_or(0))
+            .map_or(false, |tx| tx.receiver_count() == 0);
+        if unused {
+            state.pub_sub.remove(key);
+        }
+    }
+
+    /// Returns a `Receiver` for the requested pattern, it receives the messages
+    /// published to the channels matching the pattern along with the channel.
+    #[allow(clippy::significant_drop_in_scrutinee)]
+    pub(crate) fn psubscribe(&self, pattern: String) -> broadcast::Receiver<(String, Bytes)> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.pattern_sub.get(&pattern) {
+            Some(tx) => tx.subscribe(),
+            None => {
+                let (tx, rx) = broadcast::channel(1024);
+                state.pattern_sub.insert(pattern, tx);
+                rx
+            }
+        }
+    }
+
+    /// Release the broadcast channel of `pattern` once its last subscriber is
+    /// gone, must be called after dropping the `Receiver` of the pattern.
+    pub(crate) fn punsubscribe(&self, pattern: &str) {
+        let mut state = self.shared.state.lock().unwrap();
+
+        let unused = state
+            .pattern_sub
+            .get(pattern)
+            .map_or(false, |tx| tx.receiver_count() == 0);
+        if unused {
+            state.pattern_sub.remove(pattern);
+        }
+    }
+
+    /// Publish a message to the channel. Returns the number of subscribers
+    /// listening on the channel, a client subscribed to the channel and to
+    /// patterns matching it is counted once for each of them.
+    pub(crate) fn publish(&self, key: &str, value: Bytes) -> usize {
+        let mut state = self.shared.state.lock().unwrap();
+
+        // On a successful message send on the broadcast channel, the number
+        // of subscribers is returned. An error indicates there are no
+        // receivers, the channel is left over by a subscriber which did not
+        // release it and is removed.
+        let mut receivers = match state.pub_sub.get(key).map(|tx| tx.send(value.clone())) {
+            Some(Ok(receivers)) => receivers,
+            Some(Err(_)) => {
+                state.pub_sub.remove(key);
+                0
+            }
             // If there is no entry for the channel key, then there are no
-            // subscribers. In this case, return `0`.
-            .unwrap_or(0)
+            // subscribers.
+            None => 0,
+        };
+
+        // every pattern is matched against the channel, the patterns are few
+        // in practice
+        for (pattern, tx) in state.pattern_sub.iter() {
+            if glob_match(pattern.as_bytes(), key.as_bytes()) {
+                receivers += tx.send((key.to_owned(), value.clone())).unwrap_or(0);
+            }
+        }
+        receivers
+    }
+
+    /// Attach a monitor, the returned receiver gets every command fed by
+    /// `feed_monitors` until it is dropped.
+    pub(crate) fn monitor(&self) -> MonitorReceiver {
+        let rx = self.shared.monitor.subscribe();
+        self.shared.monitors.fetch_add(1, Ordering::Relaxed);
+        MonitorReceiver {
+            rx,
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Returns `true` if any monitor is attached.
+    pub(crate) fn is_monitored(&self) -> bool {
+        self.shared.monitors.load(Ordering::Relaxed) > 0
+    }
+
+    /// Send the formatted command line to all attached monitors.
+    pub(crate) fn feed_monitors(&self, line: Bytes) {
+        // no receivers left is not an error
+        let _ = self.shared.monitor.send(line);
+    }
+
+    /// Register a blocked pop on `keys`, behind the pops already waiting on them.
+    pub(crate) fn block_on_lists(&self, keys: &[String]) -> ListWaiter {
+        let notify = Arc::new(Notify::new());
+        let mut state = self.shared.state.lock().unwrap();
+        for key in keys {
+            state
+                .blocked_lists
+                .entry(key.clone())
+                .or_default()
+                .push_back(notify.clone());
+        }
+        self.shared.list_waiters.fetch_add(1, Ordering::Relaxed);
+        ListWaiter {
+            keys: keys.to_vec(),
+            notify,
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Returns `true` if any list pop is blocked.
+    pub(crate) fn has_list_waiters(&self) -> bool {
+        self.shared.list_waiters.load(Ordering::Relaxed) > 0
+    }
+
+    /// Wake the first blocked pop of each written key, an empty slice wakes the
+    /// first blocked pop of every list as the written keys are unknown.
+    pub(crate) fn wake_list_waiters(&self, keys: &[String]) {
+        let mut state = self.shared.state.lock().unwrap();
+        let mut wake = |queue: &mut VecDeque<Arc<Notify>>| {
+            if let Some(notify) = queue.pop_front() {
+                notify.notify_one();
+            }
+        };
+        if keys.is_empty() {
+            state.blocked_lists.values_mut().for_each(&mut wake);
+        } else {
+            for key in keys {
+                if let Some(queue) = state.blocked_lists.get_mut(key) {
+                    wake(queue);
+                }
+            }
+        }
+        state.blocked_lists.retain(|_, queue| !queue.is_empty());
     }
 
     /// Signals the purge background task to shut down. This is called by the
@@ -317,6 +481,68 @@ impl Db {
     }
 }
 
+impl MonitorReceiver {
+    /// Receive the next monitored command line, returns `None` if the server
+    /// is shutting down. Lines dropped for a slow monitor are skipped.
+    pub(crate) async fn recv(&mut self) -> Option<Bytes> {
+        loop {
+            match self.rx.recv().await {
+                Ok(line) => return Some(line),
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for MonitorReceiver {
+    fn drop(&mut self) {
+        self.shared.monitors.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ListWaiter {
+    /// Wait until a write to one of the keys wakes this pop. A wake sent before
+    /// the call is not lost.
+    pub(crate) async fn woken(&self) {
+        self.notify.notified().await
+    }
+
+    /// Put the pop back at the front of the lists it was woken from, a woken
+    /// pop which found the lists empty keeps its turn.
+    pub(crate) fn requeue(&self) {
+        let mut state = self.shared.state.lock().unwrap();
+        for key in &self.keys {
+            let queue = state.blocked_lists.entry(key.clone()).or_default();
+            if !queue.iter().any(|notify| Arc::ptr_eq(notify, &self.notify)) {
+                queue.push_front(self.notify.clone());
+            }
+        }
+    }
+}
+
+impl Drop for ListWaiter {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock().unwrap();
+        for key in &self.keys {
+            if let Some(queue) = state.blocked_lists.get_mut(key) {
+                if queue.iter().any(|notify| Arc::ptr_eq(notify, &self.notify)) {
+                    queue.retain(|notify| !Arc::ptr_eq(notify, &self.notify));
+                } else if let Some(notify) = queue.pop_front() {
+                    // the pop was woken from this key, the wake is passed on
+                    // so that it is not lost if the pop gives up, the next pop
+                    // waits again if the list is empty
+                    notify.notify_one();
+                }
+                if queue.is_empty() {
+                    state.blocked_lists.remove(key);
+                }
+            }
+        }
+        self.shared.list_waiters.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 impl Shared {
     /// Purge all expired keys and return the `Instant` at which the **next**
     /// key will expire. The background task will sleep until this instant.
@@ -0,0 +1,168 @@
+//! Active expiration, the keys whose ttl passed are deleted in the background
+//! instead of waiting for a command to access them.
+//!
+//! Each cycle scans a bounded number of entries after the position where the
+//! previous cycle stopped and deletes the expired keys found, so the whole
+//! keyspace is covered over many cycles as the TTL sampler does.
+//!
+//! `DEBUG SET-ACTIVE-EXPIRE 0` pauses the cycle so tests control when the
+//! expired keys are removed, the keys are still expired lazily on access.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use futures::future;
+use slog::{debug, error};
+use tikv_client::{BoundRange, Key};
+use tokio::time::{self, Duration, MissedTickBehavior};
+
+use crate::config::LOGGER;
+use crate::metrics::ACTIVE_EXPIRED_KEY_COUNTER;
+use crate::tikv::encoding::{DataType, KeyDecoder};
+use crate::tikv::errors::AsyncResult;
+use crate::tikv::hash::HashCommandCtx;
+use crate::tikv::list::ListCommandCtx;
+use crate::tikv::set::SetCommandCtx;
+use crate::tikv::string::StringCommandCtx;
+use crate::tikv::zset::ZsetCommandCtx;
+use crate::tikv::{get_txn_client, KEY_ENCODER};
+use crate::utils::key_is_expired;
+use crate::{
+    active_expire_batch_size_or_default, active_expire_interval_or_default,
+    active_expire_scan_size_or_default, is_use_txn_api,
+};
+
+static ACTIVE_EXPIRE_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Whether the active expire cycle deletes the expired keys it finds.
+pub fn active_expire_enabled() -> bool {
+    ACTIVE_EXPIRE_ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_active_expire_enabled(enabled: bool) {
+    ACTIVE_EXPIRE_ENABLED.store(enabled, Ordering::Relaxed)
+}
+
+/// Delete the expired keys in the background periodically.
+pub struct ActiveExpire {
+    // the last key scanned, the next cycle continues after it
+    cursor: Option<Key>,
+}
+
+impl ActiveExpire {
+    pub fn new() -> Self {
+        ActiveExpire { cursor: None }
+    }
+
+    pub async fn run(&mut self) {
+        let interval_ms = active_expire_interval_or_default();
+        if interval_ms == 0 || !is_use_txn_api() {
+            // the active expire cycle is disabled
+            return future::pending().await;
+        }
+        let mut interval = time::interval(Duration::from_millis(interval_ms));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if !active_expire_enabled() {
+                continue;
+            }
+            if let Err(e) = self.cycle().await {
+                error!(LOGGER, "[ACTIVE EXPIRE] expire keys failed: {:?}", e);
+            }
+        }
+    }
+
+    async fn cycle(&mut self) -> AsyncResult<()> {
+        let expired = self.scan_expired().await?;
+
+        // each key is checked again and deleted in a transaction of its own,
+        // a key written since the scan is left alone
+        let mut removed = 0;
+        for (key, data_type) in expired {
+            removed += match data_type {
+                DataType::String => {
+                    StringCommandCtx::new(None)
+                        .do_async_txnkv_string_expire_if_needed(&key)
+                        .await?
+                }
+                DataType::Hash => {
+                    HashCommandCtx::new(None)
+                        .do_async_txnkv_hash_expire_if_needed(&key)
+                        .await?
+                }
+                DataType::List => {
+                    ListCommandCtx::new(None)
+                        .do_async_txnkv_list_expire_if_needed(&key)
+                        .await?
+                }
+                DataType::Set => {
+                    SetCommandCtx::new(None)
+                        .do_async_txnkv_set_expire_if_needed(&key)
+                        .await?
+                }
+                DataType::Zset => {
+                    ZsetCommandCtx::new(None)
+                        .do_async_txnkv_zset_expire_if_needed(&key)
+                        .await?
+                }
+                DataType::Null => 0,
+            };
+        }
+        ACTIVE_EXPIRED_KEY_COUNTER.inc_by(removed as u64);
+        debug!(LOGGER, "[ACTIVE EXPIRE] removed {} expired keys", removed);
+        Ok(())
+    }
+
+    /// The expired keys among the scanned entries, along with their type. The
+    /// scan stops early once a batch of expired keys is found, the next cycle
+    /// resumes from the first key left out.
+    async fn scan_expired(&mut self) -> AsyncResult<Vec<(String, DataType)>> {
+        let txn_client = get_txn_client()?;
+        let mut txn = txn_client.begin().await?;
+
+        let start = match self.cursor.take() {
+            Some(cursor) => cursor,
+            None => KEY_ENCODER.encode_txnkv_string(""),
+        };
+        let range: BoundRange = (start.clone()..KEY_ENCODER.encode_txnkv_keyspace_end()).into();
+        let limit = active_expire_scan_size_or_default();
+        let batch_size = active_expire_batch_size_or_default();
+
+        // the scan covers the data keys of the collections as well, only the
+        // meta keys carry the ttl
+        let mut scanned = 0;
+        let mut batch_full = false;
+        let mut expired = vec![];
+        for kv in txn.scan(range, limit).await? {
+            scanned += 1;
+            if kv.0 == start {
+                continue;
+            }
+            let (userkey, is_meta_key) = KeyDecoder::decode_key_userkey_from_metakey(&kv.0);
+            if is_meta_key && key_is_expired(KeyDecoder::decode_key_ttl(&kv.1)) {
+                if expired.len() >= batch_size {
+                    batch_full = true;
+                    break;
+                }
+                expired.push((
+                    String::from_utf8_lossy(&userkey).to_string(),
+                    KeyDecoder::decode_key_type(&kv.1),
+                ));
+            }
+            self.cursor = Some(kv.0);
+        }
+        txn.rollback().await?;
+
+        // start over from the beginning once the end of the keyspace is reached
+        if !batch_full && scanned < limit {
+            self.cursor = None;
+        }
+        Ok(expired)
+    }
+}
+
+impl Default for ActiveExpire {
+    fn default() -> Self {
+        Self::new()
+    }
+}
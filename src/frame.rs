@@ -1,6 +1,7 @@
 //! Provides a type representing a Redis protocol frame as well as utilities for
 //! parsing frames from a byte array.
 
+use crate::config::{proto_max_bulk_len_or_default, proto_max_multibulk_len_or_default};
 use crate::tikv::errors::RTError;
 use bytes::{Buf, Bytes};
 use std::convert::TryInto;
@@ -9,6 +10,14 @@ use std::io::Cursor;
 use std::num::TryFromIntError;
 use std::string::FromUtf8Error;
 
+/// Max nesting depth of the arrays of a frame. A request is a flat array of
+/// bulk strings, the limit only guards the recursive check and parse.
+const MAX_FRAME_DEPTH: usize = 8;
+
+/// Max length of a line without its CRLF, same as the redis limit of an inline
+/// request. Longer lines are not buffered waiting for their end.
+const MAX_LINE_LEN: usize = 64 * 1024;
+
 /// A frame in the Redis protocol.
 #[derive(Clone, Debug)]
 pub enum Frame {
@@ -19,6 +28,21 @@ pub enum Frame {
     Bulk(Bytes),
     Null,
     Array(Vec<Frame>),
+    /// Out of band data such as pub/sub messages, RESP3 only.
+    Push(Vec<Frame>),
+    /// Key value pairs, RESP3 only.
+    Map(Vec<(Frame, Frame)>),
+    /// True or false, an integer 1 or 0 on RESP2 connections.
+    Boolean(bool),
+    /// Integer out of the 64 bit range, a bulk string on RESP2 connections.
+    BigNumber(String),
+    /// Unordered collection of distinct elements, an array on RESP2 connections.
+    Set(Vec<Frame>),
+    /// Floating point number, a bulk string on RESP2 connections.
+    Double(f64),
+    /// Plain text meant to be shown to humans as is, a bulk string on RESP2
+    /// connections.
+    Verbatim(String),
 }
 
 #[derive(Debug)]
@@ -28,6 +52,10 @@ pub enum Error {
 
     /// Invalid message encoding
     Other(crate::Error),
+
+    /// Length prefix over the protocol limits, replied to the peer before the
+    /// connection is closed
+    Protocol(&'static str),
 }
 
 impl Frame {
@@ -36,36 +64,160 @@ impl Frame {
         Frame::Array(vec![])
     }
 
-    /// Push a "bulk" frame into the array. `self` must be an Array frame.
+    /// Push a "bulk" frame into the array. `self` must be an Array or Push frame.
     ///
     /// # Panics
     ///
     /// panics if `self` is not an array
     pub(crate) fn push_bulk(&mut self, bytes: Bytes) {
         match self {
-            Frame::Array(vec) => {
+            Frame::Array(vec) | Frame::Push(vec) => {
                 vec.push(Frame::Bulk(bytes));
             }
             _ => panic!("not an array frame"),
         }
     }
 
-    /// Push an "integer" frame into the array. `self` must be an Array frame.
+    /// Push an "integer" frame into the array. `self` must be an Array or Push frame.
     ///
     /// # Panics
     ///
     /// panics if `self` is not an array
     pub(crate) fn push_int(&mut self, value: i64) {
         match self {
-            Frame::Array(vec) => {
+            Frame::Array(vec) | Frame::Push(vec) => {
                 vec.push(Frame::Integer(value));
             }
             _ => panic!("not an array frame"),
         }
     }
 
+    /// Serialize the frame into `dst`. RESP3 only types are sent as their
+    /// RESP2 equivalent unless the connection negotiated `resp3`, a push as an
+    /// array and a map as a flat array of keys and values.
+    pub fn encode(&self, dst: &mut Vec<u8>, resp3: bool) {
+        match self {
+            Frame::Simple(val) => {
+                dst.push(b'+');
+                dst.extend_from_slice(val.as_bytes());
+                dst.extend_from_slice(b"\r\n");
+            }
+            Frame::ErrorOwned(val) => {
+                dst.push(b'-');
+                dst.extend_from_slice(val.as_bytes());
+                dst.extend_from_slice(b"\r\n");
+            }
+            Frame::ErrorString(val) => {
+                dst.push(b'-');
+                dst.extend_from_slice(val.as_bytes());
+                dst.extend_from_slice(b"\r\n");
+            }
+            Frame::Integer(val) => {
+                dst.push(b':');
+                put_decimal(dst, *val);
+            }
+            Frame::Null => {
+                if resp3 {
+                    dst.extend_from_slice(b"_\r\n");
+                } else {
+                    dst.extend_from_slice(b"$-1\r\n");
+                }
+            }
+            Frame::Boolean(val) => {
+                if resp3 {
+                    dst.extend_from_slice(if *val { b"#t\r\n" } else { b"#f\r\n" });
+                } else {
+                    dst.extend_from_slice(if *val { b":1\r\n" } else { b":0\r\n" });
+                }
+            }
+            Frame::BigNumber(val) => {
+                if resp3 {
+                    dst.push(b'(');
+                    dst.extend_from_slice(val.as_bytes());
+                    dst.extend_from_slice(b"\r\n");
+                } else {
+                    dst.push(b'$');
+                    put_decimal(dst, val.len() as i64);
+                    dst.extend_from_slice(val.as_bytes());
+                    dst.extend_from_slice(b"\r\n");
+                }
+            }
+            Frame::Bulk(val) => {
+                dst.push(b'$');
+                put_decimal(dst, val.len() as i64);
+                dst.extend_from_slice(val);
+                dst.extend_from_slice(b"\r\n");
+            }
+            Frame::Array(val) => {
+                dst.push(b'*');
+                put_decimal(dst, val.len() as i64);
+                for entry in val {
+                    entry.encode(dst, resp3);
+                }
+            }
+            Frame::Push(val) => {
+                dst.push(if resp3 { b'>' } else { b'*' });
+                put_decimal(dst, val.len() as i64);
+                for entry in val {
+                    entry.encode(dst, resp3);
+                }
+            }
+            Frame::Set(val) => {
+                dst.push(if resp3 { b'~' } else { b'*' });
+                put_decimal(dst, val.len() as i64);
+                for entry in val {
+                    entry.encode(dst, resp3);
+                }
+            }
+            Frame::Double(val) => {
+                let val = format_double(*val);
+                if resp3 {
+                    dst.push(b',');
+                } else {
+                    dst.push(b'$');
+                    put_decimal(dst, val.len() as i64);
+                }
+                dst.extend_from_slice(val.as_bytes());
+                dst.extend_from_slice(b"\r\n");
+            }
+            Frame::Verbatim(val) => {
+                if resp3 {
+                    // the format prefix is part of the length
+                    dst.push(b'=');
+                    put_decimal(dst, val.len() as i64 + 4);
+                    dst.extend_from_slice(b"txt:");
+                } else {
+                    dst.push(b'$');
+                    put_decimal(dst, val.len() as i64);
+                }
+                dst.extend_from_slice(val.as_bytes());
+                dst.extend_from_slice(b"\r\n");
+            }
+            Frame::Map(val) => {
+                if resp3 {
+                    dst.push(b'%');
+                    put_decimal(dst, val.len() as i64);
+                } else {
+                    dst.push(b'*');
+                    put_decimal(dst, val.len() as i64 * 2);
+                }
+                for (key, value) in val {
+                    key.encode(dst, resp3);
+                    value.encode(dst, resp3);
+                }
+            }
+        }
+    }
+
     /// Checks if an entire message can be decoded from `src`
+    ///
+    /// Malformed input is an error, never a panic, and the length prefixes are
+    /// validated before any data they announce is buffered.
     pub fn check(src: &mut Cursor<&[u8]>) -> Result<(), Error> {
+        Frame::check_nested(src, 0)
+    }
+
+    fn check_nested(src: &mut Cursor<&[u8]>, depth: usize) -> Result<(), Error> {
         match get_u8(src)? {
             b'+' => {
                 get_line(src)?;
@@ -80,22 +232,37 @@ impl Frame {
                 Ok(())
             }
             b'$' => {
-                if b'-' == peek_u8(src)? {
-                    // Skip '-1\r\n'
-                    skip(src, 4)
-                } else {
-                    // Read the bulk string
-                    let len: usize = get_decimal(src)?.try_into()?;
+                let len = get_decimal(src)?;
+                if len == -1 {
+                    return Ok(());
+                }
+                // reject the length before buffering the bulk string
+                if len < 0 || len as u64 > proto_max_bulk_len_or_default() {
+                    return Err(Error::Protocol("invalid bulk length"));
+                }
+                let len: usize = len.try_into()?;
 
-                    // skip that number of bytes + 2 (\r\n).
-                    skip(src, len + 2)
+                skip(src, len)?;
+                // the bulk string must be followed by exactly \r\n
+                if src.remaining() < 2 {
+                    return Err(Error::Incomplete);
+                }
+                if &src.chunk()[..2] != b"\r\n" {
+                    return Err(Error::Protocol("invalid bulk terminator"));
                 }
+                skip(src, 2)
             }
             b'*' => {
                 let len = get_decimal(src)?;
+                if len < -1 || len > proto_max_multibulk_len_or_default() as i64 {
+                    return Err(Error::Protocol("invalid multibulk length"));
+                }
+                if len > 0 && depth >= MAX_FRAME_DEPTH {
+                    return Err(Error::Protocol("too deeply nested frame"));
+                }
 
                 for _ in 0..len {
-                    Frame::check(src)?;
+                    Frame::check_nested(src, depth + 1)?;
                 }
 
                 Ok(())
@@ -105,7 +272,10 @@ impl Frame {
     }
 
     /// The message has already been validated with `check`.
-    pub fn parse(src: &mut Cursor<&[u8]>) -> Result<Frame, Error> {
+    ///
+    /// `src` must be a view of `data`, bulk strings are returned as slices of
+    /// `data` instead of being copied out.
+    pub fn parse(src: &mut Cursor<&[u8]>, data: &Bytes) -> Result<Frame, Error> {
         match get_u8(src)? {
             b'+' => {
                 // Read the line and convert it to `Vec<u8>`
@@ -140,34 +310,89 @@ impl Frame {
                     Ok(Frame::Null)
                 } else {
                     // Read the bulk string
-                    let len = get_decimal(src)?.try_into()?;
-                    let n = len + 2;
+                    let len: usize = get_decimal(src)?.try_into()?;
+                    let n = len
+                        .checked_add(2)
+                        .ok_or_else(|| Error::from("protocol error; invalid frame format"))?;
 
                     if src.remaining() < n {
                         return Err(Error::Incomplete);
                     }
 
-                    let data = Bytes::copy_from_slice(&src.chunk()[..len]);
+                    let bulk = data.slice_ref(&src.chunk()[..len]);
 
                     // skip that number of bytes + 2 (\r\n).
                     skip(src, n)?;
 
-                    Ok(Frame::Bulk(data))
+                    Ok(Frame::Bulk(bulk))
                 }
             }
             b'*' => {
-                let len = get_decimal(src)?.try_into()?;
-                let mut out = Vec::with_capacity(len);
+                let len = get_decimal(src)?;
+                if len == -1 {
+                    return Ok(Frame::Null);
+                }
+                let len: usize = len.try_into()?;
+                // every element takes at least 3 bytes, a forged length does
+                // not reserve more than the data can hold
+                let mut out = Vec::with_capacity(len.min(src.remaining() / 3));
 
                 for _ in 0..len {
-                    out.push(Frame::parse(src)?);
+                    out.push(Frame::parse(src, data)?);
                 }
 
                 Ok(Frame::Array(out))
             }
-            _ => unimplemented!(),
+            actual => Err(format!("protocol error; invalid frame type byte `{}`", actual).into()),
         }
     }
+
+    /// Checks if an entire inline command can be decoded from `src`
+    ///
+    /// An inline command is a line of arguments separated by spaces, as typed
+    /// in a telnet session. The line may end with a bare `\n` as well as `\r\n`.
+    pub fn check_inline(src: &mut Cursor<&[u8]>) -> Result<(), Error> {
+        get_inline_line(src)?;
+        Ok(())
+    }
+
+    /// The inline command has already been validated with `check_inline`.
+    ///
+    /// The arguments are returned as an array of bulk strings sliced from
+    /// `data`, an argument may be quoted to hold spaces.
+    pub fn parse_inline(src: &mut Cursor<&[u8]>, data: &Bytes) -> Result<Frame, Error> {
+        let line = get_inline_line(src)?;
+        let is_space = |b: u8| b == b' ' || b == b'\t';
+
+        let mut args = vec![];
+        let mut i = 0;
+        while i < line.len() {
+            if is_space(line[i]) {
+                i += 1;
+            } else if line[i] == b'"' || line[i] == b'\'' {
+                let quote = line[i];
+                let start = i + 1;
+                let end = match line[start..].iter().position(|&b| b == quote) {
+                    Some(n) => start + n,
+                    None => return Err(Error::Protocol("unbalanced quotes in request")),
+                };
+                // the closing quote must end the argument
+                if end + 1 < line.len() && !is_space(line[end + 1]) {
+                    return Err(Error::Protocol("unbalanced quotes in request"));
+                }
+                args.push(Frame::Bulk(data.slice_ref(&line[start..end])));
+                i = end + 1;
+            } else {
+                let start = i;
+                while i < line.len() && !is_space(line[i]) {
+                    i += 1;
+                }
+                args.push(Frame::Bulk(data.slice_ref(&line[start..i])));
+            }
+        }
+
+        Ok(Frame::Array(args))
+    }
 }
 
 impl PartialEq<&str> for Frame {
@@ -194,7 +419,21 @@ impl fmt::Display for Frame {
                 Err(_) => write!(fmt, "{:?}", msg),
             },
             Frame::Null => "(nil)".fmt(fmt),
-            Frame::Array(parts) => {
+            Frame::Boolean(val) => val.fmt(fmt),
+            Frame::BigNumber(val) => val.fmt(fmt),
+            Frame::Double(val) => format_double(*val).fmt(fmt),
+            Frame::Verbatim(val) => val.fmt(fmt),
+            Frame::Map(pairs) => {
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(fmt, " ")?;
+                    }
+                    write!(fmt, "{} {}", key, value)?;
+                }
+
+                Ok(())
+            }
+            Frame::Array(parts) | Frame::Push(parts) | Frame::Set(parts) => {
                 for (i, part) in parts.iter().enumerate() {
                     if i > 0 {
                         write!(fmt, " ")?;
@@ -257,7 +496,7 @@ fn get_line<'a>(src: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], Error> {
     // Scan the bytes directly
     let start = src.position() as usize;
     // Scan to the second to last byte
-    let end = src.get_ref().len() - 1;
+    let end = src.get_ref().len().saturating_sub(1);
 
     for i in start..end {
         if src.get_ref()[i] == b'\r' && src.get_ref()[i + 1] == b'\n' {
@@ -269,9 +508,32 @@ fn get_line<'a>(src: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], Error> {
         }
     }
 
+    // a line never ending is not buffered forever
+    if end.saturating_sub(start) > MAX_LINE_LEN {
+        return Err(Error::Protocol("too big line"));
+    }
     Err(Error::Incomplete)
 }
 
+/// Find an inline command line, terminated by `\n` with an optional `\r`
+fn get_inline_line<'a>(src: &mut Cursor<&'a [u8]>) -> Result<&'a [u8], Error> {
+    let start = src.position() as usize;
+    let buf = *src.get_ref();
+
+    match buf[start..].iter().position(|&b| b == b'\n') {
+        Some(n) => {
+            // update the position to be *after* the \n
+            src.set_position((start + n + 1) as u64);
+
+            let line = &buf[start..start + n];
+            Ok(line.strip_suffix(b"\r").unwrap_or(line))
+        }
+        // a line never ending is not buffered forever
+        None if buf.len() - start > MAX_LINE_LEN => Err(Error::Protocol("too big inline request")),
+        None => Err(Error::Incomplete),
+    }
+}
+
 impl From<String> for Error {
     fn from(src: String) -> Error {
         Error::Other(src.into())
@@ -303,6 +565,267 @@ impl fmt::Display for Error {
         match self {
             Error::Incomplete => "stream ended early".fmt(fmt),
             Error::Other(err) => err.fmt(fmt),
+            Error::Protocol(msg) => write!(fmt, "Protocol error: {}", msg),
+        }
+    }
+}
+
+/// Write a decimal followed by CRLF
+fn put_decimal(dst: &mut Vec<u8>, val: i64) {
+    use std::io::Write;
+
+    // writing to a Vec never fails
+    let _ = write!(dst, "{}\r\n", val);
+}
+
+/// Shortest representation of a double that parses back to the same value,
+/// infinities and NaN are spelled as RESP3 expects.
+fn format_double(val: f64) -> String {
+    if val.is_nan() {
+        "nan".to_owned()
+    } else {
+        val.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(frame: &Frame, resp3: bool) -> Vec<u8> {
+        let mut buf = vec![];
+        frame.encode(&mut buf, resp3);
+        buf
+    }
+
+    /// Assert the RESP3 and the RESP2 encoding of the frame.
+    fn assert_encoded(frame: Frame, resp3: &[u8], resp2: &[u8]) {
+        assert_eq!(encoded(&frame, true), resp3, "{:?} on RESP3", frame);
+        assert_eq!(encoded(&frame, false), resp2, "{:?} on RESP2", frame);
+    }
+
+    fn bulk(val: &'static str) -> Frame {
+        Frame::Bulk(Bytes::from_static(val.as_bytes()))
+    }
+
+    #[test]
+    fn encode_null() {
+        assert_encoded(Frame::Null, b"_\r\n", b"$-1\r\n");
+    }
+
+    #[test]
+    fn encode_boolean() {
+        assert_encoded(Frame::Boolean(true), b"#t\r\n", b":1\r\n");
+        assert_encoded(Frame::Boolean(false), b"#f\r\n", b":0\r\n");
+    }
+
+    #[test]
+    fn encode_big_number() {
+        assert_encoded(
+            Frame::BigNumber("1234567999999999999999999999999999999".to_owned()),
+            b"(1234567999999999999999999999999999999\r\n",
+            b"$37\r\n1234567999999999999999999999999999999\r\n",
+        );
+        assert_encoded(
+            Frame::BigNumber("-18446744073709551616".to_owned()),
+            b"(-18446744073709551616\r\n",
+            b"$21\r\n-18446744073709551616\r\n",
+        );
+    }
+
+    #[test]
+    fn encode_double() {
+        assert_encoded(Frame::Double(1.5), b",1.5\r\n", b"$3\r\n1.5\r\n");
+        assert_encoded(Frame::Double(-3.0), b",-3\r\n", b"$2\r\n-3\r\n");
+        assert_encoded(Frame::Double(f64::NAN), b",nan\r\n", b"$3\r\nnan\r\n");
+        assert_encoded(Frame::Double(f64::INFINITY), b",inf\r\n", b"$3\r\ninf\r\n");
+        assert_encoded(
+            Frame::Double(f64::NEG_INFINITY),
+            b",-inf\r\n",
+            b"$4\r\n-inf\r\n",
+        );
+    }
+
+    #[test]
+    fn encode_verbatim() {
+        assert_encoded(
+            Frame::Verbatim("a\nb".to_owned()),
+            b"=7\r\ntxt:a\nb\r\n",
+            b"$3\r\na\nb\r\n",
+        );
+    }
+
+    #[test]
+    fn encode_push() {
+        assert_encoded(
+            Frame::Push(vec![bulk("message"), Frame::Integer(1)]),
+            b">2\r\n$7\r\nmessage\r\n:1\r\n",
+            b"*2\r\n$7\r\nmessage\r\n:1\r\n",
+        );
+    }
+
+    #[test]
+    fn encode_set() {
+        assert_encoded(
+            Frame::Set(vec![bulk("m1"), bulk("m2")]),
+            b"~2\r\n$2\r\nm1\r\n$2\r\nm2\r\n",
+            b"*2\r\n$2\r\nm1\r\n$2\r\nm2\r\n",
+        );
+        assert_encoded(Frame::Set(vec![]), b"~0\r\n", b"*0\r\n");
+    }
+
+    #[test]
+    fn encode_map() {
+        assert_encoded(
+            Frame::Map(vec![
+                (bulk("f1"), bulk("v1")),
+                (bulk("f2"), Frame::Double(0.5)),
+            ]),
+            b"%2\r\n$2\r\nf1\r\n$2\r\nv1\r\n$2\r\nf2\r\n,0.5\r\n",
+            b"*4\r\n$2\r\nf1\r\n$2\r\nv1\r\n$2\r\nf2\r\n$3\r\n0.5\r\n",
+        );
+    }
+
+    #[test]
+    fn encode_nested() {
+        // the elements of an array are encoded for the connection as well
+        assert_encoded(
+            Frame::Array(vec![
+                Frame::Null,
+                Frame::Boolean(true),
+                Frame::Set(vec![Frame::BigNumber("1".to_owned())]),
+            ]),
+            b"*3\r\n_\r\n#t\r\n~1\r\n(1\r\n",
+            b"*3\r\n$-1\r\n:1\r\n*1\r\n$1\r\n1\r\n",
+        );
+    }
+
+    fn check(input: &[u8]) -> Result<(), Error> {
+        Frame::check(&mut Cursor::new(input))
+    }
+
+    fn parse(input: &'static [u8]) -> Result<Frame, Error> {
+        let data = Bytes::from_static(input);
+        Frame::parse(&mut Cursor::new(&data[..]), &data)
+    }
+
+    #[test]
+    fn check_negative_length() {
+        assert!(matches!(
+            check(b"$-2\r\n"),
+            Err(Error::Protocol("invalid bulk length"))
+        ));
+        assert!(matches!(
+            check(b"*-2\r\n"),
+            Err(Error::Protocol("invalid multibulk length"))
+        ));
+        assert!(matches!(check(b"$-1\r\n"), Ok(())));
+    }
+
+    #[test]
+    fn check_bulk_terminator() {
+        assert!(matches!(
+            check(b"$3\r\nfooXY"),
+            Err(Error::Protocol("invalid bulk terminator"))
+        ));
+        assert!(matches!(
+            check(b"$3\r\nfoo\n\r"),
+            Err(Error::Protocol("invalid bulk terminator"))
+        ));
+        // the terminator may still be on its way
+        assert!(matches!(check(b"$3\r\nfoo"), Err(Error::Incomplete)));
+        assert!(matches!(check(b"$3\r\nfoo\r"), Err(Error::Incomplete)));
+        assert!(matches!(check(b"$3\r\nfoo\r\n"), Ok(())));
+    }
+
+    #[test]
+    fn check_nesting_depth() {
+        let nested = |depth: usize| {
+            let mut input = b"*1\r\n".repeat(depth);
+            input.extend_from_slice(b":1\r\n");
+            input
+        };
+        assert!(matches!(check(&nested(MAX_FRAME_DEPTH)), Ok(())));
+        assert!(matches!(
+            check(&nested(MAX_FRAME_DEPTH + 1)),
+            Err(Error::Protocol("too deeply nested frame"))
+        ));
+    }
+
+    #[test]
+    fn check_line_length() {
+        let mut input = vec![b'+'];
+        input.resize(MAX_LINE_LEN + 1, b'a');
+        assert!(matches!(check(&input), Err(Error::Incomplete)));
+        input.resize(MAX_LINE_LEN + 3, b'a');
+        assert!(matches!(
+            check(&input),
+            Err(Error::Protocol("too big line"))
+        ));
+        // the length prefixes are lines too
+        let mut input = vec![b'*'];
+        input.resize(MAX_LINE_LEN + 3, b'1');
+        assert!(matches!(
+            check(&input),
+            Err(Error::Protocol("too big line"))
+        ));
+    }
+
+    #[test]
+    fn unknown_type_byte() {
+        assert!(matches!(check(b"?1\r\n"), Err(Error::Other(_))));
+        assert!(matches!(parse(b"?1\r\n"), Err(Error::Other(_))));
+        assert!(matches!(check(b"*1\r\n?1\r\n"), Err(Error::Other(_))));
+    }
+
+    #[test]
+    fn null_array() {
+        assert!(matches!(check(b"*-1\r\n"), Ok(())));
+        assert!(matches!(parse(b"*-1\r\n"), Ok(Frame::Null)));
+        assert!(matches!(parse(b"$-1\r\n"), Ok(Frame::Null)));
+    }
+
+    #[test]
+    fn forged_array_length() {
+        assert!(matches!(
+            check(b"*9223372036854775807\r\n:1\r\n"),
+            Err(Error::Protocol("invalid multibulk length"))
+        ));
+        // parse does not reserve the announced elements, the missing ones
+        // make it incomplete instead of aborting on the allocation
+        assert!(matches!(
+            parse(b"*9223372036854775807\r\n:1\r\n"),
+            Err(Error::Incomplete)
+        ));
+        assert!(matches!(
+            parse(b"$9223372036854775807\r\nfoo\r\n"),
+            Err(Error::Incomplete)
+        ));
+    }
+
+    #[test]
+    fn partial_frame() {
+        let input: &[u8] = b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$5\r\nvalue\r\n";
+        for len in 0..input.len() {
+            assert!(
+                matches!(check(&input[..len]), Err(Error::Incomplete)),
+                "prefix of {} bytes",
+                len
+            );
+        }
+        let mut src = Cursor::new(input);
+        assert!(matches!(Frame::check(&mut src), Ok(())));
+        assert_eq!(src.position() as usize, input.len());
+    }
+
+    #[test]
+    fn bulk_with_nul() {
+        assert!(matches!(check(b"$3\r\na\0b\r\n"), Ok(())));
+        match parse(b"*1\r\n$3\r\na\0b\r\n") {
+            Ok(Frame::Array(args)) => {
+                assert!(matches!(&args[..], [Frame::Bulk(arg)] if &arg[..] == b"a\0b"))
+            }
+            frame => panic!("unexpected {:?}", frame),
         }
     }
 }
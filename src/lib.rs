@@ -13,7 +13,7 @@ use std::sync::atomic::Ordering;
 pub use cmd::Command;
 
 mod connection;
-pub use connection::Connection;
+pub use connection::{Connection, SocketType};
 
 pub mod frame;
 pub use frame::Frame;
@@ -43,9 +43,16 @@ pub mod cluster;
 
 pub mod client;
 
+pub mod acl;
+
 pub mod utils;
 
 pub mod config;
+pub use config::access_time_sample_rate_or_default;
+pub use config::acl_users_or_default;
+pub use config::active_expire_batch_size_or_default;
+pub use config::active_expire_interval_or_default;
+pub use config::active_expire_scan_size_or_default;
 pub use config::async_del_hash_threshold_or_default;
 pub use config::async_del_list_threshold_or_default;
 pub use config::async_del_set_threshold_or_default;
@@ -64,18 +71,24 @@ pub use config::backend_cert_file_or_default;
 pub use config::backend_completion_queue_size_or_default;
 pub use config::backend_grpc_keepalive_time_or_default;
 pub use config::backend_grpc_keepalive_timeout_or_default;
+pub use config::backend_health_check_interval_or_default;
 pub use config::backend_key_file_or_default;
 pub use config::backend_max_batch_size_or_default;
 pub use config::backend_max_batch_wait_time_or_default;
 pub use config::backend_max_inflight_requests_or_default;
 pub use config::backend_overload_threshold_or_default;
 pub use config::backend_timeout_or_default;
+pub use config::bulk_load_batch_size_or_default;
 pub use config::cmd_linsert_length_limit_or_default;
 pub use config::cmd_lrem_length_limit_or_default;
+pub use config::cmd_scan_count_limit_or_default;
+pub use config::cmd_size_metrics_enabled_or_default;
+pub use config::cmd_timeout_ms_or_default;
 pub use config::config_cluster_broadcast_addr_or_default;
 pub use config::config_cluster_topology_expire_or_default;
 pub use config::config_cluster_topology_interval_or_default;
 pub use config::config_instance_id_or_default;
+pub use config::config_key_prefix_or_default;
 pub use config::config_listen_or_default;
 pub use config::config_local_pool_number;
 pub use config::config_meta_key_number_or_default;
@@ -89,15 +102,33 @@ pub use config::config_tls_cert_file_or_default;
 pub use config::config_tls_key_file_or_default;
 pub use config::config_tls_listen_or_default;
 pub use config::config_tls_port_or_default;
+pub use config::config_unixsocket_or_default;
+pub use config::conn_concurrency_max_or_default;
 pub use config::conn_concurrency_or_default;
+pub use config::conn_inflight_threshold_or_default;
+pub use config::debug_raw_enabled_or_default;
 pub use config::get_global_config;
 pub use config::is_auth_enabled;
 pub use config::is_auth_matched;
 pub use config::is_try_one_pc_commit;
+pub use config::is_unixsocket_auth_enabled;
+pub use config::is_unixsocket_auth_matched;
 pub use config::is_use_async_commit;
 pub use config::is_use_pessimistic_txn;
 pub use config::is_use_txn_api;
+pub use config::lazyfree_lazy_server_del_or_default;
+pub use config::max_inflight_cmds_or_default;
+pub use config::max_subscriptions_per_client_or_default;
+pub use config::negative_cache_size_or_default;
+pub use config::negative_cache_ttl_ms_or_default;
+pub use config::notify_keyspace_events_or_default;
+pub use config::proto_max_bulk_len_or_default;
+pub use config::proto_max_multibulk_len_or_default;
 pub use config::set_global_config;
+pub use config::slowlog_log_slower_than_or_default;
+pub use config::slowlog_max_len_or_default;
+pub use config::ttl_sample_interval_or_default;
+pub use config::ttl_sample_size_or_default;
 pub use config::txn_lock_backoff_delay_attemps;
 pub use config::txn_lock_backoff_delay_ms;
 pub use config::txn_region_backoff_delay_attemps;
@@ -107,6 +138,14 @@ pub use config::Config;
 
 pub mod gc;
 
+pub mod ttl_sampler;
+
+pub mod expire;
+
+pub mod slowlog;
+
+pub mod notify;
+
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 
 /// Default port that a redis server listens on.
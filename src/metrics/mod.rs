@@ -1,5 +1,6 @@
 use prometheus::{
-    exponential_buckets, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    exponential_buckets, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 
 mod http;
@@ -32,6 +33,16 @@ lazy_static! {
         "Current tls connection counter"
     )
     .unwrap();
+    pub static ref CURRENT_UNIX_CONNECTION_COUNTER: IntGauge = register_int_gauge!(
+        "tikv_redis_current_unix_connections",
+        "Current unix socket connection counter"
+    )
+    .unwrap();
+    pub static ref INFLIGHT_CMD_GAUGE: IntGauge = register_int_gauge!(
+        "tikv_redis_inflight_commands",
+        "Commands running at the same time"
+    )
+    .unwrap();
     pub static ref REQUEST_CMD_COUNTER: IntCounterVec = register_int_counter_vec!(
         "tikv_redis_command_requests_total",
         "Request command counter",
@@ -57,12 +68,47 @@ lazy_static! {
         exponential_buckets(0.0005, 2.0, 20).unwrap()
     )
     .unwrap();
+    pub static ref REQUEST_CMD_SIZE_HANDLE_TIME: HistogramVec = register_histogram_vec!(
+        "tikv_redis_command_size_handle_time_duration_seconds",
+        "Bucketed histogram of command handle duration by request size class",
+        &["cmd", "size"],
+        exponential_buckets(0.0005, 2.0, 20).unwrap()
+    )
+    .unwrap();
+    pub static ref CMD_PATH_COUNTER: IntCounterVec = register_int_counter_vec!(
+        "tikv_redis_command_path_total",
+        "Collection deletions done in the transaction (sync) or handed to gc (async)",
+        &["cmd", "path"]
+    )
+    .unwrap();
+    pub static ref NEGATIVE_CACHE_COUNTER: IntCounterVec = register_int_counter_vec!(
+        "tikv_redis_negative_cache_lookups_total",
+        "Lookups of GET in the cache of missing keys",
+        &["result"]
+    )
+    .unwrap();
     pub static ref REMOVED_EXPIRED_KEY_COUNTER: IntCounterVec = register_int_counter_vec!(
         "tikv_redis_removed_expired_keys_count_total",
         "The number of expired keys that have been removed",
         &["kind"]
     )
     .unwrap();
+    pub static ref KEY_TTL_SAMPLE_HISTOGRAM: Histogram = register_histogram!(
+        "tikv_redis_key_ttl_seconds",
+        "Bucketed histogram of the remaining ttl of the sampled keys",
+        exponential_buckets(1.0, 4.0, 12).unwrap()
+    )
+    .unwrap();
+    pub static ref KEY_NO_TTL_RATIO_GAUGE: Gauge = register_gauge!(
+        "tikv_redis_key_no_ttl_ratio",
+        "Fraction of the keys without ttl in the last sample"
+    )
+    .unwrap();
+    pub static ref ACTIVE_EXPIRED_KEY_COUNTER: IntCounter = register_int_counter!(
+        "tikv_redis_active_expired_keys_count_total",
+        "The number of expired keys removed by the active expire cycle"
+    )
+    .unwrap();
 
     // Trasactions
     pub static ref SNAPSHOT_COUNTER: IntCounter = register_int_counter!("tikv_redis_snapshot_count_total", "Snapshot count").unwrap();
@@ -117,6 +163,28 @@ lazy_static! {
         &["err"]
     )
     .unwrap();
+    pub static ref TIKV_BROKEN_CLIENT_COUNTER: IntCounter = register_int_counter!(
+        "tikv_redis_tikv_broken_client_total",
+        "Pooled clients skipped after connection errors"
+    )
+    .unwrap();
+    pub static ref TXN_CLIENT_INFLIGHT_GAUGE: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_redis_txn_client_inflight",
+        "Requests in flight on each pooled txn client",
+        &["client"]
+    )
+    .unwrap();
+    pub static ref TXN_CLIENT_POOL_SIZE_GAUGE: IntGauge = register_int_gauge!(
+        "tikv_redis_txn_client_pool_size",
+        "Txn clients in the pool, resized with the load"
+    )
+    .unwrap();
+    pub static ref TXN_CLIENT_HEALTH_GAUGE: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_redis_txn_clients",
+        "Pooled txn clients by the state of the last health check",
+        &["state"]
+    )
+    .unwrap();
     pub static ref TIKV_ERR_COUNTER: IntCounterVec = register_int_counter_vec!(
         "tikv_redis_tikv_reported_errors_count_total",
         "TiKV reported err",
@@ -131,4 +199,16 @@ lazy_static! {
         &["worker"]
     )
     .unwrap();
+
+    // Bulk load
+    pub static ref BULK_LOAD_WRITE_COUNTER: IntCounter = register_int_counter!(
+        "tikv_redis_bulk_load_writes_total",
+        "Writes received in bulk load mode"
+    )
+    .unwrap();
+    pub static ref BULK_LOAD_BATCH_COUNTER: IntCounter = register_int_counter!(
+        "tikv_redis_bulk_load_batches_total",
+        "Batches committed in bulk load mode"
+    )
+    .unwrap();
 }
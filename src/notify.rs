@@ -0,0 +1,156 @@
+//! Keyspace notifications, the events of the written keys are published to the
+//! `__keyspace@0__:<key>` and `__keyevent@0__:<event>` channels as redis does.
+//!
+//! The classes of the events published are configured with
+//! `notify_keyspace_events` in the same format as the redis option, e.g. `KEA`.
+//! Notifications are disabled by default and cost a single check per write then.
+
+use std::sync::RwLock;
+
+use bytes::Bytes;
+use slog::error;
+
+use crate::cmd::spec::{
+    lookup_command_spec, CAT_HASH, CAT_LIST, CAT_SET, CAT_SORTEDSET, CAT_STRING,
+};
+use crate::config::LOGGER;
+use crate::db::Db;
+use crate::notify_keyspace_events_or_default;
+
+pub const NOTIFY_KEYSPACE: u32 = 1;
+pub const NOTIFY_KEYEVENT: u32 = 1 << 1;
+pub const NOTIFY_GENERIC: u32 = 1 << 2;
+pub const NOTIFY_STRING: u32 = 1 << 3;
+pub const NOTIFY_LIST: u32 = 1 << 4;
+pub const NOTIFY_SET: u32 = 1 << 5;
+pub const NOTIFY_HASH: u32 = 1 << 6;
+pub const NOTIFY_ZSET: u32 = 1 << 7;
+pub const NOTIFY_EXPIRED: u32 = 1 << 8;
+// the `A` alias of the redis option
+pub const NOTIFY_ALL: u32 = NOTIFY_GENERIC
+    | NOTIFY_STRING
+    | NOTIFY_LIST
+    | NOTIFY_SET
+    | NOTIFY_HASH
+    | NOTIFY_ZSET
+    | NOTIFY_EXPIRED;
+
+lazy_static! {
+    static ref NOTIFY_FLAGS: u32 = {
+        let events = notify_keyspace_events_or_default();
+        match parse_notify_flags(&events) {
+            Some(flags) => flags,
+            None => {
+                error!(
+                    LOGGER,
+                    "invalid notify_keyspace_events {}, keyspace notifications disabled", events
+                );
+                0
+            }
+        }
+    };
+    static ref NOTIFY_DB: RwLock<Option<Db>> = RwLock::new(None);
+}
+
+/// Parse the classes of the events to notify, `None` if a class is unknown.
+pub fn parse_notify_flags(events: &str) -> Option<u32> {
+    let mut flags = 0;
+    for c in events.chars() {
+        flags |= match c {
+            'A' => NOTIFY_ALL,
+            'g' => NOTIFY_GENERIC,
+            '$' => NOTIFY_STRING,
+            'l' => NOTIFY_LIST,
+            's' => NOTIFY_SET,
+            'h' => NOTIFY_HASH,
+            'z' => NOTIFY_ZSET,
+            'x' => NOTIFY_EXPIRED,
+            'K' => NOTIFY_KEYSPACE,
+            'E' => NOTIFY_KEYEVENT,
+            _ => return None,
+        };
+    }
+    Some(flags)
+}
+
+/// Set the db whose channels the notifications are published to.
+pub(crate) fn set_notify_db(db: Db) {
+    *NOTIFY_DB.write().unwrap() = Some(db);
+}
+
+/// Notifications are published only if any channel type and any event class
+/// are enabled, as redis does.
+pub fn notify_enabled() -> bool {
+    let flags = *NOTIFY_FLAGS;
+    flags & (NOTIFY_KEYSPACE | NOTIFY_KEYEVENT) > 0 && flags & NOTIFY_ALL > 0
+}
+
+/// Publish the `event` of the `class` on `key` to the subscribers of the
+/// keyspace and keyevent channels enabled.
+pub fn notify_keyspace_event(class: u32, event: &str, key: &str) {
+    let flags = *NOTIFY_FLAGS;
+    if flags & class == 0 {
+        return;
+    }
+    let guard = NOTIFY_DB.read().unwrap();
+    let db = match guard.as_ref() {
+        Some(db) => db,
+        None => return,
+    };
+    if flags & NOTIFY_KEYSPACE > 0 {
+        db.publish(
+            &format!("__keyspace@0__:{}", key),
+            Bytes::from(event.to_owned()),
+        );
+    }
+    if flags & NOTIFY_KEYEVENT > 0 {
+        db.publish(
+            &format!("__keyevent@0__:{}", event),
+            Bytes::from(key.to_owned()),
+        );
+    }
+}
+
+/// Publish the `expired` event of a key removed as its ttl passed.
+pub fn notify_expired(key: &str) {
+    if notify_enabled() {
+        notify_keyspace_event(NOTIFY_EXPIRED, "expired", key);
+    }
+}
+
+/// Class and name of the event a write command generates on each of its keys,
+/// `None` for the commands which notify nothing.
+pub fn command_event(name: &str) -> Option<(u32, &'static str)> {
+    let spec = lookup_command_spec(name)?;
+    if !spec.is_write() {
+        return None;
+    }
+    // the event of a command is named after the command, except for the
+    // variants of a command which notify the event of the command
+    let event = match name {
+        // some keys of these requests are read only or not written depending
+        // on the data, the keys written are not known from the request
+        "copy" | "bitop" | "blpop" | "brpop" | "lmpop" | "zmpop" | "zrangestore" | "zdiffstore"
+        | "getex" => return None,
+        "setex" | "psetex" | "setnx" | "mset" | "msetnx" | "getset" => "set",
+        "incr" => "incrby",
+        "decr" => "decrby",
+        "expireat" | "pexpire" | "pexpireat" => "expire",
+        "hmset" | "hsetnx" => "hset",
+        "hgetdel" => "hdel",
+        "zincrby" => "zincr",
+        "getdel" => "del",
+        _ => spec.name,
+    };
+    let class = match event {
+        "del" => NOTIFY_GENERIC,
+        _ if spec.in_category(CAT_STRING) => NOTIFY_STRING,
+        _ if spec.in_category(CAT_HASH) => NOTIFY_HASH,
+        _ if spec.in_category(CAT_LIST) => NOTIFY_LIST,
+        _ if spec.in_category(CAT_SET) => NOTIFY_SET,
+        _ if spec.in_category(CAT_SORTEDSET) => NOTIFY_ZSET,
+        // the commands of the keyspace work on keys of any type
+        _ => NOTIFY_GENERIC,
+    };
+    Some((class, event))
+}
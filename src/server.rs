@@ -1,22 +1,41 @@
 use crate::cluster::Cluster;
+use crate::expire::ActiveExpire;
 use crate::gc::GcMaster;
 use crate::metrics::{
-    CURRENT_CONNECTION_COUNTER, CURRENT_TLS_CONNECTION_COUNTER, REQUEST_CMD_COUNTER,
-    REQUEST_CMD_ERROR_COUNTER, REQUEST_CMD_FINISH_COUNTER, REQUEST_CMD_HANDLE_TIME,
-    REQUEST_COUNTER, TOTAL_CONNECTION_PROCESSED,
+    CURRENT_CONNECTION_COUNTER, CURRENT_TLS_CONNECTION_COUNTER, CURRENT_UNIX_CONNECTION_COUNTER,
+    INFLIGHT_CMD_GAUGE, REQUEST_CMD_COUNTER, REQUEST_CMD_ERROR_COUNTER, REQUEST_CMD_FINISH_COUNTER,
+    REQUEST_CMD_HANDLE_TIME, REQUEST_CMD_SIZE_HANDLE_TIME, REQUEST_COUNTER,
+    TOTAL_CONNECTION_PROCESSED,
 };
+use crate::notify::{command_event, notify_enabled, notify_keyspace_event, set_notify_db};
+use crate::slowlog;
+use crate::tikv::client::CMD_DEADLINE;
 use crate::tikv::encoding::KeyDecoder;
+use crate::tikv::health::HealthChecker;
+use crate::tikv::import::BulkImporter;
+use crate::tikv::negative_cache::{NegativeCache, NEGATIVE_CACHE};
+use crate::tikv::resize::PoolResizer;
+use crate::tikv::string::StringCommandCtx;
 use crate::tikv::{get_txn_client, KEY_ENCODER};
-use crate::utils::{self, resp_err, resp_invalid_arguments, resp_ok, resp_queued, sleep};
+use crate::ttl_sampler::TtlSampler;
+use crate::utils::{
+    self, now_timestamp_in_millis, resp_err, resp_invalid_arguments, resp_ok, resp_queued,
+    resp_reset, resp_subscribed_context_err, sleep,
+};
 use crate::{
-    async_gc_worker_number_or_default, config_cluster_broadcast_addr_or_default,
-    config_cluster_topology_expire_or_default, config_cluster_topology_interval_or_default,
-    config_local_pool_number, is_auth_enabled, is_auth_matched, Command, Connection, Db,
-    DbDropGuard, Shutdown,
+    access_time_sample_rate_or_default, async_gc_worker_number_or_default,
+    cmd_size_metrics_enabled_or_default, cmd_timeout_ms_or_default,
+    config_cluster_broadcast_addr_or_default, config_cluster_topology_expire_or_default,
+    config_cluster_topology_interval_or_default, config_local_pool_number, is_auth_enabled,
+    is_auth_matched, is_unixsocket_auth_enabled, is_unixsocket_auth_matched, is_use_txn_api,
+    max_inflight_cmds_or_default, Command, Connection, Db, DbDropGuard, Frame, Shutdown,
+    SocketType,
 };
 use std::collections::HashMap;
 
 use async_std::net::{TcpListener, TcpStream};
+use async_std::os::unix::net::UnixListener;
+use futures::future;
 use futures::FutureExt;
 use std::future::Future;
 use std::ops::Range;
@@ -25,24 +44,31 @@ use tikv_client::{BoundRange, Key};
 
 use async_std::prelude::StreamExt;
 use async_tls::TlsAcceptor;
+use bytes::Bytes;
 use rand::Rng;
 use slog::{debug, error, info, warn};
-use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::sync::{broadcast, mpsc, Mutex, Semaphore, SemaphorePermit};
 use tokio::time::{self, Duration, Instant, MissedTickBehavior};
 
 use mlua::{HookTriggers, Lua};
 
 use crate::config::LOGGER;
 
-use crate::client::Client;
+use crate::acl::{self, DEFAULT_USER};
+use crate::client::{Client, Clients};
 use tokio_util::task::LocalPoolHandle;
 
 use crate::tikv::errors::{
-    REDIS_AUTH_INVALID_PASSWORD_ERR, REDIS_AUTH_REQUIRED_ERR, REDIS_AUTH_WHEN_DISABLED_ERR,
-    REDIS_DISCARD_WITHOUT_MULTI_ERR, REDIS_EXEC_WITHOUT_MULTI_ERR, REDIS_MULTI_NESTED_ERR,
+    RTError, REDIS_AUTH_INVALID_PASSWORD_ERR, REDIS_AUTH_REQUIRED_ERR,
+    REDIS_AUTH_WHEN_DISABLED_ERR, REDIS_AUTH_WRONGPASS_ERR, REDIS_DISCARD_WITHOUT_MULTI_ERR,
+    REDIS_EXEC_ERR, REDIS_EXEC_WITHOUT_MULTI_ERR, REDIS_HELLO_AUTH_REQUIRED_ERR,
+    REDIS_MULTI_NESTED_ERR, REDIS_WATCH_INSIDE_MULTI_ERR,
 };
 
-use crate::cmd::{script_clear_killed, script_interuptted};
+use crate::cmd::spec::{argv_from_frame, command_keys, lookup_command_spec};
+use crate::cmd::{
+    monitor_line, release_subscriptions, script_clear_killed, script_interuptted, Subscriptions,
+};
 
 /// Server listener state. Created in the `run` call. It includes a `run` method
 /// which performs the TCP listening and initialization of per-connection state.
@@ -58,10 +84,10 @@ struct Listener {
     db_holder: DbDropGuard,
 
     topo_holder: Cluster,
-    clients: Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>,
+    clients: Clients,
 
-    /// TCP listener supplied by the `run` caller.
-    listener: TcpListener,
+    /// TCP listeners supplied by the `run` caller, one for each listen address.
+    listeners: Vec<TcpListener>,
 
     /// Limit the max number of connections.
     ///
@@ -93,22 +119,32 @@ struct Listener {
     /// `shutdown_complete_tx`. When the listener shuts down, it drops the
     /// sender held by this `shutdown_complete_tx` field. Once all handler tasks
     /// complete, all clones of the `Sender` are also dropped. This results in
-    /// `shutdown_complete_rx.recv()` completing with `None`. At this point, it
+    /// the receiver kept by `run` completing with `None`. At this point, it
     /// is safe to exit the server process.
-    shutdown_complete_rx: mpsc::Receiver<()>,
     shutdown_complete_tx: mpsc::Sender<()>,
 }
 
 struct TlsListener {
     db_holder: DbDropGuard,
     topo_holder: Cluster,
-    clients: Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>,
+    clients: Clients,
     tls_listener: TcpListener,
     tls_acceptor: TlsAcceptor,
     tls_notify_shutdown: broadcast::Sender<()>,
-    tls_shutdown_complete_rx: mpsc::Receiver<()>,
     tls_shutdown_complete_tx: mpsc::Sender<()>,
 }
+
+/// Accepts connections on the unix domain socket, unix socket clients never
+/// use TLS and may authenticate with their own password.
+struct UnixSocketListener {
+    path: String,
+    unix_listener: UnixListener,
+    db_holder: DbDropGuard,
+    topo_holder: Cluster,
+    clients: Clients,
+    unix_notify_shutdown: broadcast::Sender<()>,
+    unix_shutdown_complete_tx: mpsc::Sender<()>,
+}
 #[derive(Debug, Clone)]
 struct TopologyManager {
     /// address of this instance
@@ -124,7 +160,6 @@ struct TopologyManager {
 
 /// Per-connection handler. Reads requests from `connection` and applies the
 /// commands to `db`.
-#[derive(Debug)]
 struct Handler {
     /// Shared database handle.
     ///
@@ -135,7 +170,7 @@ struct Handler {
 
     topo: Cluster,
     cur_client: Arc<Mutex<Client>>,
-    clients: Arc<Mutex<HashMap<u64, Arc<Mutex<Client>>>>>,
+    clients: Clients,
 
     /// The TCP connection decorated with the redis protocol encoder / decoder
     /// implemented using a buffered `TcpStream`.
@@ -149,6 +184,11 @@ struct Handler {
     /// The txn state of this connection.
     inner_txn: bool,
     queued_commands: Vec<Command>,
+    /// A command was rejected while queued, `EXEC` discards the transaction.
+    txn_aborted: bool,
+    /// Keys watched by `WATCH` along with the timestamp of the snapshot `EXEC`
+    /// compares them with.
+    watched_keys: Vec<(String, u64)>,
 
     /// Max connection semaphore.
     ///
@@ -172,9 +212,20 @@ struct Handler {
     /// set authorized to true after `AUTH password` command executed.
     authorized: bool,
 
+    /// The user of the access control list the connection is authenticated
+    /// as, `default` until `AUTH username password` is executed.
+    user: String,
+
     /// Lua vm context, lazy initialized when eval/evalsha called
     lua: Option<Lua>,
 
+    /// Bulk load state, set between `BULKLOAD BEGIN` and `BULKLOAD END`
+    importer: Option<BulkImporter>,
+
+    /// Channels and patterns the connection is subscribed to, their messages are written
+    /// between the replies of the commands.
+    subscriptions: Subscriptions,
+
     /// Not used directly. Instead, when `Handler` is dropped...?
     _shutdown_complete: mpsc::Sender<()>,
 }
@@ -203,13 +254,16 @@ struct Handler {
 /// `tokio::signal::ctrl_c()` can be used as the `shutdown` argument. This will
 /// listen for a SIGINT signal.
 pub async fn run(
-    listener: Option<TcpListener>,
+    listeners: Vec<TcpListener>,
     tls_listener: Option<TcpListener>,
     tls_acceptor: Option<TlsAcceptor>,
+    unix_listener: Option<(String, UnixListener)>,
     shutdown: impl Future,
 ) {
-    let tcp_enabled = listener.is_some();
-    let tls_enabled = tls_listener.is_some();
+    if listeners.is_empty() && tls_listener.is_none() && unix_listener.is_none() {
+        error!(LOGGER, "no listener enabled for tcp, tls or unix socket");
+        return;
+    }
 
     let topo_addr = config_cluster_broadcast_addr_or_default();
 
@@ -221,6 +275,7 @@ pub async fn run(
     // a receiver is needed, the subscribe() method on the sender is used to create
     // one.
     let db_holder = DbDropGuard::new();
+    set_notify_db(db_holder.db());
 
     let topo_manager = TopologyManager {
         address: topo_addr,
@@ -232,167 +287,123 @@ pub async fn run(
     let mut gc_master = GcMaster::new(async_gc_worker_number_or_default(), topo_holder.clone());
     gc_master.start_workers().await;
 
-    if tcp_enabled && !tls_enabled {
-        let (notify_shutdown, _) = broadcast::channel(1);
-        let (shutdown_complete_tx, shutdown_complete_rx) = mpsc::channel(1);
+    let mut ttl_sampler = TtlSampler::new();
+    let mut active_expire = ActiveExpire::new();
+    let health_checker = HealthChecker::new();
+    let mut pool_resizer = PoolResizer::new();
+
+    // All the accept loops share the shutdown channels and the client registry,
+    // so `CLIENT LIST` and `CLIENT KILL` see the clients of every socket type.
+    let (notify_shutdown, _) = broadcast::channel(1);
+    let (shutdown_complete_tx, mut shutdown_complete_rx) = mpsc::channel(1);
+    let clients = Arc::new(Mutex::new(HashMap::new()));
 
-        // Initialize the listener state
-        let mut server = Listener {
-            listener: listener.unwrap(),
+    // Initialize the listener state
+    let mut server = if listeners.is_empty() {
+        None
+    } else {
+        Some(Listener {
+            listeners,
             db_holder: db_holder.clone(),
             topo_holder: topo_holder.clone(),
-            clients: Arc::new(Mutex::new(HashMap::new())),
+            clients: clients.clone(),
             // limit_connections: Arc::new(Semaphore::new(MAX_CONNECTIONS)),
-            notify_shutdown,
-            shutdown_complete_tx,
-            shutdown_complete_rx,
-        };
-
-        tokio::select! {
-            res = server.run() => {
-                if let Err(err) = res {
-                    error!(LOGGER, "failed to accept, cause {}", err.to_string());
-                }
-            }
-            _ = topo_manager.run() => {
-                error!(LOGGER, "topology manager exit");
-            }
-            _ = gc_master.run() => {
-                error!(LOGGER, "gc master exit");
-            }
-            _ = shutdown => {
-                // The shutdown signal has been received.
-                info!(LOGGER, "shutting down");
-            }
-        }
+            notify_shutdown: notify_shutdown.clone(),
+            shutdown_complete_tx: shutdown_complete_tx.clone(),
+        })
+    };
 
-        let Listener {
-            mut shutdown_complete_rx,
-            shutdown_complete_tx,
-            notify_shutdown,
-            ..
-        } = server;
-
-        drop(notify_shutdown);
-        drop(shutdown_complete_tx);
-
-        shutdown_complete_rx.recv().await;
-    } else if tls_enabled && !tcp_enabled {
-        let (tls_notify_shutdown, _) = broadcast::channel(1);
-        let (tls_shutdown_complete_tx, tls_shutdown_complete_rx) = mpsc::channel(1);
-        let mut tls_server = TlsListener {
-            tls_listener: tls_listener.unwrap(),
+    let mut tls_server = match (tls_listener, tls_acceptor) {
+        (Some(tls_listener), Some(tls_acceptor)) => Some(TlsListener {
+            tls_listener,
             db_holder: db_holder.clone(),
             topo_holder: topo_holder.clone(),
-            clients: Arc::new(Mutex::new(HashMap::new())),
-            tls_acceptor: tls_acceptor.unwrap(),
-            tls_notify_shutdown,
-            tls_shutdown_complete_tx,
-            tls_shutdown_complete_rx,
-        };
+            clients: clients.clone(),
+            tls_acceptor,
+            tls_notify_shutdown: notify_shutdown.clone(),
+            tls_shutdown_complete_tx: shutdown_complete_tx.clone(),
+        }),
+        _ => None,
+    };
 
-        tokio::select! {
-            tls_res = tls_server.run() => {
-                if let Err(err) = tls_res {
-                    error!(LOGGER, "failed to accept, cause {}", err.to_string());
-                }
-            }
-            _ = topo_manager.run() => {
-                error!(LOGGER, "topology manager exit");
-            }
-            _ = gc_master.run() => {
-                error!(LOGGER, "gc master exit");
+    let mut unix_server = unix_listener.map(|(path, unix_listener)| UnixSocketListener {
+        path,
+        unix_listener,
+        db_holder: db_holder.clone(),
+        topo_holder: topo_holder.clone(),
+        clients: clients.clone(),
+        unix_notify_shutdown: notify_shutdown.clone(),
+        unix_shutdown_complete_tx: shutdown_complete_tx.clone(),
+    });
+
+    tokio::select! {
+        res = async {
+            match server.as_mut() {
+                Some(server) => server.run().await,
+                None => future::pending().await,
             }
-            _ = shutdown => {
-                // The shutdown signal has been received.
-                info!(LOGGER, "shutting down");
+        } => {
+            if let Err(err) = res {
+                error!(LOGGER, "failed to accept, cause {}", err.to_string());
             }
         }
-
-        let TlsListener {
-            mut tls_shutdown_complete_rx,
-            tls_shutdown_complete_tx,
-            tls_notify_shutdown,
-            ..
-        } = tls_server;
-
-        drop(tls_notify_shutdown);
-        drop(tls_shutdown_complete_tx);
-        tls_shutdown_complete_rx.recv().await;
-    } else if tcp_enabled && tls_enabled {
-        let (notify_shutdown, _) = broadcast::channel(1);
-        let (shutdown_complete_tx, shutdown_complete_rx) = mpsc::channel(1);
-
-        // Initialize the listener state
-        let mut server = Listener {
-            listener: listener.unwrap(),
-            db_holder: db_holder.clone(),
-            topo_holder: topo_holder.clone(),
-            clients: Arc::new(Mutex::new(HashMap::new())),
-            // limit_connections: Arc::new(Semaphore::new(MAX_CONNECTIONS)),
-            notify_shutdown,
-            shutdown_complete_tx,
-            shutdown_complete_rx,
-        };
-
-        let (tls_notify_shutdown, _) = broadcast::channel(1);
-        let (tls_shutdown_complete_tx, tls_shutdown_complete_rx) = mpsc::channel(1);
-        let mut tls_server = TlsListener {
-            tls_listener: tls_listener.unwrap(),
-            db_holder: db_holder.clone(),
-            topo_holder: topo_holder.clone(),
-            clients: Arc::new(Mutex::new(HashMap::new())),
-            tls_acceptor: tls_acceptor.unwrap(),
-            tls_notify_shutdown,
-            tls_shutdown_complete_tx,
-            tls_shutdown_complete_rx,
-        };
-
-        tokio::select! {
-            res = server.run() => {
-                if let Err(err) = res {
-                    error!(LOGGER, "failed to accept, cause {}", err.to_string());
-                }
-            }
-            tls_res = tls_server.run() => {
-                if let Err(err) = tls_res {
-                    error!(LOGGER, "failed to accept, cause {}", err.to_string());
-                }
+        tls_res = async {
+            match tls_server.as_mut() {
+                Some(tls_server) => tls_server.run().await,
+                None => future::pending().await,
             }
-            _ = topo_manager.run() => {
-                error!(LOGGER, "topology manager exit");
+        } => {
+            if let Err(err) = tls_res {
+                error!(LOGGER, "failed to accept, cause {}", err.to_string());
             }
-            _ = gc_master.run() => {
-                error!(LOGGER, "gc master exit");
+        }
+        unix_res = async {
+            match unix_server.as_mut() {
+                Some(unix_server) => unix_server.run().await,
+                None => future::pending().await,
             }
-            _ = shutdown => {
-                // The shutdown signal has been received.
-                info!(LOGGER, "shutting down");
+        } => {
+            if let Err(err) = unix_res {
+                error!(LOGGER, "failed to accept, cause {}", err.to_string());
             }
         }
-        let Listener {
-            mut shutdown_complete_rx,
-            shutdown_complete_tx,
-            notify_shutdown,
-            ..
-        } = server;
-
-        drop(notify_shutdown);
-        drop(shutdown_complete_tx);
-        shutdown_complete_rx.recv().await;
-
-        let TlsListener {
-            mut tls_shutdown_complete_rx,
-            tls_shutdown_complete_tx,
-            tls_notify_shutdown,
-            ..
-        } = tls_server;
-        drop(tls_notify_shutdown);
-        drop(tls_shutdown_complete_tx);
-        tls_shutdown_complete_rx.recv().await;
-    } else {
-        error!(LOGGER, "no listener enabled for tcp or tls");
+        _ = topo_manager.run() => {
+            error!(LOGGER, "topology manager exit");
+        }
+        _ = gc_master.run() => {
+            error!(LOGGER, "gc master exit");
+        }
+        _ = ttl_sampler.run() => {
+            error!(LOGGER, "ttl sampler exit");
+        }
+        _ = active_expire.run() => {
+            error!(LOGGER, "active expire exit");
+        }
+        _ = health_checker.run() => {
+            error!(LOGGER, "health checker exit");
+        }
+        _ = pool_resizer.run() => {
+            error!(LOGGER, "pool resizer exit");
+        }
+        _ = shutdown => {
+            // The shutdown signal has been received.
+            info!(LOGGER, "shutting down");
+        }
+    }
+
+    // The listeners hold clones of the shutdown channels, drop them before
+    // waiting for the connection handlers to complete.
+    drop(server);
+    drop(tls_server);
+    if let Some(unix_server) = unix_server.take() {
+        // remove the socket file so the next start can bind the path again
+        let _ = std::fs::remove_file(&unix_server.path);
     }
+
+    drop(notify_shutdown);
+    drop(shutdown_complete_tx);
+
+    shutdown_complete_rx.recv().await;
 }
 
 impl Listener {
@@ -437,7 +448,7 @@ impl Listener {
             // error here is non-recoverable.
             let socket = self.accept().await?;
             let (kill_tx, kill_rx) = mpsc::channel(1);
-            let client = Client::new(socket.clone(), kill_tx);
+            let client = Client::new(socket.clone(), SocketType::Tcp, kill_tx);
             let client_id = client.id();
             let arc_client = Arc::new(Mutex::new(client));
             self.clients
@@ -460,6 +471,8 @@ impl Listener {
 
                 inner_txn: false,
                 queued_commands: vec![],
+                txn_aborted: false,
+                watched_keys: vec![],
 
                 // The connection state needs a handle to the max connections
                 // semaphore. When the handler is done processing the
@@ -471,7 +484,12 @@ impl Listener {
 
                 authorized: !is_auth_enabled(),
 
+                user: DEFAULT_USER.to_owned(),
+
                 lua: None,
+                subscriptions: Subscriptions::new(),
+
+                importer: None,
 
                 // Notifies the receiver half once all clones are
                 // dropped.
@@ -484,6 +502,7 @@ impl Listener {
                 if let Err(err) = handler.run().await {
                     error!(LOGGER, "connection error {:?}", err);
                 }
+                handler.finish_bulk_load().await;
                 handler
                     .clients
                     .lock()
@@ -508,7 +527,11 @@ impl Listener {
         loop {
             // Perform the accept operation. If a socket is successfully
             // accepted, return it. Otherwise, save the error.
-            match self.listener.accept().await {
+            let accepts = self
+                .listeners
+                .iter()
+                .map(|listener| Box::pin(listener.accept()));
+            match future::select_all(accepts).await.0 {
                 Ok((socket, _)) => return Ok(socket),
                 Err(err) => {
                     error!(LOGGER, "Accept Error! {:?}", &err);
@@ -540,13 +563,7 @@ impl TlsListener {
             let acceptor = self.tls_acceptor.clone();
             let stream = stream?;
             let (kill_tx, kill_rx) = mpsc::channel(1);
-            let client = Client::new(stream.clone(), kill_tx);
-            let client_id = client.id();
-            let arc_client = Arc::new(Mutex::new(client));
-            self.clients
-                .lock()
-                .await
-                .insert(client_id, arc_client.clone());
+            let client = Client::new(stream.clone(), SocketType::Tls, kill_tx);
 
             let local_addr = stream.local_addr().unwrap().to_string();
             let peer_addr = stream.peer_addr().unwrap().to_string();
@@ -568,6 +585,15 @@ impl TlsListener {
                 }
             };
 
+            // registered once the handshake succeeds, a failed handshake
+            // leaves nothing to remove
+            let client_id = client.id();
+            let arc_client = Arc::new(Mutex::new(client));
+            self.clients
+                .lock()
+                .await
+                .insert(client_id, arc_client.clone());
+
             let mut handler = Handler {
                 db: self.db_holder.db(),
                 topo: self.topo_holder.clone(),
@@ -576,9 +602,14 @@ impl TlsListener {
                 connection: Connection::new_tls(&local_addr, &peer_addr, tls_stream),
                 inner_txn: false,
                 queued_commands: vec![],
+                txn_aborted: false,
+                watched_keys: vec![],
                 shutdown: Shutdown::new(self.tls_notify_shutdown.subscribe(), kill_rx),
                 authorized: !is_auth_enabled(),
+                user: DEFAULT_USER.to_owned(),
                 lua: None,
+                subscriptions: Subscriptions::new(),
+                importer: None,
                 _shutdown_complete: self.tls_shutdown_complete_tx.clone(),
             };
 
@@ -589,6 +620,7 @@ impl TlsListener {
                 if let Err(err) = handler.run().await {
                     error!(LOGGER, "tls connection error {:?}", err);
                 }
+                handler.finish_bulk_load().await;
                 handler
                     .clients
                     .lock()
@@ -602,6 +634,65 @@ impl TlsListener {
     }
 }
 
+impl UnixSocketListener {
+    async fn run(&mut self) -> crate::Result<()> {
+        info!(LOGGER, "accepting inbound unix socket connections");
+
+        let local_pool_number = config_local_pool_number();
+        let local_pool = LocalPoolHandle::new(local_pool_number);
+
+        let mut incoming = self.unix_listener.incoming();
+        while let Some(stream) = incoming.next().await {
+            let stream = stream?;
+            let (kill_tx, kill_rx) = mpsc::channel(1);
+            let client = Client::new_unix(&self.path, &stream, kill_tx);
+            let client_id = client.id();
+            let arc_client = Arc::new(Mutex::new(client));
+            self.clients
+                .lock()
+                .await
+                .insert(client_id, arc_client.clone());
+
+            let mut handler = Handler {
+                db: self.db_holder.db(),
+                topo: self.topo_holder.clone(),
+                cur_client: arc_client.clone(),
+                clients: self.clients.clone(),
+                connection: Connection::new_unix(&self.path, stream),
+                inner_txn: false,
+                queued_commands: vec![],
+                txn_aborted: false,
+                watched_keys: vec![],
+                shutdown: Shutdown::new(self.unix_notify_shutdown.subscribe(), kill_rx),
+                authorized: !is_unixsocket_auth_enabled(),
+                user: DEFAULT_USER.to_owned(),
+                lua: None,
+                subscriptions: Subscriptions::new(),
+                importer: None,
+                _shutdown_complete: self.unix_shutdown_complete_tx.clone(),
+            };
+
+            local_pool.spawn_pinned(|| async move {
+                // Process the connection. If an error is encountered, log it.
+                CURRENT_UNIX_CONNECTION_COUNTER.inc();
+                TOTAL_CONNECTION_PROCESSED.inc();
+                if let Err(err) = handler.run().await {
+                    error!(LOGGER, "unix socket connection error {:?}", err);
+                }
+                handler.finish_bulk_load().await;
+                handler
+                    .clients
+                    .lock()
+                    .await
+                    .remove(&handler.cur_client.lock().await.id());
+                CURRENT_UNIX_CONNECTION_COUNTER.dec();
+            });
+        }
+
+        Ok(())
+    }
+}
+
 impl TopologyManager {
     async fn run(self) -> crate::Result<()> {
         let mut interval = time::interval(Duration::from_millis(self.interval));
@@ -614,17 +705,18 @@ impl TopologyManager {
             interval.tick().await;
 
             let mut txn_client = get_txn_client()?;
+            // the expire timestamp is fixed before the txn, a retried txn must not
+            // extend it
+            let ttl = utils::timestamp_from_ttl(expire);
             // do all work in one txn
             let resp = txn_client
                 .exec_in_txn(None, |txn_rc| {
                     let address = address.clone();
-                    let expire = expire;
                     let mut topo_holder = topo_holder.clone();
                     async move {
                         let mut txn = txn_rc.lock().await;
                         // refresh myself infomation to backend store
                         let topo_key = KEY_ENCODER.encode_txnkv_cluster_topo(&address);
-                        let ttl = utils::timestamp_from_ttl(expire);
                         let topo_value = KEY_ENCODER.encode_txnkv_cluster_topo_value(ttl);
                         txn.put(topo_key, topo_value).await?;
 
@@ -676,7 +768,44 @@ impl TopologyManager {
     }
 }
 
+lazy_static! {
+    /// Slots of the commands running at the same time, see `max_inflight_cmds`.
+    static ref INFLIGHT_CMD_SLOTS: Option<Semaphore> = match max_inflight_cmds_or_default() {
+        0 => None,
+        n => Some(Semaphore::new(n)),
+    };
+}
+
+/// A running command, it holds a slot of the in-flight limit until dropped.
+struct InflightCmd {
+    _permit: Option<SemaphorePermit<'static>>,
+}
+
+impl InflightCmd {
+    async fn start() -> InflightCmd {
+        let permit = match INFLIGHT_CMD_SLOTS.as_ref() {
+            // the semaphore is never closed
+            Some(slots) => Some(slots.acquire().await.unwrap()),
+            None => None,
+        };
+        INFLIGHT_CMD_GAUGE.inc();
+        InflightCmd { _permit: permit }
+    }
+}
+
+impl Drop for InflightCmd {
+    fn drop(&mut self) {
+        INFLIGHT_CMD_GAUGE.dec();
+    }
+}
+
 impl Handler {
+    /// In the subscribed state of a RESP2 connection only the pub/sub commands,
+    /// PING and RESET are allowed.
+    fn in_resp2_subscribed_context(&self) -> bool {
+        !self.subscriptions.is_empty() && !self.connection.is_resp3()
+    }
+
     /// Process a single connection.
     ///
     /// Request frames are read from the socket and processed. Responses are
@@ -694,9 +823,14 @@ impl Handler {
         // new request frame.
         while !self.shutdown.is_shutdown() {
             // While reading a request frame, also listen for the shutdown
-            // signal.
+            // signal and the messages of the subscribed channels.
             let maybe_frame = tokio::select! {
                 res = self.connection.read_frame() => res?,
+                Some((_, msg)) = tokio_stream::StreamExt::next(&mut self.subscriptions),
+                    if !self.subscriptions.is_empty() => {
+                    self.connection.write_frame(&msg).await?;
+                    continue;
+                }
                 _ = self.shutdown.recv() => {
                     // If a shutdown signal is received, return from `run`.
                     // This will result in the task terminating.
@@ -712,15 +846,91 @@ impl Handler {
                 None => return Ok(()),
             };
 
+            // The arguments are kept for the monitors and the slow log only,
+            // never leak the password to them, of AUTH, HELLO AUTH or the ACL
+            // rules.
+            let argv = if self.db.is_monitored() || slowlog::slowlog_enabled() {
+                let argv = argv_from_frame(&frame);
+                let has_password = !argv.is_empty()
+                    && (argv[0].eq_ignore_ascii_case(b"auth")
+                        || argv[0].eq_ignore_ascii_case(b"acl")
+                        || (argv[0].eq_ignore_ascii_case(b"hello")
+                            && argv.iter().any(|arg| arg.eq_ignore_ascii_case(b"auth"))));
+                if has_password {
+                    vec![]
+                } else {
+                    argv
+                }
+            } else {
+                vec![]
+            };
+            if self.db.is_monitored() && !argv.is_empty() {
+                self.db
+                    .feed_monitors(monitor_line(self.connection.peer_addr(), &argv));
+            }
+
+            // Only a sample of requests record the access time of their keys,
+            // to keep the write amplification of reads low.
+            let access_keys = if access_time_sampled() {
+                command_keys(&frame)
+            } else {
+                vec![]
+            };
+
+            // The keys written by the request invalidate the negative cache,
+            // wake the blocked list pops waiting on them and notify the
+            // subscribers of their keyspace events.
+            let written_keys =
+                if NegativeCache::enabled() || self.db.has_list_waiters() || notify_enabled() {
+                    request_written_keys(&frame)
+                } else {
+                    None
+                };
+
+            let size_class = if cmd_size_metrics_enabled_or_default() {
+                Some(request_size_class(&frame))
+            } else {
+                None
+            };
+
+            // The user of the connection must be allowed to run the command
+            // on its keys, the denial is replied once the connection is
+            // authorized.
+            let permission = acl::check_permission(&self.user, &frame);
+
             // Convert the redis frame into a command struct. This returns an
             // error if the frame is not a valid redis command or it is an
             // unsupported command.
             let cmd = Command::from_frame(frame)?;
             let cmd_name = cmd.get_name().to_owned();
 
+            // The command waits for a slot of the in-flight limit, the
+            // connection is not read meanwhile so TCP flow control pushes back
+            // on the client. Blocking commands and `DEBUG SLEEP` run without a
+            // slot, they would hold it for as long as they block.
+            let _inflight = match cmd {
+                Command::Blpop(_) | Command::Brpop(_) | Command::Monitor(_) => None,
+                Command::Debug(ref c) if c.is_sleep() => None,
+                _ => Some(InflightCmd::start().await),
+            };
+            // unknown commands share one label to cap the series of the size metric
+            let size_cmd_name = match cmd {
+                Command::Unknown(_) => "unknown".to_owned(),
+                _ => cmd_name.clone(),
+            };
+
             {
                 let mut w_client = self.cur_client.lock().await;
                 w_client.interact(&cmd_name);
+                w_client.set_conn_state(
+                    self.connection.is_resp3(),
+                    if self.inner_txn {
+                        Some(self.queued_commands.len())
+                    } else {
+                        None
+                    },
+                    self.connection.last_error(),
+                );
             }
 
             let start_at = Instant::now();
@@ -741,18 +951,31 @@ impl Handler {
                         self.connection
                             .write_frame(&resp_invalid_arguments())
                             .await?;
-                    } else if !is_auth_enabled() {
-                        // check password and update connection authorized flag
-                        self.connection
-                            .write_frame(&resp_err(REDIS_AUTH_WHEN_DISABLED_ERR))
-                            .await?;
-                    } else if is_auth_matched(c.passwd()) {
-                        self.connection.write_frame(&resp_ok()).await?;
-                        self.authorized = true;
                     } else {
-                        self.connection
-                            .write_frame(&resp_err(REDIS_AUTH_INVALID_PASSWORD_ERR))
-                            .await?;
+                        // check password and update connection authorized flag
+                        let response = match self.authenticate(c.username(), c.passwd()) {
+                            Ok(()) => resp_ok(),
+                            Err(e) => resp_err(e),
+                        };
+                        self.connection.write_frame(&response).await?;
+                    }
+                }
+                Command::Quit(_) => {
+                    // the connection is closed once the reply is sent
+                    self.connection.write_frame(&resp_ok()).await?;
+                    return Ok(());
+                }
+                Command::Hello(c) if !self.authorized || c.auth().is_some() => {
+                    let result = match c.auth() {
+                        Some((username, passwd)) => self.authenticate(Some(username), passwd),
+                        None => Err(REDIS_HELLO_AUTH_REQUIRED_ERR),
+                    };
+                    match result {
+                        Ok(()) => {
+                            c.apply(&mut self.connection, self.cur_client.clone())
+                                .await?
+                        }
+                        Err(e) => self.connection.write_frame(&resp_err(e)).await?,
                     }
                 }
                 _ => {
@@ -760,8 +983,58 @@ impl Handler {
                         self.connection
                             .write_frame(&resp_err(REDIS_AUTH_REQUIRED_ERR))
                             .await?;
+                    } else if let Err(e) = permission {
+                        // a denied command aborts the transaction as an
+                        // unknown one does
+                        if self.inner_txn {
+                            self.txn_aborted = true;
+                        }
+                        self.connection.write_frame(&resp_err(e)).await?;
+                    } else if self.in_resp2_subscribed_context()
+                        && !matches!(
+                            cmd,
+                            Command::Subscribe(_)
+                                | Command::Unsubscribe(_)
+                                | Command::Psubscribe(_)
+                                | Command::Punsubscribe(_)
+                                | Command::Ping(_)
+                                | Command::Reset(_)
+                                | Command::Unknown(_)
+                        )
+                    {
+                        // replies of other commands are not distinguishable
+                        // from the messages in RESP2
+                        self.connection
+                            .write_frame(&resp_subscribed_context_err(&cmd_name))
+                            .await?;
                     } else {
-                        match cmd {
+                        // The commands handled here are done, the others are
+                        // applied below.
+                        let pending = match cmd {
+                            Command::Subscribe(c) => {
+                                c.apply(&self.db, &mut self.connection, &mut self.subscriptions)
+                                    .await?;
+                                None
+                            }
+                            Command::Unsubscribe(c) => {
+                                c.apply(&self.db, &mut self.connection, &mut self.subscriptions)
+                                    .await?;
+                                None
+                            }
+                            Command::Psubscribe(c) => {
+                                c.apply(&self.db, &mut self.connection, &mut self.subscriptions)
+                                    .await?;
+                                None
+                            }
+                            Command::Punsubscribe(c) => {
+                                c.apply(&self.db, &mut self.connection, &mut self.subscriptions)
+                                    .await?;
+                                None
+                            }
+                            Command::Ping(c) if self.in_resp2_subscribed_context() => {
+                                c.apply_subscribed(&mut self.connection).await?;
+                                None
+                            }
                             Command::Eval(_) | Command::Evalsha(_) => {
                                 if self.lua.is_none() {
                                     // initialize the mlua once in same connection
@@ -788,6 +1061,7 @@ impl Handler {
 
                                     self.lua = Some(lua);
                                 }
+                                Some(cmd)
                             }
                             Command::Multi(_) => {
                                 if self.inner_txn {
@@ -796,26 +1070,41 @@ impl Handler {
                                         .await?;
                                 } else {
                                     self.inner_txn = true;
+                                    self.txn_aborted = false;
                                     self.queued_commands.clear();
                                     self.connection.write_frame(&resp_ok()).await?;
                                 }
+                                None
                             }
                             Command::Exec(c) => {
                                 if !self.inner_txn {
                                     self.connection
                                         .write_frame(&resp_err(REDIS_EXEC_WITHOUT_MULTI_ERR))
                                         .await?;
+                                } else if self.txn_aborted {
+                                    self.inner_txn = false;
+                                    self.queued_commands.clear();
+                                    self.watched_keys.clear();
+                                    self.connection
+                                        .write_frame(&resp_err(REDIS_EXEC_ERR))
+                                        .await?;
                                 } else {
                                     self.inner_txn = false;
                                     c.clone()
-                                        .exec(&mut self.connection, self.queued_commands.clone())
+                                        .exec(
+                                            &mut self.connection,
+                                            self.queued_commands.clone(),
+                                            std::mem::take(&mut self.watched_keys),
+                                        )
                                         .await?;
+                                    self.keys_written(&written_keys);
                                 }
 
                                 let duration = Instant::now() - start_at;
                                 REQUEST_CMD_HANDLE_TIME
                                     .with_label_values(&[&cmd_name])
                                     .observe(duration_to_sec(duration));
+                                self.log_if_slow(duration, &argv).await;
                                 REQUEST_CMD_FINISH_COUNTER
                                     .with_label_values(&[&cmd_name])
                                     .inc();
@@ -825,12 +1114,68 @@ impl Handler {
                                 if self.inner_txn {
                                     self.inner_txn = false;
                                     self.queued_commands.clear();
+                                    self.watched_keys.clear();
                                     self.connection.write_frame(&resp_ok()).await?;
                                 } else {
                                     self.connection
                                         .write_frame(&resp_err(REDIS_DISCARD_WITHOUT_MULTI_ERR))
                                         .await?;
                                 }
+                                None
+                            }
+                            Command::Reset(_) => {
+                                release_subscriptions(&self.db, &mut self.subscriptions);
+                                self.inner_txn = false;
+                                self.queued_commands.clear();
+                                self.watched_keys.clear();
+                                self.connection.set_resp3(false);
+                                self.connection.write_frame(&resp_reset()).await?;
+                                None
+                            }
+                            Command::Watch(_) if self.inner_txn => {
+                                self.connection
+                                    .write_frame(&resp_err(REDIS_WATCH_INSIDE_MULTI_ERR))
+                                    .await?;
+                                None
+                            }
+                            Command::Watch(c) => {
+                                let response = c
+                                    .watch(&mut self.watched_keys)
+                                    .await
+                                    .unwrap_or_else(Into::into);
+                                self.connection.write_frame(&response).await?;
+                                None
+                            }
+                            Command::Unwatch(c) if !self.inner_txn => {
+                                let response = c.unwatch(&mut self.watched_keys);
+                                self.connection.write_frame(&response).await?;
+                                None
+                            }
+                            Command::Acl(c) if !self.inner_txn => {
+                                let response = c.acl(&self.user);
+                                self.connection.write_frame(&response).await?;
+                                None
+                            }
+                            Command::Bulkload(c) => {
+                                c.clone()
+                                    .apply(&mut self.connection, &mut self.importer)
+                                    .await?;
+                                self.keys_written(&written_keys);
+
+                                let duration = Instant::now() - start_at;
+                                REQUEST_CMD_HANDLE_TIME
+                                    .with_label_values(&[&cmd_name])
+                                    .observe(duration_to_sec(duration));
+                                self.log_if_slow(duration, &argv).await;
+                                REQUEST_CMD_FINISH_COUNTER
+                                    .with_label_values(&[&cmd_name])
+                                    .inc();
+                                continue;
+                            }
+                            Command::Unknown(c) if self.inner_txn => {
+                                self.txn_aborted = true;
+                                c.apply(&mut self.connection).await?;
+                                None
                             }
                             _ => {
                                 if self.inner_txn {
@@ -838,35 +1183,94 @@ impl Handler {
                                     self.connection.write_frame(&resp_queued()).await?;
                                     continue;
                                 }
-                            }
-                        }
-                        // Perform the work needed to apply the command. This may mutate the
-                        // database state as a result.
-                        //
-                        // The connection is passed into the apply function which allows the
-                        // command to write response frames directly to the connection. In
-                        // the case of pub/sub, multiple frames may be send back to the
-                        // peer.
-                        match cmd
-                            .apply(
-                                &self.db,
-                                &self.topo,
-                                &mut self.connection,
-                                self.cur_client.clone(),
-                                self.clients.clone(),
-                                &mut self.lua,
-                                &mut self.shutdown,
-                            )
-                            .await
-                        {
-                            Ok(_) => (),
-                            Err(e) => {
-                                REQUEST_CMD_ERROR_COUNTER
-                                    .with_label_values(&[&cmd_name])
-                                    .inc();
-                                return Err(e);
+                                if let Some(importer) = self
+                                    .importer
+                                    .as_mut()
+                                    .filter(|_| BulkImporter::accepts(&cmd_name))
+                                {
+                                    // the data commands go to the pending batch in bulk
+                                    // load mode, the others are applied as usual
+                                    let batches = importer.batches();
+                                    let response =
+                                        importer.exec(cmd).await.unwrap_or_else(Into::into);
+                                    // the write is committed with a later batch, a
+                                    // miss cached meanwhile must not survive it
+                                    if written_keys.is_some() {
+                                        NEGATIVE_CACHE.invalidate(&[]);
+                                    }
+                                    // the keys of a batch are not tracked, all blocked
+                                    // pops are woken once it is committed
+                                    if importer.batches() != batches {
+                                        self.keys_written(&Some(vec![]));
+                                    }
+                                    self.connection.write_frame(&response).await?;
+
+                                    let duration = Instant::now() - start_at;
+                                    REQUEST_CMD_HANDLE_TIME
+                                        .with_label_values(&[&cmd_name])
+                                        .observe(duration_to_sec(duration));
+                                    self.log_if_slow(duration, &argv).await;
+                                    REQUEST_CMD_FINISH_COUNTER
+                                        .with_label_values(&[&cmd_name])
+                                        .inc();
+                                    continue;
+                                }
+                                Some(cmd)
                             }
                         };
+                        if let Some(cmd) = pending {
+                            // Perform the work needed to apply the command. This may mutate the
+                            // database state as a result.
+                            //
+                            // The connection is passed into the apply function which allows the
+                            // command to write response frames directly to the connection. In
+                            // the case of pub/sub, multiple frames may be send back to the
+                            // peer.
+                            //
+                            // The backend transactions of the command are cancelled once
+                            // its deadline passes, the command replies a timeout error.
+                            // Blocking commands wait as long as their own timeout says.
+                            let deadline = if matches!(cmd, Command::Blpop(_) | Command::Brpop(_)) {
+                                None
+                            } else {
+                                match cmd_timeout_ms_or_default(&cmd_name) {
+                                    0 => None,
+                                    ms => Some(start_at + Duration::from_millis(ms)),
+                                }
+                            };
+                            let error_replies = self.connection.error_replies();
+                            match CMD_DEADLINE
+                                .scope(
+                                    deadline,
+                                    cmd.apply(
+                                        &self.db,
+                                        &self.topo,
+                                        &mut self.connection,
+                                        self.cur_client.clone(),
+                                        self.clients.clone(),
+                                        &mut self.lua,
+                                        &self.user,
+                                        &mut self.shutdown,
+                                    ),
+                                )
+                                .await
+                            {
+                                Ok(_) => {
+                                    self.keys_written(&written_keys);
+                                    // a command replying an error wrote nothing
+                                    if self.connection.error_replies() == error_replies {
+                                        notify_keys_written(&cmd_name, &written_keys);
+                                    }
+                                    record_access_time(access_keys);
+                                }
+                                Err(e) => {
+                                    REQUEST_CMD_ERROR_COUNTER
+                                        .with_label_values(&[&cmd_name])
+                                        .inc();
+                                    return Err(e);
+                                }
+                            };
+                        }
                     }
                 }
             }
@@ -875,6 +1279,12 @@ impl Handler {
             REQUEST_CMD_HANDLE_TIME
                 .with_label_values(&[&cmd_name])
                 .observe(duration_to_sec(duration));
+            self.log_if_slow(duration, &argv).await;
+            if let Some(size_class) = size_class {
+                REQUEST_CMD_SIZE_HANDLE_TIME
+                    .with_label_values(&[&size_cmd_name, size_class])
+                    .observe(duration_to_sec(duration));
+            }
             REQUEST_CMD_FINISH_COUNTER
                 .with_label_values(&[&cmd_name])
                 .inc();
@@ -882,6 +1292,162 @@ impl Handler {
 
         Ok(())
     }
+
+    /// Add the request to the slow log if it took longer than
+    /// `slowlog_log_slower_than`.
+    async fn log_if_slow(&self, duration: Duration, argv: &[Bytes]) {
+        if argv.is_empty() || !slowlog::is_slow(duration) {
+            return;
+        }
+        let name = self.cur_client.lock().await.name().to_owned();
+        slowlog::record(duration, argv, self.connection.peer_addr(), &name);
+    }
+
+    /// Invalidate the negative cache and wake the blocked list pops of the
+    /// keys written by a request, an empty list stands for unknown keys.
+    fn keys_written(&self, keys: &Option<Vec<String>>) {
+        if let Some(keys) = keys {
+            if NegativeCache::enabled() {
+                NEGATIVE_CACHE.invalidate(keys);
+            }
+            if self.db.has_list_waiters() {
+                self.db.wake_list_waiters(keys);
+            }
+        }
+    }
+
+    /// Unix socket connections may have their own password.
+    fn auth_enabled(&self) -> bool {
+        match self.connection.socket_type() {
+            SocketType::Unix => is_unixsocket_auth_enabled(),
+            _ => is_auth_enabled(),
+        }
+    }
+
+    fn auth_matched(&self, password: &str) -> bool {
+        match self.connection.socket_type() {
+            SocketType::Unix => is_unixsocket_auth_matched(password),
+            _ => is_auth_matched(password),
+        }
+    }
+
+    /// Check the credentials of `AUTH` or `HELLO AUTH` and authorize the
+    /// connection as the user if they match. The password of the `default`
+    /// user is the one of the server, it accepts any password when no
+    /// password is set as in redis. The other users are checked against the
+    /// access control list.
+    fn authenticate(&mut self, username: Option<&str>, password: &str) -> Result<(), RTError> {
+        let user = username.unwrap_or(DEFAULT_USER);
+        let matched = if user != DEFAULT_USER {
+            acl::authenticate(user, password)
+        } else if !self.auth_enabled() {
+            if username.is_none() {
+                return Err(REDIS_AUTH_WHEN_DISABLED_ERR);
+            }
+            true
+        } else {
+            acl::default_user_enabled() && self.auth_matched(password)
+        };
+        if !matched {
+            return Err(match username {
+                Some(_) => REDIS_AUTH_WRONGPASS_ERR,
+                None => REDIS_AUTH_INVALID_PASSWORD_ERR,
+            });
+        }
+        self.authorized = true;
+        self.user = user.to_owned();
+        Ok(())
+    }
+
+    /// Commit the pending bulk load batch if the peer disconnected without `BULKLOAD END`,
+    /// all writes in it have been acknowledged already.
+    async fn finish_bulk_load(&mut self) {
+        if let Some(mut importer) = self.importer.take() {
+            if let Err(e) = importer.flush().await {
+                error!(LOGGER, "failed to commit pending bulk load batch: {}", e);
+            }
+            self.keys_written(&Some(vec![]));
+        }
+    }
+}
+
+/// Coarse class of the request payload size, the label set is fixed to keep
+/// the series of the size metric bounded.
+fn request_size_class(frame: &Frame) -> &'static str {
+    let size: usize = match frame {
+        Frame::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Frame::Bulk(data) => data.len(),
+                Frame::Simple(s) => s.len(),
+                _ => 0,
+            })
+            .sum(),
+        _ => 0,
+    };
+    match size {
+        0..=1023 => "small",
+        1024..=65535 => "medium",
+        _ => "large",
+    }
+}
+
+/// Keys written by the request once it is applied, an empty list stands for
+/// unknown keys. `None` if the request writes nothing.
+fn request_written_keys(frame: &Frame) -> Option<Vec<String>> {
+    let argv = argv_from_frame(frame);
+    let name = String::from_utf8_lossy(argv.first()?).to_lowercase();
+    match name.as_str() {
+        // the keys written by a transaction or a bulk load are not known here,
+        // nor those of a script which may write keys it didn't declare
+        "exec" | "bulkload" | "eval" | "evalsha" => Some(vec![]),
+        _ => {
+            let spec = lookup_command_spec(&name)?;
+            if !spec.is_write() {
+                return None;
+            }
+            // the keys after numkeys included, e.g. of LMPOP
+            Some(
+                spec.keys(&argv)
+                    .into_iter()
+                    .map(|key| String::from_utf8_lossy(key).to_string())
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// Publish the keyspace event of the command on each key it wrote, the events of
+/// the keys written by transactions, scripts and bulk loads are not known.
+fn notify_keys_written(cmd_name: &str, keys: &Option<Vec<String>>) {
+    if !notify_enabled() {
+        return;
+    }
+    if let (Some(keys), Some((class, event))) = (keys, command_event(cmd_name)) {
+        for key in keys {
+            notify_keyspace_event(class, event, key);
+        }
+    }
+}
+
+fn access_time_sampled() -> bool {
+    let rate = access_time_sample_rate_or_default();
+    is_use_txn_api() && rate > 0.0 && rand::thread_rng().gen::<f64>() < rate
+}
+
+/// Update the access time in background, a failed update only loses a sample.
+fn record_access_time(keys: Vec<String>) {
+    if keys.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        if let Err(e) = StringCommandCtx::new(None)
+            .do_async_txnkv_touch(&keys, now_timestamp_in_millis())
+            .await
+        {
+            debug!(LOGGER, "failed to record key access time: {}", e);
+        }
+    });
 }
 
 #[inline]
@@ -905,5 +1471,8 @@ impl Drop for Handler {
         // self.limit_connections.add_permits(1);
         // println!("Drop Handler")
         // CURRENT_CONNECTION_COUNTER.dec();
+
+        // Release the broadcast channels left without subscribers.
+        release_subscriptions(&self.db, &mut self.subscriptions);
     }
 }
@@ -0,0 +1,130 @@
+//! Slow log, the last commands whose execution took longer than
+//! `slowlog_log_slower_than` microseconds, kept in memory on each instance.
+//!
+//! The requests keep their arguments only while the slow log is enabled, a
+//! command is recorded after its handle time is measured.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::utils::{now_timestamp_in_millis, resp_array, resp_bulk, resp_int};
+use crate::{slowlog_log_slower_than_or_default, slowlog_max_len_or_default, Frame};
+
+/// Max arguments kept of a command, the last one kept tells how many more
+/// there were.
+const SLOWLOG_ENTRY_MAX_ARGC: usize = 32;
+/// Max bytes kept of an argument.
+const SLOWLOG_ENTRY_MAX_STRING: usize = 128;
+
+lazy_static! {
+    static ref SLOWLOG: Mutex<VecDeque<SlowlogEntry>> = Mutex::new(VecDeque::new());
+}
+
+static SLOWLOG_NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+struct SlowlogEntry {
+    id: u64,
+    // unix time in seconds the command was logged at
+    timestamp: u64,
+    duration_us: u64,
+    argv: Vec<Bytes>,
+    peer_addr: String,
+    client_name: String,
+}
+
+impl SlowlogEntry {
+    fn to_frame(&self) -> Frame {
+        resp_array(vec![
+            resp_int(self.id as i64),
+            resp_int(self.timestamp as i64),
+            resp_int(self.duration_us as i64),
+            resp_array(
+                self.argv
+                    .iter()
+                    .map(|arg| resp_bulk(arg.to_vec()))
+                    .collect(),
+            ),
+            resp_bulk(self.peer_addr.as_bytes().to_vec()),
+            resp_bulk(self.client_name.as_bytes().to_vec()),
+        ])
+    }
+}
+
+/// Whether the requests must keep their arguments for the slow log.
+pub fn slowlog_enabled() -> bool {
+    slowlog_log_slower_than_or_default() >= 0
+}
+
+/// Whether a command taking `duration` goes to the slow log.
+pub fn is_slow(duration: Duration) -> bool {
+    let threshold = slowlog_log_slower_than_or_default();
+    threshold >= 0 && duration.as_micros() >= threshold as u128
+}
+
+/// Add a command to the slow log, dropping the oldest entries beyond
+/// `slowlog_max_len`. The arguments are trimmed as redis does, so a large
+/// command doesn't hold its whole payload.
+pub fn record(duration: Duration, argv: &[Bytes], peer_addr: &str, client_name: &str) {
+    let mut trimmed: Vec<Bytes> = argv
+        .iter()
+        .take(if argv.len() > SLOWLOG_ENTRY_MAX_ARGC {
+            SLOWLOG_ENTRY_MAX_ARGC - 1
+        } else {
+            argv.len()
+        })
+        .map(|arg| {
+            if arg.len() > SLOWLOG_ENTRY_MAX_STRING {
+                let mut s = arg[..SLOWLOG_ENTRY_MAX_STRING].to_vec();
+                s.extend_from_slice(
+                    format!("... ({} more bytes)", arg.len() - SLOWLOG_ENTRY_MAX_STRING).as_bytes(),
+                );
+                Bytes::from(s)
+            } else {
+                arg.clone()
+            }
+        })
+        .collect();
+    if argv.len() > SLOWLOG_ENTRY_MAX_ARGC {
+        trimmed.push(Bytes::from(format!(
+            "... ({} more arguments)",
+            argv.len() - SLOWLOG_ENTRY_MAX_ARGC + 1
+        )));
+    }
+
+    let entry = SlowlogEntry {
+        id: SLOWLOG_NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        timestamp: now_timestamp_in_millis() / 1000,
+        duration_us: duration.as_micros() as u64,
+        argv: trimmed,
+        peer_addr: peer_addr.to_owned(),
+        client_name: client_name.to_owned(),
+    };
+    let mut slowlog = SLOWLOG.lock().unwrap();
+    slowlog.push_front(entry);
+    slowlog.truncate(slowlog_max_len_or_default());
+}
+
+/// The `count` most recent entries, newest first, all of them if `None`.
+pub fn slowlog_entries(count: Option<usize>) -> Frame {
+    let slowlog = SLOWLOG.lock().unwrap();
+    let count = count.unwrap_or(slowlog.len());
+    resp_array(
+        slowlog
+            .iter()
+            .take(count)
+            .map(SlowlogEntry::to_frame)
+            .collect(),
+    )
+}
+
+pub fn slowlog_len() -> usize {
+    SLOWLOG.lock().unwrap().len()
+}
+
+pub fn slowlog_reset() {
+    SLOWLOG.lock().unwrap().clear();
+}
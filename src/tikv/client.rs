@@ -16,9 +16,10 @@ use crate::{
     txn_region_backoff_delay_attemps, txn_region_backoff_delay_ms, txn_retry_count,
 };
 
-use super::errors::{AsyncResult, RTError, KEY_VERSION_EXHUSTED_ERR};
+use super::errors::{AsyncResult, RTError, KEY_VERSION_EXHUSTED_ERR, REDIS_COMMAND_TIMEOUT_ERR};
 
 use futures::future::BoxFuture;
+use std::future::Future;
 
 use slog::{debug, error};
 
@@ -27,26 +28,71 @@ use crate::metrics::{
     TXN_MECHANISM_COUNTER, TXN_RETRY_COUNTER, TXN_RETRY_ERR, TXN_RETRY_KIND_COUNTER,
 };
 
-use super::{sleep, KEY_ENCODER};
+use super::{sleep, TxnClientPool, KEY_ENCODER};
 use crate::server::duration_to_sec;
-use tokio::time::Instant;
+use tokio::time::{timeout_at, Instant};
 
 const MAX_DELAY_MS: u64 = 500;
 
-pub struct TxnClientWrapper<'a> {
-    client: &'a TransactionClient,
+tokio::task_local! {
+    /// Deadline of the command being executed by the task, transactions
+    /// running past it are cancelled and rolled back.
+    pub static CMD_DEADLINE: Option<Instant>;
+}
+
+/// Run the transaction body within the deadline of the current command.
+async fn with_cmd_deadline<T, Fut>(fut: Fut) -> AsyncResult<T>
+where
+    Fut: Future<Output = AsyncResult<T>>,
+{
+    match CMD_DEADLINE.try_with(|deadline| *deadline).ok().flatten() {
+        Some(deadline) => match timeout_at(deadline, fut).await {
+            Ok(result) => result,
+            Err(_) => Err(REDIS_COMMAND_TIMEOUT_ERR),
+        },
+        None => fut.await,
+    }
+}
+
+pub struct TxnClientWrapper {
+    pool: Arc<TxnClientPool>,
+    /// index of the client in the pool
+    idx: usize,
+    client: Arc<TransactionClient>,
     retries: u32,
 }
 
-impl TxnClientWrapper<'static> {
-    pub fn new(c: &'static TransactionClient) -> Self {
+impl TxnClientWrapper {
+    pub fn new(pool: Arc<TxnClientPool>) -> Self {
+        let idx = pool.pick();
         TxnClientWrapper {
-            client: c,
+            client: pool.client(idx),
+            pool,
+            idx,
             retries: txn_retry_count(),
         }
     }
 
+    /// Connection errors mean the grpc connection of the client is broken, the
+    /// request can be retried on another client of the pool.
+    fn error_broken_client(&self, err: &Error) -> bool {
+        matches!(err, Error::Grpc(_) | Error::Canceled(_))
+    }
+
+    /// Mark the current client broken and switch to a healthy one for the retry.
+    fn switch_client(&mut self) {
+        self.pool.mark_broken(self.idx);
+        self.pool.release(self.idx);
+        self.idx = self.pool.pick();
+        self.client = self.pool.client(self.idx);
+    }
+
     pub fn begin_with_latest(&self) -> Transaction {
+        self.begin_at(Timestamp::from_version(u64::MAX))
+    }
+
+    /// Begin a read only transaction on the snapshot at the timestamp.
+    pub fn begin_at(&self, ts: Timestamp) -> Transaction {
         // add retry options
         let region_backoff = Backoff::no_jitter_backoff(
             txn_region_backoff_delay_ms(),
@@ -80,8 +126,13 @@ impl TxnClientWrapper<'static> {
             txn_options
         };
 
-        self.client
-            .new_transaction(Timestamp::from_version(u64::MAX), txn_options.read_only())
+        self.client.new_transaction(ts, txn_options.read_only())
+    }
+
+    /// Get a timestamp from PD, the snapshot at it sees all the transactions
+    /// committed before.
+    pub async fn current_timestamp(&self) -> TiKVResult<Timestamp> {
+        self.client.current_timestamp().await
     }
 
     pub async fn begin(&self) -> TiKVResult<Transaction> {
@@ -166,12 +217,21 @@ impl TxnClientWrapper<'static> {
             Some(txn) => {
                 // call f
                 let start_at = Instant::now();
-                let result = f(txn).await;
+                let result = with_cmd_deadline(f(txn)).await;
                 let duration = Instant::now() - start_at;
                 TXN_DURATION.observe(duration_to_sec(duration));
                 match result {
                     Ok(res) => Ok(res),
-                    Err(err) => Err(err),
+                    Err(err) => {
+                        // the txn is bound to its client and can not be retried,
+                        // skip the client for the following requests
+                        if let RTError::TikvClient(client_err) = &err {
+                            if self.error_broken_client(client_err) {
+                                self.pool.mark_broken(self.idx);
+                            }
+                        }
+                        Err(err)
+                    }
                 }
             }
             None => {
@@ -197,6 +257,9 @@ impl TxnClientWrapper<'static> {
                         Ok(t) => t,
                         Err(e) => {
                             error!(LOGGER, "error to begin new transaction: {}", e);
+                            if self.error_broken_client(&e) {
+                                self.switch_client();
+                            }
                             if self.retries == 0 {
                                 return Err(RTError::TikvClient(Box::new(e)));
                             }
@@ -208,7 +271,7 @@ impl TxnClientWrapper<'static> {
 
                     // call f
                     let start_at = Instant::now();
-                    let result = f(txn_arc.clone()).await;
+                    let result = with_cmd_deadline(f(txn_arc.clone())).await;
                     let duration = Instant::now() - start_at;
                     TXN_DURATION.observe(duration_to_sec(duration));
 
@@ -223,7 +286,10 @@ impl TxnClientWrapper<'static> {
                             }
                             Err(e) => {
                                 error!(LOGGER, "error to commit transaction: {}", e);
-                                if self.error_retryable(&e) {
+                                if self.error_broken_client(&e) {
+                                    self.switch_client();
+                                }
+                                if self.error_retryable(&e) || self.error_broken_client(&e) {
                                     if self.retries == 0 {
                                         return Err(RTError::TikvClient(Box::new(e)));
                                     }
@@ -237,9 +303,22 @@ impl TxnClientWrapper<'static> {
                             }
                         },
                         Err(e) => {
-                            txn.rollback().await?;
                             error!(LOGGER, "error occured so rollback transaction: {}", e);
                             if let RTError::TikvClient(client_err) = e {
+                                if self.error_broken_client(&client_err) {
+                                    // the rollback can not reach tikv either, locks are
+                                    // resolved after the ttl
+                                    if let Err(e) = txn.rollback().await {
+                                        error!(LOGGER, "error to rollback transaction: {}", e);
+                                    }
+                                    self.switch_client();
+                                    if self.retries == 0 {
+                                        return Err(RTError::TikvClient(client_err));
+                                    }
+                                    TXN_RETRY_ERR.with_label_values(&["broken_client"]).inc();
+                                    continue;
+                                }
+                                txn.rollback().await?;
                                 if self.error_retryable(&client_err) {
                                     if self.retries == 0 {
                                         return Err(RTError::TikvClient(client_err));
@@ -255,6 +334,7 @@ impl TxnClientWrapper<'static> {
                                     return Err(RTError::TikvClient(client_err));
                                 }
                             } else {
+                                txn.rollback().await?;
                                 return Err(e);
                             }
                         }
@@ -275,6 +355,12 @@ impl TxnClientWrapper<'static> {
     }
 }
 
+impl Drop for TxnClientWrapper {
+    fn drop(&mut self) {
+        self.pool.release(self.idx);
+    }
+}
+
 // get_version_for_new must be called outside of a MutexGuard, otherwise it will deadlock.
 pub async fn get_version_for_new(key: &str, txn_rc: Arc<Mutex<Transaction>>) -> AsyncResult<u16> {
     // check if async deletion is enabled, return ASAP if not
@@ -61,6 +61,10 @@ impl KeyDecoder {
         u64::from_be_bytes(value.try_into().unwrap())
     }
 
+    pub fn decode_access_value(value: &[u8]) -> u64 {
+        u64::from_be_bytes(value.try_into().unwrap())
+    }
+
     pub fn decode_key_string_value(value: &[u8]) -> Value {
         value[11..].to_vec()
     }
@@ -169,17 +173,18 @@ impl KeyDecoder {
 
     pub fn decode_key_gc_userkey_version(key: Key) -> (Vec<u8>, u16) {
         let key: Vec<u8> = key.into();
-        let enc_key_start = 5;
+        // the user key follows the encoded key prefix
+        let enc_key_start = 5 + KEY_ENCODER.key_prefix_len();
         let ukey = Self::decode_bytes(&key[enc_key_start..]);
-        let idx = 5 + Self::encoded_bytes_len(&key[enc_key_start..]);
+        let idx = enc_key_start + Self::encoded_bytes_len(&key[enc_key_start..]);
         let version = u16::from_be_bytes(key[idx..idx + 2].try_into().unwrap());
         (ukey, version)
     }
 
     pub fn decode_key_userkey_from_metakey(key: &Key) -> (Vec<u8>, bool) {
         let key: Vec<u8> = key.to_owned().into();
-        let enc_key_start = 4;
-        let idx = 4 + Self::encoded_bytes_len(&key[enc_key_start..]);
+        let enc_key_start = 4 + KEY_ENCODER.key_prefix_len();
+        let idx = enc_key_start + Self::encoded_bytes_len(&key[enc_key_start..]);
         let ukey = Self::decode_bytes(&key[enc_key_start..]);
         (ukey, key[idx] == DATA_TYPE_META && idx + 1 == key.len())
     }
@@ -3,6 +3,7 @@ use super::ENC_ASC_PADDING;
 use super::ENC_GROUP_SIZE;
 use super::ENC_MARKER;
 use super::SIGN_MASK;
+use crate::config_key_prefix_or_default;
 use crate::config_meta_key_number_or_default;
 use crate::tikv::get_instance_id;
 use std::convert::TryFrom;
@@ -17,6 +18,10 @@ pub struct KeyEncoder {
     instance_id: [u8; 2],
     // meta_key_number is the number of sub meta key of a new key
     meta_key_number: u16,
+    // key_prefix is the encoded key prefix prepended to all encoded user keys,
+    // isolating the keyspace of a tenant sharing the instance id. It is encoded
+    // on its own, so no prefix can be a prefix of another once encoded.
+    key_prefix: Vec<u8>,
 }
 pub const RAW_KEY_PREFIX: u8 = b'r';
 pub const TXN_KEY_PREFIX: u8 = b'x';
@@ -26,6 +31,7 @@ pub const DATA_TYPE_USER_END: u8 = b'v';
 pub const DATA_TYPE_TOPO: u8 = b't';
 pub const DATA_TYPE_GC: u8 = b'g';
 pub const DATA_TYPE_GC_VERSION: u8 = b'v';
+pub const DATA_TYPE_ACCESS: u8 = b'a';
 
 pub const DATA_TYPE_META: u8 = b'm';
 pub const DATA_TYPE_SCORE: u8 = b'S';
@@ -41,10 +47,41 @@ impl KeyEncoder {
         KeyEncoder {
             instance_id: u16::try_from(get_instance_id()).unwrap().to_be_bytes(),
             meta_key_number: config_meta_key_number_or_default(),
+            key_prefix: match config_key_prefix_or_default() {
+                prefix if prefix.is_empty() => vec![],
+                prefix => Self::encode_raw_bytes(prefix.as_bytes()),
+            },
         }
     }
 
+    /// Encode the user key with the encoded key prefix prepended.
     pub fn encode_bytes(&self, key: &[u8]) -> Vec<u8> {
+        let mut enc = Vec::with_capacity(self.key_prefix.len() + key.len() + 9);
+        enc.extend_from_slice(&self.key_prefix);
+        enc.extend_from_slice(&Self::encode_raw_bytes(key));
+        enc
+    }
+
+    /// The length of the encoded key prefix, which precedes the encoded user
+    /// keys.
+    pub fn key_prefix_len(&self) -> usize {
+        self.key_prefix.len()
+    }
+
+    /// The upper bound of all encoded user keys with the key prefix, i.e. the
+    /// successor of the encoded prefix. `None` if there is no prefix.
+    fn encode_key_prefix_end(&self) -> Option<Vec<u8>> {
+        let mut end = self.key_prefix.clone();
+        while let Some(last) = end.pop() {
+            if last < u8::MAX {
+                end.push(last + 1);
+                return Some(end);
+            }
+        }
+        None
+    }
+
+    fn encode_raw_bytes(key: &[u8]) -> Vec<u8> {
         let len = key.len();
         let mut index = 0;
         let mut enc = vec![];
@@ -107,10 +144,11 @@ impl KeyEncoder {
     }
 
     pub fn encode_rawkv_string(&self, ukey: &str) -> Key {
-        let mut key = Vec::with_capacity(4 + ukey.len());
+        let mut key = Vec::with_capacity(4 + self.key_prefix.len() + ukey.len());
         key.push(RAW_KEY_PREFIX);
         key.extend_from_slice(self.instance_id.as_slice());
         key.push(DATA_TYPE_META);
+        key.extend_from_slice(&self.key_prefix);
         key.extend_from_slice(ukey.as_bytes());
         key.into()
     }
@@ -176,11 +214,35 @@ impl KeyEncoder {
         key.into()
     }
 
+    /// The meta key of a user key which may not be valid utf8.
+    pub fn encode_txnkv_meta_key_from_bytes(&self, ukey: &[u8]) -> Key {
+        let enc_ukey = self.encode_bytes(ukey);
+        let mut key = Vec::with_capacity(5 + enc_ukey.len());
+
+        self.encode_txnkv_meta_common_prefix(&enc_ukey, &mut key);
+        key.into()
+    }
+
+    pub fn encode_txnkv_keyspace_start(&self) -> Key {
+        let mut key = Vec::with_capacity(4 + self.key_prefix.len());
+        key.push(TXN_KEY_PREFIX);
+        key.extend_from_slice(self.instance_id.as_slice());
+        key.push(DATA_TYPE_USER);
+        key.extend_from_slice(&self.key_prefix);
+        key.into()
+    }
+
     pub fn encode_txnkv_keyspace_end(&self) -> Key {
         let mut key = Vec::with_capacity(4);
         key.push(TXN_KEY_PREFIX);
         key.extend_from_slice(self.instance_id.as_slice());
-        key.push(DATA_TYPE_USER_END);
+        match self.encode_key_prefix_end() {
+            Some(prefix_end) => {
+                key.push(DATA_TYPE_USER);
+                key.extend_from_slice(&prefix_end);
+            }
+            None => key.push(DATA_TYPE_USER_END),
+        }
         key.into()
     }
 
@@ -320,7 +382,7 @@ impl KeyEncoder {
         range.into()
     }
 
-    fn encode_txnkv_list_data_key_start(&self, ukey: &str, version: u16) -> Key {
+    pub fn encode_txnkv_list_data_key_start(&self, ukey: &str, version: u16) -> Key {
         let enc_ukey = self.encode_bytes(ukey.as_bytes());
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
@@ -329,7 +391,7 @@ impl KeyEncoder {
         key.into()
     }
 
-    fn encode_txnkv_list_data_key_end(&self, ukey: &str, version: u16) -> Key {
+    pub fn encode_txnkv_list_data_key_end(&self, ukey: &str, version: u16) -> Key {
         let enc_ukey = self.encode_bytes(ukey.as_bytes());
         let mut key = Vec::with_capacity(8 + enc_ukey.len());
 
@@ -584,6 +646,17 @@ impl KeyEncoder {
             .into()
     }
 
+    /// Last access time of the user key, kept out of the meta value so sampled
+    /// reads do not rewrite the meta key and conflict with concurrent writers.
+    pub fn encode_txnkv_access_key(&self, ukey: &str) -> Key {
+        self.encode_txnkv_gc_key_prefix(ukey, DATA_TYPE_ACCESS, 5)
+            .into()
+    }
+
+    pub fn encode_txnkv_access_value(&self, timestamp: u64) -> Value {
+        timestamp.to_be_bytes().to_vec()
+    }
+
     pub fn encode_txnkv_gc_version_key(&self, ukey: &str, version: u16) -> Key {
         let mut key = self.encode_txnkv_gc_key_prefix(ukey, DATA_TYPE_GC_VERSION, 7);
         key.extend_from_slice(&version.to_be_bytes());
@@ -595,10 +668,18 @@ impl KeyEncoder {
         key.push(TXN_KEY_PREFIX);
         key.extend_from_slice(self.instance_id.as_slice());
         key.push(DATA_TYPE_GC_VERSION);
-        if start {
-            key.push(PLACE_HOLDER);
-        } else {
-            key.push(PLACE_HOLDER + 1);
+        // only the gc versions of the keys with the key prefix
+        match (start, self.encode_key_prefix_end()) {
+            (true, _) if !self.key_prefix.is_empty() => {
+                key.push(PLACE_HOLDER);
+                key.extend_from_slice(&self.key_prefix);
+            }
+            (true, _) => key.push(PLACE_HOLDER),
+            (false, Some(prefix_end)) => {
+                key.push(PLACE_HOLDER);
+                key.extend_from_slice(&prefix_end);
+            }
+            (false, None) => key.push(PLACE_HOLDER + 1),
         }
         key.into()
     }
@@ -70,12 +70,22 @@ pub const REDIS_BACKEND_NOT_CONNECTED_ERR: RTError = RTError::String("ERR backen
 pub const REDIS_COMPARE_AND_SWAP_EXHAUSTED_ERR: RTError =
     RTError::String("ERR compare-and-swap exhausted");
 pub const REDIS_NOT_SUPPORTED_ERR: RTError = RTError::String("ERR not supported");
+pub const REDIS_SYNTAX_ERR: RTError = RTError::String("ERR syntax error");
+pub const REDIS_WRONG_PROTOCOL_TYPE_ERR: RTError = RTError::String(
+    "ERR Wrong protocol type name. Please use one of the following: string|integer|double|bignum|null|array|set|map|verbatim|true|false",
+);
 pub const REDIS_NOT_SUPPORTED_DEBUG_SUB_COMMAND_ERR: RTError =
     RTError::String("ERR not supported debug sub command");
 pub const REDIS_AUTH_WHEN_DISABLED_ERR: RTError =
     RTError::String("ERR Client sent AUTH, but no password is set");
 pub const REDIS_AUTH_INVALID_PASSWORD_ERR: RTError = RTError::String("ERR invalid password");
 pub const REDIS_AUTH_REQUIRED_ERR: RTError = RTError::String("NOAUTH Authentication required.");
+pub const REDIS_AUTH_WRONGPASS_ERR: RTError =
+    RTError::String("WRONGPASS invalid username-password pair or user is disabled.");
+pub const REDIS_HELLO_AUTH_REQUIRED_ERR: RTError = RTError::String(
+    "NOAUTH HELLO must be called with the client already authenticated, otherwise the HELLO <proto> AUTH <user> <pass> option can be used to authenticate the client and select the RESP protocol version at the same time",
+);
+pub const REDIS_NOPERM_KEY_ERR: RTError = RTError::String("NOPERM No permissions to access a key");
 pub const REDIS_NO_MATCHING_SCRIPT_ERR: RTError =
     RTError::String("NOSCRIPT No matching script. Please use EVAL.");
 pub const REDIS_LUA_CONTEXT_IS_NOT_INITIALIZED_ERR: RTError =
@@ -84,13 +94,76 @@ pub const REDIS_LUA_PANIC: RTError = RTError::String("ERR lua panic");
 pub const REDIS_UNKNOWN_SUBCOMMAND: RTError =
     RTError::String("Unknown subcommand or wrong number of arguments");
 pub const DECREMENT_OVERFLOW: RTError = RTError::String("Decrement would overflow");
+pub const REDIS_INCR_OVERFLOW_ERR: RTError =
+    RTError::String("ERR increment or decrement would overflow");
 pub const REDIS_LIST_TOO_LARGE_ERR: RTError = RTError::String("ERR list is too large to execute");
 pub const KEY_VERSION_EXHUSTED_ERR: RTError = RTError::String("ERR key version exhausted");
 pub const REDIS_MULTI_NESTED_ERR: RTError = RTError::String("ERR MULTI calls can not be nested");
 pub const REDIS_DISCARD_WITHOUT_MULTI_ERR: RTError = RTError::String("ERR DISCARD without MULTI");
 pub const REDIS_EXEC_WITHOUT_MULTI_ERR: RTError = RTError::String("ERR EXEC without MULTI");
+pub const REDIS_WATCH_INSIDE_MULTI_ERR: RTError =
+    RTError::String("ERR WATCH inside MULTI is not allowed");
 pub const REDIS_EXEC_ERR: RTError =
     RTError::String("EXECABORT Transaction discarded because of previous errors.");
 
 pub const REDIS_INVALID_CLIENT_ID_ERR: RTError = RTError::String("ERR Invalid client ID");
 pub const REDIS_NO_SUCH_CLIENT_ERR: RTError = RTError::String("ERR No such client");
+pub const REDIS_CLIENT_NAME_INVALID_ERR: RTError =
+    RTError::String("ERR Client names cannot contain spaces, newlines or special characters.");
+pub const REDIS_BULK_LOAD_ALREADY_STARTED_ERR: RTError =
+    RTError::String("ERR bulk load already started");
+pub const REDIS_DEBUG_RAW_DISABLED_ERR: RTError =
+    RTError::String("ERR DEBUG RAW is disabled, set debug_raw_enabled to enable it");
+pub const REDIS_PROFILER_NOT_STARTED_ERR: RTError = RTError::String("ERR profiler is not started");
+pub const REDIS_NOPROTO_ERR: RTError = RTError::String("NOPROTO unsupported protocol version");
+pub const REDIS_HELLO_OPTION_NOT_SUPPORTED_ERR: RTError =
+    RTError::String("ERR Syntax error in HELLO option");
+pub const REDIS_COMMAND_TIMEOUT_ERR: RTError = RTError::String("ERR command timed out");
+pub const REDIS_TIMEOUT_IS_NOT_FLOAT_ERR: RTError =
+    RTError::String("ERR timeout is not a float or out of range");
+pub const REDIS_TIMEOUT_IS_NEGATIVE_ERR: RTError = RTError::String("ERR timeout is negative");
+pub const REDIS_BULK_LOAD_NOT_STARTED_ERR: RTError = RTError::String("ERR bulk load not started");
+pub const REDIS_MAX_SUBSCRIPTIONS_ERR: RTError =
+    RTError::String("ERR max number of subscriptions per client reached");
+pub const REDIS_MIN_MAX_NOT_FLOAT_ERR: RTError = RTError::String("ERR min or max is not a float");
+pub const REDIS_SAME_OBJECT_ERR: RTError =
+    RTError::String("ERR source and destination objects are the same");
+pub const REDIS_DB_INDEX_OUT_OF_RANGE_ERR: RTError =
+    RTError::String("ERR DB index is out of range");
+pub const REDIS_LPOS_RANK_ZERO_ERR: RTError = RTError::String(
+    "ERR RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list",
+);
+pub const REDIS_LPOS_COUNT_NEGATIVE_ERR: RTError = RTError::String("ERR COUNT can't be negative");
+pub const REDIS_LPOS_MAXLEN_NEGATIVE_ERR: RTError = RTError::String("ERR MAXLEN can't be negative");
+pub const REDIS_NUMKEYS_NOT_POSITIVE_ERR: RTError =
+    RTError::String("ERR numkeys should be greater than 0");
+pub const REDIS_NUMKEYS_GREATER_THAN_ARGS_ERR: RTError =
+    RTError::String("ERR Number of keys can't be greater than number of args");
+pub const REDIS_LIMIT_NEGATIVE_ERR: RTError = RTError::String("ERR LIMIT can't be negative");
+pub const REDIS_ZRANGE_LIMIT_WITHOUT_BY_ERR: RTError = RTError::String(
+    "ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX",
+);
+pub const REDIS_COUNT_NOT_POSITIVE_ERR: RTError =
+    RTError::String("ERR count should be greater than 0");
+pub const REDIS_BIT_ARGUMENT_ERR: RTError = RTError::String("ERR The bit argument must be 1 or 0.");
+pub const REDIS_BITOP_NOT_ERR: RTError =
+    RTError::String("ERR BITOP NOT must be called with a single source key.");
+pub const REDIS_BIT_OFFSET_ERR: RTError =
+    RTError::String("ERR bit offset is not an integer or out of range");
+pub const REDIS_BIT_VALUE_ERR: RTError =
+    RTError::String("ERR bit is not an integer or out of range");
+pub const REDIS_BITFIELD_TYPE_ERR: RTError = RTError::String(
+    "ERR Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is.",
+);
+pub const REDIS_BITFIELD_OVERFLOW_ERR: RTError =
+    RTError::String("ERR Invalid OVERFLOW type specified");
+pub const REDIS_OFFSET_OUT_OF_RANGE_ERR: RTError = RTError::String("ERR offset is out of range");
+pub const REDIS_VALUE_OUT_OF_RANGE_ERR: RTError = RTError::String("ERR value is out of range");
+pub const REDIS_STRING_EXCEEDS_MAX_SIZE_ERR: RTError =
+    RTError::String("ERR string exceeds maximum allowed size (proto-max-bulk-len)");
+pub const REDIS_INCR_FLOAT_NAN_OR_INFINITY_ERR: RTError =
+    RTError::String("ERR increment would produce NaN or Infinity");
+pub const REDIS_WEIGHT_NOT_FLOAT_ERR: RTError = RTError::String("ERR weight value is not a float");
+pub const REDIS_INVALID_CURSOR_ERR: RTError = RTError::String("ERR invalid cursor");
+pub const REDIS_NO_SIZE_META_ERR: RTError =
+    RTError::String("ERR only hash, set and zset keys have a size meta");
@@ -3,13 +3,14 @@ use super::{
     encoding::{DataType, KeyDecoder},
     errors::AsyncResult,
     gen_next_meta_index,
+    sample::Sampling,
 };
 use super::{get_txn_client, KEY_ENCODER};
 use crate::{
     async_del_hash_threshold_or_default, async_expire_hash_threshold_or_default,
     config::LOGGER,
     config_meta_key_number_or_default,
-    utils::{count_unique_keys, key_is_expired, resp_ok},
+    utils::{count_unique_keys, format_float, glob_match, key_is_expired, resp_ok, resp_scan},
     Frame,
 };
 
@@ -22,7 +23,8 @@ use tokio::sync::Mutex;
 use super::errors::*;
 use crate::utils::{resp_array, resp_bulk, resp_err, resp_int, resp_nil};
 
-use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::metrics::{CMD_PATH_COUNTER, REMOVED_EXPIRED_KEY_COUNTER};
+use crate::notify::notify_expired;
 
 #[derive(Clone)]
 pub struct HashCommandCtx {
@@ -142,8 +144,10 @@ impl<'a> HashCommandCtx {
                                     );
                                     fields_data_key.push(datakey);
                                 }
-                                // batch get
-                                let real_fields_count = count_unique_keys(&fields_data_key);
+                                // a field repeated in the request is read and counted once
+                                fields_data_key.sort();
+                                fields_data_key.dedup();
+                                let real_fields_count = fields_data_key.len();
                                 added_count = real_fields_count as i64
                                     - txn.batch_get(fields_data_key).await?.count() as i64;
                             }
@@ -180,6 +184,7 @@ impl<'a> HashCommandCtx {
                                     .encode_txnkv_hash_meta_value(ttl, version, meta_size);
                                 txn.put(meta_key, new_metaval).await?;
                             }
+                            Ok(added_count)
                         }
                         None => {
                             drop(txn);
@@ -224,9 +229,9 @@ impl<'a> HashCommandCtx {
                                 KEY_ENCODER.encode_txnkv_sub_meta_key(&key, version, idx);
                             txn.put(sub_meta_key, real_fields_count.to_be_bytes().to_vec())
                                 .await?;
+                            Ok(real_fields_count as i64)
                         }
                     }
-                    Ok(fvs_len)
                 }
                 .boxed()
             })
@@ -236,7 +241,7 @@ impl<'a> HashCommandCtx {
                 if is_hmset {
                     Ok(resp_ok())
                 } else {
-                    Ok(resp_int(num as i64))
+                    Ok(resp_int(num))
                 }
             }
             Err(e) => Ok(resp_err(e)),
@@ -544,15 +549,18 @@ impl<'a> HashCommandCtx {
 
                         let resp: Vec<Frame>;
                         if with_field && with_value {
-                            resp = iter
-                                .flat_map(|kv| {
+                            // a map on RESP3 connections, the flat array of
+                            // fields and values on RESP2 ones
+                            return Ok(Frame::Map(
+                                iter.map(|kv| {
                                     let field: Vec<u8> =
                                         KeyDecoder::decode_key_hash_userkey_from_datakey(
                                             &key, kv.0,
                                         );
-                                    [resp_bulk(field), resp_bulk(kv.1)]
+                                    (resp_bulk(field), resp_bulk(kv.1))
                                 })
-                                .collect();
+                                .collect(),
+                            ));
                         } else if with_field {
                             resp = iter
                                 .flat_map(|kv| {
@@ -568,6 +576,8 @@ impl<'a> HashCommandCtx {
                         }
 
                         Ok(resp_array(resp))
+                    } else if with_field && with_value {
+                        Ok(Frame::Map(vec![]))
                     } else {
                         Ok(resp_array(vec![]))
                     }
@@ -577,13 +587,122 @@ impl<'a> HashCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_hdel(mut self, key: &str, fields: &[String]) -> AsyncResult<Frame> {
+    /// Scan at most `count` fields from the field `start`, the fields not
+    /// matching `pattern` are filtered out after they are read. The scan
+    /// resumes from the field after the last one read.
+    pub async fn do_async_txnkv_hscan(
+        mut self,
+        key: &str,
+        start: &[u8],
+        count: u32,
+        pattern: Option<&str>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+        let start = start.to_vec();
+        let pattern = pattern.map(|p| p.as_bytes().to_vec());
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(resp_scan(None, vec![])),
+                    };
+                    drop(txn);
+
+                    // check key type and ttl
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Hash) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
+
+                    let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        self.clone()
+                            .do_async_txnkv_hash_expire_if_needed(&key)
+                            .await?;
+                        return Ok(resp_scan(None, vec![]));
+                    }
+
+                    let mut range_start: Vec<u8> = KEY_ENCODER
+                        .encode_txnkv_hash_data_key_start(&key, version)
+                        .into();
+                    range_start.extend_from_slice(&start);
+                    let range: Range<Key> = range_start.into()
+                        ..KEY_ENCODER.encode_txnkv_hash_data_key_end(&key, version);
+                    // one more field is read, the next scan starts from it
+                    let mut kvs: Vec<KvPair> = txn_rc
+                        .lock()
+                        .await
+                        .scan(range, count.saturating_add(1))
+                        .await?
+                        .collect();
+                    let next = if kvs.len() > count as usize {
+                        kvs.pop()
+                            .map(|kv| KeyDecoder::decode_key_hash_userkey_from_datakey(&key, kv.0))
+                    } else {
+                        None
+                    };
+
+                    let resp = kvs
+                        .into_iter()
+                        .flat_map(|kv| {
+                            let field =
+                                KeyDecoder::decode_key_hash_userkey_from_datakey(&key, kv.0);
+                            match &pattern {
+                                Some(pattern) if !glob_match(pattern, &field) => vec![],
+                                _ => vec![resp_bulk(field), resp_bulk(kv.1)],
+                            }
+                        })
+                        .collect();
+                    Ok(resp_scan(next, resp))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    pub async fn do_async_txnkv_hdel(self, key: &str, fields: &[String]) -> AsyncResult<Frame> {
+        match self.txnkv_hdel(key, fields).await {
+            Ok((deleted, _)) => Ok(resp_int(deleted)),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_hgetdel(self, key: &str, fields: &[String]) -> AsyncResult<Frame> {
+        match self.txnkv_hdel(key, fields).await {
+            Ok((_, values)) => Ok(resp_array(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        Some(data) => resp_bulk(data),
+                        None => resp_nil(),
+                    })
+                    .collect(),
+            )),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// Delete the fields of the hash, returns the number of fields deleted and
+    /// the value of each field before deletion.
+    async fn txnkv_hdel(
+        mut self,
+        key: &str,
+        fields: &[String],
+    ) -> AsyncResult<(i64, Vec<Option<Value>>)> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
         let fields = fields.to_vec();
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
 
-        let resp = client
+        client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
@@ -604,7 +723,7 @@ impl<'a> HashCommandCtx {
                                 self.clone()
                                     .do_async_txnkv_hash_expire_if_needed(&key)
                                     .await?;
-                                return Ok(0);
+                                return Ok((0, vec![None; fields.len()]));
                             }
 
                             let mut deleted: i64 = 0;
@@ -614,10 +733,16 @@ impl<'a> HashCommandCtx {
                                     KEY_ENCODER.encode_txnkv_hash_data_key(&key, field, version)
                                 })
                                 .collect();
-                            for pair in txn.batch_get(data_keys).await? {
-                                txn.delete(pair.0).await?;
+                            let mut old_values = HashMap::with_capacity(data_keys.len());
+                            for pair in txn.batch_get(data_keys.clone()).await? {
+                                txn.delete(pair.0.clone()).await?;
+                                old_values.insert(pair.0, pair.1);
                                 deleted += 1;
                             }
+                            let values = data_keys
+                                .iter()
+                                .map(|data_key| old_values.get(data_key).cloned())
+                                .collect();
 
                             let idx = gen_next_meta_index();
 
@@ -653,19 +778,14 @@ impl<'a> HashCommandCtx {
                                 txn.put(sub_meta_key, new_size.to_be_bytes().to_vec())
                                     .await?;
                             }
-                            Ok(deleted)
+                            Ok((deleted, values))
                         }
-                        None => Ok(0),
+                        None => Ok((0, vec![None; fields.len()])),
                     }
                 }
                 .boxed()
             })
-            .await;
-
-        match resp {
-            Ok(n) => Ok(resp_int(n)),
-            Err(e) => Ok(resp_err(e)),
-        }
+            .await
     }
 
     pub async fn do_async_txnkv_hincrby(
@@ -786,6 +906,198 @@ impl<'a> HashCommandCtx {
         }
     }
 
+    pub async fn do_async_txnkv_hincrbyfloat(
+        self,
+        key: &str,
+        field: &str,
+        step: f64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let field = field.to_owned();
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
+        let idx = gen_next_meta_index();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let prev_float;
+                    let data_key;
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(meta_key.clone()).await? {
+                        Some(meta_value) => {
+                            // check key type and ttl
+                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Hash) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+
+                            let mut expired = false;
+
+                            let (ttl, mut version, _meta_size) =
+                                KeyDecoder::decode_key_meta(&meta_value);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.do_async_txnkv_hash_expire_if_needed(&key).await?;
+                                expired = true;
+                                version = get_version_for_new(&key, txn_rc.clone()).await?;
+                                // regain txn mutexguard
+                                txn = txn_rc.lock().await;
+                            }
+
+                            data_key =
+                                KEY_ENCODER.encode_txnkv_hash_data_key(&key, &field, version);
+
+                            match txn.get(data_key.clone()).await? {
+                                Some(data_value) => {
+                                    // try to convert to float
+                                    match String::from_utf8_lossy(&data_value).parse::<f64>() {
+                                        Ok(fval) if fval.is_finite() => {
+                                            prev_float = fval;
+                                        }
+                                        _ => {
+                                            return Err(REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR);
+                                        }
+                                    }
+                                }
+                                None => {
+                                    // filed not exist
+                                    prev_float = 0f64;
+                                    // add size to a random sub meta key
+                                    let sub_meta_key =
+                                        KEY_ENCODER.encode_txnkv_sub_meta_key(&key, version, idx);
+
+                                    let sub_size =
+                                        txn.get(sub_meta_key.clone()).await?.map_or_else(
+                                            || 1,
+                                            |value| i64::from_be_bytes(value.try_into().unwrap()),
+                                        );
+
+                                    // add or update sub meta key
+                                    txn.put(sub_meta_key, sub_size.to_be_bytes().to_vec())
+                                        .await?;
+
+                                    // add meta key if needed
+                                    if expired {
+                                        // add meta key
+                                        let meta_size = config_meta_key_number_or_default();
+                                        let meta_value = KEY_ENCODER
+                                            .encode_txnkv_hash_meta_value(ttl, version, meta_size);
+                                        txn.put(meta_key, meta_value).await?;
+                                    }
+                                }
+                            }
+                        }
+                        None => {
+                            drop(txn);
+                            let version = get_version_for_new(&key, txn_rc.clone()).await?;
+                            txn = txn_rc.lock().await;
+
+                            prev_float = 0f64;
+                            // create new meta key first
+                            let meta_size = config_meta_key_number_or_default();
+                            let meta_value =
+                                KEY_ENCODER.encode_txnkv_hash_meta_value(0, version, meta_size);
+                            txn.put(meta_key, meta_value).await?;
+
+                            // add a sub meta key with a random index
+                            let sub_meta_key =
+                                KEY_ENCODER.encode_txnkv_sub_meta_key(&key, version, idx);
+                            txn.put(sub_meta_key, 1_i64.to_be_bytes().to_vec()).await?;
+                            data_key =
+                                KEY_ENCODER.encode_txnkv_hash_data_key(&key, &field, version);
+                        }
+                    }
+                    let new_float = prev_float + step;
+                    if !new_float.is_finite() {
+                        return Err(REDIS_INCR_FLOAT_NAN_OR_INFINITY_ERR);
+                    }
+                    let new_val = format_float(new_float);
+                    // update data key
+                    txn.put(data_key, new_val.as_bytes().to_vec()).await?;
+
+                    Ok(new_val)
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(v) => Ok(resp_bulk(v.into_bytes())),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    pub async fn do_async_txnkv_hrandfield(
+        mut self,
+        key: &str,
+        sampling: Sampling,
+        array_resp: bool,
+        with_values: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+        let empty_resp = if array_resp {
+            resp_array(vec![])
+        } else {
+            resp_nil()
+        };
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(empty_resp),
+                    };
+                    drop(txn);
+
+                    // check key type and ttl
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Hash) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
+
+                    let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        self.clone()
+                            .do_async_txnkv_hash_expire_if_needed(&key)
+                            .await?;
+                        return Ok(empty_resp);
+                    }
+
+                    let size = self.clone().txnkv_sum_key_size(&key, version).await?;
+                    let bound_range = KEY_ENCODER.encode_txnkv_hash_data_key_range(&key, version);
+                    let mut resp: Vec<Frame> = sampling
+                        .sample(&mut *txn_rc.lock().await, bound_range, size as usize)
+                        .await?
+                        .into_iter()
+                        .flat_map(|kv| {
+                            let field =
+                                KeyDecoder::decode_key_hash_userkey_from_datakey(&key, kv.0);
+                            if with_values {
+                                vec![resp_bulk(field), resp_bulk(kv.1)]
+                            } else {
+                                vec![resp_bulk(field)]
+                            }
+                        })
+                        .collect();
+
+                    if !array_resp {
+                        // called with no count argument, return bulk reply
+                        return Ok(resp.pop().unwrap_or(empty_resp));
+                    }
+                    Ok(resp_array(resp))
+                }
+                .boxed()
+            })
+            .await
+    }
+
     pub async fn do_async_txnkv_hash_del(mut self, key: &str) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
@@ -807,6 +1119,7 @@ impl<'a> HashCommandCtx {
                             txn = txn_arc.lock().await;
 
                             if meta_size > async_del_hash_threshold_or_default() as i64 {
+                                CMD_PATH_COUNTER.with_label_values(&["del", "async"]).inc();
                                 // do async del
                                 txn.delete(meta_key).await?;
 
@@ -821,6 +1134,7 @@ impl<'a> HashCommandCtx {
                                 )
                                 .await?;
                             } else {
+                                CMD_PATH_COUNTER.with_label_values(&["del", "sync"]).inc();
                                 let bound_range =
                                     KEY_ENCODER.encode_txnkv_hash_data_key_range(&key, version);
                                 // scan return iterator
@@ -874,6 +1188,9 @@ impl<'a> HashCommandCtx {
                             txn = txn_arc.lock().await;
 
                             if meta_size > async_expire_hash_threshold_or_default() as i64 {
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["expire", "async"])
+                                    .inc();
                                 // do async del
                                 txn.delete(meta_key).await?;
 
@@ -888,6 +1205,9 @@ impl<'a> HashCommandCtx {
                                 )
                                 .await?;
                             } else {
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["expire", "sync"])
+                                    .inc();
                                 let bound_range =
                                     KEY_ENCODER.encode_txnkv_hash_data_key_range(&key, version);
                                 // scan return iterator
@@ -910,6 +1230,7 @@ impl<'a> HashCommandCtx {
                             REMOVED_EXPIRED_KEY_COUNTER
                                 .with_label_values(&["hash"])
                                 .inc();
+                            notify_expired(&key);
                             Ok(1)
                         }
                         None => Ok(0),
@@ -0,0 +1,103 @@
+use futures::future;
+use slog::{info, warn};
+use tikv_client::{TransactionClient, TransactionOptions};
+use tokio::time::{self, Duration, MissedTickBehavior};
+
+use crate::config::LOGGER;
+use crate::metrics::TXN_CLIENT_HEALTH_GAUGE;
+use crate::{backend_health_check_interval_or_default, backend_timeout_or_default, is_use_txn_api};
+
+use super::{new_txn_client, KEY_ENCODER, PD_ADDRS, TIKV_TXN_CLIENT_POOL};
+
+/// The key read by the health checks, it doesn't need to exist.
+const HEALTH_CHECK_KEY: &str = "__tidis_health_check__";
+
+/// Check the pooled txn clients periodically and reconnect the unhealthy ones.
+///
+/// A client is checked by reading a key on a fresh snapshot, which takes a
+/// timestamp from PD and a get from TiKV. A client failing the check is
+/// skipped by the requests until it passes again, either by itself or after
+/// it is replaced by a new client connected to the same PD addresses.
+pub struct HealthChecker {}
+
+impl HealthChecker {
+    pub fn new() -> Self {
+        HealthChecker {}
+    }
+
+    pub async fn run(&self) {
+        let interval_ms = backend_health_check_interval_or_default();
+        if interval_ms == 0 || !is_use_txn_api() {
+            // the health checks are disabled
+            return future::pending().await;
+        }
+        let mut interval = time::interval(Duration::from_millis(interval_ms));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.check_pool().await;
+        }
+    }
+
+    async fn check_pool(&self) {
+        let pool = match TIKV_TXN_CLIENT_POOL.read().unwrap().clone() {
+            Some(pool) => pool,
+            None => return,
+        };
+        let addrs = PD_ADDRS.read().unwrap().clone().unwrap_or_default();
+
+        let mut healthy = 0;
+        for idx in 0..pool.clients.len() {
+            if check_client(&pool.client(idx)).await {
+                pool.set_healthy(idx, true);
+                healthy += 1;
+                continue;
+            }
+            pool.set_healthy(idx, false);
+            warn!(LOGGER, "[HEALTH CHECK] txn client {} is unhealthy", idx);
+
+            match new_txn_client(&addrs).await {
+                Ok(client) if check_client(&client).await => {
+                    pool.replace_client(idx, client);
+                    pool.set_healthy(idx, true);
+                    healthy += 1;
+                    info!(LOGGER, "[HEALTH CHECK] txn client {} is reconnected", idx);
+                }
+                Ok(_) => warn!(
+                    LOGGER,
+                    "[HEALTH CHECK] reconnected txn client {} is still unhealthy", idx
+                ),
+                Err(e) => warn!(
+                    LOGGER,
+                    "[HEALTH CHECK] reconnect txn client {} failed: {:?}", idx, e
+                ),
+            }
+        }
+
+        TXN_CLIENT_HEALTH_GAUGE
+            .with_label_values(&["healthy"])
+            .set(healthy as i64);
+        TXN_CLIENT_HEALTH_GAUGE
+            .with_label_values(&["unhealthy"])
+            .set((pool.clients.len() - healthy) as i64);
+    }
+}
+
+impl Default for HealthChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn check_client(client: &TransactionClient) -> bool {
+    let check = async {
+        let ts = client.current_timestamp().await?;
+        let mut snapshot = client.snapshot(ts, TransactionOptions::new_optimistic().read_only());
+        snapshot
+            .get(KEY_ENCODER.encode_txnkv_string(HEALTH_CHECK_KEY))
+            .await?;
+        Ok::<(), tikv_client::Error>(())
+    };
+    let timeout = Duration::from_millis(backend_timeout_or_default());
+    matches!(time::timeout(timeout, check).await, Ok(Ok(())))
+}
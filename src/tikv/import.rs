@@ -0,0 +1,203 @@
+use std::fmt;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tikv_client::Transaction;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use slog::{debug, error};
+
+use crate::cmd::exec_cmd_in_txn;
+use crate::cmd::spec::{lookup_command_spec, CAT_SCRIPTING};
+use crate::config::LOGGER;
+use crate::metrics::{BULK_LOAD_BATCH_COUNTER, BULK_LOAD_WRITE_COUNTER};
+use crate::utils::{resp_bulk, resp_invalid_expire_time, resp_ok, timestamp_from_relative_ttl};
+use crate::{Command, Frame};
+
+use super::errors::AsyncResult;
+use super::{get_txn_client, KEY_ENCODER};
+
+/// Bulk importer batches the writes of one connection into large transactions.
+///
+/// It is used for initial data loading only, the tradeoffs are:
+///
+/// * string writes (SET/MSET) are blind puts, the old value of the key is never read,
+///   so overwriting a key of another type leaves the old collection data orphaned
+///   instead of returning WRONGTYPE
+/// * every write is replied before its batch is committed, a failed commit discards
+///   all writes of the batch and the error is returned to the command that triggered
+///   the commit (or to BULKLOAD END)
+/// * writes of a pending batch are invisible to other connections until committed
+pub struct BulkImporter {
+    batch_size: usize,
+    txn: Option<Arc<Mutex<Transaction>>>,
+    pending: usize,
+    total: u64,
+    batches: u64,
+    started_at: Instant,
+}
+
+impl BulkImporter {
+    pub fn new(batch_size: usize) -> BulkImporter {
+        BulkImporter {
+            batch_size,
+            txn: None,
+            pending: 0,
+            total: 0,
+            batches: 0,
+            started_at: Instant::now(),
+        }
+    }
+
+    async fn current_txn(&mut self) -> AsyncResult<Arc<Mutex<Transaction>>> {
+        if let Some(txn) = &self.txn {
+            return Ok(txn.clone());
+        }
+        let txn = get_txn_client()?.begin().await?;
+        let txn_rc = Arc::new(Mutex::new(txn));
+        self.txn = Some(txn_rc.clone());
+        Ok(txn_rc)
+    }
+
+    async fn put_string(&mut self, key: &str, value: &Bytes, ttl: u64) -> AsyncResult<()> {
+        let txn_rc = self.current_txn().await?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let eval = KEY_ENCODER.encode_txnkv_string_value(&mut value.to_vec(), ttl);
+        txn_rc.lock().await.put(ekey, eval).await?;
+        Ok(())
+    }
+
+    /// Whether the command is executed in the batches, i.e. it reads or
+    /// writes the keyspace. Scripts and the connection and server commands
+    /// are applied as usual in bulk load mode.
+    pub fn accepts(name: &str) -> bool {
+        lookup_command_spec(name).map_or(false, |spec| {
+            (spec.is_write() || spec.is_readonly()) && !spec.in_category(CAT_SCRIPTING)
+        })
+    }
+
+    /// Execute the command in the current batch, commit the batch if it is full.
+    pub async fn exec(&mut self, cmd: Command) -> AsyncResult<Frame> {
+        let (resp, writes) = match cmd {
+            Command::Set(cmd) if cmd.is_plain() && cmd.valid() => {
+                let ttl = match cmd.expire() {
+                    Some(ms) => match timestamp_from_relative_ttl(ms) {
+                        Some(ts) => ts,
+                        None => return Ok(resp_invalid_expire_time("set")),
+                    },
+                    None => 0,
+                };
+                if let Err(e) = self.put_string(cmd.key(), cmd.value(), ttl).await {
+                    self.abort().await;
+                    return Err(e);
+                }
+                (resp_ok(), 1)
+            }
+            Command::Mset(cmd) if cmd.valid() => {
+                for (key, value) in cmd.keys().iter().zip(cmd.vals()) {
+                    if let Err(e) = self.put_string(key, value, 0).await {
+                        self.abort().await;
+                        return Err(e);
+                    }
+                }
+                (resp_ok(), cmd.keys().len())
+            }
+            cmd => {
+                let is_write =
+                    lookup_command_spec(cmd.get_name()).map_or(false, |spec| spec.is_write());
+                let txn_rc = self.current_txn().await?;
+                match exec_cmd_in_txn(cmd, Some(txn_rc)).await {
+                    // error replies such as WRONGTYPE write nothing, nor do reads
+                    Ok(resp) => match resp {
+                        Frame::ErrorOwned(_) | Frame::ErrorString(_) => (resp, 0),
+                        _ if !is_write => (resp, 0),
+                        _ => (resp, 1),
+                    },
+                    Err(e) => {
+                        // the transaction may be broken, drop the whole batch
+                        self.abort().await;
+                        return Err(e);
+                    }
+                }
+            }
+        };
+
+        self.pending += writes;
+        self.total += writes as u64;
+        BULK_LOAD_WRITE_COUNTER.inc_by(writes as u64);
+        if self.pending >= self.batch_size {
+            self.flush().await?;
+        }
+        Ok(resp)
+    }
+
+    /// Commit the pending batch, if any.
+    pub async fn flush(&mut self) -> AsyncResult<()> {
+        if let Some(txn_rc) = self.txn.take() {
+            let pending = self.pending;
+            self.pending = 0;
+            let mut txn = txn_rc.lock().await;
+            if let Err(e) = txn.commit().await {
+                error!(
+                    LOGGER,
+                    "bulk load commit failed, {} writes discarded: {}", pending, e
+                );
+                self.total -= pending as u64;
+                return Err(e.into());
+            }
+            self.batches += 1;
+            BULK_LOAD_BATCH_COUNTER.inc();
+            debug!(LOGGER, "bulk load batch committed, {} writes", pending);
+        }
+        Ok(())
+    }
+
+    /// Roll back the pending batch, if any.
+    pub async fn abort(&mut self) {
+        if let Some(txn_rc) = self.txn.take() {
+            self.total -= self.pending as u64;
+            self.pending = 0;
+            if let Err(e) = txn_rc.lock().await.rollback().await {
+                error!(LOGGER, "bulk load rollback failed: {}", e);
+            }
+        }
+    }
+
+    /// The number of batches committed so far.
+    pub fn batches(&self) -> u64 {
+        self.batches
+    }
+
+    /// Report the committed writes and throughput since the import began.
+    pub fn report(&self) -> Frame {
+        let elapsed = self.started_at.elapsed();
+        let elapsed_ms = elapsed.as_millis();
+        let ops_per_sec = if elapsed_ms == 0 {
+            self.total as f64
+        } else {
+            self.total as f64 * 1000.0 / elapsed_ms as f64
+        };
+        let report = format!(
+            "imported:{}\r\npending:{}\r\nbatches:{}\r\nbatch_size:{}\r\nelapsed_ms:{}\r\nops_per_sec:{:.2}\r\n",
+            self.total - self.pending as u64,
+            self.pending,
+            self.batches,
+            self.batch_size,
+            elapsed_ms,
+            ops_per_sec
+        );
+        resp_bulk(report.into_bytes())
+    }
+}
+
+impl fmt::Debug for BulkImporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkImporter")
+            .field("batch_size", &self.batch_size)
+            .field("pending", &self.pending)
+            .field("total", &self.total)
+            .field("batches", &self.batches)
+            .finish()
+    }
+}
@@ -9,7 +9,8 @@ use super::{
 use crate::async_del_list_threshold_or_default;
 use crate::cmd_linsert_length_limit_or_default;
 use crate::cmd_lrem_length_limit_or_default;
-use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::metrics::{CMD_PATH_COUNTER, REMOVED_EXPIRED_KEY_COUNTER};
+use crate::notify::notify_expired;
 use crate::utils::{resp_array, resp_bulk, resp_err, resp_int, resp_nil, resp_ok};
 use crate::{utils::key_is_expired, Frame};
 use bytes::Bytes;
@@ -17,11 +18,14 @@ use core::ops::RangeFrom;
 use futures::future::FutureExt;
 use std::convert::TryInto;
 use std::sync::Arc;
-use tikv_client::{BoundRange, Key, Transaction};
+use tikv_client::{BoundRange, Key, KvPair, Transaction};
 use tokio::sync::Mutex;
 
 const INIT_INDEX: u64 = 1 << 32;
 
+/// Elements read at a time by LPOS
+const LPOS_SCAN_BATCH: u64 = 1000;
+
 #[derive(Clone)]
 pub struct ListCommandCtx {
     txn: Option<Arc<Mutex<Transaction>>>,
@@ -259,6 +263,48 @@ impl<'a> ListCommandCtx {
         }
     }
 
+    /// Pop at most `count` elements from the first non-empty list of `keys`,
+    /// the lists are checked and popped in the same transaction. Replies the
+    /// key and the popped elements, nil if all the lists are empty.
+    pub async fn do_async_txnkv_lmpop(
+        mut self,
+        keys: &[String],
+        op_left: bool,
+        count: u64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    for key in &keys {
+                        let values = match self
+                            .clone()
+                            .do_async_txnkv_pop(key, op_left, count as i64)
+                            .await?
+                        {
+                            Frame::Null => continue,
+                            Frame::Bulk(value) => vec![Frame::Bulk(value)],
+                            Frame::Array(values) => values,
+                            resp => return Ok(resp),
+                        };
+                        return Ok(resp_array(vec![
+                            resp_bulk(key.as_bytes().to_vec()),
+                            resp_array(values),
+                        ]));
+                    }
+                    Ok(resp_nil())
+                }
+                .boxed()
+            })
+            .await
+    }
+
     pub async fn do_async_txnkv_ltrim(
         mut self,
         key: &str,
@@ -444,6 +490,111 @@ impl<'a> ListCommandCtx {
             .await
     }
 
+    /// Find the positions of `ele`, skipping the first `|rank| - 1` matches,
+    /// a negative `rank` searches from the tail. At most `count` positions
+    /// are replied, 0 replies all of them, and `None` replies the first one
+    /// or nil. At most `maxlen` elements are read, 0 reads the whole list.
+    pub async fn do_async_txnkv_lpos(
+        mut self,
+        key: &str,
+        ele: &Bytes,
+        rank: i64,
+        count: Option<u64>,
+        maxlen: u64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+        let ele = ele.to_owned();
+
+        let positions = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(vec![]),
+                    };
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::List) {
+                        return Err(REDIS_WRONG_TYPE_ERR);
+                    }
+                    let (ttl, version, left, right) = KeyDecoder::decode_key_list_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        drop(txn);
+                        self.clone()
+                            .do_async_txnkv_list_expire_if_needed(&key)
+                            .await?;
+                        return Ok(vec![]);
+                    }
+
+                    let len = right - left;
+                    let to_read = if maxlen == 0 { len } else { maxlen.min(len) };
+                    let limit = match count {
+                        Some(0) => u64::MAX,
+                        Some(count) => count,
+                        None => 1,
+                    };
+                    let mut skip = rank.unsigned_abs() - 1;
+                    let mut positions = vec![];
+                    let mut read = 0;
+
+                    // read the list in batches from the side of the rank, stop
+                    // as soon as enough matches are found
+                    while read < to_read && (positions.len() as u64) < limit {
+                        let batch = (to_read - read).min(LPOS_SCAN_BATCH);
+                        let (start, end) = if rank > 0 {
+                            (left + read, left + read + batch)
+                        } else {
+                            (right - read - batch, right - read)
+                        };
+                        let range: BoundRange = (KEY_ENCODER
+                            .encode_txnkv_list_data_key(&key, start, version)
+                            ..KEY_ENCODER.encode_txnkv_list_data_key(&key, end, version))
+                            .into();
+                        let kvs: Vec<KvPair> = if rank > 0 {
+                            txn.scan(range, batch as u32).await?.collect()
+                        } else {
+                            txn.scan_reverse(range, batch as u32).await?.collect()
+                        };
+                        read += batch;
+
+                        for kv in kvs {
+                            if kv.1 != ele.to_vec() {
+                                continue;
+                            }
+                            if skip > 0 {
+                                skip -= 1;
+                                continue;
+                            }
+                            let idx = KeyDecoder::decode_key_list_idx_from_datakey(&key, kv.0);
+                            positions.push((idx - left) as i64);
+                            if positions.len() as u64 == limit {
+                                break;
+                            }
+                        }
+                    }
+                    Ok(positions)
+                }
+                .boxed()
+            })
+            .await;
+
+        match (positions, count) {
+            (Ok(positions), Some(_)) => {
+                Ok(resp_array(positions.into_iter().map(resp_int).collect()))
+            }
+            (Ok(positions), None) => match positions.first() {
+                Some(&position) => Ok(resp_int(position)),
+                None => Ok(resp_nil()),
+            },
+            (Err(e), _) => Ok(resp_err(e)),
+        }
+    }
+
     pub async fn do_async_txnkv_llen(mut self, key: &str) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
@@ -965,6 +1116,7 @@ impl<'a> ListCommandCtx {
                                 KeyDecoder::decode_key_list_meta(&meta_value);
                             let len = right - left;
                             if len >= async_del_list_threshold_or_default() as u64 {
+                                CMD_PATH_COUNTER.with_label_values(&["del", "async"]).inc();
                                 // async delete
                                 // delete meta key and create gc key and gc version key with the version
                                 txn.delete(meta_key).await?;
@@ -980,6 +1132,7 @@ impl<'a> ListCommandCtx {
                                 )
                                 .await?;
                             } else {
+                                CMD_PATH_COUNTER.with_label_values(&["del", "sync"]).inc();
                                 let bound_range =
                                     KEY_ENCODER.encode_txnkv_list_data_key_range(&key, version);
                                 let iter = txn.scan_keys(bound_range, u32::MAX).await?;
@@ -1021,6 +1174,9 @@ impl<'a> ListCommandCtx {
                             }
                             let len = right - left;
                             if len >= async_del_list_threshold_or_default() as u64 {
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["expire", "async"])
+                                    .inc();
                                 // async delete
                                 // delete meta key and create gc key and gc version key with the version
                                 txn.delete(meta_key).await?;
@@ -1036,6 +1192,9 @@ impl<'a> ListCommandCtx {
                                 )
                                 .await?;
                             } else {
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["expire", "sync"])
+                                    .inc();
                                 let bound_range =
                                     KEY_ENCODER.encode_txnkv_list_data_key_range(&key, version);
                                 let iter = txn.scan_keys(bound_range, u32::MAX).await?;
@@ -1049,6 +1208,7 @@ impl<'a> ListCommandCtx {
                             REMOVED_EXPIRED_KEY_COUNTER
                                 .with_label_values(&["list"])
                                 .inc();
+                            notify_expired(&key);
                             Ok(1)
                         }
                         None => Ok(0),
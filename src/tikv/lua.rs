@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use super::errors::AsyncResult;
+use crate::acl;
 use crate::db::Db;
 use crate::utils::{lua_resp_to_redis_resp, redis_resp_to_lua_resp, resp_err, sha1hex};
 use crate::{utils::resp_invalid_arguments, Command, Frame};
@@ -18,11 +19,14 @@ use mlua::{prelude::*, Lua, Value as LuaValue, Variadic};
 pub struct LuaCommandCtx<'a> {
     txn: Option<Arc<Mutex<Transaction>>>,
     lua: &'a Option<Lua>,
+    // the user running the script, the commands it calls are checked against
+    // the ACL of the user
+    user: &'a str,
 }
 
 impl<'a> LuaCommandCtx<'a> {
-    pub fn new(txn: Option<Arc<Mutex<Transaction>>>, lua: &'a Option<Lua>) -> Self {
-        LuaCommandCtx { txn, lua }
+    pub fn new(txn: Option<Arc<Mutex<Transaction>>>, lua: &'a Option<Lua>, user: &'a str) -> Self {
+        LuaCommandCtx { txn, lua, user }
     }
 
     pub async fn do_async_eval_inner(
@@ -57,11 +61,13 @@ impl<'a> LuaCommandCtx<'a> {
         // create redis.* commands table
         let redis = lua.create_table()?;
         let txn_rc = self.txn;
+        let user = self.user.to_owned();
 
         // redis.call()
         // redis.pcall()
         let redis_call = lua.create_async_function(move |_lua, args: Variadic<LuaValue>| {
             let txn_rc = txn_rc.clone();
+            let user = user.clone();
             // package arguments(without cmd) to argv
             async move {
                 if args.len() == 0 {
@@ -84,6 +90,15 @@ impl<'a> LuaCommandCtx<'a> {
                     }
                 }
 
+                // the ACL of the user applies to the commands called as well
+                let mut cmd_argv = vec![Bytes::from(cmd_name.clone())];
+                cmd_argv.extend(argv.iter().cloned());
+                if let Err(e) = acl::check_argv_permission(&user, &cmd_argv) {
+                    let table = _lua.create_table().unwrap();
+                    table.raw_set("err", e.to_string()).unwrap();
+                    return Ok(LuaValue::Table(table));
+                }
+
                 let cmd = Command::from_argv(&cmd_name, &argv).unwrap();
                 let txn_rc1 = txn_rc.clone().unwrap();
                 let txn = txn_rc1.lock().await;
@@ -96,6 +111,19 @@ impl<'a> LuaCommandCtx<'a> {
                     Command::Decr(mut cmd) => cmd.incr_by(txn_rc.clone(), false).await,
                     Command::DecrBy(mut cmd) => cmd.incr_by(txn_rc.clone(), false).await,
                     Command::Strlen(cmd) => cmd.strlen(txn_rc.clone()).await,
+                    Command::Bitcount(cmd) => cmd.bitcount(txn_rc.clone()).await,
+                    Command::Bitpos(cmd) => cmd.bitpos(txn_rc.clone()).await,
+                    Command::Bitop(cmd) => cmd.bitop(txn_rc.clone()).await,
+                    Command::Setbit(cmd) => cmd.setbit(txn_rc.clone()).await,
+                    Command::Getbit(cmd) => cmd.getbit(txn_rc.clone()).await,
+                    Command::Bitfield(cmd) => cmd.bitfield(txn_rc.clone()).await,
+                    Command::Setrange(cmd) => cmd.setrange(txn_rc.clone()).await,
+                    Command::Getrange(cmd) => cmd.getrange(txn_rc.clone()).await,
+                    Command::Append(cmd) => cmd.append(txn_rc.clone()).await,
+                    Command::Incrbyfloat(cmd) => cmd.incrbyfloat(txn_rc.clone()).await,
+                    Command::GetDel(cmd) => cmd.getdel(txn_rc.clone()).await,
+                    Command::GetEx(cmd) => cmd.getex(txn_rc.clone()).await,
+                    Command::Copy(cmd) => cmd.copy(txn_rc.clone()).await,
                     Command::Del(cmd) => cmd.del(txn_rc.clone()).await,
                     Command::Exists(cmd) => cmd.exists(txn_rc.clone()).await,
                     Command::Get(cmd) => cmd.get(txn_rc.clone()).await,
@@ -107,6 +135,8 @@ impl<'a> LuaCommandCtx<'a> {
                     Command::Type(cmd) => cmd.cmd_type(txn_rc.clone()).await,
                     Command::TTL(cmd) => cmd.ttl(false, txn_rc.clone()).await,
                     Command::PTTL(cmd) => cmd.ttl(true, txn_rc.clone()).await,
+                    Command::Expiretime(cmd) => cmd.expiretime(false, txn_rc.clone()).await,
+                    Command::Pexpiretime(cmd) => cmd.expiretime(true, txn_rc.clone()).await,
                     Command::Expire(cmd) => cmd.expire(false, false, txn_rc.clone()).await,
                     Command::ExpireAt(cmd) => cmd.expire(false, true, txn_rc.clone()).await,
                     Command::Pexpire(cmd) => cmd.expire(true, false, txn_rc.clone()).await,
@@ -123,25 +153,35 @@ impl<'a> LuaCommandCtx<'a> {
                     Command::Hkeys(cmd) => cmd.hkeys(txn_rc.clone()).await,
                     Command::Hvals(cmd) => cmd.hvals(txn_rc.clone()).await,
                     Command::Hincrby(cmd) => cmd.hincrby(txn_rc.clone()).await,
+                    Command::Hincrbyfloat(cmd) => cmd.hincrbyfloat(txn_rc.clone()).await,
+                    Command::Hrandfield(cmd) => cmd.hrandfield(txn_rc.clone()).await,
+                    Command::Hscan(cmd) => cmd.hscan(txn_rc.clone()).await,
                     Command::Hexists(cmd) => cmd.hexists(txn_rc.clone()).await,
                     Command::Hstrlen(cmd) => cmd.hstrlen(txn_rc.clone()).await,
+                    Command::Hgetdel(cmd) => cmd.hgetdel(txn_rc.clone()).await,
                     Command::Lpush(cmd) => cmd.push(txn_rc.clone(), true).await,
                     Command::Rpush(cmd) => cmd.push(txn_rc.clone(), false).await,
                     Command::Lpop(cmd) => cmd.pop(txn_rc.clone(), true).await,
                     Command::Rpop(cmd) => cmd.pop(txn_rc.clone(), false).await,
+                    Command::Blpop(cmd) => cmd.bpop(txn_rc.clone(), true).await,
+                    Command::Brpop(cmd) => cmd.bpop(txn_rc.clone(), false).await,
+                    Command::Lmpop(cmd) => cmd.lmpop(txn_rc.clone()).await,
                     Command::Lrange(cmd) => cmd.lrange(txn_rc.clone()).await,
                     Command::Llen(cmd) => cmd.llen(txn_rc.clone()).await,
                     Command::Lindex(cmd) => cmd.lindex(txn_rc.clone()).await,
                     Command::Lset(cmd) => cmd.lset(txn_rc.clone()).await,
                     Command::Ltrim(cmd) => cmd.ltrim(txn_rc.clone()).await,
                     Command::Lrem(cmd) => cmd.lrem(txn_rc.clone()).await,
+                    Command::Lpos(cmd) => cmd.lpos(txn_rc.clone()).await,
                     Command::Linsert(cmd) => cmd.linsert(txn_rc.clone()).await,
                     Command::Sadd(cmd) => cmd.sadd(txn_rc.clone()).await,
                     Command::Scard(cmd) => cmd.scard(txn_rc.clone()).await,
+                    Command::Sintercard(cmd) => cmd.sintercard(txn_rc.clone()).await,
                     Command::Sismember(cmd) => cmd.sismember(txn_rc.clone()).await,
                     Command::Smismember(cmd) => cmd.smismember(txn_rc.clone()).await,
                     Command::Smembers(cmd) => cmd.smembers(txn_rc.clone()).await,
                     Command::Srandmember(cmd) => cmd.srandmember(txn_rc.clone()).await,
+                    Command::Sscan(cmd) => cmd.sscan(txn_rc.clone()).await,
                     Command::Spop(cmd) => cmd.spop(txn_rc.clone()).await,
                     Command::Srem(cmd) => cmd.srem(txn_rc.clone()).await,
                     Command::Zadd(cmd) => cmd.zadd(txn_rc.clone()).await,
@@ -151,15 +191,24 @@ impl<'a> LuaCommandCtx<'a> {
                     Command::Zremrangebyscore(cmd) => cmd.zremrangebyscore(txn_rc.clone()).await,
                     Command::Zremrangebyrank(cmd) => cmd.zremrangebyrank(txn_rc.clone()).await,
                     Command::Zrange(cmd) => cmd.zrange(txn_rc.clone()).await,
+                    Command::Zrangestore(cmd) => cmd.zrangestore(txn_rc.clone()).await,
                     Command::Zrevrange(cmd) => cmd.zrevrange(txn_rc.clone()).await,
                     Command::Zrangebyscore(cmd) => cmd.zrangebyscore(txn_rc.clone(), false).await,
                     Command::Zrevrangebyscore(cmd) => cmd.zrangebyscore(txn_rc.clone(), true).await,
                     Command::Zcount(cmd) => cmd.zcount(txn_rc.clone()).await,
                     Command::Zpopmin(cmd) => cmd.zpop(txn_rc.clone(), true).await,
                     Command::Zpopmax(cmd) => cmd.zpop(txn_rc.clone(), false).await,
+                    Command::Zmpop(cmd) => cmd.zmpop(txn_rc.clone()).await,
                     Command::Zrank(cmd) => cmd.zrank(txn_rc.clone()).await,
+                    Command::Zrandmember(cmd) => cmd.zrandmember(txn_rc.clone()).await,
+                    Command::Zscan(cmd) => cmd.zscan(txn_rc.clone()).await,
+                    Command::Zdiff(cmd) => cmd.zdiff(txn_rc.clone()).await,
+                    Command::Zdiffstore(cmd) => cmd.zdiffstore(txn_rc.clone()).await,
+                    Command::Zunion(cmd) => cmd.zunion(txn_rc.clone()).await,
+                    Command::Zinter(cmd) => cmd.zinter(txn_rc.clone()).await,
                     Command::Zincryby(cmd) => cmd.zincrby(txn_rc.clone()).await,
                     Command::Scan(cmd) => cmd.scan(txn_rc.clone()).await,
+                    Command::Randomkey(cmd) => cmd.randomkey(txn_rc.clone()).await,
                     Command::Xscan(cmd) => cmd.scan(txn_rc.clone()).await,
                     _ => Ok(resp_invalid_arguments()),
                 };
@@ -2,17 +2,24 @@ use pprof::protos::Message;
 use std::collections::{HashMap, LinkedList};
 use std::fs::File;
 use std::io::Write;
-use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use tokio::sync::Mutex;
 
+use prometheus::IntGauge;
 use tikv_client::{RawClient, Transaction, TransactionClient};
 
 use crate::config::LOGGER;
+use crate::metrics::{
+    TIKV_BROKEN_CLIENT_COUNTER, TXN_CLIENT_INFLIGHT_GAUGE, TXN_CLIENT_POOL_SIZE_GAUGE,
+};
 use crate::tikv::encoding::KeyEncoder;
-use crate::tikv::errors::REDIS_BACKEND_NOT_CONNECTED_ERR;
+use crate::tikv::errors::{
+    RTError, REDIS_BACKEND_NOT_CONNECTED_ERR, REDIS_PROFILER_NOT_STARTED_ERR,
+};
+use crate::utils::now_timestamp_in_millis;
 use crate::{
     backend_allow_batch_or_default, backend_ca_file_or_default, backend_cert_file_or_default,
     backend_completion_queue_size_or_default, backend_grpc_keepalive_time_or_default,
@@ -22,6 +29,7 @@ use crate::{
     backend_timeout_or_default, config_meta_key_number_or_default, conn_concurrency_or_default,
     fetch_idx_and_add,
 };
+use slog::warn;
 
 use self::client::RawClientWrapper;
 use self::client::TxnClientWrapper;
@@ -32,8 +40,13 @@ pub mod client;
 pub mod encoding;
 pub mod errors;
 pub mod hash;
+pub mod health;
+pub mod import;
 pub mod list;
 pub mod lua;
+pub mod negative_cache;
+pub mod resize;
+pub mod sample;
 pub mod set;
 pub mod string;
 pub mod zset;
@@ -45,89 +58,282 @@ lazy_static! {
     pub static ref TIKV_TNX_CONN_POOL: Arc<Mutex<LinkedList<TransactionClient>>> =
         Arc::new(Mutex::new(LinkedList::new()));
     pub static ref KEY_ENCODER: KeyEncoder = KeyEncoder::new();
+    static ref TIKV_RAW_CLIENT: RwLock<Option<RawClient>> = RwLock::new(None);
+    // the requests in flight keep the pool they picked a client from, a pool
+    // replaced by a reconnection is dropped once they are all done
+    static ref TIKV_TXN_CLIENT_POOL: RwLock<Option<Arc<TxnClientPool>>> = RwLock::new(None);
+    // connections are made one at a time, so the clients swapped in all come
+    // from the same addresses
+    static ref CONNECT_LOCK: Mutex<()> = Mutex::new(());
+    static ref PROFILER_GUARD: std::sync::Mutex<Option<pprof::ProfilerGuard<'static>>> =
+        std::sync::Mutex::new(None);
 }
 
-pub static mut PROFILER_GUARD: Option<pprof::ProfilerGuard> = None;
+/// The samples per second taken by the profiler if none is given
+pub const DEFAULT_PROFILER_FREQUENCY: i32 = 99;
+/// The files of the profile are written to the working directory, named
+/// after this prefix, if none is given
+pub const DEFAULT_PROFILER_PREFIX: &str = "tikv-service-server";
 
-pub fn start_profiler() {
-    unsafe {
-        let guard = pprof::ProfilerGuardBuilder::default()
-            .frequency(99)
-            //.blocklist(&["libc", "libgcc", "pthread", "vdso"])
-            .build()
-            .unwrap();
-        PROFILER_GUARD = Some(guard);
-    }
+/// Start sampling the stacks `frequency` times per second, a running profile
+/// is discarded.
+pub fn start_profiler(frequency: i32) -> Result<(), RTError> {
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(frequency)
+        //.blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .map_err(|e| RTError::Owned(format!("ERR failed to start the profiler: {}", e)))?;
+    PROFILER_GUARD.lock().unwrap().replace(guard);
+    Ok(())
 }
 
-pub fn stop_profiler() {
-    unsafe {
-        if let Some(guard) = &PROFILER_GUARD {
-            if let Ok(report) = guard.report().build() {
-                // generate flamegraph file
-                let flame_graph_file = File::create("tikv-service-server-flamegraph.svg").unwrap();
-                report.flamegraph(flame_graph_file).unwrap();
-
-                // generate profile file
-                let mut profile_file = File::create("tikv-service-server-profile.pb").unwrap();
-                let profile = report.pprof().unwrap();
-                let mut content = Vec::new();
-                profile.write_to_vec(&mut content).unwrap();
-                profile_file.write_all(&content).unwrap();
-            };
-            PROFILER_GUARD.take();
+/// Stop the profiler and write the flamegraph and the pprof profile to
+/// `<prefix>-flamegraph.svg` and `<prefix>-profile.pb`, the prefix may
+/// include a directory. Returns the paths of the files written.
+pub fn stop_profiler(prefix: &str) -> Result<Vec<String>, RTError> {
+    let guard = match PROFILER_GUARD.lock().unwrap().take() {
+        Some(guard) => guard,
+        None => return Err(REDIS_PROFILER_NOT_STARTED_ERR),
+    };
+    let report = guard
+        .report()
+        .build()
+        .map_err(|e| profiler_error("build the report", e))?;
+
+    // generate flamegraph file
+    let flame_graph_path = format!("{}-flamegraph.svg", prefix);
+    let write_flame_graph = format!("write {}", flame_graph_path);
+    let flame_graph_file =
+        File::create(&flame_graph_path).map_err(|e| profiler_error(&write_flame_graph, e))?;
+    report
+        .flamegraph(flame_graph_file)
+        .map_err(|e| profiler_error(&write_flame_graph, e))?;
+
+    // generate profile file
+    let profile_path = format!("{}-profile.pb", prefix);
+    let mut content = Vec::new();
+    report
+        .pprof()
+        .map_err(|e| profiler_error("build the profile", e))?
+        .write_to_vec(&mut content)
+        .map_err(|e| profiler_error("encode the profile", e))?;
+    File::create(&profile_path)
+        .and_then(|mut profile_file| profile_file.write_all(&content))
+        .map_err(|e| profiler_error(&format!("write {}", profile_path), e))?;
+
+    Ok(vec![flame_graph_path, profile_path])
+}
+
+fn profiler_error(what: &str, e: impl std::fmt::Display) -> RTError {
+    RTError::Owned(format!("ERR profiler failed to {}: {}", what, e))
+}
+
+/// The transaction clients requests are spread over, along with the state
+/// of each client.
+pub struct TxnClientPool {
+    // a client is replaced in place when it is reconnected
+    clients: Vec<RwLock<Arc<TransactionClient>>>,
+    /// index of the client picked last
+    idx: AtomicUsize,
+    /// Timestamp in ms until which the client at the same index is skipped,
+    /// set after the client returned a connection error.
+    broken_until: Vec<AtomicU64>,
+    /// Requests in flight on the client at the same index, exported as metrics.
+    inflight: Vec<IntGauge>,
+    /// Whether the client at the same index passed its last health check.
+    healthy: Vec<AtomicBool>,
+}
+
+impl TxnClientPool {
+    fn new(clients: Vec<TransactionClient>) -> Self {
+        TxnClientPool {
+            broken_until: (0..clients.len()).map(|_| AtomicU64::new(0)).collect(),
+            inflight: (0..clients.len()).map(inflight_gauge).collect(),
+            healthy: (0..clients.len()).map(|_| AtomicBool::new(true)).collect(),
+            idx: AtomicUsize::new(0),
+            clients: clients
+                .into_iter()
+                .map(|client| RwLock::new(Arc::new(client)))
+                .collect(),
+        }
+    }
+
+    pub fn client(&self, idx: usize) -> Arc<TransactionClient> {
+        self.clients[idx].read().unwrap().clone()
+    }
+
+    /// Pick the client with the least requests in flight, the ties are broken
+    /// in round robin. Unhealthy clients and clients with recent connection
+    /// errors are skipped unless all clients are.
+    ///
+    /// The request is counted in flight on the client until `release`.
+    pub fn pick(&self) -> usize {
+        let now = now_timestamp_in_millis();
+        let start = self.idx.load(Relaxed);
+        let mut picked: Option<(usize, i64)> = None;
+        for i in 1..=self.clients.len() {
+            let idx = (start + i) % self.clients.len();
+            if self.broken_until[idx].load(Relaxed) > now || !self.healthy[idx].load(Relaxed) {
+                continue;
+            }
+            let load = self.inflight[idx].get();
+            if picked.map_or(true, |(_, least)| load < least) {
+                picked = Some((idx, load));
+            }
+        }
+        let idx = picked.map_or((start + 1) % self.clients.len(), |(idx, _)| idx);
+        self.idx.store(idx, Relaxed);
+        self.inflight[idx].inc();
+        idx
+    }
+
+    /// The request picked the client by `pick` is done with it.
+    pub fn release(&self, idx: usize) {
+        self.inflight[idx].dec();
+    }
+
+    /// Skip the client for a while after it returned a connection error.
+    pub fn mark_broken(&self, idx: usize) {
+        let now = now_timestamp_in_millis();
+        if self.broken_until[idx].swap(now + BROKEN_CLIENT_SKIP_MS, Relaxed) <= now {
+            warn!(
+                LOGGER,
+                "txn client {} is broken, skip it for {}ms", idx, BROKEN_CLIENT_SKIP_MS
+            );
+            TIKV_BROKEN_CLIENT_COUNTER.inc();
+        }
+    }
+
+    fn set_healthy(&self, idx: usize, healthy: bool) {
+        self.healthy[idx].store(healthy, Relaxed);
+    }
+
+    /// Replace the client at the index, the requests in flight finish on the
+    /// client they hold.
+    fn replace_client(&self, idx: usize, client: TransactionClient) {
+        *self.clients[idx].write().unwrap() = Arc::new(client);
+    }
+
+    /// A pool of the first `len` clients of this one followed by the `added`
+    /// clients, the state of the clients kept is carried over.
+    fn resized(&self, len: usize, added: Vec<TransactionClient>) -> TxnClientPool {
+        let kept = len.min(self.clients.len());
+        let clients: Vec<_> = (0..kept)
+            .map(|idx| RwLock::new(self.client(idx)))
+            .chain(
+                added
+                    .into_iter()
+                    .map(|client| RwLock::new(Arc::new(client))),
+            )
+            .collect();
+        let len = clients.len();
+        TxnClientPool {
+            broken_until: (0..len)
+                .map(|idx| {
+                    AtomicU64::new(self.broken_until.get(idx).map_or(0, |b| b.load(Relaxed)))
+                })
+                .collect(),
+            inflight: (0..len).map(inflight_gauge).collect(),
+            healthy: (0..len)
+                .map(|idx| AtomicBool::new(self.healthy.get(idx).map_or(true, |h| h.load(Relaxed))))
+                .collect(),
+            idx: AtomicUsize::new(self.idx.load(Relaxed) % len),
+            clients,
         }
     }
 }
 
-pub static mut TIKV_RAW_CLIENT: Option<RawClient> = None;
+/// The gauges are shared by the pools replacing each other, by a reconnection
+/// or a resize, the requests still in flight on a replaced pool keep counting.
+fn inflight_gauge(idx: usize) -> IntGauge {
+    TXN_CLIENT_INFLIGHT_GAUGE.with_label_values(&[&idx.to_string()])
+}
 
-pub static mut TIKV_TXN_CLIENTS: Option<Vec<TransactionClient>> = None;
-pub static mut TIKV_TXN_CLIENT_IDX: AtomicUsize = AtomicUsize::new(0);
+/// How long a client is skipped after a connection error.
+const BROKEN_CLIENT_SKIP_MS: u64 = 5000;
 
-pub static mut INSTANCE_ID: u64 = 0;
+static INSTANCE_ID: AtomicU64 = AtomicU64::new(0);
 
 pub fn set_instance_id(id: u64) {
-    unsafe {
-        INSTANCE_ID = id;
-    }
+    INSTANCE_ID.store(id, Relaxed);
 }
 
 pub fn get_instance_id() -> u64 {
-    unsafe { INSTANCE_ID }
+    INSTANCE_ID.load(Relaxed)
 }
 
 pub fn get_client() -> Result<RawClientWrapper, RTError> {
-    if unsafe { TIKV_RAW_CLIENT.is_none() } {
-        return Err(REDIS_BACKEND_NOT_CONNECTED_ERR);
-    }
-    let client = unsafe { TIKV_RAW_CLIENT.as_ref().unwrap() };
-    let ret = RawClientWrapper::new(client);
-    Ok(ret)
+    TIKV_RAW_CLIENT
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(RawClientWrapper::new)
+        .ok_or(REDIS_BACKEND_NOT_CONNECTED_ERR)
 }
 
-pub fn get_txn_client() -> Result<TxnClientWrapper<'static>, RTError> {
-    if unsafe { TIKV_RAW_CLIENT.is_none() } {
-        return Err(REDIS_BACKEND_NOT_CONNECTED_ERR);
-    }
-    let client = unsafe {
-        let mut idx = TIKV_TXN_CLIENT_IDX.load(Relaxed);
-        idx = (idx + 1) % TIKV_TXN_CLIENTS.as_ref().unwrap().len();
-        TIKV_TXN_CLIENT_IDX.store(idx, Relaxed);
+pub fn get_txn_client() -> Result<TxnClientWrapper, RTError> {
+    let pool = TIKV_TXN_CLIENT_POOL
+        .read()
+        .unwrap()
+        .clone()
+        .ok_or(REDIS_BACKEND_NOT_CONNECTED_ERR)?;
+    Ok(TxnClientWrapper::new(pool))
+}
 
-        &TIKV_TXN_CLIENTS.as_ref().unwrap()[idx]
-    };
-    let ret = TxnClientWrapper::new(client);
-    Ok(ret)
+/// State of the backend client pool, reported by `DEBUG BACKEND`.
+pub struct BackendStats {
+    pub pd_addrs: Vec<String>,
+    pub txn_client_pool_size: usize,
+    /// index of the client picked last
+    pub txn_client_index: usize,
+    /// indexes of the clients skipped after connection errors
+    pub broken_txn_clients: Vec<usize>,
+    /// indexes of the clients failing their health check
+    pub unhealthy_txn_clients: Vec<usize>,
+    /// requests in flight on each client
+    pub txn_client_inflight: Vec<i64>,
+}
+
+pub fn backend_stats() -> BackendStats {
+    let pd_addrs = PD_ADDRS.read().unwrap().clone().unwrap_or_default();
+    let now = now_timestamp_in_millis();
+    match TIKV_TXN_CLIENT_POOL.read().unwrap().as_ref() {
+        Some(pool) => BackendStats {
+            pd_addrs,
+            txn_client_pool_size: pool.clients.len(),
+            txn_client_index: pool.idx.load(Relaxed),
+            broken_txn_clients: pool
+                .broken_until
+                .iter()
+                .enumerate()
+                .filter(|(_, until)| until.load(Relaxed) > now)
+                .map(|(idx, _)| idx)
+                .collect(),
+            unhealthy_txn_clients: pool
+                .healthy
+                .iter()
+                .enumerate()
+                .filter(|(_, healthy)| !healthy.load(Relaxed))
+                .map(|(idx, _)| idx)
+                .collect(),
+            txn_client_inflight: pool.inflight.iter().map(|gauge| gauge.get()).collect(),
+        },
+        None => BackendStats {
+            pd_addrs,
+            txn_client_pool_size: 0,
+            txn_client_index: 0,
+            broken_txn_clients: vec![],
+            unhealthy_txn_clients: vec![],
+            txn_client_inflight: vec![],
+        },
+    }
 }
 
 pub async fn sleep(ms: u32) {
     tokio::time::sleep(Duration::from_millis(ms as u64)).await;
 }
 
-pub async fn do_async_txn_connect(addrs: Vec<String>) -> AsyncResult<()> {
-    PD_ADDRS.write().unwrap().replace(addrs.clone());
-
+fn txn_client_config() -> tikv_client::Config {
     let mut config = tikv_client::Config::default()
         .with_timeout(Duration::from_millis(backend_timeout_or_default()))
         .with_kv_timeout(backend_timeout_or_default())
@@ -150,21 +356,28 @@ pub async fn do_async_txn_connect(addrs: Vec<String>) -> AsyncResult<()> {
             backend_key_file_or_default(),
         );
     }
+    config
+}
+
+async fn new_txn_client(addrs: &[String]) -> AsyncResult<TransactionClient> {
+    let client = TransactionClient::new_with_config(
+        addrs.to_vec(),
+        txn_client_config(),
+        Some(LOGGER.clone()),
+    )
+    .await?;
+    Ok(client)
+}
+
+async fn new_txn_client_pool(addrs: &[String]) -> AsyncResult<TxnClientPool> {
     let mut clients = Vec::with_capacity(conn_concurrency_or_default());
     for _ in 0..conn_concurrency_or_default() {
-        let client =
-            TransactionClient::new_with_config(addrs.clone(), config.clone(), Some(LOGGER.clone()))
-                .await?;
-        clients.push(client);
+        clients.push(new_txn_client(addrs).await?);
     }
-    unsafe {
-        TIKV_TXN_CLIENTS.replace(clients);
-    }
-
-    Ok(())
+    Ok(TxnClientPool::new(clients))
 }
 
-pub async fn do_async_raw_connect(addrs: Vec<String>) -> AsyncResult<()> {
+async fn new_raw_client(addrs: &[String]) -> AsyncResult<RawClient> {
     let mut config = tikv_client::Config::default()
         .with_timeout(Duration::from_millis(backend_timeout_or_default()));
     if !backend_ca_file_or_default().is_empty()
@@ -177,16 +390,45 @@ pub async fn do_async_raw_connect(addrs: Vec<String>) -> AsyncResult<()> {
             backend_key_file_or_default(),
         );
     }
-    let client = RawClient::new_with_config(addrs.clone(), config, Some(LOGGER.clone())).await?;
-    unsafe {
-        TIKV_RAW_CLIENT.replace(client);
-    }
+    let client = RawClient::new_with_config(addrs.to_vec(), config, Some(LOGGER.clone())).await?;
+    Ok(client)
+}
+
+pub async fn do_async_txn_connect(addrs: Vec<String>) -> AsyncResult<()> {
+    let _guard = CONNECT_LOCK.lock().await;
+    let pool = new_txn_client_pool(&addrs).await?;
+    TXN_CLIENT_POOL_SIZE_GAUGE.set(pool.clients.len() as i64);
+    TIKV_TXN_CLIENT_POOL
+        .write()
+        .unwrap()
+        .replace(Arc::new(pool));
+    PD_ADDRS.write().unwrap().replace(addrs);
+    Ok(())
+}
+
+pub async fn do_async_raw_connect(addrs: Vec<String>) -> AsyncResult<()> {
+    let _guard = CONNECT_LOCK.lock().await;
+    let client = new_raw_client(&addrs).await?;
+    TIKV_RAW_CLIENT.write().unwrap().replace(client);
     Ok(())
 }
 
+/// Connect the clients to the PD addresses and swap them in, the clients in
+/// use are left untouched if any connection fails.
+///
+/// The requests in flight finish on the clients they started with, the new
+/// requests use the new clients.
 pub async fn do_async_connect(addrs: Vec<String>) -> AsyncResult<()> {
-    do_async_txn_connect(addrs.clone()).await?;
-    do_async_raw_connect(addrs).await?;
+    let _guard = CONNECT_LOCK.lock().await;
+    let pool = new_txn_client_pool(&addrs).await?;
+    let raw_client = new_raw_client(&addrs).await?;
+    TXN_CLIENT_POOL_SIZE_GAUGE.set(pool.clients.len() as i64);
+    TIKV_TXN_CLIENT_POOL
+        .write()
+        .unwrap()
+        .replace(Arc::new(pool));
+    TIKV_RAW_CLIENT.write().unwrap().replace(raw_client);
+    PD_ADDRS.write().unwrap().replace(addrs);
     Ok(())
 }
 
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::metrics::NEGATIVE_CACHE_COUNTER;
+use crate::{negative_cache_size_or_default, negative_cache_ttl_ms_or_default};
+
+lazy_static! {
+    pub static ref NEGATIVE_CACHE: NegativeCache = NegativeCache::new();
+}
+
+/// Remembers keys recently confirmed absent by GET, so repeated GETs of a
+/// missing key are answered without a backend read.
+///
+/// Writes received by this instance invalidate the entries of their keys,
+/// writes through other instances are only seen after the entry expires, so
+/// the ttl bounds the staleness of a cached miss.
+pub struct NegativeCache {
+    entries: Mutex<HashMap<String, Instant>>,
+    /// bumped by every invalidation, a miss read before an invalidation
+    /// must not be cached after it
+    epoch: AtomicU64,
+}
+
+impl NegativeCache {
+    fn new() -> NegativeCache {
+        NegativeCache {
+            entries: Mutex::new(HashMap::new()),
+            epoch: AtomicU64::new(0),
+        }
+    }
+
+    pub fn enabled() -> bool {
+        negative_cache_size_or_default() > 0
+    }
+
+    /// Take the epoch before reading the backend, pass it to `insert` later.
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        let hit = match entries.get(key) {
+            Some(expire_at) if *expire_at > Instant::now() => true,
+            Some(_) => {
+                entries.remove(key);
+                false
+            }
+            None => false,
+        };
+        NEGATIVE_CACHE_COUNTER
+            .with_label_values(&[if hit { "hit" } else { "miss" }])
+            .inc();
+        hit
+    }
+
+    /// Cache the key as absent, unless an invalidation happened since `epoch`.
+    pub fn insert(&self, key: &str, epoch: u64) {
+        let mut entries = self.entries.lock().unwrap();
+        if self.epoch() != epoch {
+            return;
+        }
+        let now = Instant::now();
+        if entries.len() >= negative_cache_size_or_default() {
+            entries.retain(|_, expire_at| *expire_at > now);
+            if entries.len() >= negative_cache_size_or_default() {
+                return;
+            }
+        }
+        entries.insert(
+            key.to_owned(),
+            now + Duration::from_millis(negative_cache_ttl_ms_or_default()),
+        );
+    }
+
+    /// Drop the entries of the written keys, all entries if `keys` is empty.
+    pub fn invalidate(&self, keys: &[String]) {
+        let mut entries = self.entries.lock().unwrap();
+        self.epoch.fetch_add(1, Ordering::AcqRel);
+        if keys.is_empty() {
+            entries.clear();
+        } else {
+            for key in keys {
+                entries.remove(key);
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::Arc;
+
+use futures::future;
+use slog::{info, warn};
+use tokio::time::{self, Duration, MissedTickBehavior};
+
+use crate::config::LOGGER;
+use crate::metrics::TXN_CLIENT_POOL_SIZE_GAUGE;
+use crate::utils::now_timestamp_in_millis;
+use crate::{
+    conn_concurrency_max_or_default, conn_concurrency_or_default,
+    conn_inflight_threshold_or_default, is_use_txn_api,
+};
+
+use super::{new_txn_client, TxnClientPool, CONNECT_LOCK, PD_ADDRS, TIKV_TXN_CLIENT_POOL};
+
+/// How often the load of the pool is looked at.
+const POOL_RESIZE_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Consecutive rounds the load must fit in one client less before the pool
+/// shrinks, a short lull does not drop a client the next burst needs.
+const POOL_SHRINK_QUIET_ROUNDS: usize = 30;
+
+/// Resize the txn client pool with the load, between `conn_concurrency` and
+/// `conn_concurrency_max` clients.
+///
+/// The pool grows by one client when every usable client has at least
+/// `conn_inflight_threshold` requests in flight, and shrinks by one after the
+/// requests in flight fit in one client less at half the threshold for
+/// `POOL_SHRINK_QUIET_ROUNDS` rounds. A resized pool is swapped in as by a
+/// reconnection, it shares the clients kept with the pool it replaces and the
+/// requests in flight finish on the pool they picked their client from.
+pub struct PoolResizer {
+    quiet_rounds: usize,
+}
+
+impl PoolResizer {
+    pub fn new() -> Self {
+        PoolResizer { quiet_rounds: 0 }
+    }
+
+    pub async fn run(&mut self) {
+        if conn_concurrency_max_or_default() <= conn_concurrency_or_default() || !is_use_txn_api() {
+            // the pool size is fixed
+            return future::pending().await;
+        }
+        let mut interval = time::interval(POOL_RESIZE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.resize_pool().await;
+        }
+    }
+
+    async fn resize_pool(&mut self) {
+        let pool = match TIKV_TXN_CLIENT_POOL.read().unwrap().clone() {
+            Some(pool) => pool,
+            None => return,
+        };
+        let size = pool.clients.len();
+        TXN_CLIENT_POOL_SIZE_GAUGE.set(size as i64);
+
+        let threshold = conn_inflight_threshold_or_default();
+        let loads = usable_loads(&pool);
+        if size < conn_concurrency_max_or_default()
+            && !loads.is_empty()
+            && loads.iter().all(|&load| load >= threshold)
+        {
+            self.quiet_rounds = 0;
+            let addrs = PD_ADDRS.read().unwrap().clone().unwrap_or_default();
+            match new_txn_client(&addrs).await {
+                Ok(client) => {
+                    if swap_pool(&pool, pool.resized(size, vec![client])).await {
+                        info!(LOGGER, "txn client pool grown to {} clients", size + 1);
+                    }
+                }
+                Err(e) => warn!(LOGGER, "failed to grow the txn client pool: {:?}", e),
+            }
+            return;
+        }
+
+        let total: i64 = pool.inflight.iter().map(|gauge| gauge.get()).sum();
+        if size > conn_concurrency_or_default() && total <= threshold / 2 * (size as i64 - 1) {
+            self.quiet_rounds += 1;
+        } else {
+            self.quiet_rounds = 0;
+        }
+        if self.quiet_rounds >= POOL_SHRINK_QUIET_ROUNDS {
+            self.quiet_rounds = 0;
+            if swap_pool(&pool, pool.resized(size - 1, vec![])).await {
+                info!(LOGGER, "txn client pool shrunk to {} clients", size - 1);
+            }
+        }
+    }
+}
+
+impl Default for PoolResizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The requests in flight on the clients picked by the requests, i.e. the
+/// healthy clients without recent connection errors.
+fn usable_loads(pool: &TxnClientPool) -> Vec<i64> {
+    let now = now_timestamp_in_millis();
+    (0..pool.clients.len())
+        .filter(|&idx| {
+            pool.healthy[idx].load(Relaxed) && pool.broken_until[idx].load(Relaxed) <= now
+        })
+        .map(|idx| pool.inflight[idx].get())
+        .collect()
+}
+
+/// Swap in the resized pool unless a reconnection or another resize replaced
+/// the pool it was made from meanwhile.
+async fn swap_pool(from: &Arc<TxnClientPool>, resized: TxnClientPool) -> bool {
+    let _guard = CONNECT_LOCK.lock().await;
+    let mut current = TIKV_TXN_CLIENT_POOL.write().unwrap();
+    if !matches!(current.as_ref(), Some(pool) if Arc::ptr_eq(pool, from)) {
+        return false;
+    }
+    TXN_CLIENT_POOL_SIZE_GAUGE.set(resized.clients.len() as i64);
+    current.replace(Arc::new(resized));
+    true
+}
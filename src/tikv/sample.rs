@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+
+use rand::rngs::SmallRng;
+use rand::seq::{index, SliceRandom};
+use rand::{Rng, SeedableRng};
+use tikv_client::{BoundRange, Key, KvPair, Transaction};
+
+use super::errors::{AsyncResult, RTError, REDIS_VALUE_OUT_OF_RANGE_ERR};
+use crate::proto_max_multibulk_len_or_default;
+
+/// Collections up to this size are sampled exactly, scanning them up to the
+/// last sampled position.
+const SAMPLE_EXACT_MAX_SIZE: usize = 1024;
+/// Elements read from each random start key of a larger collection.
+const SAMPLE_WINDOW: usize = 16;
+
+/// Parameters of a random sampling over the elements of a collection, shared by
+/// SRANDMEMBER, HRANDFIELD and ZRANDMEMBER.
+///
+/// A positive count samples distinct elements, at most the size of the collection,
+/// a negative count samples `-count` elements which may repeat.
+///
+/// The count is bounded as in redis, within `-i64::MAX..=i64::MAX` and half that
+/// when the values are replied too. A negative count is also bounded by
+/// `proto_max_multibulk_len`, the repeated elements are all held in memory.
+#[derive(Debug, Clone, Copy)]
+pub struct Sampling {
+    pub count: usize,
+    pub repeatable: bool,
+}
+
+impl Sampling {
+    pub fn new(count: i64, with_values: bool) -> Result<Sampling, RTError> {
+        let max = if with_values { i64::MAX / 2 } else { i64::MAX };
+        if count < -max || count > max {
+            return Err(REDIS_VALUE_OUT_OF_RANGE_ERR);
+        }
+        if count < 0 && count.unsigned_abs() > proto_max_multibulk_len_or_default() {
+            return Err(REDIS_VALUE_OUT_OF_RANGE_ERR);
+        }
+        Ok(Sampling {
+            count: count.unsigned_abs() as usize,
+            repeatable: count < 0,
+        })
+    }
+
+    /// Pick the positions of the sampled elements in a collection of `size`
+    /// elements, every element has the same chance to be picked. The positions
+    /// are in random order.
+    pub fn positions(&self, size: usize) -> Vec<usize> {
+        if size == 0 || self.count == 0 {
+            return vec![];
+        }
+        let mut rng = SmallRng::from_entropy();
+        if self.repeatable {
+            (0..self.count).map(|_| rng.gen_range(0..size)).collect()
+        } else {
+            index::sample(&mut rng, size, self.count.min(size)).into_vec()
+        }
+    }
+
+    /// Sample the data keys in `range` of a collection with `size` elements.
+    ///
+    /// A small collection, or a sample of most of a collection, is sampled
+    /// exactly by scanning up to the last sampled position. A larger one is
+    /// sampled by windows of a few elements following random start keys, so the
+    /// keys read scale with the count rather than the size. The elements
+    /// following a sparse part of the member space are more likely picked then,
+    /// the sample is not strictly uniform.
+    pub async fn sample(
+        &self,
+        txn: &mut Transaction,
+        range: BoundRange,
+        size: usize,
+    ) -> AsyncResult<Vec<KvPair>> {
+        if size == 0 || self.count == 0 {
+            return Ok(vec![]);
+        }
+        if size <= SAMPLE_EXACT_MAX_SIZE || (!self.repeatable && self.count >= size / 2) {
+            return self.sample_exact(txn, range, size).await;
+        }
+        self.sample_windows(txn, range, size).await
+    }
+
+    async fn sample_exact(
+        &self,
+        txn: &mut Transaction,
+        range: BoundRange,
+        size: usize,
+    ) -> AsyncResult<Vec<KvPair>> {
+        let positions = self.positions(size);
+        let limit = match positions.iter().max() {
+            Some(pos) => pos + 1,
+            None => return Ok(vec![]),
+        };
+        let elements: Vec<KvPair> = txn.scan(range, limit as u32).await?.collect();
+
+        // skip positions out of range in case the size counters drift from
+        // the elements stored
+        Ok(positions
+            .into_iter()
+            .filter_map(|pos| elements.get(pos).cloned())
+            .collect())
+    }
+
+    async fn sample_windows(
+        &self,
+        txn: &mut Transaction,
+        range: BoundRange,
+        size: usize,
+    ) -> AsyncResult<Vec<KvPair>> {
+        let wanted = if self.repeatable {
+            self.count
+        } else {
+            self.count.min(size)
+        };
+        let (start, end) = range.into_keys();
+        let start: Vec<u8> = start.into();
+        let bounded = |from: Vec<u8>| -> BoundRange {
+            match end.clone() {
+                Some(end) => (Key::from(from)..end).into(),
+                None => (Key::from(from)..).into(),
+            }
+        };
+
+        // The first byte of the random start keys is drawn between the first
+        // bytes of the first and the last elements, the members of a
+        // collection usually share a small part of the byte values, e.g.
+        // digits. The last first byte is found by a binary search of scans of
+        // one key.
+        let lo = match txn.scan(bounded(start.clone()), 1).await?.next() {
+            Some(kv) => Vec::<u8>::from(kv.0).get(start.len()).copied().unwrap_or(0),
+            None => return Ok(vec![]),
+        };
+        let (mut low, mut hi) = (lo, u8::MAX);
+        while low < hi {
+            let mid = ((low as u16 + hi as u16 + 1) / 2) as u8;
+            let mut from = start.clone();
+            from.push(mid);
+            if txn.scan(bounded(from), 1).await?.next().is_some() {
+                low = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+
+        let mut rng = SmallRng::from_entropy();
+        let mut sampled = Vec::with_capacity(wanted);
+        let mut seen = HashSet::new();
+        // a window hitting sampled elements is followed by the elements after
+        // it, so the distinct samples keep progressing
+        let mut next_from: Option<Vec<u8>> = None;
+
+        // bound the windows in case the size counters drift from the elements
+        // stored
+        let max_windows = 4 * (wanted / SAMPLE_WINDOW + 1) + 8;
+        for _ in 0..max_windows {
+            if sampled.len() >= wanted {
+                break;
+            }
+            let window = (wanted - sampled.len()).min(SAMPLE_WINDOW) as u32;
+            let from = next_from.take().unwrap_or_else(|| {
+                let mut from = start.clone();
+                from.push(rng.gen_range(lo..=hi));
+                from.extend((0..7).map(|_| rng.gen::<u8>()));
+                from
+            });
+            let mut elements: Vec<KvPair> = txn.scan(bounded(from), window).await?.collect();
+            if (elements.len() as u32) < window {
+                // wrap around to the first elements
+                let remain = window - elements.len() as u32;
+                elements.extend(txn.scan(bounded(start.clone()), remain).await?);
+            }
+            let mut hit_sampled = false;
+            let last_key = elements.last().map(|kv| Vec::<u8>::from(kv.0.clone()));
+            for kv in elements {
+                if self.repeatable || seen.insert(kv.0.clone()) {
+                    sampled.push(kv);
+                } else {
+                    hit_sampled = true;
+                }
+            }
+            if hit_sampled {
+                // the successor of the last key of the window
+                next_from = last_key.map(|mut key| {
+                    key.push(0);
+                    key
+                });
+            }
+        }
+
+        sampled.truncate(wanted);
+        sampled.shuffle(&mut rng);
+        Ok(sampled)
+    }
+}
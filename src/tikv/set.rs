@@ -2,6 +2,7 @@ use super::client::get_version_for_new;
 use super::errors::*;
 use super::gen_next_meta_index;
 use super::get_txn_client;
+use super::sample::Sampling;
 use super::KEY_ENCODER;
 use super::{
     encoding::{DataType, KeyDecoder},
@@ -9,24 +10,24 @@ use super::{
 };
 use crate::async_del_set_threshold_or_default;
 use crate::async_expire_set_threshold_or_default;
-use crate::utils::count_unique_keys;
+use crate::utils::{count_unique_keys, glob_match, resp_scan};
 use crate::utils::{key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil};
 use crate::Frame;
 use ::futures::future::FutureExt;
-use rand::prelude::SliceRandom;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
+use std::ops::Range;
 use std::sync::Arc;
-use tikv_client::Key;
 use tikv_client::Transaction;
 use tikv_client::Value;
+use tikv_client::{BoundRange, Key};
 use tokio::sync::Mutex;
 
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use crate::metrics::{CMD_PATH_COUNTER, REMOVED_EXPIRED_KEY_COUNTER};
+use crate::notify::notify_expired;
 
-use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
-
-const RANDOM_BASE: i64 = 100;
+/// Members of the smallest set scanned at a time by SINTERCARD
+const SINTERCARD_SCAN_BATCH: u32 = 1000;
 
 #[derive(Clone)]
 pub struct SetCommandCtx {
@@ -333,19 +334,23 @@ impl SetCommandCtx {
             .await
     }
 
-    // The rand is a fake algirithm for performance, the members will be random
-    // get from first 100 elements, if count is above 100, `count` members will be
-    // returned, so client should not be strongly rely on the random behavior
+    // The members are sampled by `Sampling::sample`, exactly for small sets and
+    // by windows after random start keys for large ones, so clients should not
+    // strongly rely on the random behavior
     pub async fn do_async_txnkv_srandmemeber(
         mut self,
         key: &str,
-        count: i64,
-        repeatable: bool,
+        sampling: Sampling,
         array_resp: bool,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
         let key = key.to_owned();
+        let empty_resp = if array_resp {
+            resp_array(vec![])
+        } else {
+            resp_nil()
+        };
 
         client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
@@ -355,82 +360,42 @@ impl SetCommandCtx {
                     }
 
                     let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(empty_resp),
+                    };
+                    drop(txn);
+
+                    // check key type and ttl
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Set) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
 
-                    match txn.get(meta_key).await? {
-                        Some(meta_value) => {
-                            // check key type and ttl
-                            if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Set) {
-                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
-                            }
-
-                            let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
-                            if key_is_expired(ttl) {
-                                drop(txn);
-                                self.clone()
-                                    .do_async_txnkv_set_expire_if_needed(&key)
-                                    .await?;
-                                return Ok(resp_array(vec![]));
-                            }
-
-                            // create random
-                            let mut rng = SmallRng::from_entropy();
-
-                            let mut ele_count = RANDOM_BASE;
-                            if count > RANDOM_BASE {
-                                ele_count = count;
-                            }
-
-                            let bound_range =
-                                KEY_ENCODER.encode_txnkv_set_data_key_range(&key, version);
-                            let iter = txn
-                                .scan_keys(bound_range, ele_count.try_into().unwrap())
-                                .await?;
-
-                            let mut resp: Vec<Frame> = iter
-                                .map(|k| {
-                                    // decode member from data key
-                                    let user_key =
-                                        KeyDecoder::decode_key_set_member_from_datakey(&key, k);
-                                    resp_bulk(user_key)
-                                })
-                                .collect();
-
-                            // shuffle the resp vector
-                            resp.shuffle(&mut rng);
-
-                            let resp_len = resp.len();
-
-                            if !array_resp {
-                                assert!(count == 1);
-
-                                // called with no count argument, return bulk reply
-                                // choose a random from resp
-                                let rand_idx = rng.gen_range(0..resp_len);
-                                return Ok(resp[rand_idx].clone());
-                            }
-
-                            // check resp is enough when repeatable is set, fill it with random element in resp vector
-                            while repeatable && (resp.len() as i64) < count {
-                                let rand_idx = rng.gen_range(0..resp_len);
-                                resp.push(resp[rand_idx].clone());
-                            }
-
-                            // if count is less than resp.len(), truncate it
-                            if count < resp_len as i64 {
-                                resp.truncate(count.try_into().unwrap());
-                            }
+                    let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        self.clone()
+                            .do_async_txnkv_set_expire_if_needed(&key)
+                            .await?;
+                        return Ok(empty_resp);
+                    }
 
-                            Ok(resp_array(resp))
-                        }
-                        None => {
-                            if array_resp {
-                                Ok(resp_array(vec![]))
-                            } else {
-                                assert!(count == 1);
-                                Ok(resp_nil())
-                            }
-                        }
+                    let size = self.clone().txnkv_sum_key_size(&key, version).await?;
+                    let bound_range = KEY_ENCODER.encode_txnkv_set_data_key_range(&key, version);
+                    let mut resp: Vec<Frame> = sampling
+                        .sample(&mut *txn_rc.lock().await, bound_range, size as usize)
+                        .await?
+                        .into_iter()
+                        .map(|kv| {
+                            // decode member from data key
+                            resp_bulk(KeyDecoder::decode_key_set_member_from_datakey(&key, kv.0))
+                        })
+                        .collect();
+
+                    if !array_resp {
+                        // called with no count argument, return bulk reply
+                        return Ok(resp.pop().unwrap_or(empty_resp));
                     }
+                    Ok(resp_array(resp))
                 }
                 .boxed()
             })
@@ -480,9 +445,9 @@ impl SetCommandCtx {
                                 })
                                 .collect();
 
-                            Ok(resp_array(resp))
+                            Ok(Frame::Set(resp))
                         }
-                        None => Ok(resp_array(vec![])),
+                        None => Ok(Frame::Set(vec![])),
                     }
                 }
                 .boxed()
@@ -490,6 +455,84 @@ impl SetCommandCtx {
             .await
     }
 
+    /// Scan at most `count` members from the member `start`, the members not
+    /// matching `pattern` are filtered out after they are read. The scan
+    /// resumes from the member after the last one read.
+    pub async fn do_async_txnkv_sscan(
+        mut self,
+        key: &str,
+        start: &[u8],
+        count: u32,
+        pattern: Option<&str>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+        let start = start.to_vec();
+        let pattern = pattern.map(|p| p.as_bytes().to_vec());
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(resp_scan(None, vec![])),
+                    };
+                    drop(txn);
+
+                    // check key type and ttl
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Set) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
+
+                    let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        self.clone()
+                            .do_async_txnkv_set_expire_if_needed(&key)
+                            .await?;
+                        return Ok(resp_scan(None, vec![]));
+                    }
+
+                    let mut range_start: Vec<u8> = KEY_ENCODER
+                        .encode_txnkv_set_data_key_start(&key, version)
+                        .into();
+                    range_start.extend_from_slice(&start);
+                    let range: Range<Key> = range_start.into()
+                        ..KEY_ENCODER.encode_txnkv_set_data_key_end(&key, version);
+                    // one more member is read, the next scan starts from it
+                    let mut members: Vec<Vec<u8>> = txn_rc
+                        .lock()
+                        .await
+                        .scan_keys(range, count.saturating_add(1))
+                        .await?
+                        .map(|k| KeyDecoder::decode_key_set_member_from_datakey(&key, k))
+                        .collect();
+                    let next = if members.len() > count as usize {
+                        members.pop()
+                    } else {
+                        None
+                    };
+
+                    let resp = members
+                        .into_iter()
+                        .filter(|member| match &pattern {
+                            Some(pattern) => glob_match(pattern, member),
+                            None => true,
+                        })
+                        .map(resp_bulk)
+                        .collect();
+                    Ok(resp_scan(next, resp))
+                }
+                .boxed()
+            })
+            .await
+    }
+
     pub async fn do_async_txnkv_srem(
         mut self,
         key: &str,
@@ -714,6 +757,7 @@ impl SetCommandCtx {
                             txn = txn_rc.lock().await;
 
                             if size > async_del_set_threshold_or_default() as i64 {
+                                CMD_PATH_COUNTER.with_label_values(&["del", "async"]).inc();
                                 // async del set
                                 // do async del
                                 txn.delete(meta_key).await?;
@@ -729,6 +773,7 @@ impl SetCommandCtx {
                                 )
                                 .await?;
                             } else {
+                                CMD_PATH_COUNTER.with_label_values(&["del", "sync"]).inc();
                                 let sub_meta_range =
                                     KEY_ENCODER.encode_txnkv_sub_meta_key_range(&key, version);
                                 let iter = txn.scan_keys(sub_meta_range, u32::MAX).await?;
@@ -756,6 +801,134 @@ impl SetCommandCtx {
             .await
     }
 
+    /// Count the members of the intersection of the sets without building
+    /// it, the smallest set is scanned in batches and its members are looked
+    /// up in the other sets. Counting stops once `limit` members are found,
+    /// 0 means no limit.
+    pub async fn do_async_txnkv_sintercard(
+        mut self,
+        keys: &[String],
+        limit: u64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    // (size, key, version) of the sets, all the keys are type
+                    // checked even if one of them does not exist
+                    let mut sets = Vec::with_capacity(keys.len());
+                    let mut empty = false;
+                    for key in &keys {
+                        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+                        let meta_value = txn_rc.lock().await.get(meta_key).await?;
+                        let meta_value = match meta_value {
+                            Some(meta_value) => meta_value,
+                            None => {
+                                empty = true;
+                                continue;
+                            }
+                        };
+                        if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Set) {
+                            return Err(REDIS_WRONG_TYPE_ERR);
+                        }
+                        let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                        if key_is_expired(ttl) {
+                            self.clone()
+                                .do_async_txnkv_set_expire_if_needed(key)
+                                .await?;
+                            empty = true;
+                            continue;
+                        }
+                        let size = self.clone().txnkv_sum_key_size(key, version).await?;
+                        sets.push((size, key.clone(), version));
+                    }
+                    if empty {
+                        return Ok(0);
+                    }
+
+                    sets.sort_by_key(|set| set.0);
+                    let (_, first_key, first_version) = &sets[0];
+                    let mut start =
+                        KEY_ENCODER.encode_txnkv_set_data_key_start(first_key, *first_version);
+                    let end = KEY_ENCODER.encode_txnkv_set_data_key_end(first_key, *first_version);
+                    let prefix_len = Vec::<u8>::from(start.clone()).len();
+                    // the data keys of a member only differ in the prefix
+                    let others: Vec<Vec<u8>> = sets[1..]
+                        .iter()
+                        .map(|(_, key, version)| {
+                            KEY_ENCODER
+                                .encode_txnkv_set_data_key_start(key, *version)
+                                .into()
+                        })
+                        .collect();
+
+                    let mut txn = txn_rc.lock().await;
+                    let mut count = 0;
+                    loop {
+                        let range: BoundRange = (start..end.clone()).into();
+                        let data_keys: Vec<Key> =
+                            txn.scan_keys(range, SINTERCARD_SCAN_BATCH).await?.collect();
+                        let scanned = data_keys.len();
+                        let last = match data_keys.last() {
+                            Some(last) => last.clone(),
+                            None => break,
+                        };
+
+                        let mut members: Vec<Vec<u8>> = data_keys
+                            .into_iter()
+                            .map(|k| Vec::<u8>::from(k)[prefix_len..].to_vec())
+                            .collect();
+                        for other in &others {
+                            if members.is_empty() {
+                                break;
+                            }
+                            let other_keys: Vec<Key> = members
+                                .iter()
+                                .map(|m| [other.as_slice(), m.as_slice()].concat().into())
+                                .collect();
+                            let found: HashSet<Key> = txn
+                                .batch_get(other_keys.clone())
+                                .await?
+                                .map(|kv| kv.0)
+                                .collect();
+                            members = members
+                                .into_iter()
+                                .zip(other_keys)
+                                .filter(|(_, k)| found.contains(k))
+                                .map(|(m, _)| m)
+                                .collect();
+                        }
+
+                        count += members.len() as u64;
+                        if limit > 0 && count >= limit {
+                            count = limit;
+                            break;
+                        }
+                        if scanned < SINTERCARD_SCAN_BATCH as usize {
+                            break;
+                        }
+                        let mut next: Vec<u8> = last.into();
+                        next.push(0);
+                        start = next.into();
+                    }
+                    Ok(count as i64)
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(v) => Ok(resp_int(v)),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
     pub async fn do_async_txnkv_set_expire_if_needed(mut self, key: &str) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
@@ -780,6 +953,9 @@ impl SetCommandCtx {
                             txn = txn_rc.lock().await;
 
                             if size > async_expire_set_threshold_or_default() as i64 {
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["expire", "async"])
+                                    .inc();
                                 // async del set
                                 txn.delete(meta_key).await?;
 
@@ -794,6 +970,9 @@ impl SetCommandCtx {
                                 )
                                 .await?;
                             } else {
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["expire", "sync"])
+                                    .inc();
                                 let sub_meta_range =
                                     KEY_ENCODER.encode_txnkv_sub_meta_key_range(&key, version);
                                 let iter = txn.scan_keys(sub_meta_range, u32::MAX).await?;
@@ -813,6 +992,7 @@ impl SetCommandCtx {
                             REMOVED_EXPIRED_KEY_COUNTER
                                 .with_label_values(&["set"])
                                 .inc();
+                            notify_expired(&key);
 
                             Ok(1)
                         }
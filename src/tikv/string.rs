@@ -9,22 +9,373 @@ use crate::{
     Frame,
 };
 use ::futures::future::FutureExt;
+use rand::{thread_rng, Rng};
 use regex::bytes::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryInto;
+use std::ops::Range;
 use std::str;
 use std::sync::Arc;
 use tikv_client::{BoundRange, Key, KvPair, Transaction, Value};
 use tokio::sync::Mutex;
 
+use super::client::get_version_for_new;
 use super::errors::*;
 use super::{get_client, get_txn_client};
 use super::{hash::HashCommandCtx, list::ListCommandCtx, set::SetCommandCtx, zset::ZsetCommandCtx};
 use crate::utils::{
-    key_is_expired, resp_err, resp_int, resp_ok_ignore, resp_str, sleep, ttl_from_timestamp,
+    format_float, key_is_expired, now_timestamp_in_millis, resp_err, resp_int, resp_ok_ignore,
+    resp_str, sleep, ttl_from_timestamp,
 };
 use bytes::Bytes;
 
-use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::metrics::{CMD_PATH_COUNTER, REMOVED_EXPIRED_KEY_COUNTER};
+use crate::notify::notify_expired;
+use crate::{lazyfree_lazy_server_del_or_default, proto_max_bulk_len_or_default};
+
+/// Member keys counted at a time by the size meta repair
+const REPAIR_META_SCAN_BATCH: u32 = 1000;
+
+/// Keys deleted in one transaction by a DEL out of MULTI and scripts
+const DEL_BATCH_KEYS: usize = 256;
+
+/// Bitwise operation of BITOP
+#[derive(Debug, Clone, Copy)]
+pub enum BitOperation {
+    And,
+    Or,
+    Xor,
+    Not,
+}
+
+/// Apply a bitwise operation across the values, the shorter values are
+/// padded with zero bytes to the length of the longest.
+fn bitop(op: BitOperation, values: &[Vec<u8>]) -> Vec<u8> {
+    let len = values.iter().map(|v| v.len()).max().unwrap_or(0);
+    let byte_at = |v: &Vec<u8>, idx: usize| v.get(idx).copied().unwrap_or(0);
+    (0..len)
+        .map(|idx| {
+            let mut bytes = values.iter().map(|v| byte_at(v, idx));
+            let first = bytes.next().unwrap_or(0);
+            match op {
+                BitOperation::And => bytes.fold(first, |acc, b| acc & b),
+                BitOperation::Or => bytes.fold(first, |acc, b| acc | b),
+                BitOperation::Xor => bytes.fold(first, |acc, b| acc ^ b),
+                BitOperation::Not => !first,
+            }
+        })
+        .collect()
+}
+
+/// Type of a BITFIELD field, i1..i64 or u1..u63
+#[derive(Debug, Clone, Copy)]
+pub struct BitfieldType {
+    pub signed: bool,
+    pub bits: u32,
+}
+
+/// Behavior of BITFIELD SET and INCRBY on overflow
+#[derive(Debug, Clone, Copy)]
+pub enum BitfieldOverflow {
+    Wrap,
+    Sat,
+    Fail,
+}
+
+/// Sub-operation of BITFIELD, the offsets are in bits
+#[derive(Debug, Clone, Copy)]
+pub enum BitfieldOp {
+    Get(BitfieldType, u64),
+    Set(BitfieldType, u64, i64, BitfieldOverflow),
+    Incrby(BitfieldType, u64, i64, BitfieldOverflow),
+}
+
+/// Read a field of a string, the bits beyond the string are 0.
+fn bitfield_get(value: &[u8], ty: BitfieldType, offset: u64) -> i64 {
+    let mut raw: u64 = 0;
+    for pos in offset..offset + ty.bits as u64 {
+        let byte = value.get((pos >> 3) as usize).copied().unwrap_or(0);
+        raw = (raw << 1) | ((byte >> (7 - (pos & 7))) & 1) as u64;
+    }
+    // sign extend
+    if ty.signed && ty.bits < 64 && raw & (1 << (ty.bits - 1)) != 0 {
+        raw |= u64::MAX << ty.bits;
+    }
+    raw as i64
+}
+
+/// Write a field of a string, the string is grown with zero bytes to hold it.
+fn bitfield_set(value: &mut Vec<u8>, ty: BitfieldType, offset: u64, field: i64) {
+    let end = ((offset + ty.bits as u64 + 7) >> 3) as usize;
+    if value.len() < end {
+        value.resize(end, 0);
+    }
+    let raw = field as u64;
+    for i in 0..ty.bits as u64 {
+        let pos = offset + i;
+        let mask = 1u8 << (7 - (pos & 7));
+        if (raw >> (ty.bits as u64 - 1 - i)) & 1 == 1 {
+            value[(pos >> 3) as usize] |= mask;
+        } else {
+            value[(pos >> 3) as usize] &= !mask;
+        }
+    }
+}
+
+/// Fit a value in the range of a field type, `None` if it overflows with FAIL.
+fn bitfield_fit(ty: BitfieldType, v: i128, overflow: BitfieldOverflow) -> Option<i64> {
+    let (min, max) = if ty.signed {
+        (-(1i128 << (ty.bits - 1)), (1i128 << (ty.bits - 1)) - 1)
+    } else {
+        (0, (1i128 << ty.bits) - 1)
+    };
+    if v >= min && v <= max {
+        return Some(v as i64);
+    }
+    match overflow {
+        BitfieldOverflow::Wrap => Some(((v - min).rem_euclid(1i128 << ty.bits) + min) as i64),
+        BitfieldOverflow::Sat => Some(if v > max { max as i64 } else { min as i64 }),
+        BitfieldOverflow::Fail => None,
+    }
+}
+
+/// Version of the members of a collection, `None` for a string.
+fn collection_version(dt: &DataType, meta_value: &[u8]) -> Option<u16> {
+    match dt {
+        DataType::Hash | DataType::List | DataType::Set | DataType::Zset => {
+            Some(KeyDecoder::decode_key_version(meta_value))
+        }
+        DataType::String | DataType::Null => None,
+    }
+}
+
+/// Read the encoded keys and values stored for the key: the meta, sub meta
+/// keys and data keys, at most `limit` entries per range. The access time
+/// record is not included, empty if the key does not exist.
+pub(crate) async fn read_key_pairs(
+    txn: &mut Transaction,
+    key: &str,
+    limit: u32,
+) -> AsyncResult<Vec<KvPair>> {
+    let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+    let meta_value = match txn.get(meta_key.clone()).await? {
+        Some(v) => v,
+        None => return Ok(vec![]),
+    };
+    let ranges = key_ranges(key, &meta_value);
+
+    let mut pairs = vec![KvPair::new(meta_key, meta_value)];
+    for (range, _) in ranges {
+        pairs.extend(txn.scan(range, limit).await?);
+    }
+    Ok(pairs)
+}
+
+/// The ranges of the sub meta and data keys of the key described by the meta
+/// value, each flagged whether it holds one entry per member of a collection.
+fn key_ranges(key: &str, meta_value: &[u8]) -> Vec<(BoundRange, bool)> {
+    let version = KeyDecoder::decode_key_version(meta_value);
+    match KeyDecoder::decode_key_type(meta_value) {
+        DataType::Hash => vec![
+            (
+                KEY_ENCODER.encode_txnkv_sub_meta_key_range(key, version),
+                false,
+            ),
+            (
+                KEY_ENCODER.encode_txnkv_hash_data_key_range(key, version),
+                true,
+            ),
+        ],
+        DataType::List => vec![(
+            KEY_ENCODER.encode_txnkv_list_data_key_range(key, version),
+            true,
+        )],
+        DataType::Set => vec![
+            (
+                KEY_ENCODER.encode_txnkv_sub_meta_key_range(key, version),
+                false,
+            ),
+            (
+                KEY_ENCODER.encode_txnkv_set_data_key_range(key, version),
+                true,
+            ),
+        ],
+        DataType::Zset => vec![
+            (
+                KEY_ENCODER.encode_txnkv_sub_meta_key_range(key, version),
+                false,
+            ),
+            (
+                KEY_ENCODER.encode_txnkv_zset_data_key_range(key, version),
+                true,
+            ),
+            (
+                KEY_ENCODER.encode_txnkv_zset_score_key_range(key, version),
+                false,
+            ),
+        ],
+        _ => vec![],
+    }
+}
+
+/// Values up to this length are reported as embstr strings, as in redis.
+const OBJECT_EMBSTR_SIZE_LIMIT: usize = 44;
+
+/// Encoding name reported by OBJECT ENCODING for the meta value of a key. All
+/// the values are stored the same way in TiKV, the names of the general
+/// encodings of redis are used. The encoding of a string is derived from its
+/// value, a short value modified by APPEND or SETRANGE is embstr, not raw.
+fn object_encoding(meta_value: &[u8]) -> &'static str {
+    match KeyDecoder::decode_key_type(meta_value) {
+        DataType::String => {
+            let value = KeyDecoder::decode_key_string_slice(meta_value);
+            match str::from_utf8(value).map(str::parse::<i64>) {
+                // only the canonical form of an integer is encoded as int
+                Ok(Ok(n)) if n.to_string().as_bytes() == value => "int",
+                _ if value.len() <= OBJECT_EMBSTR_SIZE_LIMIT => "embstr",
+                _ => "raw",
+            }
+        }
+        DataType::Hash | DataType::Set => "hashtable",
+        DataType::List => "quicklist",
+        DataType::Zset => "skiplist",
+        DataType::Null => "none",
+    }
+}
+
+/// Clamp an inclusive range of a value of `len` bytes the same way as redis,
+/// offsets are in bytes or in bits if `in_bits` is set, negative ones count
+/// from the end. Returns the first and last bytes of the range and the masks
+/// of the bits out of the range in them, `None` if the range is empty.
+fn bit_range(
+    len: usize,
+    mut start: i64,
+    mut end: i64,
+    in_bits: bool,
+) -> Option<(usize, usize, u8, u8)> {
+    let total = if in_bits { len as i64 * 8 } else { len as i64 };
+    if start < 0 {
+        start += total;
+    }
+    if end < 0 {
+        end += total;
+    }
+    start = start.max(0);
+    end = end.max(0).min(total - 1);
+    if start > end {
+        return None;
+    }
+
+    // bits before start in the first byte and after end in the last byte
+    let (mut first_mask, mut last_mask) = (0u8, 0u8);
+    if in_bits {
+        first_mask = !(0xffu8 >> (start & 7));
+        last_mask = ((1u16 << (7 - (end & 7))) - 1) as u8;
+        start >>= 3;
+        end >>= 3;
+    }
+    Some((start as usize, end as usize, first_mask, last_mask))
+}
+
+/// Count the set bits of `value` in the inclusive `range` of (start, end,
+/// in bits), the whole value if `None`.
+fn bitcount(value: &[u8], range: Option<(i64, i64, bool)>) -> i64 {
+    let (start, end, in_bits) = range.unwrap_or((0, -1, false));
+    let (start, end, first_mask, last_mask) = match bit_range(value.len(), start, end, in_bits) {
+        Some(range) => range,
+        None => return 0,
+    };
+    let bytes = &value[start..=end];
+    let mut count: i64 = bytes.iter().map(|b| b.count_ones() as i64).sum();
+    count -= (bytes[0] & first_mask).count_ones() as i64;
+    count -= (bytes[bytes.len() - 1] & last_mask).count_ones() as i64;
+    count
+}
+
+/// Find the first bit set to `bit` in the inclusive range of `value`, see
+/// `bit_range`. If `end` is not given a clear bit is found in the zero bits
+/// padded after the value, otherwise -1 is returned if the bit is not found.
+fn bitpos(value: &[u8], bit: bool, start: Option<i64>, end: Option<i64>, in_bits: bool) -> i64 {
+    let padded = end.is_none();
+    let range = bit_range(value.len(), start.unwrap_or(0), end.unwrap_or(-1), in_bits);
+    let (start, end, first_mask, last_mask) = match range {
+        Some(range) => range,
+        None => return -1,
+    };
+
+    // the bits out of the range are flipped to the bit not looked for
+    let mut bytes = value[start..=end].to_vec();
+    let last = bytes.len() - 1;
+    if bit {
+        bytes[0] &= !first_mask;
+        bytes[last] &= !last_mask;
+    } else {
+        bytes[0] |= first_mask;
+        bytes[last] |= last_mask;
+    }
+
+    let skip = if bit { 0x00 } else { 0xff };
+    if let Some(idx) = bytes.iter().position(|&b| b != skip) {
+        let b = if bit { bytes[idx] } else { !bytes[idx] };
+        return ((start + idx) * 8) as i64 + b.leading_zeros() as i64;
+    }
+    if !bit && padded {
+        return ((start + bytes.len()) * 8) as i64;
+    }
+    -1
+}
+
+/// A random user key between `first` and `last`. The bytes after their common
+/// prefix are read as a big endian number, a number is picked uniformly in
+/// between and appended to the common prefix.
+fn random_key_pivot(first: &[u8], last: &[u8]) -> Vec<u8> {
+    let common = first
+        .iter()
+        .zip(last.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let window = |key: &[u8]| {
+        let tail = &key[common..];
+        let mut buf = [0u8; 8];
+        let len = tail.len().min(8);
+        buf[..len].copy_from_slice(&tail[..len]);
+        u64::from_be_bytes(buf)
+    };
+    let mut pivot = first[..common].to_vec();
+    pivot.extend_from_slice(
+        &thread_rng()
+            .gen_range(window(first)..=window(last))
+            .to_be_bytes(),
+    );
+    pivot
+}
+
+/// The first user key of a meta key in `from..to` which is not expired.
+async fn txnkv_first_live_key(
+    txn: &mut Transaction,
+    from: Key,
+    to: Key,
+) -> AsyncResult<Option<Vec<u8>>> {
+    let batch = 100;
+    let mut left: Vec<u8> = from.into();
+    loop {
+        let range: Range<Key> = left.clone().into()..to.clone();
+        let kvs: Vec<KvPair> = txn.scan(range, batch).await?.collect();
+        let len = kvs.len();
+        for kv in kvs {
+            let (userkey, is_meta_key) = KeyDecoder::decode_key_userkey_from_metakey(&kv.0);
+            if is_meta_key && !key_is_expired(KeyDecoder::decode_key_ttl(&kv.1)) {
+                return Ok(Some(userkey));
+            }
+            left = kv.0.into();
+        }
+        if len < batch as usize {
+            return Ok(None);
+        }
+        // continue right after the last key of the batch
+        left.push(0);
+    }
+}
 
 #[derive(Clone)]
 pub struct StringCommandCtx {
@@ -142,6 +493,61 @@ impl StringCommandCtx {
             .await
     }
 
+    /// Reply a random key of the keyspace, nil if it is empty.
+    ///
+    /// TiKV has no random access to the keys. A random pivot is picked between
+    /// the first and the last user key and the first key at or after it is
+    /// replied, wrapping to the start of the keyspace. The pivot is uniform over
+    /// the byte strings in between, not over the keys, so the choice is biased:
+    /// a key following a wide gap in the key order is picked more often than a
+    /// key in a dense run, e.g. `b` is picked far more often than `a1` from
+    /// `a1 a2 a3 b`. The keys after the data of a big collection are as likely
+    /// but slower to reach. Expired keys are skipped.
+    pub async fn do_async_txnkv_randomkey(mut self) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+
+        // if randomkey is executed from a new transaction, read the latest commit
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let start = KEY_ENCODER.encode_txnkv_keyspace_start();
+                    let end = KEY_ENCODER.encode_txnkv_keyspace_end();
+
+                    let range: Range<Key> = start.clone()..end.clone();
+                    let first = match txn.scan(range.clone(), 1).await?.next() {
+                        Some(kv) => KeyDecoder::decode_key_userkey_from_metakey(&kv.0).0,
+                        None => return Ok(resp_nil()),
+                    };
+                    let last = match txn.scan_reverse(range, 1).await?.next() {
+                        Some(kv) => KeyDecoder::decode_key_userkey_from_metakey(&kv.0).0,
+                        None => first.clone(),
+                    };
+
+                    let pivot = KEY_ENCODER
+                        .encode_txnkv_meta_key_from_bytes(&random_key_pivot(&first, &last));
+                    if let Some(key) = txnkv_first_live_key(&mut txn, pivot.clone(), end).await? {
+                        return Ok(resp_bulk(key));
+                    }
+                    match txnkv_first_live_key(&mut txn, start, pivot).await? {
+                        Some(key) => Ok(resp_bulk(key)),
+                        None => Ok(resp_nil()),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
     pub async fn do_async_rawkv_strlen(&self, key: &str) -> AsyncResult<Frame> {
         let client = get_client()?;
         let ekey = KEY_ENCODER.encode_rawkv_string(key);
@@ -212,7 +618,8 @@ impl StringCommandCtx {
         timestamp: u64,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
-        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+        let ekey = KEY_ENCODER.encode_txnkv_string(&key);
         let eval = KEY_ENCODER.encode_txnkv_string_value(&mut val.to_vec(), timestamp);
         let resp = client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
@@ -221,6 +628,33 @@ impl StringCommandCtx {
                         self.txn = Some(txn_rc.clone());
                     }
                     let mut txn = txn_rc.lock().await;
+
+                    // the members of a collection overwritten by the string are
+                    // freed in the same transaction, readers never see a mix of
+                    // the old and the new value
+                    if let Some(meta_value) = txn.get(ekey.clone()).await? {
+                        let dt = KeyDecoder::decode_key_type(&meta_value);
+                        if let Some(version) = collection_version(&dt, &meta_value) {
+                            if lazyfree_lazy_server_del_or_default() {
+                                // the gc workers sweep the members of the old version
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["overwrite", "async"])
+                                    .inc();
+                                let gc_key = KEY_ENCODER.encode_txnkv_gc_key(&key);
+                                txn.put(gc_key, version.to_be_bytes()).await?;
+
+                                let gc_version_key =
+                                    KEY_ENCODER.encode_txnkv_gc_version_key(&key, version);
+                                txn.put(gc_version_key, vec![KEY_ENCODER.get_type_bytes(dt)])
+                                    .await?;
+                            } else {
+                                drop(txn);
+                                self.clone().txnkv_collection_del(&key, dt).await?;
+                                txn = txn_rc.lock().await;
+                            }
+                        }
+                    }
+
                     txn.put(ekey, eval).await?;
                     Ok(())
                 }
@@ -347,47 +781,848 @@ impl StringCommandCtx {
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
-        let ekey = KEY_ENCODER.encode_txnkv_string(&key);
-        let eval = KEY_ENCODER.encode_txnkv_string_value(&mut value.to_vec(), 0);
+        let ekey = KEY_ENCODER.encode_txnkv_string(&key);
+        let eval = KEY_ENCODER.encode_txnkv_string_value(&mut value.to_vec(), 0);
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let old_value = txn.get(ekey.clone()).await?;
+                    if let Some(ref v) = old_value {
+                        let ttl = KeyDecoder::decode_key_ttl(v);
+                        if key_is_expired(ttl) {
+                            // no need to delete, just overwrite
+                            txn.put(ekey, eval).await?;
+                            return Ok(1);
+                        }
+                        Ok(0)
+                    } else {
+                        txn.put(ekey, eval).await?;
+                        Ok(1)
+                    }
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(n) => {
+                if return_number {
+                    return Ok(resp_int(n as i64));
+                }
+                if n == 0 {
+                    Ok(resp_nil())
+                } else {
+                    Ok(resp_ok())
+                }
+            }
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
+    /// SET with the IFEQ or GET option. The old value is read, compared and
+    /// replaced in the same transaction, so a lease token is swapped atomically.
+    ///
+    /// Replies the old value with GET, otherwise OK or nil if the value was not
+    /// set.
+    pub async fn do_async_txnkv_put_cond(
+        mut self,
+        key: &str,
+        value: &Bytes,
+        timestamp: u64,
+        nx: bool,
+        ifeq: Option<Bytes>,
+        get: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let eval = KEY_ENCODER.encode_txnkv_string_value(&mut value.to_vec(), timestamp);
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let old_value = match txn.get(ekey.clone()).await? {
+                        Some(meta_value) => {
+                            let dt = KeyDecoder::decode_key_type(&meta_value);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+                            // an expired value is overwritten as if the key is absent
+                            let ttl = KeyDecoder::decode_key_ttl(&meta_value);
+                            if key_is_expired(ttl) {
+                                None
+                            } else {
+                                Some(KeyDecoder::decode_key_string_value(&meta_value))
+                            }
+                        }
+                        None => None,
+                    };
+
+                    let matched = match &ifeq {
+                        Some(expected) => old_value.as_deref() == Some(&expected[..]),
+                        None => !nx || old_value.is_none(),
+                    };
+                    if matched {
+                        txn.put(ekey, eval).await?;
+                    }
+
+                    if get {
+                        Ok(old_value.map_or_else(resp_nil, resp_bulk))
+                    } else if matched {
+                        Ok(resp_ok())
+                    } else {
+                        Ok(resp_nil())
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Read the value of a string and delete the key in the same transaction.
+    pub async fn do_async_txnkv_getdel(mut self, key: &str) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let ekey = KEY_ENCODER.encode_txnkv_string(&key);
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(ekey.clone()).await? {
+                        Some(meta_value) => {
+                            let dt = KeyDecoder::decode_key_type(&meta_value);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            txn.delete(ekey).await?;
+                            txn.delete(KEY_ENCODER.encode_txnkv_access_key(&key))
+                                .await?;
+
+                            let ttl = KeyDecoder::decode_key_ttl(&meta_value);
+                            if key_is_expired(ttl) {
+                                REMOVED_EXPIRED_KEY_COUNTER
+                                    .with_label_values(&["string"])
+                                    .inc();
+                                notify_expired(&key);
+                                return Ok(resp_nil());
+                            }
+                            Ok(resp_bulk(KeyDecoder::decode_key_string_value(&meta_value)))
+                        }
+                        None => Ok(resp_nil()),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Read the value of a string and update its expire `timestamp` in the
+    /// same transaction. `None` keeps the ttl, 0 persists the key and a
+    /// timestamp in the past deletes it.
+    pub async fn do_async_txnkv_getex(
+        mut self,
+        key: &str,
+        timestamp: Option<u64>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let ekey = KEY_ENCODER.encode_txnkv_string(&key);
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(ekey.clone()).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(resp_nil()),
+                    };
+                    let dt = KeyDecoder::decode_key_type(&meta_value);
+                    if !matches!(dt, DataType::String) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
+
+                    let ttl = KeyDecoder::decode_key_ttl(&meta_value);
+                    if key_is_expired(ttl) {
+                        drop(txn);
+                        self.do_async_txnkv_string_expire_if_needed(&key).await?;
+                        return Ok(resp_nil());
+                    }
+
+                    let value = KeyDecoder::decode_key_string_slice(&meta_value);
+                    match timestamp {
+                        Some(ts) if ts > 0 && ts <= now_timestamp_in_millis() => {
+                            txn.delete(ekey).await?;
+                            txn.delete(KEY_ENCODER.encode_txnkv_access_key(&key))
+                                .await?;
+                            return Ok(resp_nil());
+                        }
+                        Some(ts) if ts != ttl => {
+                            let new_meta_value = KEY_ENCODER.encode_txnkv_string_slice(value, ts);
+                            txn.put(ekey, new_meta_value).await?;
+                        }
+                        _ => {}
+                    }
+                    Ok(resp_bulk(value.to_vec()))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Copy the value and the ttl of `src` to `dst`, whatever the type. The
+    /// members of a collection are copied under a new version of `dst`.
+    ///
+    /// Replies 0 without changes if `src` does not exist, or `dst` exists and
+    /// `replace` is not set. Otherwise the old value of `dst` is removed the
+    /// same way as DEL before the copy.
+    pub async fn do_async_txnkv_copy(
+        mut self,
+        src: &str,
+        dst: &str,
+        replace: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let src = src.to_owned();
+        let dst = dst.to_owned();
+        let src_meta_key = KEY_ENCODER.encode_txnkv_meta_key(&src);
+        let dst_meta_key = KEY_ENCODER.encode_txnkv_meta_key(&dst);
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let src_meta = match txn.get(src_meta_key).await? {
+                        Some(v) if !key_is_expired(KeyDecoder::decode_key_ttl(&v)) => v,
+                        _ => return Ok(resp_int(0)),
+                    };
+
+                    if let Some(dst_meta) = txn.get(dst_meta_key.clone()).await? {
+                        if !replace && !key_is_expired(KeyDecoder::decode_key_ttl(&dst_meta)) {
+                            return Ok(resp_int(0));
+                        }
+                        let dt = KeyDecoder::decode_key_type(&dst_meta);
+                        if collection_version(&dt, &dst_meta).is_some() {
+                            drop(txn);
+                            self.clone().txnkv_collection_del(&dst, dt).await?;
+                            txn = txn_rc.lock().await;
+                        } else {
+                            txn.delete(dst_meta_key.clone()).await?;
+                        }
+                        txn.delete(KEY_ENCODER.encode_txnkv_access_key(&dst))
+                            .await?;
+                    }
+
+                    let dt = KeyDecoder::decode_key_type(&src_meta);
+                    let src_version = match collection_version(&dt, &src_meta) {
+                        Some(version) => version,
+                        None => {
+                            // the value of a string is stored in its meta
+                            txn.put(dst_meta_key, src_meta).await?;
+                            return Ok(resp_int(1));
+                        }
+                    };
+                    drop(txn);
+                    let dst_version = get_version_for_new(&dst, txn_rc.clone()).await?;
+                    let mut txn = txn_rc.lock().await;
+
+                    // (start and end of the src range, start of the dst range)
+                    let mut ranges = vec![(
+                        KEY_ENCODER.encode_txnkv_sub_meta_key_start(&src, src_version),
+                        KEY_ENCODER.encode_txnkv_sub_meta_key_end(&src, src_version),
+                        KEY_ENCODER.encode_txnkv_sub_meta_key_start(&dst, dst_version),
+                    )];
+                    match dt {
+                        DataType::Hash => ranges.push((
+                            KEY_ENCODER.encode_txnkv_hash_data_key_start(&src, src_version),
+                            KEY_ENCODER.encode_txnkv_hash_data_key_end(&src, src_version),
+                            KEY_ENCODER.encode_txnkv_hash_data_key_start(&dst, dst_version),
+                        )),
+                        DataType::List => ranges.push((
+                            KEY_ENCODER.encode_txnkv_list_data_key_start(&src, src_version),
+                            KEY_ENCODER.encode_txnkv_list_data_key_end(&src, src_version),
+                            KEY_ENCODER.encode_txnkv_list_data_key_start(&dst, dst_version),
+                        )),
+                        DataType::Set => ranges.push((
+                            KEY_ENCODER.encode_txnkv_set_data_key_start(&src, src_version),
+                            KEY_ENCODER.encode_txnkv_set_data_key_end(&src, src_version),
+                            KEY_ENCODER.encode_txnkv_set_data_key_start(&dst, dst_version),
+                        )),
+                        DataType::Zset => {
+                            ranges.push((
+                                KEY_ENCODER.encode_txnkv_zset_data_key_start(&src, src_version),
+                                KEY_ENCODER.encode_txnkv_zset_data_key_end(&src, src_version),
+                                KEY_ENCODER.encode_txnkv_zset_data_key_start(&dst, dst_version),
+                            ));
+                            ranges.push((
+                                KEY_ENCODER.encode_txnkv_zset_score_key_start(&src, src_version),
+                                KEY_ENCODER.encode_txnkv_zset_score_key_end(&src, src_version),
+                                KEY_ENCODER.encode_txnkv_zset_score_key_start(&dst, dst_version),
+                            ));
+                        }
+                        DataType::String | DataType::Null => {}
+                    }
+
+                    // the keys of the members only differ in the prefix of the
+                    // user key and version
+                    for (start, end, dst_start) in ranges {
+                        let prefix_len = Vec::<u8>::from(start.clone()).len();
+                        let dst_start: Vec<u8> = dst_start.into();
+                        let range: BoundRange = (start..end).into();
+                        for kv in txn.scan(range, u32::MAX).await? {
+                            let src_key: Vec<u8> = kv.0.into();
+                            let mut dst_key = dst_start.clone();
+                            dst_key.extend_from_slice(&src_key[prefix_len..]);
+                            txn.put(dst_key, kv.1).await?;
+                        }
+                    }
+
+                    let mut dst_meta = src_meta;
+                    dst_meta[9..11].copy_from_slice(&dst_version.to_be_bytes());
+                    txn.put(dst_meta_key, dst_meta).await?;
+                    Ok(resp_int(1))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Count the set bits of a string in `range`, see `bitcount`. A missing
+    /// key counts 0.
+    pub async fn do_async_txnkv_bitcount(
+        mut self,
+        key: &str,
+        range: Option<(i64, i64, bool)>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(ekey).await? {
+                        Some(val) => {
+                            let dt = KeyDecoder::decode_key_type(&val);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.do_async_txnkv_string_expire_if_needed(&key).await?;
+                                return Ok(resp_int(0));
+                            }
+
+                            let value = KeyDecoder::decode_key_string_slice(&val);
+                            Ok(resp_int(bitcount(value, range)))
+                        }
+                        None => Ok(resp_int(0)),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Find the first bit set to `bit` in a string, see `bitpos`. A missing
+    /// key is looked up as an empty string padded with zero bits.
+    pub async fn do_async_txnkv_bitpos(
+        mut self,
+        key: &str,
+        bit: bool,
+        start: Option<i64>,
+        end: Option<i64>,
+        in_bits: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+        let missing = if bit { -1 } else { 0 };
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(ekey).await? {
+                        Some(val) => {
+                            let dt = KeyDecoder::decode_key_type(&val);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.do_async_txnkv_string_expire_if_needed(&key).await?;
+                                return Ok(resp_int(missing));
+                            }
+
+                            let value = KeyDecoder::decode_key_string_slice(&val);
+                            Ok(resp_int(bitpos(value, bit, start, end, in_bits)))
+                        }
+                        None => Ok(resp_int(missing)),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Store the result of a bitwise operation across the source strings in
+    /// dst, the sources are read and dst is written in one transaction.
+    pub async fn do_async_txnkv_bitop(
+        mut self,
+        op: BitOperation,
+        dst: &str,
+        srcs: &[String],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let dst = dst.to_owned();
+        let ekeys = KEY_ENCODER.encode_txnkv_strings(srcs);
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut values = Vec::with_capacity(ekeys.len());
+                    {
+                        let mut txn = txn_rc.lock().await;
+                        for ekey in ekeys {
+                            let value = match txn.get(ekey).await? {
+                                Some(val) => {
+                                    let dt = KeyDecoder::decode_key_type(&val);
+                                    if !matches!(dt, DataType::String) {
+                                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                                    }
+                                    // an expired source is read as an empty string
+                                    let ttl = KeyDecoder::decode_key_ttl(&val);
+                                    if key_is_expired(ttl) {
+                                        vec![]
+                                    } else {
+                                        KeyDecoder::decode_key_string_value(&val)
+                                    }
+                                }
+                                None => vec![],
+                            };
+                            values.push(value);
+                        }
+                    }
+
+                    let result = bitop(op, &values);
+                    if result.is_empty() {
+                        return match self.do_async_txnkv_del(&vec![dst]).await? {
+                            Frame::Integer(_) => Ok(resp_int(0)),
+                            resp => Ok(resp),
+                        };
+                    }
+
+                    let len = result.len() as i64;
+                    self.do_async_txnkv_put(&dst, &Bytes::from(result), 0)
+                        .await?;
+                    Ok(resp_int(len))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    pub async fn do_async_txnkv_getbit(mut self, key: &str, offset: u64) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(ekey).await? {
+                        Some(val) => {
+                            let dt = KeyDecoder::decode_key_type(&val);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.do_async_txnkv_string_expire_if_needed(&key).await?;
+                                return Ok(resp_int(0));
+                            }
+
+                            let value = KeyDecoder::decode_key_string_slice(&val);
+                            let byte = value.get((offset >> 3) as usize).copied().unwrap_or(0);
+                            Ok(resp_int(((byte >> (7 - (offset & 7))) & 1) as i64))
+                        }
+                        None => Ok(resp_int(0)),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Set a bit of a string and reply the old bit, the string is grown with
+    /// zero bytes to hold the offset. The ttl of the key is kept.
+    pub async fn do_async_txnkv_setbit(
+        mut self,
+        key: &str,
+        offset: u64,
+        bit: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let (mut value, ttl) = match txn.get(ekey.clone()).await? {
+                        Some(val) => {
+                            let dt = KeyDecoder::decode_key_type(&val);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_string_expire_if_needed(&key)
+                                    .await?;
+                                txn = txn_rc.lock().await;
+                                (vec![], 0)
+                            } else {
+                                (KeyDecoder::decode_key_string_value(&val), ttl)
+                            }
+                        }
+                        None => (vec![], 0),
+                    };
+
+                    let idx = (offset >> 3) as usize;
+                    let mask = 1u8 << (7 - (offset & 7));
+                    if value.len() <= idx {
+                        value.resize(idx + 1, 0);
+                    }
+                    let old_bit = value[idx] & mask != 0;
+                    if bit {
+                        value[idx] |= mask;
+                    } else {
+                        value[idx] &= !mask;
+                    }
+
+                    let eval = KEY_ENCODER.encode_txnkv_string_value(&mut value, ttl);
+                    txn.put(ekey, eval).await?;
+                    Ok(resp_int(old_bit as i64))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Apply the BITFIELD sub-operations in order in one transaction, the
+    /// string is only written if a field is changed.
+    pub async fn do_async_txnkv_bitfield(
+        mut self,
+        key: &str,
+        ops: Vec<BitfieldOp>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+
+        // a BITFIELD of GETs only reads with latest commit
+        let readonly = ops.iter().all(|op| matches!(op, BitfieldOp::Get(..)));
+        if readonly && self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let (mut value, ttl) = match txn.get(ekey.clone()).await? {
+                        Some(val) => {
+                            let dt = KeyDecoder::decode_key_type(&val);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_string_expire_if_needed(&key)
+                                    .await?;
+                                txn = txn_rc.lock().await;
+                                (vec![], 0)
+                            } else {
+                                (KeyDecoder::decode_key_string_value(&val), ttl)
+                            }
+                        }
+                        None => (vec![], 0),
+                    };
+
+                    let mut changed = false;
+                    let mut resp = Vec::with_capacity(ops.len());
+                    for op in ops {
+                        match op {
+                            BitfieldOp::Get(ty, offset) => {
+                                resp.push(resp_int(bitfield_get(&value, ty, offset)));
+                            }
+                            BitfieldOp::Set(ty, offset, field, overflow) => {
+                                let old = bitfield_get(&value, ty, offset);
+                                match bitfield_fit(ty, field as i128, overflow) {
+                                    Some(field) => {
+                                        bitfield_set(&mut value, ty, offset, field);
+                                        changed = true;
+                                        resp.push(resp_int(old));
+                                    }
+                                    None => resp.push(resp_nil()),
+                                }
+                            }
+                            BitfieldOp::Incrby(ty, offset, incr, overflow) => {
+                                let old = bitfield_get(&value, ty, offset);
+                                match bitfield_fit(ty, old as i128 + incr as i128, overflow) {
+                                    Some(field) => {
+                                        bitfield_set(&mut value, ty, offset, field);
+                                        changed = true;
+                                        resp.push(resp_int(field));
+                                    }
+                                    None => resp.push(resp_nil()),
+                                }
+                            }
+                        }
+                    }
+
+                    if changed {
+                        let eval = KEY_ENCODER.encode_txnkv_string_value(&mut value, ttl);
+                        txn.put(ekey, eval).await?;
+                    }
+                    Ok(resp_array(resp))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Overwrite a string from offset and reply the new length, the string is
+    /// padded with zero bytes up to offset. An empty value changes nothing and
+    /// does not create the key. The ttl of the key is kept.
+    pub async fn do_async_txnkv_setrange(
+        mut self,
+        key: &str,
+        offset: usize,
+        value: &Bytes,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+        let value = value.clone();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let (mut old_value, ttl) = match txn.get(ekey.clone()).await? {
+                        Some(val) => {
+                            let dt = KeyDecoder::decode_key_type(&val);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_string_expire_if_needed(&key)
+                                    .await?;
+                                txn = txn_rc.lock().await;
+                                (vec![], 0)
+                            } else {
+                                (KeyDecoder::decode_key_string_value(&val), ttl)
+                            }
+                        }
+                        None => (vec![], 0),
+                    };
+
+                    if value.is_empty() {
+                        return Ok(resp_int(old_value.len() as i64));
+                    }
+
+                    let end = offset + value.len();
+                    if old_value.len() < end {
+                        old_value.resize(end, 0);
+                    }
+                    old_value[offset..end].copy_from_slice(&value);
+                    let len = old_value.len() as i64;
+                    let eval = KEY_ENCODER.encode_txnkv_string_value(&mut old_value, ttl);
+                    txn.put(ekey, eval).await?;
+                    Ok(resp_int(len))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Reply the bytes of a string from start to end inclusive, negative
+    /// indexes count from the end. Only the slice is sent to the client.
+    pub async fn do_async_txnkv_getrange(
+        mut self,
+        key: &str,
+        start: i64,
+        end: i64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(ekey).await? {
+                        Some(val) => {
+                            let dt = KeyDecoder::decode_key_type(&val);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.do_async_txnkv_string_expire_if_needed(&key).await?;
+                                return Ok(resp_bulk(vec![]));
+                            }
+
+                            let value = KeyDecoder::decode_key_string_slice(&val);
+                            let len = value.len() as i64;
+                            if start < 0 && end < 0 && start > end {
+                                return Ok(resp_bulk(vec![]));
+                            }
+                            let start = if start < 0 {
+                                (start + len).max(0)
+                            } else {
+                                start
+                            };
+                            let end = if end < 0 { (end + len).max(0) } else { end };
+                            let end = end.min(len - 1);
+                            if len == 0 || start > end {
+                                return Ok(resp_bulk(vec![]));
+                            }
+                            Ok(resp_bulk(value[start as usize..=end as usize].to_vec()))
+                        }
+                        None => Ok(resp_bulk(vec![])),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Append to a string, creating it if missing, and reply the new length.
+    /// The ttl of the key is kept.
+    pub async fn do_async_txnkv_append(mut self, key: &str, value: &Bytes) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+        let value = value.clone();
 
-        let resp = client
+        client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
                         self.txn = Some(txn_rc.clone());
                     }
                     let mut txn = txn_rc.lock().await;
-                    let old_value = txn.get(ekey.clone()).await?;
-                    if let Some(ref v) = old_value {
-                        let ttl = KeyDecoder::decode_key_ttl(v);
-                        if key_is_expired(ttl) {
-                            // no need to delete, just overwrite
-                            txn.put(ekey, eval).await?;
-                            return Ok(1);
+                    let (mut old_value, ttl) = match txn.get(ekey.clone()).await? {
+                        Some(val) => {
+                            let dt = KeyDecoder::decode_key_type(&val);
+                            if !matches!(dt, DataType::String) {
+                                return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                            }
+
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_string_expire_if_needed(&key)
+                                    .await?;
+                                txn = txn_rc.lock().await;
+                                (vec![], 0)
+                            } else {
+                                (KeyDecoder::decode_key_string_value(&val), ttl)
+                            }
                         }
-                        Ok(0)
-                    } else {
-                        txn.put(ekey, eval).await?;
-                        Ok(1)
+                        None => (vec![], 0),
+                    };
+
+                    if (old_value.len() + value.len()) as u64 > proto_max_bulk_len_or_default() {
+                        return Ok(resp_err(REDIS_STRING_EXCEEDS_MAX_SIZE_ERR));
                     }
+                    old_value.extend_from_slice(&value);
+                    let len = old_value.len() as i64;
+                    let eval = KEY_ENCODER.encode_txnkv_string_value(&mut old_value, ttl);
+                    txn.put(ekey, eval).await?;
+                    Ok(resp_int(len))
                 }
                 .boxed()
             })
-            .await;
-
-        match resp {
-            Ok(n) => {
-                if return_number {
-                    return Ok(resp_int(n as i64));
-                }
-                if n == 0 {
-                    Ok(resp_nil())
-                } else {
-                    Ok(resp_ok())
-                }
-            }
-            Err(e) => Ok(resp_err(e)),
-        }
+            .await
     }
 
     pub async fn do_async_rawkv_exists(self, keys: &[String]) -> AsyncResult<Frame> {
@@ -466,7 +1701,7 @@ impl StringCommandCtx {
                 }
             }
 
-            new_int = prev_int + step;
+            new_int = prev_int.checked_add(step).ok_or(REDIS_INCR_OVERFLOW_ERR)?;
             let new_val = new_int.to_string();
             let (_, ret) = client
                 .compare_and_swap(ekey.clone(), prev, new_val.into())
@@ -519,7 +1754,7 @@ impl StringCommandCtx {
                         }
                     }
 
-                    let new_int = prev_int + step;
+                    let new_int = prev_int.checked_add(step).ok_or(REDIS_INCR_OVERFLOW_ERR)?;
                     let new_val = new_int.to_string();
                     let eval =
                         KEY_ENCODER.encode_txnkv_string_value(&mut new_val.as_bytes().to_vec(), 0);
@@ -536,6 +1771,64 @@ impl StringCommandCtx {
         }
     }
 
+    /// Add a float increment to a string and reply the new value. The ttl of
+    /// the key is kept.
+    pub async fn do_async_txnkv_incrbyfloat(mut self, key: &str, step: f64) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let ekey = KEY_ENCODER.encode_txnkv_string(key);
+        let key = key.to_owned();
+
+        let resp = client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone())
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let (prev, ttl) = match txn.get(ekey.clone()).await? {
+                        Some(val) => {
+                            if !matches!(KeyDecoder::decode_key_type(&val), DataType::String) {
+                                return Err(REDIS_WRONG_TYPE_ERR);
+                            }
+                            let ttl = KeyDecoder::decode_key_ttl(&val);
+                            if key_is_expired(ttl) {
+                                drop(txn);
+                                self.clone()
+                                    .do_async_txnkv_string_expire_if_needed(&key)
+                                    .await?;
+                                txn = txn_rc.lock().await;
+                                (0f64, 0)
+                            } else {
+                                let real_value = KeyDecoder::decode_key_string_slice(&val);
+                                match str::from_utf8(real_value).map(str::parse::<f64>) {
+                                    Ok(Ok(prev)) if prev.is_finite() => (prev, ttl),
+                                    _ => return Err(REDIS_VALUE_IS_NOT_VALID_FLOAT_ERR),
+                                }
+                            }
+                        }
+                        None => (0f64, 0),
+                    };
+
+                    let new_float = prev + step;
+                    if !new_float.is_finite() {
+                        return Err(REDIS_INCR_FLOAT_NAN_OR_INFINITY_ERR);
+                    }
+                    let new_val = format_float(new_float);
+                    let eval = KEY_ENCODER
+                        .encode_txnkv_string_value(&mut new_val.as_bytes().to_vec(), ttl);
+                    txn.put(ekey, eval).await?;
+                    Ok(new_val)
+                }
+                .boxed()
+            })
+            .await;
+
+        match resp {
+            Ok(v) => Ok(resp_bulk(v.into_bytes())),
+            Err(e) => Ok(resp_err(e)),
+        }
+    }
+
     pub async fn do_async_txnkv_string_del(mut self, key: &str) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
@@ -578,6 +1871,7 @@ impl StringCommandCtx {
                             REMOVED_EXPIRED_KEY_COUNTER
                                 .with_label_values(&["string"])
                                 .inc();
+                            notify_expired(&key);
                             return Ok(1);
                         }
                     }
@@ -588,6 +1882,10 @@ impl StringCommandCtx {
             .await
     }
 
+    /// Set the absolute expire `timestamp` of the key, 0 persists the key.
+    ///
+    /// Relative expiration must be converted by the caller before, so a
+    /// retried transaction applies the same timestamp as the first attempt.
     pub async fn do_async_txnkv_expire(mut self, key: &str, timestamp: u64) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let key = key.to_owned();
@@ -695,7 +1993,19 @@ impl StringCommandCtx {
         }
     }
 
-    pub async fn do_async_txnkv_ttl(mut self, key: &str, is_millis: bool) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_ttl(self, key: &str, is_millis: bool) -> AsyncResult<Frame> {
+        self.txnkv_ttl(key, is_millis, false).await
+    }
+
+    /// The absolute unix time the key expires at, the expire time is stored in
+    /// milliseconds so it is replied as is for PEXPIRETIME.
+    pub async fn do_async_txnkv_expiretime(self, key: &str, is_millis: bool) -> AsyncResult<Frame> {
+        self.txnkv_ttl(key, is_millis, true).await
+    }
+
+    /// Reply the remaining time to live of the key, or its expire time if
+    /// `absolute`. -1 if the key has no ttl, -2 if it does not exist.
+    async fn txnkv_ttl(mut self, key: &str, is_millis: bool, absolute: bool) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekey = KEY_ENCODER.encode_txnkv_string(key);
         let key = key.to_owned();
@@ -747,7 +2057,11 @@ impl StringCommandCtx {
                             if ttl == 0 {
                                 Ok(resp_int(-1))
                             } else {
-                                let mut ttl = ttl_from_timestamp(ttl) as i64;
+                                let mut ttl = if absolute {
+                                    ttl as i64
+                                } else {
+                                    ttl_from_timestamp(ttl) as i64
+                                };
                                 if !is_millis {
                                     ttl /= 1000;
                                 }
@@ -762,20 +2076,45 @@ impl StringCommandCtx {
             .await
     }
 
-    pub async fn do_async_txnkv_del(mut self, keys: &Vec<String>) -> AsyncResult<Frame> {
+    pub async fn do_async_txnkv_del(self, keys: &Vec<String>) -> AsyncResult<Frame> {
+        // a key repeated in the command is deleted and counted once
+        let mut seen = HashSet::with_capacity(keys.len());
+        let keys: Vec<String> = keys
+            .iter()
+            .filter(|key| seen.insert(key.as_str()))
+            .cloned()
+            .collect();
+
+        // out of MULTI and scripts the keys are deleted in bounded transactions,
+        // the deletions of the batches committed stay if a later batch fails
+        let batch_size = match self.txn {
+            Some(_) => keys.len().max(1),
+            None => DEL_BATCH_KEYS,
+        };
+        let mut resp = 0;
+        for batch in keys.chunks(batch_size) {
+            match self.clone().do_async_txnkv_del_batch(batch).await {
+                Ok(v) => resp += v,
+                Err(e) => return Ok(resp_err(e)),
+            }
+        }
+        Ok(resp_int(resp))
+    }
+
+    /// Delete the keys in one transaction, the metas are read in a single
+    /// batch. Returns the number of keys deleted, expired keys are cleaned up
+    /// but not counted.
+    async fn do_async_txnkv_del_batch(mut self, keys: &[String]) -> AsyncResult<i64> {
         let mut client = get_txn_client()?;
         let keys = keys.to_owned();
-        let keys_len = keys.len();
 
-        let resp = client
+        client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
                 async move {
                     if self.txn.is_none() {
                         self.txn = Some(txn_rc.clone());
                     }
 
-                    let mut dts = Vec::with_capacity(keys_len);
-
                     let ekeys = KEY_ENCODER.encode_txnkv_strings(&keys);
                     let kv_map: HashMap<Key, Value> = txn_rc
                         .lock()
@@ -786,57 +2125,365 @@ impl StringCommandCtx {
                         .map(|pair| (pair.0, pair.1))
                         .collect();
 
-                    assert_eq!(ekeys.len(), keys_len);
-                    for ekey in &ekeys {
-                        match kv_map.get(ekey) {
-                            Some(v) => dts.push(KeyDecoder::decode_key_type(v)),
-                            None => dts.push(DataType::Null),
-                        }
-                    }
-
                     let mut resp = 0;
-                    for idx in 0..keys_len {
-                        match dts[idx] {
+                    for (key, ekey) in keys.iter().zip(ekeys) {
+                        let meta_value = match kv_map.get(&ekey) {
+                            Some(v) => v,
+                            None => continue,
+                        };
+                        match KeyDecoder::decode_key_type(meta_value) {
+                            // the value of a string is stored with its meta
                             DataType::String => {
-                                self.clone().do_async_txnkv_string_del(&keys[idx]).await?;
-                                resp += 1;
+                                txn_rc.lock().await.delete(ekey).await?;
                             }
+                            // the members of a big collection are left to the
+                            // gc, see the async deletion thresholds
                             DataType::Hash => {
                                 HashCommandCtx::new(self.txn.clone())
-                                    .do_async_txnkv_hash_del(&keys[idx])
+                                    .do_async_txnkv_hash_del(key)
                                     .await?;
-                                resp += 1;
                             }
                             DataType::List => {
                                 ListCommandCtx::new(self.txn.clone())
-                                    .do_async_txnkv_list_del(&keys[idx])
+                                    .do_async_txnkv_list_del(key)
                                     .await?;
-                                resp += 1;
                             }
                             DataType::Set => {
                                 SetCommandCtx::new(self.txn.clone())
-                                    .do_async_txnkv_set_del(&keys[idx])
+                                    .do_async_txnkv_set_del(key)
                                     .await?;
-                                resp += 1;
                             }
                             DataType::Zset => {
                                 ZsetCommandCtx::new(self.txn.clone())
-                                    .do_async_txnk_zset_del(&keys[idx])
+                                    .do_async_txnk_zset_del(key)
                                     .await?;
-                                resp += 1;
                             }
-                            DataType::Null => {}
+                            DataType::Null => continue,
+                        }
+                        txn_rc
+                            .lock()
+                            .await
+                            .delete(KEY_ENCODER.encode_txnkv_access_key(key))
+                            .await?;
+                        if !key_is_expired(KeyDecoder::decode_key_ttl(meta_value)) {
+                            resp += 1;
                         }
                     }
                     Ok(resp)
                 }
                 .boxed()
             })
-            .await;
-        match resp {
-            Ok(v) => Ok(resp_int(v)),
-            Err(e) => Ok(resp_err(e)),
+            .await
+    }
+
+    /// Delete a collection the same way as DEL, the members of a big one are
+    /// left to the gc, see the async deletion thresholds.
+    async fn txnkv_collection_del(self, key: &str, dt: DataType) -> AsyncResult<()> {
+        match dt {
+            DataType::Hash => {
+                HashCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_hash_del(key)
+                    .await?;
+            }
+            DataType::List => {
+                ListCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_list_del(key)
+                    .await?;
+            }
+            DataType::Set => {
+                SetCommandCtx::new(self.txn.clone())
+                    .do_async_txnkv_set_del(key)
+                    .await?;
+            }
+            DataType::Zset => {
+                ZsetCommandCtx::new(self.txn.clone())
+                    .do_async_txnk_zset_del(key)
+                    .await?;
+            }
+            DataType::String | DataType::Null => {}
+        }
+        Ok(())
+    }
+
+    /// Record the access time of the keys, keys not exist are skipped.
+    pub async fn do_async_txnkv_touch(self, keys: &[String], timestamp: u64) -> AsyncResult<()> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    let ekeys = KEY_ENCODER.encode_txnkv_strings(&keys);
+                    let kv_map: HashMap<Key, Value> = txn
+                        .batch_get(ekeys.clone())
+                        .await?
+                        .into_iter()
+                        .map(|pair| (pair.0, pair.1))
+                        .collect();
+
+                    for (idx, ekey) in ekeys.iter().enumerate() {
+                        if kv_map.contains_key(ekey) {
+                            txn.put(
+                                KEY_ENCODER.encode_txnkv_access_key(&keys[idx]),
+                                KEY_ENCODER.encode_txnkv_access_value(timestamp),
+                            )
+                            .await?;
+                        }
+                    }
+                    Ok(())
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Seconds since the key was last accessed, the access time is sampled so
+    /// a key without any record is reported as just accessed.
+    pub async fn do_async_txnkv_idletime(mut self, key: &str) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let access_key = KEY_ENCODER.encode_txnkv_access_key(key);
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(meta_key).await? {
+                        Some(meta_value) => {
+                            if key_is_expired(KeyDecoder::decode_key_ttl(&meta_value)) {
+                                return Ok(resp_nil());
+                            }
+                            let idle = match txn.get(access_key).await? {
+                                Some(v) => now_timestamp_in_millis()
+                                    .saturating_sub(KeyDecoder::decode_access_value(&v)),
+                                None => 0,
+                            };
+                            Ok(resp_int((idle / 1000) as i64))
+                        }
+                        None => Ok(resp_nil()),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Reply the encoding name of the value, nil if the key does not exist.
+    pub async fn do_async_txnkv_encoding(mut self, key: &str) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    match txn.get(meta_key).await? {
+                        Some(meta_value) => {
+                            if key_is_expired(KeyDecoder::decode_key_ttl(&meta_value)) {
+                                return Ok(resp_nil());
+                            }
+                            Ok(resp_bulk(object_encoding(&meta_value).as_bytes().to_vec()))
+                        }
+                        None => Ok(resp_nil()),
+                    }
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Describe the storage of the key for DEBUG OBJECT: its type, encoding,
+    /// the bytes of the keys and values stored in TiKV for it, the members of
+    /// a collection and the idle time. Nil if the key does not exist.
+    pub async fn do_async_txnkv_debug_object(mut self, key: &str) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
+        let access_key = KEY_ENCODER.encode_txnkv_access_key(&key);
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key.clone()).await? {
+                        Some(v) => v,
+                        None => return Ok(resp_nil()),
+                    };
+                    if key_is_expired(KeyDecoder::decode_key_ttl(&meta_value)) {
+                        return Ok(resp_nil());
+                    }
+                    let dt = KeyDecoder::decode_key_type(&meta_value);
+
+                    let mut serialized_len = Vec::<u8>::from(meta_key).len() + meta_value.len();
+                    let mut members = 0;
+                    for (range, is_members) in key_ranges(&key, &meta_value) {
+                        for kv in txn.scan(range, u32::MAX).await? {
+                            serialized_len += Vec::<u8>::from(kv.0).len() + kv.1.len();
+                            if is_members {
+                                members += 1;
+                            }
+                        }
+                    }
+                    let idle = match txn.get(access_key).await? {
+                        Some(v) => now_timestamp_in_millis()
+                            .saturating_sub(KeyDecoder::decode_access_value(&v)),
+                        None => 0,
+                    };
+
+                    let mut line = format!(
+                        "Value at:0 refcount:1 encoding:{} serializedlength:{} lru_seconds_idle:{} type:{}",
+                        object_encoding(&meta_value),
+                        serialized_len,
+                        idle / 1000,
+                        dt
+                    );
+                    if !matches!(dt, DataType::String) {
+                        line.push_str(&format!(" members:{}", members));
+                    }
+                    Ok(resp_str(&line))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Dump the encoded keys and values stored for the key as hex pairs: the
+    /// meta, sub meta keys, data keys and the access time record. At most
+    /// `limit` entries are dumped per range.
+    pub async fn do_async_txnkv_raw(mut self, key: &str, limit: u32) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
         }
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    let mut txn = txn_rc.lock().await;
+                    let mut pairs = read_key_pairs(&mut txn, &key, limit).await?;
+                    if pairs.is_empty() {
+                        return Ok(resp_nil());
+                    }
+                    let access_key = KEY_ENCODER.encode_txnkv_access_key(&key);
+                    if let Some(v) = txn.get(access_key.clone()).await? {
+                        pairs.push(KvPair::new(access_key, v));
+                    }
+
+                    let resp = pairs
+                        .into_iter()
+                        .map(|kv| {
+                            let ekey: Vec<u8> = kv.0.into();
+                            resp_array(vec![
+                                resp_bulk(hex::encode(ekey).into_bytes()),
+                                resp_bulk(hex::encode(kv.1).into_bytes()),
+                            ])
+                        })
+                        .collect();
+                    Ok(resp_array(resp))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Recount the members of a hash, set or sorted set and rewrite its size
+    /// meta if it drifted, the old and new sizes are replied.
+    pub async fn do_async_txnkv_repair_meta(mut self, key: &str) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let key = key.to_owned();
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(&key);
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(v) => v,
+                        None => return Ok(resp_nil()),
+                    };
+                    if key_is_expired(KeyDecoder::decode_key_ttl(&meta_value)) {
+                        return Ok(resp_nil());
+                    }
+                    let version = KeyDecoder::decode_key_version(&meta_value);
+                    let (mut data_start, data_end) = match KeyDecoder::decode_key_type(&meta_value)
+                    {
+                        DataType::Hash => (
+                            KEY_ENCODER.encode_txnkv_hash_data_key_start(&key, version),
+                            KEY_ENCODER.encode_txnkv_hash_data_key_end(&key, version),
+                        ),
+                        DataType::Set => (
+                            KEY_ENCODER.encode_txnkv_set_data_key_start(&key, version),
+                            KEY_ENCODER.encode_txnkv_set_data_key_end(&key, version),
+                        ),
+                        DataType::Zset => (
+                            KEY_ENCODER.encode_txnkv_zset_data_key_start(&key, version),
+                            KEY_ENCODER.encode_txnkv_zset_data_key_end(&key, version),
+                        ),
+                        _ => return Err(REDIS_NO_SIZE_META_ERR),
+                    };
+
+                    let sub_meta_range = KEY_ENCODER.encode_txnkv_sub_meta_key_range(&key, version);
+                    let sub_metas: Vec<KvPair> =
+                        txn.scan(sub_meta_range, u32::MAX).await?.collect();
+                    let old_size: i64 = sub_metas
+                        .iter()
+                        .map(|kv| i64::from_be_bytes(kv.1.clone().try_into().unwrap()))
+                        .sum();
+
+                    // count the members in batches, only the keys of a batch
+                    // are held at a time
+                    let mut new_size = 0;
+                    loop {
+                        let range: BoundRange = (data_start..data_end.clone()).into();
+                        let keys: Vec<Key> = txn
+                            .scan_keys(range, REPAIR_META_SCAN_BATCH)
+                            .await?
+                            .collect();
+                        new_size += keys.len() as i64;
+                        if keys.len() < REPAIR_META_SCAN_BATCH as usize {
+                            break;
+                        }
+                        let mut next: Vec<u8> = keys.last().unwrap().clone().into();
+                        next.push(0);
+                        data_start = next.into();
+                    }
+
+                    if old_size != new_size {
+                        // keep a single sub meta key holding the whole size
+                        for kv in sub_metas {
+                            txn.delete(kv.0).await?;
+                        }
+                        let sub_meta_key = KEY_ENCODER.encode_txnkv_sub_meta_key(&key, version, 0);
+                        txn.put(sub_meta_key, new_size.to_be_bytes().to_vec())
+                            .await?;
+                    }
+                    Ok(resp_array(vec![resp_int(old_size), resp_int(new_size)]))
+                }
+                .boxed()
+            })
+            .await
     }
 
     pub async fn do_async_txnkv_scan(
@@ -844,10 +2491,13 @@ impl StringCommandCtx {
         start: &str,
         count: u32,
         regex: &str,
+        key_type: Option<&str>,
     ) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let ekey = KEY_ENCODER.encode_txnkv_string(start);
         let re = Regex::new(regex).unwrap();
+        // an unknown type matches no key, the scan still advances the cursor
+        let key_type = key_type.map(|t| t.to_lowercase());
 
         client
             .exec_in_txn(self.txn.clone(), |txn_rc| {
@@ -897,6 +2547,11 @@ impl StringCommandCtx {
                             }
 
                             let ttl = KeyDecoder::decode_key_ttl(&kv.1);
+                            // the type is filtered like the pattern, the key is still counted
+                            let matched = re.is_match(&userkey)
+                                && key_type.as_ref().map_or(true, |t| {
+                                    *t == KeyDecoder::decode_key_type(&kv.1).to_string()
+                                });
                             // delete it if it is expired
                             if key_is_expired(ttl) {
                                 drop(txn);
@@ -910,13 +2565,13 @@ impl StringCommandCtx {
                             if retrieved_key_count == (count - 1) as usize {
                                 next_key = userkey.clone();
                                 retrieved_key_count += 1;
-                                if re.is_match(&userkey) {
+                                if matched {
                                     keys.push(resp_bulk(userkey));
                                 }
                                 break;
                             }
                             retrieved_key_count += 1;
-                            if re.is_match(&userkey) {
+                            if matched {
                                 keys.push(resp_bulk(userkey));
                             }
                         }
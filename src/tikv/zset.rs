@@ -2,6 +2,8 @@ use super::client::get_version_for_new;
 use super::errors::*;
 use super::gen_next_meta_index;
 use super::get_txn_client;
+use super::sample::Sampling;
+use super::string::StringCommandCtx;
 use super::KEY_ENCODER;
 use super::{
     encoding::{DataType, KeyDecoder},
@@ -9,16 +11,84 @@ use super::{
 };
 use crate::async_del_zset_threshold_or_default;
 use crate::async_expire_zset_threshold_or_default;
-use crate::utils::{key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil};
+use crate::utils::{
+    glob_match, key_is_expired, resp_array, resp_bulk, resp_err, resp_int, resp_nil, resp_scan,
+};
 use crate::Frame;
 use ::futures::future::FutureExt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
+use std::ops::Range;
 use std::sync::Arc;
 use tikv_client::{BoundRange, Key, Transaction, Value};
 use tokio::sync::Mutex;
 
-use crate::metrics::REMOVED_EXPIRED_KEY_COUNTER;
+use crate::metrics::{CMD_PATH_COUNTER, REMOVED_EXPIRED_KEY_COUNTER};
+use crate::notify::notify_expired;
+
+/// Score keys scanned at a time by ZCOUNT
+const ZCOUNT_SCAN_BATCH: u32 = 1000;
+
+/// Range of a sorted set selected by ZRANGESTORE
+#[derive(Debug, Clone, Copy)]
+pub enum ZrangeBy {
+    /// min and max ranks
+    Rank(i64, i64),
+    /// min, min inclusive, max and max inclusive scores
+    Score(f64, bool, f64, bool),
+}
+
+/// Aggregation of the scores of a member found in several sorted sets by
+/// ZUNION and ZINTER
+#[derive(Debug, Clone, Copy)]
+pub enum ZsetAggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+impl ZsetAggregate {
+    fn aggregate(self, a: f64, b: f64) -> f64 {
+        match self {
+            // the sum of opposite infinities is 0, like in redis
+            ZsetAggregate::Sum => zero_if_nan(a + b),
+            ZsetAggregate::Min => a.min(b),
+            ZsetAggregate::Max => a.max(b),
+        }
+    }
+}
+
+fn zero_if_nan(score: f64) -> f64 {
+    if score.is_nan() {
+        0.0
+    } else {
+        score
+    }
+}
+
+/// Reply of the members of a computed sorted set, with the scores after the
+/// members if `with_scores`.
+fn zset_pairs_resp(pairs: Vec<(Vec<u8>, f64)>, with_scores: bool) -> Frame {
+    let resp = pairs
+        .into_iter()
+        .flat_map(|(member, score)| {
+            if with_scores {
+                vec![
+                    resp_bulk(member),
+                    resp_bulk(score.to_string().as_bytes().to_vec()),
+                ]
+            } else {
+                vec![resp_bulk(member)]
+            }
+        })
+        .collect();
+    resp_array(resp)
+}
+
+/// Sort the members of a computed sorted set by score, then by member.
+fn sort_zset_pairs(pairs: &mut [(Vec<u8>, f64)]) {
+    pairs.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+}
 
 #[derive(Clone)]
 pub struct ZsetCommandCtx {
@@ -70,6 +140,215 @@ impl ZsetCommandCtx {
             .await
     }
 
+    /// Read all the members of a sorted set with their scores, a missing or
+    /// expired key is empty. `None` if the key holds another type.
+    async fn txnkv_zset_members(mut self, key: &str) -> AsyncResult<Option<Vec<(Vec<u8>, f64)>>> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(Some(vec![])),
+                    };
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                        return Ok(None);
+                    }
+
+                    let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        drop(txn);
+                        self.do_async_txnkv_zset_expire_if_needed(&key).await?;
+                        return Ok(Some(vec![]));
+                    }
+
+                    let bound_range = KEY_ENCODER.encode_txnkv_zset_data_key_range(&key, version);
+                    let pairs = txn
+                        .scan(bound_range, u32::MAX)
+                        .await?
+                        .map(|kv| {
+                            let score = KeyDecoder::decode_key_zset_data_value(&kv.1);
+                            let member =
+                                KeyDecoder::decode_key_zset_member_from_datakey(&key, kv.0);
+                            (member, score)
+                        })
+                        .collect();
+                    Ok(Some(pairs))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Members of the first sorted set that are in none of the others, with
+    /// the scores of the first one. `None` if a key holds another type.
+    async fn txnkv_zdiff(self, keys: &[String]) -> AsyncResult<Option<Vec<(Vec<u8>, f64)>>> {
+        let mut first = match self.clone().txnkv_zset_members(&keys[0]).await? {
+            Some(pairs) => pairs,
+            None => return Ok(None),
+        };
+        for key in &keys[1..] {
+            let members: HashSet<Vec<u8>> = match self.clone().txnkv_zset_members(key).await? {
+                Some(pairs) => pairs.into_iter().map(|(member, _)| member).collect(),
+                None => return Ok(None),
+            };
+            first.retain(|(member, _)| !members.contains(member));
+        }
+        sort_zset_pairs(&mut first);
+        Ok(Some(first))
+    }
+
+    /// Union or intersection of the sorted sets, the score of a member in a
+    /// set is multiplied by the weight of the set, then the scores of the
+    /// member in the sets are aggregated. `None` if a key holds another type.
+    async fn txnkv_zunion_inter(
+        self,
+        keys: &[String],
+        weights: &[f64],
+        aggregate: ZsetAggregate,
+        inter: bool,
+    ) -> AsyncResult<Option<Vec<(Vec<u8>, f64)>>> {
+        let mut result: Option<HashMap<Vec<u8>, f64>> = None;
+        for (idx, key) in keys.iter().enumerate() {
+            let weight = weights.get(idx).copied().unwrap_or(1.0);
+            let pairs = match self.clone().txnkv_zset_members(key).await? {
+                Some(pairs) => pairs,
+                None => return Ok(None),
+            };
+            let weighted = pairs
+                .into_iter()
+                .map(|(member, score)| (member, zero_if_nan(score * weight)));
+
+            result = Some(match result {
+                None => weighted.collect(),
+                Some(mut acc) if inter => {
+                    let scores: HashMap<Vec<u8>, f64> = weighted.collect();
+                    acc.retain(|member, _| scores.contains_key(member));
+                    for (member, score) in acc.iter_mut() {
+                        *score = aggregate.aggregate(*score, scores[member]);
+                    }
+                    acc
+                }
+                Some(mut acc) => {
+                    for (member, score) in weighted {
+                        acc.entry(member)
+                            .and_modify(|s| *s = aggregate.aggregate(*s, score))
+                            .or_insert(score);
+                    }
+                    acc
+                }
+            });
+        }
+
+        let mut pairs: Vec<(Vec<u8>, f64)> = result.unwrap_or_default().into_iter().collect();
+        sort_zset_pairs(&mut pairs);
+        Ok(Some(pairs))
+    }
+
+    pub async fn do_async_txnkv_zunion_inter(
+        mut self,
+        keys: &[String],
+        weights: &[f64],
+        aggregate: ZsetAggregate,
+        inter: bool,
+        with_scores: bool,
+    ) -> AsyncResult<Frame> {
+        let client = get_txn_client()?;
+
+        // nothing is written, the keys are read from one snapshot
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        match self
+            .txnkv_zunion_inter(keys, weights, aggregate, inter)
+            .await?
+        {
+            Some(pairs) => Ok(zset_pairs_resp(pairs, with_scores)),
+            None => Ok(resp_err(REDIS_WRONG_TYPE_ERR)),
+        }
+    }
+
+    pub async fn do_async_txnkv_zdiff(
+        mut self,
+        keys: &[String],
+        with_scores: bool,
+    ) -> AsyncResult<Frame> {
+        let client = get_txn_client()?;
+
+        // nothing is written, the keys are read from one snapshot
+        if self.txn.is_none() {
+            let readonly_txn = client.begin_with_latest();
+            self.txn = Some(Arc::new(Mutex::new(readonly_txn)));
+        }
+
+        match self.txnkv_zdiff(keys).await? {
+            Some(pairs) => Ok(zset_pairs_resp(pairs, with_scores)),
+            None => Ok(resp_err(REDIS_WRONG_TYPE_ERR)),
+        }
+    }
+
+    /// Store the difference of the sorted sets in `dst`, the keys are read and
+    /// `dst` is written in one transaction. `dst` is deleted if the difference
+    /// is empty.
+    pub async fn do_async_txnkv_zdiffstore(
+        mut self,
+        dst: &str,
+        keys: &[String],
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let dst = dst.to_owned();
+        let keys = keys.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let pairs = match self.clone().txnkv_zdiff(&keys).await? {
+                        Some(pairs) => pairs,
+                        None => return Ok(resp_err(REDIS_WRONG_TYPE_ERR)),
+                    };
+                    self.txnkv_zstore(&dst, pairs).await
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Replace `dst` by a sorted set of the pairs, or delete it if there is
+    /// no pair. Replies the number of members stored.
+    async fn txnkv_zstore(self, dst: &str, pairs: Vec<(Vec<u8>, f64)>) -> AsyncResult<Frame> {
+        // the old value of dst is removed whatever its type
+        match StringCommandCtx::new(self.txn.clone())
+            .do_async_txnkv_del(&vec![dst.to_owned()])
+            .await?
+        {
+            Frame::Integer(_) => {}
+            resp => return Ok(resp),
+        }
+        if pairs.is_empty() {
+            return Ok(resp_int(0));
+        }
+        let (members, scores): (Vec<String>, Vec<f64>) = pairs
+            .into_iter()
+            .map(|(member, score)| (String::from_utf8_lossy(&member).to_string(), score))
+            .unzip();
+        self.do_async_txnkv_zadd(dst, &members, &scores, None, false, false)
+            .await
+    }
+
     pub async fn do_async_txnkv_zadd(
         mut self,
         key: &str,
@@ -446,10 +725,10 @@ impl ZsetCommandCtx {
                                 self.clone()
                                     .do_async_txnkv_zset_expire_if_needed(&key)
                                     .await?;
-                                return Ok(resp_array(vec![]));
+                                return Ok(resp_int(0));
                             }
 
-                            if min > max {
+                            if min > max || (min == max && !(min_inclusive && max_inclusive)) {
                                 return Ok(resp_int(0));
                             }
 
@@ -465,11 +744,28 @@ impl ZsetCommandCtx {
                                 max_inclusive,
                                 version,
                             );
-                            let range = start_key..=end_key;
-                            let bound_range: BoundRange = range.into();
-                            let iter = txn.scan(bound_range, u32::MAX).await?;
+                            // count the score keys in batches, only the keys of
+                            // a batch are held at a time
+                            let mut count = 0;
+                            let mut left_bound = start_key;
+                            loop {
+                                let range = left_bound..=end_key.clone();
+                                let bound_range: BoundRange = range.into();
+                                let keys: Vec<Key> = txn
+                                    .scan_keys(bound_range, ZCOUNT_SCAN_BATCH)
+                                    .await?
+                                    .collect();
+                                count += keys.len() as i64;
+                                if keys.len() < ZCOUNT_SCAN_BATCH as usize {
+                                    break;
+                                }
+                                // continue right after the last key of the batch
+                                let mut next: Vec<u8> = keys.last().unwrap().clone().into();
+                                next.push(0);
+                                left_bound = next.into();
+                            }
 
-                            Ok(resp_int(iter.count() as i64))
+                            Ok(resp_int(count))
                         }
                         None => Ok(resp_int(0)),
                     }
@@ -679,10 +975,250 @@ impl ZsetCommandCtx {
             .await
     }
 
+    /// Store the members of a range of `src` into `dst` with their scores, the
+    /// range is read by ZRANGE or ZRANGEBYSCORE in the same transaction. `dst`
+    /// is replaced, or deleted if the range is empty. At most `count` members
+    /// after `offset` are stored, `None` stores all of them.
+    pub async fn do_async_txnkv_zrangestore(
+        mut self,
+        dst: &str,
+        src: &str,
+        range: ZrangeBy,
+        reverse: bool,
+        offset: usize,
+        count: Option<usize>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let dst = dst.to_owned();
+        let src = src.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let resp = match range {
+                        ZrangeBy::Rank(min, max) => {
+                            self.clone()
+                                .do_async_txnkv_zrange(&src, min, max, true, reverse)
+                                .await?
+                        }
+                        ZrangeBy::Score(min, min_inclusive, max, max_inclusive) => {
+                            self.clone()
+                                .do_async_txnkv_zrange_by_score(
+                                    &src,
+                                    min,
+                                    min_inclusive,
+                                    max,
+                                    max_inclusive,
+                                    true,
+                                    reverse,
+                                )
+                                .await?
+                        }
+                    };
+                    let pairs = match resp {
+                        Frame::Array(pairs) => pairs,
+                        resp => return Ok(resp),
+                    };
+
+                    let mut members = vec![];
+                    let mut scores = vec![];
+                    for pair in pairs
+                        .chunks(2)
+                        .skip(offset)
+                        .take(count.unwrap_or(usize::MAX))
+                    {
+                        if let [Frame::Bulk(member), Frame::Bulk(score)] = pair {
+                            members.push(String::from_utf8_lossy(member).to_string());
+                            scores.push(String::from_utf8_lossy(score).parse::<f64>()?);
+                        }
+                    }
+
+                    // the old value of dst is removed whatever its type
+                    match StringCommandCtx::new(self.txn.clone())
+                        .do_async_txnkv_del(&vec![dst.clone()])
+                        .await?
+                    {
+                        Frame::Integer(_) => {}
+                        resp => return Ok(resp),
+                    }
+                    if members.is_empty() {
+                        return Ok(resp_int(0));
+                    }
+                    self.do_async_txnkv_zadd(&dst, &members, &scores, None, false, false)
+                        .await
+                }
+                .boxed()
+            })
+            .await
+    }
+
     // pub async fn do_async_txnkv_zrange_by_lex(self, key: &str, min: &str, with_min: bool, max: &str, with_max: bool, with_scores: bool, reverse: bool) -> AsyncResult<Frame> {
     //     Ok(resp_nil())
     // }
 
+    pub async fn do_async_txnkv_zrandmember(
+        mut self,
+        key: &str,
+        sampling: Sampling,
+        array_resp: bool,
+        with_scores: bool,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+        let empty_resp = if array_resp {
+            resp_array(vec![])
+        } else {
+            resp_nil()
+        };
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(empty_resp),
+                    };
+                    drop(txn);
+
+                    // check key type and ttl
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
+
+                    let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        self.clone()
+                            .do_async_txnkv_zset_expire_if_needed(&key)
+                            .await?;
+                        return Ok(empty_resp);
+                    }
+
+                    let size = self.clone().txnkv_sum_key_size(&key, version).await?;
+                    let bound_range = KEY_ENCODER.encode_txnkv_zset_data_key_range(&key, version);
+                    let mut resp: Vec<Frame> = sampling
+                        .sample(&mut *txn_rc.lock().await, bound_range, size as usize)
+                        .await?
+                        .into_iter()
+                        .flat_map(|kv| {
+                            let member =
+                                KeyDecoder::decode_key_zset_member_from_datakey(&key, kv.0);
+                            if with_scores {
+                                let score = KeyDecoder::decode_key_zset_data_value(&kv.1);
+                                vec![
+                                    resp_bulk(member),
+                                    resp_bulk(score.to_string().as_bytes().to_vec()),
+                                ]
+                            } else {
+                                vec![resp_bulk(member)]
+                            }
+                        })
+                        .collect();
+
+                    if !array_resp {
+                        // called with no count argument, return bulk reply
+                        return Ok(resp.pop().unwrap_or(empty_resp));
+                    }
+                    Ok(resp_array(resp))
+                }
+                .boxed()
+            })
+            .await
+    }
+
+    /// Scan at most `count` members and their scores from the member `start`,
+    /// the members not matching `pattern` are filtered out after they are
+    /// read. The scan resumes from the member after the last one read.
+    pub async fn do_async_txnkv_zscan(
+        mut self,
+        key: &str,
+        start: &[u8],
+        count: u32,
+        pattern: Option<&str>,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
+        let key = key.to_owned();
+        let start = start.to_vec();
+        let pattern = pattern.map(|p| p.as_bytes().to_vec());
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    let mut txn = txn_rc.lock().await;
+                    let meta_value = match txn.get(meta_key).await? {
+                        Some(meta_value) => meta_value,
+                        None => return Ok(resp_scan(None, vec![])),
+                    };
+                    drop(txn);
+
+                    // check key type and ttl
+                    if !matches!(KeyDecoder::decode_key_type(&meta_value), DataType::Zset) {
+                        return Ok(resp_err(REDIS_WRONG_TYPE_ERR));
+                    }
+
+                    let (ttl, version, _) = KeyDecoder::decode_key_meta(&meta_value);
+                    if key_is_expired(ttl) {
+                        self.clone()
+                            .do_async_txnkv_zset_expire_if_needed(&key)
+                            .await?;
+                        return Ok(resp_scan(None, vec![]));
+                    }
+
+                    let mut range_start: Vec<u8> = KEY_ENCODER
+                        .encode_txnkv_zset_data_key_start(&key, version)
+                        .into();
+                    range_start.extend_from_slice(&start);
+                    let range: Range<Key> = range_start.into()
+                        ..KEY_ENCODER.encode_txnkv_zset_data_key_end(&key, version);
+                    // one more member is read, the next scan starts from it
+                    let mut pairs: Vec<(Vec<u8>, f64)> = txn_rc
+                        .lock()
+                        .await
+                        .scan(range, count.saturating_add(1))
+                        .await?
+                        .map(|kv| {
+                            let member =
+                                KeyDecoder::decode_key_zset_member_from_datakey(&key, kv.0);
+                            (member, KeyDecoder::decode_key_zset_data_value(&kv.1))
+                        })
+                        .collect();
+                    let next = if pairs.len() > count as usize {
+                        pairs.pop().map(|(member, _)| member)
+                    } else {
+                        None
+                    };
+
+                    let resp = pairs
+                        .into_iter()
+                        .flat_map(|(member, score)| match &pattern {
+                            Some(pattern) if !glob_match(pattern, &member) => vec![],
+                            _ => vec![
+                                resp_bulk(member),
+                                resp_bulk(score.to_string().as_bytes().to_vec()),
+                            ],
+                        })
+                        .collect();
+                    Ok(resp_scan(next, resp))
+                }
+                .boxed()
+            })
+            .await
+    }
+
     pub async fn do_async_txnkv_zpop(
         mut self,
         key: &str,
@@ -824,6 +1360,52 @@ impl ZsetCommandCtx {
         }
     }
 
+    /// Pop at most `count` members from the first non-empty sorted set of
+    /// `keys`, the sets are checked and popped in the same transaction.
+    /// Replies the key and the popped [member, score] pairs, nil if all the
+    /// sorted sets are empty.
+    pub async fn do_async_txnkv_zmpop(
+        mut self,
+        keys: &[String],
+        from_min: bool,
+        count: u64,
+    ) -> AsyncResult<Frame> {
+        let mut client = get_txn_client()?;
+        let keys = keys.to_owned();
+
+        client
+            .exec_in_txn(self.txn.clone(), |txn_rc| {
+                async move {
+                    if self.txn.is_none() {
+                        self.txn = Some(txn_rc.clone());
+                    }
+
+                    for key in &keys {
+                        let values = match self
+                            .clone()
+                            .do_async_txnkv_zpop(key, from_min, count)
+                            .await?
+                        {
+                            Frame::Array(values) if values.is_empty() => continue,
+                            Frame::Array(values) => values,
+                            resp => return Ok(resp),
+                        };
+                        let pairs = values
+                            .chunks(2)
+                            .map(|pair| resp_array(pair.to_vec()))
+                            .collect();
+                        return Ok(resp_array(vec![
+                            resp_bulk(key.as_bytes().to_vec()),
+                            resp_array(pairs),
+                        ]));
+                    }
+                    Ok(resp_nil())
+                }
+                .boxed()
+            })
+            .await
+    }
+
     pub async fn do_async_txnkv_zrank(mut self, key: &str, member: &str) -> AsyncResult<Frame> {
         let mut client = get_txn_client()?;
         let meta_key = KEY_ENCODER.encode_txnkv_meta_key(key);
@@ -1376,6 +1958,7 @@ impl ZsetCommandCtx {
                             txn = txn_rc.lock().await;
 
                             if size > async_del_zset_threshold_or_default() as i64 {
+                                CMD_PATH_COUNTER.with_label_values(&["del", "async"]).inc();
                                 // async del zset
                                 txn.delete(meta_key).await?;
 
@@ -1390,6 +1973,7 @@ impl ZsetCommandCtx {
                                 )
                                 .await?;
                             } else {
+                                CMD_PATH_COUNTER.with_label_values(&["del", "sync"]).inc();
                                 let bound_range =
                                     KEY_ENCODER.encode_txnkv_zset_data_key_range(&key, version);
                                 let iter = txn.scan(bound_range, u32::MAX).await?;
@@ -1460,6 +2044,9 @@ impl ZsetCommandCtx {
                             txn = txn_rc.lock().await;
 
                             if size > async_expire_zset_threshold_or_default() as i64 {
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["expire", "async"])
+                                    .inc();
                                 // async del zset
                                 txn.delete(meta_key).await?;
 
@@ -1474,6 +2061,9 @@ impl ZsetCommandCtx {
                                 )
                                 .await?;
                             } else {
+                                CMD_PATH_COUNTER
+                                    .with_label_values(&["expire", "sync"])
+                                    .inc();
                                 let bound_range =
                                     KEY_ENCODER.encode_txnkv_zset_data_key_range(&key, version);
                                 let iter = txn.scan(bound_range, u32::MAX).await?;
@@ -1511,6 +2101,7 @@ impl ZsetCommandCtx {
                             REMOVED_EXPIRED_KEY_COUNTER
                                 .with_label_values(&["zset"])
                                 .inc();
+                            notify_expired(&key);
                             Ok(1)
                         }
                         None => Ok(0),
@@ -0,0 +1,99 @@
+use futures::future;
+use slog::{debug, error};
+use tikv_client::{BoundRange, Key};
+use tokio::time::{self, Duration, MissedTickBehavior};
+
+use crate::config::LOGGER;
+use crate::metrics::{KEY_NO_TTL_RATIO_GAUGE, KEY_TTL_SAMPLE_HISTOGRAM};
+use crate::tikv::encoding::KeyDecoder;
+use crate::tikv::errors::AsyncResult;
+use crate::tikv::{get_txn_client, KEY_ENCODER};
+use crate::utils::{key_is_expired, ttl_from_timestamp};
+use crate::{is_use_txn_api, ttl_sample_interval_or_default, ttl_sample_size_or_default};
+
+/// Sample the remaining TTL of the keys periodically for the metrics.
+///
+/// Each round scans a bounded number of entries after the position where the
+/// previous round stopped, so the whole keyspace is covered over many rounds and
+/// a single round never holds a long scan.
+pub struct TtlSampler {
+    // the last key scanned, the next round continues after it
+    cursor: Option<Key>,
+}
+
+impl TtlSampler {
+    pub fn new() -> Self {
+        TtlSampler { cursor: None }
+    }
+
+    pub async fn run(&mut self) {
+        let interval_ms = ttl_sample_interval_or_default();
+        if interval_ms == 0 || !is_use_txn_api() {
+            // the sampler is disabled
+            return future::pending().await;
+        }
+        let mut interval = time::interval(Duration::from_millis(interval_ms));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.sample().await {
+                error!(LOGGER, "[TTL SAMPLER] sample keys failed: {:?}", e);
+            }
+        }
+    }
+
+    async fn sample(&mut self) -> AsyncResult<()> {
+        let txn_client = get_txn_client()?;
+        let mut txn = txn_client.begin().await?;
+
+        let start = match self.cursor.take() {
+            Some(cursor) => cursor,
+            None => KEY_ENCODER.encode_txnkv_string(""),
+        };
+        let range: BoundRange = (start.clone()..KEY_ENCODER.encode_txnkv_keyspace_end()).into();
+        let limit = ttl_sample_size_or_default();
+
+        // the scan covers the data keys of the collections as well, only the
+        // meta keys are sampled
+        let mut scanned = 0;
+        let mut with_ttl = 0;
+        let mut without_ttl = 0;
+        for kv in txn.scan(range, limit).await? {
+            scanned += 1;
+            if kv.0 == start {
+                continue;
+            }
+            let (_, is_meta_key) = KeyDecoder::decode_key_userkey_from_metakey(&kv.0);
+            if is_meta_key {
+                let ttl = KeyDecoder::decode_key_ttl(&kv.1);
+                if ttl == 0 {
+                    without_ttl += 1;
+                } else if !key_is_expired(ttl) {
+                    with_ttl += 1;
+                    KEY_TTL_SAMPLE_HISTOGRAM.observe(ttl_from_timestamp(ttl) as f64 / 1000.0);
+                }
+            }
+            self.cursor = Some(kv.0);
+        }
+        txn.rollback().await?;
+
+        // start over from the beginning once the end of the keyspace is reached
+        if scanned < limit {
+            self.cursor = None;
+        }
+        if with_ttl + without_ttl > 0 {
+            KEY_NO_TTL_RATIO_GAUGE.set(without_ttl as f64 / (with_ttl + without_ttl) as f64);
+        }
+        debug!(
+            LOGGER,
+            "[TTL SAMPLER] sampled {} keys with ttl, {} keys without ttl", with_ttl, without_ttl
+        );
+        Ok(())
+    }
+}
+
+impl Default for TtlSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -10,7 +10,7 @@ use std::{
 };
 use tokio::time::Duration;
 
-use crate::tikv::errors::{RTError, REDIS_LUA_PANIC};
+use crate::tikv::errors::{RTError, REDIS_INVALID_CURSOR_ERR, REDIS_LUA_PANIC};
 use rustls::{
     internal::pemfile::{certs, rsa_private_keys},
     AllowAnyAuthenticatedClient, RootCertStore,
@@ -30,6 +30,10 @@ pub fn resp_queued() -> Frame {
     Frame::Simple("QUEUED".to_string())
 }
 
+pub fn resp_reset() -> Frame {
+    Frame::Simple("RESET".to_string())
+}
+
 pub fn resp_ok_ignore<T>(_: T) -> Frame {
     resp_ok()
 }
@@ -38,6 +42,17 @@ pub fn resp_invalid_arguments() -> Frame {
     Frame::ErrorString("Invalid arguments")
 }
 
+pub fn resp_invalid_expire_time(cmd: &str) -> Frame {
+    Frame::ErrorOwned(format!("ERR invalid expire time in '{}' command", cmd))
+}
+
+pub fn resp_subscribed_context_err(cmd: &str) -> Frame {
+    Frame::ErrorOwned(format!(
+        "ERR Can't execute '{}': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING / QUIT / RESET are allowed in this context",
+        cmd
+    ))
+}
+
 pub fn resp_err(e: RTError) -> Frame {
     e.into()
 }
@@ -66,6 +81,29 @@ pub fn resp_array(val: Vec<Frame>) -> Frame {
     Frame::Array(val)
 }
 
+/// Reply the flat array of members and scores of a sorted set as a map of the
+/// members to their scores, RESP2 peers still receive the flat array.
+pub fn resp_scores_map(resp: Frame) -> Frame {
+    match resp {
+        Frame::Array(items) => Frame::Map(
+            items
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [member, Frame::Bulk(score)] => {
+                        let score = match String::from_utf8_lossy(score).parse::<f64>() {
+                            Ok(score) => Frame::Double(score),
+                            Err(_) => Frame::Bulk(score.clone()),
+                        };
+                        Some((member.clone(), score))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        ),
+        resp => resp,
+    }
+}
+
 pub async fn sleep(ms: u32) {
     tokio::time::sleep(Duration::from_millis(ms as u64)).await;
 }
@@ -75,11 +113,12 @@ pub fn lua_resp_to_redis_resp(resp: LuaValue) -> Frame {
         LuaValue::Nil => Frame::Null,
         LuaValue::String(r) => resp_bulk(r.to_str().unwrap().as_bytes().to_vec()),
         LuaValue::Integer(r) => resp_int(r),
+        // true is a boolean for RESP3 connections, an integer 1 otherwise
         LuaValue::Boolean(r) => {
             if !r {
                 resp_nil()
             } else {
-                resp_int(1)
+                Frame::Boolean(true)
             }
         }
         // just return integer part of the float in redis
@@ -134,8 +173,21 @@ pub fn redis_resp_to_lua_resp(resp: Frame, lua: &Lua) -> LuaValue {
             LuaValue::Table(table)
         }
         Frame::Integer(i) => LuaValue::Integer(i),
+        Frame::Boolean(b) => LuaValue::Integer(b as i64),
+        Frame::BigNumber(v) => LuaValue::String(lua.create_string(&v).unwrap()),
+        Frame::Verbatim(v) => LuaValue::String(lua.create_string(&v).unwrap()),
+        // scores are strings in scripts like in the RESP2 replies
+        Frame::Double(v) => LuaValue::String(lua.create_string(&v.to_string()).unwrap()),
         Frame::Null => LuaValue::Boolean(false),
-        Frame::Array(arr) => {
+        Frame::Map(pairs) => {
+            // flatten the map like the RESP2 reply
+            let arr = pairs
+                .into_iter()
+                .flat_map(|(key, value)| vec![key, value])
+                .collect();
+            redis_resp_to_lua_resp(Frame::Array(arr), lua)
+        }
+        Frame::Array(arr) | Frame::Push(arr) | Frame::Set(arr) => {
             let table = lua.create_table().unwrap();
             for (idx, value) in arr.iter().enumerate() {
                 let v = redis_resp_to_lua_resp(value.clone(), lua);
@@ -147,6 +199,123 @@ pub fn redis_resp_to_lua_resp(resp: Frame, lua: &Lua) -> LuaValue {
     }
 }
 
+/// Format the result of a float increment. The shortest representation that
+/// parses back to the same value is used, it has at most 17 significant
+/// digits, no exponent and no trailing zeros, e.g. `3` or `10.5`.
+pub fn format_float(value: f64) -> String {
+    value.to_string()
+}
+
+/// Match a string against a glob pattern the way Redis does: `*` and `?`
+/// match any substring and any byte, `[...]` a set or range of bytes and is
+/// negated by a leading `^`, `\` escapes the next byte.
+pub fn glob_match(pattern: &[u8], string: &[u8]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    while p < pattern.len() {
+        match pattern[p] {
+            b'*' => {
+                while p + 1 < pattern.len() && pattern[p + 1] == b'*' {
+                    p += 1;
+                }
+                if p + 1 == pattern.len() {
+                    return true;
+                }
+                return (s..=string.len()).any(|i| glob_match(&pattern[p + 1..], &string[i..]));
+            }
+            b'?' => {
+                if s == string.len() {
+                    return false;
+                }
+                s += 1;
+            }
+            b'[' => {
+                if s == string.len() {
+                    return false;
+                }
+                p += 1;
+                let negated = p < pattern.len() && pattern[p] == b'^';
+                if negated {
+                    p += 1;
+                }
+                let mut matched = false;
+                loop {
+                    if p >= pattern.len() {
+                        // an unterminated set ends with the pattern
+                        p -= 1;
+                        break;
+                    }
+                    if pattern[p] == b']' {
+                        break;
+                    }
+                    if pattern[p] == b'\\' && p + 1 < pattern.len() {
+                        p += 1;
+                        matched |= pattern[p] == string[s];
+                    } else if p + 2 < pattern.len() && pattern[p + 1] == b'-' {
+                        let (lo, hi) = if pattern[p] <= pattern[p + 2] {
+                            (pattern[p], pattern[p + 2])
+                        } else {
+                            (pattern[p + 2], pattern[p])
+                        };
+                        matched |= lo <= string[s] && string[s] <= hi;
+                        p += 2;
+                    } else {
+                        matched |= pattern[p] == string[s];
+                    }
+                    p += 1;
+                }
+                if matched == negated {
+                    return false;
+                }
+                s += 1;
+            }
+            c => {
+                let c = if c == b'\\' && p + 1 < pattern.len() {
+                    p += 1;
+                    pattern[p]
+                } else {
+                    c
+                };
+                if s == string.len() || string[s] != c {
+                    return false;
+                }
+                s += 1;
+            }
+        }
+        p += 1;
+    }
+    s == string.len()
+}
+
+/// Encode the position a collection scan resumes from into an opaque cursor,
+/// `0` once the scan is complete. The position is hex encoded, it is never
+/// empty so the cursor can not be mistaken for `0`.
+pub fn encode_scan_cursor(next: Option<Vec<u8>>) -> String {
+    match next {
+        Some(next) => hex::encode(next),
+        None => "0".to_owned(),
+    }
+}
+
+/// Decode a cursor returned by `encode_scan_cursor`, `0` starts a new scan
+/// from the first element.
+pub fn decode_scan_cursor(cursor: &str) -> Result<Vec<u8>, RTError> {
+    if cursor == "0" {
+        return Ok(vec![]);
+    }
+    match hex::decode(cursor) {
+        Ok(next) if !next.is_empty() => Ok(next),
+        _ => Err(REDIS_INVALID_CURSOR_ERR),
+    }
+}
+
+/// Reply of a collection scan, the cursor followed by the elements.
+pub fn resp_scan(next: Option<Vec<u8>>, elements: Vec<Frame>) -> Frame {
+    resp_array(vec![
+        resp_bulk(encode_scan_cursor(next).into_bytes()),
+        resp_array(elements),
+    ])
+}
+
 pub fn key_is_expired(ttl: u64) -> bool {
     let d = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -166,6 +335,34 @@ pub fn timestamp_from_ttl(ttl: u64) -> u64 {
     ttl + now_timestamp_in_millis()
 }
 
+/// Expire timestamp in ms of the relative ttl in ms given to SET EX/PX and
+/// SETEX. `None` if the ttl is not positive or the timestamp overflows, which
+/// is an invalid expire time.
+pub fn timestamp_from_relative_ttl(ttl: i64) -> Option<u64> {
+    if ttl <= 0 {
+        return None;
+    }
+    (ttl as u64)
+        .checked_add(now_timestamp_in_millis())
+        .filter(|ts| *ts <= i64::MAX as u64)
+}
+
+/// Expire timestamp in ms of EXPIRE, PEXPIRE, EXPIREAT and PEXPIREAT, `None`
+/// if it overflows, which is an invalid expire time. A timestamp in the past
+/// deletes the key.
+pub fn expire_timestamp(value: i64, is_millis: bool, expire_at: bool) -> Option<i64> {
+    let ms = if is_millis {
+        value
+    } else {
+        value.checked_mul(1000)?
+    };
+    if expire_at {
+        Some(ms)
+    } else {
+        ms.checked_add(now_timestamp_in_millis() as i64)
+    }
+}
+
 pub fn ttl_from_timestamp(timestamp: u64) -> u64 {
     let now = now_timestamp_in_millis();
     if now > timestamp {
@@ -227,6 +424,15 @@ pub fn sha1hex(s: &str) -> String {
     sha1.encode_hex::<String>()
 }
 
+/// Join a listen address and port, IPv6 addresses are bracketed.
+pub fn addr_with_port(addr: &str, port: &str) -> String {
+    if addr.contains(':') && !addr.starts_with('[') {
+        format!("[{}]:{}", addr, port)
+    } else {
+        format!("{}:{}", addr, port)
+    }
+}
+
 pub fn count_unique_keys<T: std::hash::Hash + std::cmp::Eq>(keys: &[T]) -> usize {
     keys.iter().collect::<HashSet<&T>>().len()
 }
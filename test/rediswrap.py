@@ -13,6 +13,9 @@ class RedisWrapper:
     # auth password when requirepass is true
     password = ""
 
+    # the server publishes all the keyspace events, `notify_keyspace_events = "KEA"`
+    notify_keyspace_events = False
+
     @classmethod
     def set_instance_manually(cls, ip=default_ip, port=default_port):
         cls._set_instance(ip, port)
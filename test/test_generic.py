@@ -1,3 +1,7 @@
+import hashlib
+import socket
+import threading
+import time
 import unittest
 
 from redis import exceptions
@@ -24,9 +28,157 @@ class GenericTest(unittest.TestCase):
         self.assertTrue(RedisWrapper.auth())
         self.assertTrue(self.r.ping())
 
+    @unittest.skipUnless(RedisWrapper.requirepass, "skip auth when requirepass is false")
+    def test_auth_username(self):
+        client = RedisWrapper.clone()
+        with self.assertRaises(Exception) as cm:
+            client.execute_command('hello', 3)
+        self.assertIn('HELLO must be called with the client already authenticated', str(cm.exception))
+        with self.assertRaises(Exception) as cm:
+            client.execute_command('auth', 'nobody', RedisWrapper.password)
+        self.assertIn('invalid username-password pair', str(cm.exception))
+        self.assertRaises(exceptions.AuthenticationError, client.ping)
+        self.assertTrue(client.execute_command('auth', 'default', RedisWrapper.password))
+        self.assertTrue(client.ping())
+
+        client = RedisWrapper.clone()
+        hello = client.execute_command('hello', 2, 'auth', 'default', RedisWrapper.password)
+        self.assertIn('proto', hello)
+        self.assertTrue(client.ping())
+
+    def test_acl(self):
+        user = 'acl_' + random_string(8)
+        self.assertEqual(self.r.execute_command('acl whoami'), 'default')
+        self.assertEqual(self.r.execute_command('acl setuser', user, 'on', '>secret',
+                                                '~' + self.k1, '+@read', '+set', '+@transaction'), 'OK')
+        digest = hashlib.sha1(b'secret').hexdigest()
+        rules = [r for r in self.r.execute_command('acl list') if r.startswith('user %s ' % user)]
+        self.assertEqual(len(rules), 1)
+        self.assertTrue(rules[0].startswith('user %s on #%s ~%s -@all +' % (user, digest, self.k1)))
+        getuser = self.r.execute_command('acl getuser', user)
+        self.assertEqual(getuser[getuser.index('keys') + 1], '~' + self.k1)
+        self.assertIsNone(self.r.execute_command('acl getuser', 'nobody_' + user))
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('acl setuser', user, '+nosuchcommand')
+        self.assertIn('Unknown command', str(cm.exception))
+
+        client = RedisWrapper.clone()
+        with self.assertRaises(Exception) as cm:
+            client.execute_command('auth', user, 'wrong')
+        self.assertIn('invalid username-password pair', str(cm.exception))
+        self.assertTrue(client.execute_command('auth', user, 'secret'))
+        self.assertTrue(client.set(self.k1, 'value1'))
+        self.assertEqual(client.get(self.k1), 'value1')
+        with self.assertRaises(Exception) as cm:
+            client.get(self.k2)
+        self.assertIn('No permissions to access a key', str(cm.exception))
+        with self.assertRaises(Exception) as cm:
+            client.delete(self.k1)
+        self.assertIn("User %s has no permissions to run the 'del' command" % user, str(cm.exception))
+
+        # a denied command aborts the transaction
+        client.execute_command('multi')
+        with self.assertRaises(Exception):
+            client.execute_command('del', self.k1)
+        self.assertRaises(exceptions.ResponseError, client.execute_command, 'exec')
+
+        # disabled users can not authenticate
+        self.r.execute_command('acl setuser', user, 'off')
+        client = RedisWrapper.clone()
+        with self.assertRaises(Exception) as cm:
+            client.execute_command('auth', user, 'secret')
+        self.assertIn('invalid username-password pair', str(cm.exception))
+
+    def test_acl_numkeys(self):
+        user = 'acl_' + random_string(8)
+        public = 'public:' + random_string(8)
+        self.r.execute_command('acl setuser', user, 'on', '>secret', '~public:*', '+@all')
+        sha = self.r.script_load('return 1')
+        client = RedisWrapper.clone()
+        self.assertTrue(client.execute_command('auth', user, 'secret'))
+        self.assertEqual(client.execute_command('zunion', 1, public), [])
+        self.assertEqual(client.eval("return redis.call('get', KEYS[1])", 1, public), None)
+
+        # the keys following numkeys are checked, the secret key among them
+        for args in [('zunion', 1, self.k1), ('zinter', 2, public, self.k1), ('zdiff', 1, self.k1),
+                     ('zdiffstore', public, 1, self.k1), ('zmpop', 1, self.k1, 'min'),
+                     ('lmpop', 2, public, self.k1, 'left'), ('sintercard', 1, self.k1),
+                     ('eval', 'return 1', 1, self.k1), ('evalsha', sha, 1, self.k1)]:
+            with self.assertRaises(Exception) as cm:
+                client.execute_command(*args)
+            self.assertIn('No permissions to access a key', str(cm.exception), args)
+
+        # so are the commands called by scripts
+        with self.assertRaises(Exception) as cm:
+            client.eval("return redis.call('get', ARGV[1])", 0, self.k1)
+        self.assertIn('No permissions to access a key', str(cm.exception))
+        self.r.execute_command('acl setuser', user, 'off')
+
     def test_ping(self):
         self.assertTrue(self.r.ping())
 
+    def test_wait(self):
+        self.r.set(self.k1, 'value1')
+        self.assertEqual(self.r.execute_command('wait', 1, 1000), 1)
+        self.assertEqual(self.r.execute_command('wait', 0, 0), 0)
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('wait', 1, -1)
+        self.assertEqual(str(cm.exception), 'timeout is negative')
+
+    def test_config_get(self):
+        config = self.r.config_get('backend_*')
+        self.assertIn('backend_timeout', config)
+        self.assertTrue(all(name.startswith('backend_') for name in config))
+        self.assertEqual(self.r.config_get('PORT'), self.r.config_get('port'))
+        self.assertEqual(len(self.r.config_get('port')), 1)
+        self.assertGreater(len(self.r.config_get('*')), len(config))
+        self.assertEqual(self.r.config_get('maxmemory*'), {})
+        self.assertEqual(self.r.config_get('no_such_parameter'), {})
+        self.assertNotIn('password', self.r.config_get('*'))
+
+    def test_config_set(self):
+        limit = self.r.config_get('cmd_scan_count_limit')['cmd_scan_count_limit']
+        self.assertTrue(self.r.config_set('cmd_scan_count_limit', 123))
+        self.assertEqual(self.r.config_get('cmd_scan_count_limit'), {'cmd_scan_count_limit': '123'})
+        self.assertTrue(self.r.config_set('cmd_scan_count_limit', limit))
+
+        enabled = self.r.config_get('debug_raw_enabled')['debug_raw_enabled']
+        self.assertTrue(self.r.config_set('debug_raw_enabled', 'no'))
+        self.assertEqual(self.r.config_get('debug_raw_enabled'), {'debug_raw_enabled': 'false'})
+        self.assertTrue(self.r.config_set('debug_raw_enabled', enabled))
+
+        with self.assertRaises(Exception) as cm:
+            self.r.config_set('cmd_scan_count_limit', 'abc')
+        self.assertIn("couldn't be parsed into an integer", str(cm.exception))
+        with self.assertRaises(Exception) as cm:
+            self.r.config_set('access_time_sample_rate', 2)
+        self.assertIn('between 0 and 1', str(cm.exception))
+        with self.assertRaises(Exception) as cm:
+            self.r.config_set('backend_timeout', 1000)
+        self.assertIn("can't set immutable config", str(cm.exception))
+        with self.assertRaises(Exception) as cm:
+            self.r.config_set('no_such_parameter', 1)
+        self.assertIn('Unknown option', str(cm.exception))
+
+        # nothing is set if any parameter is rejected
+        with self.assertRaises(Exception):
+            self.r.execute_command('config set', 'cmd_scan_count_limit', 7, 'port', 1)
+        self.assertEqual(self.r.config_get('cmd_scan_count_limit'), {'cmd_scan_count_limit': limit})
+
+    def test_cmd_timeout(self):
+        self.r.rpush(self.k1, *range(20000))
+        timeout = self.r.config_get('cmd_timeout_ms')['cmd_timeout_ms']
+        self.assertTrue(self.r.config_set('cmd_timeout_ms', 1))
+        try:
+            with self.assertRaises(Exception) as cm:
+                self.r.lrange(self.k1, 0, -1)
+            self.assertEqual(str(cm.exception), 'command timed out')
+            # the connection stays usable
+            self.assertTrue(self.r.ping())
+        finally:
+            self.assertTrue(self.r.config_set('cmd_timeout_ms', timeout))
+        self.assertEqual(len(self.r.lrange(self.k1, 0, -1)), 20000)
+
     def test_multi_exec(self):
         self.assertTrue(self.r.execute_command('multi'))
         self.r.execute_command('set', self.k1, 'value1')
@@ -58,6 +210,661 @@ class GenericTest(unittest.TestCase):
         err = cm.exception
         self.assertEqual(str(err), 'DISCARD without MULTI')
 
+    def test_multi_execabort(self):
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('set', self.k1, 'value1')
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'notacommand', self.k1)
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('exec')
+        self.assertIn('Transaction discarded', str(cm.exception))
+        # nothing queued ran
+        self.assertIsNone(self.r.get(self.k1))
+
+    def test_watch(self):
+        other = RedisWrapper.clone()
+        self.r.set(self.k1, 'value1')
+        # written by another connection after the watch
+        self.assertEqual(self.r.execute_command('watch', self.k1, self.k2), 'OK')
+        other.set(self.k1, 'value2')
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('set', self.k2, 'value1')
+        self.assertIsNone(self.r.execute_command('exec'))
+        self.assertIsNone(self.r.get(self.k2))
+        # the watched key is created
+        self.assertEqual(self.r.execute_command('watch', self.k2), 'OK')
+        other.hset(self.k2, 'field1', 'value1')
+        self.assertTrue(self.r.execute_command('multi'))
+        self.assertIsNone(self.r.execute_command('exec'))
+        # the keys are forgotten by exec
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('set', self.k1, 'value3')
+        self.assertListEqual(self.r.execute_command('exec'), ['OK'])
+        # unchanged key, then written by the queued commands themselves
+        self.assertEqual(self.r.execute_command('watch', self.k1), 'OK')
+        self.assertTrue(self.r.execute_command('multi'))
+        self.r.execute_command('set', self.k1, 'value4')
+        self.assertListEqual(self.r.execute_command('exec'), ['OK'])
+        self.assertEqual(self.r.get(self.k1), 'value4')
+        # a collection field overwritten without changing its size
+        self.assertEqual(self.r.execute_command('watch', self.k2), 'OK')
+        other.hset(self.k2, 'field1', 'value2')
+        self.assertTrue(self.r.execute_command('multi'))
+        self.assertIsNone(self.r.execute_command('exec'))
+        # unwatch
+        self.assertEqual(self.r.execute_command('watch', self.k1), 'OK')
+        other.set(self.k1, 'value5')
+        self.assertEqual(self.r.execute_command('unwatch'), 'OK')
+        self.assertTrue(self.r.execute_command('multi'))
+        self.assertListEqual(self.r.execute_command('exec'), [])
+        # watch inside multi
+        self.assertTrue(self.r.execute_command('multi'))
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('watch', self.k1)
+        self.assertEqual(str(cm.exception), 'WATCH inside MULTI is not allowed')
+        self.assertEqual(self.r.execute_command('discard'), 'OK')
+
+    def test_watch_commit_conflict(self):
+        other = RedisWrapper.clone()
+        k3 = random_string(10)
+        self.r.rpush(self.k2, *range(5000))
+        stop = threading.Event()
+
+        # the watched key is written while the queued commands run, after it
+        # was compared, so that the commit conflicts with its lock
+        def writer():
+            while not stop.is_set():
+                other.set(self.k1, random_string(8))
+
+        thread = threading.Thread(target=writer)
+        thread.start()
+        aborted = 0
+        try:
+            for _ in range(10):
+                self.assertEqual(self.r.execute_command('watch', self.k1), 'OK')
+                self.assertTrue(self.r.execute_command('multi'))
+                for _ in range(5):
+                    self.r.execute_command('lrange', self.k2, 0, -1)
+                self.r.execute_command('set', k3, 'value1')
+                # never an error, the transaction is either aborted or applied
+                res = self.r.execute_command('exec')
+                if res is None:
+                    aborted += 1
+                    self.assertIsNone(self.r.get(k3))
+                else:
+                    self.assertEqual(res[-1], 'OK')
+                    self.assertEqual(self.r.get(k3), 'value1')
+                    self.r.delete(k3)
+        finally:
+            stop.set()
+            thread.join()
+        self.assertGreater(aborted, 0)
+        self.r.delete(k3)
+
+    def test_bulk_load(self):
+        self.assertEqual(self.r.execute_command('bulkload begin batch', 2), 'OK')
+        self.assertEqual(self.r.execute_command('set', self.k1, 'value1'), 'OK')
+        self.assertEqual(self.r.execute_command('hset', self.k2, 'field1', 'value1'), 1)
+        self.assertEqual(self.r.execute_command('set', self.k1, 'value2'), 'OK')
+        # reads are no writes, the other commands are applied as usual
+        self.assertEqual(self.r.execute_command('get', self.k1), 'value2')
+        self.assertTrue(self.r.ping())
+        self.assertIn('bulk_load_batch_size', self.r.config_get('bulk_load_batch_size'))
+        self.assertIn('pending:1', self.r.execute_command('bulkload status'))
+        report = self.r.execute_command('bulkload end')
+        self.assertIn('imported:3', report)
+        self.assertIn('batches:2', report)
+        self.assertEqual(self.r.get(self.k1), 'value2')
+        self.assertEqual(self.r.hget(self.k2, 'field1'), 'value1')
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('bulkload end')
+        self.assertEqual(str(cm.exception), 'bulk load not started')
+
+    def test_del_many(self):
+        keys = ['__del_many_{}__'.format(i) for i in range(1000)]
+        self.r.delete(*keys)
+        pipe = self.r.pipeline(transaction=False)
+        for i, key in enumerate(keys):
+            kind = i % 5
+            if kind == 0:
+                pipe.set(key, 'v')
+            elif kind == 1:
+                pipe.hset(key, mapping={'f1': 'v1', 'f2': 'v2'})
+            elif kind == 2:
+                pipe.rpush(key, 'e1', 'e2')
+            elif kind == 3:
+                pipe.sadd(key, 'm1', 'm2')
+            else:
+                pipe.zadd(key, {'m1': 1, 'm2': 2})
+        pipe.execute()
+
+        # repeated and missing keys are not counted
+        self.assertEqual(self.r.delete(*(keys + keys[:10] + ['__del_many_missing__'])), 1000)
+        self.assertEqual(self.r.exists(*keys), 0)
+        self.assertEqual(self.r.delete(*keys), 0)
+
+        # no member of the deleted collections is left behind
+        self.r.hset(keys[1], 'f3', 'v3')
+        self.assertEqual(self.r.hgetall(keys[1]), {'f3': 'v3'})
+        self.r.rpush(keys[2], 'e3')
+        self.assertEqual(self.r.lrange(keys[2], 0, -1), ['e3'])
+        self.r.sadd(keys[3], 'm3')
+        self.assertEqual(self.r.smembers(keys[3]), {'m3'})
+        self.r.zadd(keys[4], {'m3': 3})
+        self.assertEqual(self.r.zrange(keys[4], 0, -1), ['m3'])
+        self.assertEqual(self.r.delete(*keys[:5]), 4)
+
+    def test_copy(self):
+        self.r.hset(self.k1, mapping={'f1': 'v1', 'f2': 'v2'})
+        self.r.expire(self.k1, 100)
+        self.assertEqual(self.r.execute_command('copy', self.k1, self.k2), 1)
+        self.assertEqual(self.r.hgetall(self.k2), {'f1': 'v1', 'f2': 'v2'})
+        self.assertEqual(self.r.hlen(self.k2), 2)
+        self.assertGreater(self.r.ttl(self.k2), 0)
+        # the copy is independent of the source
+        self.r.hset(self.k2, 'f3', 'v3')
+        self.assertEqual(self.r.hlen(self.k1), 2)
+
+        # an existing destination is kept without REPLACE
+        self.r.delete(self.k1)
+        self.r.rpush(self.k1, 'e1', 'e2')
+        self.assertEqual(self.r.execute_command('copy', self.k1, self.k2), 0)
+        self.assertEqual(self.r.type(self.k2), 'hash')
+        self.assertEqual(self.r.execute_command('copy', self.k1, self.k2, 'replace'), 1)
+        self.assertEqual(self.r.lrange(self.k2, 0, -1), ['e1', 'e2'])
+        self.assertEqual(self.r.ttl(self.k2), -1)
+
+        self.r.delete(self.k1)
+        self.r.sadd(self.k1, 'm1', 'm2')
+        self.assertEqual(self.r.execute_command('copy', self.k1, self.k2, 'db', 0, 'replace'), 1)
+        self.assertEqual(self.r.smembers(self.k2), {'m1', 'm2'})
+
+        self.r.delete(self.k1)
+        self.r.zadd(self.k1, {'m1': 1, 'm2': 2})
+        self.assertEqual(self.r.execute_command('copy', self.k1, self.k2, 'replace'), 1)
+        self.assertEqual(self.r.zrangebyscore(self.k2, 2, 2), ['m2'])
+        self.assertEqual(self.r.zcard(self.k2), 2)
+
+        self.r.set(self.k1, 'value1', ex=100)
+        self.assertEqual(self.r.execute_command('copy', self.k1, self.k2, 'replace'), 1)
+        self.assertEqual(self.r.get(self.k2), 'value1')
+        self.assertGreater(self.r.ttl(self.k2), 0)
+
+        self.r.delete(self.k1)
+        self.assertEqual(self.r.execute_command('copy', self.k1, self.k2), 0)
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('copy', self.k2, self.k2)
+        self.assertEqual(str(cm.exception), 'source and destination objects are the same')
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('copy', self.k2, self.k1, 'db', 1)
+        self.assertEqual(str(cm.exception), 'DB index is out of range')
+
+    def test_object_idletime(self):
+        self.assertIsNone(self.r.object('idletime', self.k1))
+        self.assertEqual(self.r.set(self.k1, 'value1'), True)
+        self.assertGreaterEqual(self.r.object('idletime', self.k1), 0)
+        self.assertGreaterEqual(self.r.debug_object(self.k1)['lru_seconds_idle'], 0)
+        self.assertEqual(self.r.delete(self.k1), 1)
+        with self.assertRaises(Exception) as cm:
+            self.r.debug_object(self.k1)
+        self.assertEqual(str(cm.exception), 'no such key')
+
+    def test_debug_object(self):
+        self.r.set(self.k1, 'value1')
+        obj = self.r.debug_object(self.k1)
+        self.assertEqual(obj['type'], 'string')
+        self.assertEqual(obj['encoding'], 'embstr')
+        self.assertGreater(obj['serializedlength'], len('value1'))
+        self.assertNotIn('members', obj)
+
+        self.r.hset(self.k2, mapping={'f1': 'v1', 'f2': 'v2', 'f3': 'v3'})
+        obj = self.r.debug_object(self.k2)
+        self.assertEqual(obj['type'], 'hash')
+        self.assertEqual(obj['encoding'], 'hashtable')
+        self.assertEqual(int(obj['members']), 3)
+        self.r.hset(self.k2, 'f4', 'v4' * 100)
+        self.assertGreater(self.r.debug_object(self.k2)['serializedlength'], obj['serializedlength'] + 200)
+
+    def test_object_encoding(self):
+        self.assertIsNone(self.r.object('encoding', self.k1))
+        self.assertIsNone(self.r.object('refcount', self.k1))
+        self.r.set(self.k1, 'value1')
+        self.assertEqual(self.r.object('encoding', self.k1), 'embstr')
+        self.assertEqual(self.r.object('refcount', self.k1), 1)
+        self.r.set(self.k1, 12345)
+        self.assertEqual(self.r.object('encoding', self.k1), 'int')
+        self.r.set(self.k1, 'v' * 45)
+        self.assertEqual(self.r.object('encoding', self.k1), 'raw')
+        self.r.zadd(self.k2, {'m1': 1})
+        self.assertEqual(self.r.object('encoding', self.k2), 'skiplist')
+        self.r.delete(self.k2)
+        self.r.rpush(self.k2, 'e1')
+        self.assertEqual(self.r.object('encoding', self.k2), 'quicklist')
+
+    def test_debug_backend(self):
+        stats = self.r.execute_command('debug', 'backend')
+        stats = dict(zip(stats[::2], stats[1::2]))
+        self.assertGreater(stats['txn_client_pool_size'], 0)
+        # the pool is resized within its bounds
+        config = self.r.config_get('conn_*')
+        self.assertGreaterEqual(stats['txn_client_pool_size'], int(config['conn_concurrency']))
+        self.assertLessEqual(stats['txn_client_pool_size'], int(config['conn_concurrency_max']))
+        self.assertLess(stats['txn_client_index'], stats['txn_client_pool_size'])
+        self.assertEqual(len(stats['txn_client_inflight']), stats['txn_client_pool_size'])
+        self.assertEqual(stats['unhealthy_txn_clients'], [])
+        self.assertGreater(len(stats['pd_addrs']), 0)
+
+    def test_info(self):
+        info = self.r.info()
+        for field in ['redis_version', 'uptime_in_seconds', 'connected_clients', 'used_memory',
+                      'total_commands_processed', 'total_net_input_bytes', 'instance_id']:
+            self.assertIn(field, info)
+        self.assertGreaterEqual(info['connected_clients'], 1)
+
+        commands = self.r.info('stats')['total_commands_processed']
+        self.r.set(self.k1, 'value1')
+        stats = self.r.info('stats')
+        self.assertNotIn('connected_clients', stats)
+        self.assertGreater(stats['total_commands_processed'], commands)
+        self.assertIn('txn_client_pool_size', self.r.info('tidis'))
+        self.assertEqual(self.r.info('nosuchsection'), {})
+
+    def test_slowlog(self):
+        config = self.r.config_get('slowlog_*')
+        try:
+            # the dash names of redis are accepted as well
+            self.assertTrue(self.r.config_set('slowlog-log-slower-than', 0))
+            self.assertTrue(self.r.slowlog_reset())
+            self.r.set(self.k1, 'value1')
+            self.assertGreaterEqual(self.r.slowlog_len(), 1)
+            entries = self.r.slowlog_get(1)
+            self.assertEqual(len(entries), 1)
+            self.assertIn('duration', entries[0])
+
+            self.r.set(self.k1, 'v' * 1000)
+            self.assertIn('more bytes', self.r.slowlog_get(1)[0]['command'])
+            self.assertGreaterEqual(len(self.r.slowlog_get(-1)), 2)
+
+            # the passwords are never logged
+            self.r.slowlog_reset()
+            try:
+                self.r.execute_command('auth', 'secret')
+            except exceptions.ResponseError:
+                pass
+            self.assertTrue(all('secret' not in e['command'] for e in self.r.slowlog_get(-1)))
+
+            self.assertTrue(self.r.config_set('slowlog-max-len', 1))
+            self.r.get(self.k1)
+            self.r.get(self.k1)
+            self.assertEqual(self.r.slowlog_len(), 1)
+
+            self.assertTrue(self.r.config_set('slowlog_log_slower_than', -1))
+            self.r.slowlog_reset()
+            self.r.get(self.k1)
+            self.assertEqual(self.r.slowlog_len(), 0)
+        finally:
+            for name, value in config.items():
+                self.r.config_set(name, value)
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'slowlog', 'nosuch')
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'slowlog', 'get', -2)
+
+    def test_debug_reconnect(self):
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'debug', 'reconnect')
+        stats = self.r.execute_command('debug', 'backend')
+        pd_addrs = dict(zip(stats[::2], stats[1::2]))['pd_addrs']
+        self.assertTrue(self.r.execute_command('debug', 'reconnect', *pd_addrs))
+        self.r.set(self.k1, 'value1')
+        self.assertEqual(self.r.get(self.k1), 'value1')
+
+        # the clients in use are kept if the new addresses can't be reached
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'debug', 'reconnect', '127.0.0.1:1')
+        stats = self.r.execute_command('debug', 'backend')
+        self.assertEqual(dict(zip(stats[::2], stats[1::2]))['pd_addrs'], pd_addrs)
+        self.assertEqual(self.r.get(self.k1), 'value1')
+
+    def test_debug_repair_meta(self):
+        self.r.hset(self.k1, mapping={'f1': 'v1', 'f2': 'v2', 'f3': 'v3'})
+        self.r.hset(self.k1, 'f1', 'v0')
+        # a consistent size meta is kept
+        self.assertEqual(self.r.execute_command('debug', 'repair_meta', self.k1), [3, 3])
+        self.assertEqual(self.r.hlen(self.k1), 3)
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'debug', 'repair_meta', self.k2)
+        self.r.set(self.k2, 'v')
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'debug', 'repair_meta', self.k2)
+
+    def test_debug_set_active_expire(self):
+        self.assertTrue(self.r.execute_command('debug', 'set-active-expire', 0))
+        self.r.set(self.k1, 'value1', px=100)
+        time.sleep(0.2)
+        # expired lazily on access
+        self.assertIsNone(self.r.get(self.k1))
+        self.assertTrue(self.r.execute_command('debug', 'set-active-expire', 1))
+        self.assertRaises(Exception, self.r.execute_command, 'debug', 'set-active-expire', 2)
+
+    def test_active_expire(self):
+        enabled = self.r.config_get('debug_raw_enabled')['debug_raw_enabled']
+        self.assertTrue(self.r.config_set('debug_raw_enabled', 'yes'))
+        try:
+            self.r.set(self.k1, 'value1', px=100)
+            # debug raw reads the stored entries without expiring the key
+            deadline = time.time() + 10
+            while time.time() < deadline:
+                try:
+                    self.r.execute_command('debug', 'raw', self.k1)
+                except exceptions.ResponseError:
+                    break
+                time.sleep(0.2)
+            self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'debug', 'raw', self.k1)
+        finally:
+            self.r.config_set('debug_raw_enabled', enabled)
+
+    def test_debug_profiler(self):
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'debug', 'profiler_stop')
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'debug', 'profiler_start', 0)
+        self.assertTrue(self.r.execute_command('debug', 'profiler_start', 199))
+        self.r.set(self.k1, 'value1')
+        prefix = '/tmp/tidis-test-profile'
+        paths = self.r.execute_command('debug', 'profiler_stop', prefix)
+        self.assertEqual(paths, [prefix + '-flamegraph.svg', prefix + '-profile.pb'])
+        self.assertRaises(exceptions.ResponseError, self.r.execute_command, 'debug', 'profiler_stop')
+
+    def test_debug_sleep(self):
+        self.assertTrue(self.r.execute_command('debug', 'sleep', 0))
+        self.assertRaises(Exception, self.r.execute_command, 'debug', 'sleep', 'abc')
+        self.assertRaises(Exception, self.r.execute_command, 'debug', 'sleep', -1)
+
+        # other connections stay responsive while a connection sleeps
+        sleeper = threading.Thread(target=self.r.execute_command, args=('debug', 'sleep', 1.5))
+        start = time.time()
+        sleeper.start()
+        time.sleep(0.2)
+        self.assertTrue(self.r.ping())
+        self.assertLess(time.time() - start, 1.0)
+        sleeper.join()
+        self.assertGreaterEqual(time.time() - start, 1.5)
+
+    def test_debug_raw(self):
+        self.r.hset(self.k1, 'f1', 'v1')
+        try:
+            pairs = self.r.execute_command('debug', 'raw', self.k1)
+        except exceptions.ResponseError as e:
+            self.assertIn('disabled', str(e))
+            return
+        # meta and data key at least
+        self.assertGreaterEqual(len(pairs), 2)
+        for pair in pairs:
+            self.assertEqual(len(pair), 2)
+            bytes.fromhex(pair[0])
+
+    def test_key_prefix_encoding(self):
+        def encode(data):
+            # memcomparable groups of 8 bytes, each followed by a marker
+            enc = b''
+            for i in range(0, len(data) + 1, 8):
+                group = data[i:i + 8]
+                enc += group + b'\x00' * (8 - len(group)) + bytes([0xff - (8 - len(group))])
+            return enc
+
+        enabled = self.r.config_get('debug_raw_enabled')['debug_raw_enabled']
+        self.assertTrue(self.r.config_set('debug_raw_enabled', 'yes'))
+        try:
+            self.r.set(self.k1, 'value1')
+            pairs = self.r.execute_command('debug', 'raw', self.k1)
+        finally:
+            self.r.config_set('debug_raw_enabled', enabled)
+        # the key prefix is encoded on its own before the user key, so the keys
+        # of the tenants `a` and `ab` never share an encoded prefix
+        prefix = self.r.config_get('key_prefix')['key_prefix'].encode()
+        instance_id = int(self.r.info('tidis')['instance_id'])
+        expected = b'x' + instance_id.to_bytes(2, 'big') + b'u' + \
+            (encode(prefix) if prefix else b'') + encode(self.k1.encode()) + b'm'
+        self.assertIn(expected.hex(), [key for key, _ in pairs])
+
+    def _raw_command(self, sock, *args):
+        req = '*{}\r\n'.format(len(args)) + ''.join('${}\r\n{}\r\n'.format(len(str(a)), a) for a in args)
+        sock.sendall(req.encode())
+        time.sleep(0.2)
+        return sock.recv(4096)
+
+    def _raw_connection(self):
+        kwargs = self.r.connection_pool.connection_kwargs
+        return socket.create_connection((kwargs['host'], int(kwargs['port'])))
+
+    def test_hello(self):
+        sock = self._raw_connection()
+        try:
+            self.assertTrue(self._raw_command(sock, 'hello', 3).startswith(b'%7\r\n'))
+            self.assertTrue(self._raw_command(sock, 'hello', 2).startswith(b'*14\r\n'))
+            self.assertTrue(self._raw_command(sock, 'hello', 4).startswith(b'-NOPROTO'))
+        finally:
+            sock.close()
+
+    def test_resp3_push(self):
+        resp3 = self._raw_connection()
+        resp2 = self._raw_connection()
+        try:
+            self._raw_command(resp3, 'hello', 3)
+            self.assertTrue(self._raw_command(resp3, 'subscribe', 'ch').startswith(b'>3\r\n$9\r\nsubscribe'))
+            self.assertTrue(self._raw_command(resp2, 'subscribe', 'ch').startswith(b'*3\r\n$9\r\nsubscribe'))
+
+            self.r.publish('ch', 'hi')
+            time.sleep(0.2)
+            self.assertEqual(resp3.recv(4096), b'>3\r\n$7\r\nmessage\r\n$2\r\nch\r\n$2\r\nhi\r\n')
+            self.assertEqual(resp2.recv(4096), b'*3\r\n$7\r\nmessage\r\n$2\r\nch\r\n$2\r\nhi\r\n')
+        finally:
+            resp3.close()
+            resp2.close()
+
+    def test_resp3_types(self):
+        resp3 = self._raw_connection()
+        resp2 = self._raw_connection()
+        try:
+            self._raw_command(resp3, 'hello', 3)
+            self.assertEqual(self._raw_command(resp3, 'get', self.k1), b'_\r\n')
+            self.assertEqual(self._raw_command(resp2, 'get', self.k1), b'$-1\r\n')
+            self.assertEqual(self._raw_command(resp3, 'eval', 'return true', 0), b'#t\r\n')
+            self.assertEqual(self._raw_command(resp2, 'eval', 'return true', 0), b':1\r\n')
+            self.assertEqual(self._raw_command(resp3, 'debug', 'protocol', 'false'), b'#f\r\n')
+            self.assertEqual(self._raw_command(resp2, 'debug', 'protocol', 'false'), b':0\r\n')
+            self.assertEqual(self._raw_command(resp3, 'debug', 'protocol', 'bignum'),
+                             b'(1234567999999999999999999999999999999\r\n')
+            self.assertEqual(self._raw_command(resp2, 'debug', 'protocol', 'bignum'),
+                             b'$37\r\n1234567999999999999999999999999999999\r\n')
+            self.assertEqual(self._raw_command(resp3, 'debug', 'protocol', 'map'),
+                             b'%3\r\n:0\r\n#f\r\n:1\r\n#t\r\n:2\r\n#f\r\n')
+            self.assertTrue(self._raw_command(resp2, 'debug', 'protocol', 'attrib').startswith(b'-ERR Wrong protocol type'))
+
+            self.r.hset(self.k1, 'f', 'v')
+            self.assertEqual(self._raw_command(resp3, 'hgetall', self.k1), b'%1\r\n$1\r\nf\r\n$1\r\nv\r\n')
+            self.assertEqual(self._raw_command(resp2, 'hgetall', self.k1), b'*2\r\n$1\r\nf\r\n$1\r\nv\r\n')
+            self.r.sadd(self.k2, 'm')
+            self.assertEqual(self._raw_command(resp3, 'smembers', self.k2), b'~1\r\n$1\r\nm\r\n')
+            self.assertEqual(self._raw_command(resp2, 'smembers', self.k2), b'*1\r\n$1\r\nm\r\n')
+            self.r.delete(self.k1)
+            self.r.execute_command('zadd', self.k1, 1.5, 'm')
+            self.assertEqual(self._raw_command(resp3, 'zrange', self.k1, 0, -1, 'withscores'),
+                             b'%1\r\n$1\r\nm\r\n,1.5\r\n')
+            self.assertEqual(self._raw_command(resp2, 'zrange', self.k1, 0, -1, 'withscores'),
+                             b'*2\r\n$1\r\nm\r\n$3\r\n1.5\r\n')
+        finally:
+            resp3.close()
+            resp2.close()
+
+    @unittest.skipUnless(RedisWrapper.notify_keyspace_events, "skip when keyspace notifications are disabled")
+    def test_keyspace_notifications(self):
+        sock = self._raw_connection()
+        try:
+            self._raw_command(sock, 'subscribe', '__keyspace@0__:' + self.k1, '__keyevent@0__:set')
+            self.r.set(self.k1, 'v1')
+            time.sleep(0.2)
+            reply = sock.recv(4096)
+            self.assertIn(b'__keyspace@0__:' + self.k1.encode() + b'\r\n$3\r\nset\r\n', reply)
+            self.assertIn(b'__keyevent@0__:set\r\n$' + str(len(self.k1)).encode() + b'\r\n' + self.k1.encode(), reply)
+
+            # a failed write notifies nothing
+            self.assertRaises(exceptions.ResponseError, self.r.lpush, self.k1, 'v')
+            self.r.delete(self.k1)
+            time.sleep(0.2)
+            self.assertEqual(sock.recv(4096), b'*3\r\n$7\r\nmessage\r\n$' + str(len(self.k1) + 15).encode()
+                             + b'\r\n__keyspace@0__:' + self.k1.encode() + b'\r\n$3\r\ndel\r\n')
+        finally:
+            sock.close()
+
+    def test_psubscribe(self):
+        sock = self._raw_connection()
+        try:
+            self.assertEqual(self._raw_command(sock, 'psubscribe', 'news.*'),
+                             b'*3\r\n$10\r\npsubscribe\r\n$6\r\nnews.*\r\n:1\r\n')
+            self.assertTrue(self._raw_command(sock, 'subscribe', 'news.tech').endswith(b':2\r\n'))
+            # delivered once for the channel and once for the pattern
+            self.assertEqual(self.r.publish('news.tech', 'hi'), 2)
+            self.assertEqual(self.r.publish('news', 'hi'), 0)
+            time.sleep(0.2)
+            reply = sock.recv(4096)
+            self.assertIn(b'*3\r\n$7\r\nmessage\r\n$9\r\nnews.tech\r\n$2\r\nhi\r\n', reply)
+            self.assertIn(b'*4\r\n$8\r\npmessage\r\n$6\r\nnews.*\r\n$9\r\nnews.tech\r\n$2\r\nhi\r\n', reply)
+
+            # other commands are not allowed while subscribed to patterns only
+            self.assertEqual(self._raw_command(sock, 'unsubscribe'),
+                             b'*3\r\n$11\r\nunsubscribe\r\n$9\r\nnews.tech\r\n:1\r\n')
+            self.assertTrue(self._raw_command(sock, 'get', self.k1).startswith(b'-ERR'))
+            self.assertEqual(self._raw_command(sock, 'punsubscribe'),
+                             b'*3\r\n$12\r\npunsubscribe\r\n$6\r\nnews.*\r\n:0\r\n')
+            self.assertEqual(self.r.publish('news.tech', 'hi'), 0)
+            self.assertEqual(self._raw_command(sock, 'get', self.k1), b'$-1\r\n')
+        finally:
+            sock.close()
+
+    def test_subscribe_reuse(self):
+        sock = self._raw_connection()
+        try:
+            self.assertTrue(self._raw_command(sock, 'subscribe', 'ch1', 'ch2').startswith(b'*3\r\n$9\r\nsubscribe'))
+            self.assertEqual(self.r.publish('ch1', 'hi'), 1)
+            self.assertEqual(self.r.publish('ch2', 'hi'), 1)
+            sock.recv(4096)
+
+            # unsubscribed from all the channels, back to the normal state
+            self.assertTrue(self._raw_command(sock, 'unsubscribe').startswith(b'*3\r\n$11\r\nunsubscribe'))
+            self.assertEqual(self.r.publish('ch1', 'hi'), 0)
+            self.assertEqual(self.r.publish('ch2', 'hi'), 0)
+            self.assertEqual(self._raw_command(sock, 'get', self.k1), b'$-1\r\n')
+            self.assertEqual(self._raw_command(sock, 'unsubscribe'), b'*3\r\n$11\r\nunsubscribe\r\n$-1\r\n:0\r\n')
+
+            self.assertTrue(self._raw_command(sock, 'subscribe', 'ch1').startswith(b'*3\r\n$9\r\nsubscribe'))
+            self.assertEqual(self.r.publish('ch1', 'hi'), 1)
+            self.assertEqual(self.r.publish('ch2', 'hi'), 0)
+            sock.recv(4096)
+
+            # reset leaves the subscribed state as well
+            self.assertEqual(self._raw_command(sock, 'reset'), b'+RESET\r\n')
+            self.assertEqual(self.r.publish('ch1', 'hi'), 0)
+            self.assertEqual(self._raw_command(sock, 'ping'), b'+PONG\r\n')
+        finally:
+            sock.close()
+
+    def test_subscribed_context(self):
+        resp2 = self._raw_connection()
+        resp3 = self._raw_connection()
+        try:
+            self._raw_command(resp2, 'subscribe', 'ch')
+            self.assertTrue(self._raw_command(resp2, 'get', self.k1).startswith(b"-ERR Can't execute 'get'"))
+            self.assertEqual(self._raw_command(resp2, 'ping'), b'*2\r\n$4\r\npong\r\n$0\r\n\r\n')
+
+            # replies and messages never mix in RESP3
+            self._raw_command(resp3, 'hello', 3)
+            self._raw_command(resp3, 'subscribe', 'ch')
+            self.assertEqual(self._raw_command(resp3, 'get', self.k1), b'_\r\n')
+            self.assertEqual(self._raw_command(resp3, 'ping'), b'+PONG\r\n')
+        finally:
+            resp2.close()
+            resp3.close()
+
+    def test_pipeline_backpressure(self):
+        sock = self._raw_connection()
+        try:
+            # replies are never read, the server stops reading the flood once
+            # the socket buffers are full instead of buffering it
+            sock.settimeout(3)
+            req = b'*3\r\n$5\r\ndebug\r\n$5\r\nsleep\r\n$4\r\n0.01\r\n' * 1024
+            sent = 0
+            with self.assertRaises(socket.timeout):
+                while sent < 1024 * 1024 * 1024:
+                    sock.sendall(req)
+                    sent += len(req)
+        finally:
+            sock.close()
+        # other connections are still served
+        self.assertTrue(self.r.ping())
+
+    def test_proto_limits(self):
+        for req, err in [(b'*1\r\n$2147483648\r\n', b'-ERR Protocol error: invalid bulk length\r\n'),
+                         (b'*2147483648\r\n', b'-ERR Protocol error: invalid multibulk length\r\n')]:
+            sock = self._raw_connection()
+            try:
+                sock.sendall(req)
+                time.sleep(0.2)
+                self.assertEqual(sock.recv(4096), err)
+                # the connection is closed after the error
+                self.assertEqual(sock.recv(4096), b'')
+            finally:
+                sock.close()
+        self.assertTrue(self.r.ping())
+
+    def test_proto_malformed(self):
+        for req, err in [(b'*-2\r\n', b'-ERR Protocol error: invalid multibulk length\r\n'),
+                         (b'*1\r\n$-2\r\n', b'-ERR Protocol error: invalid bulk length\r\n'),
+                         (b'*1\r\n$4\r\npingXY', b'-ERR Protocol error: invalid bulk terminator\r\n'),
+                         (b'*1\r\n' * 16, b'-ERR Protocol error: too deeply nested frame\r\n'),
+                         (b'*' + b'1' * 70000, b'-ERR Protocol error: too big line\r\n')]:
+            sock = self._raw_connection()
+            try:
+                sock.sendall(req)
+                time.sleep(0.2)
+                self.assertEqual(sock.recv(4096), err)
+                self.assertEqual(sock.recv(4096), b'')
+            finally:
+                sock.close()
+
+        # a request split at every byte and binary values are fine
+        sock = self._raw_connection()
+        try:
+            for b in b'*1\r\n$4\r\nping\r\n':
+                sock.sendall(bytes([b]))
+            time.sleep(0.2)
+            self.assertEqual(sock.recv(4096), b'+PONG\r\n')
+        finally:
+            sock.close()
+        self.assertTrue(self.r.set(self.k1, 'a\x00\r\nb'))
+        self.assertEqual(self.r.get(self.k1), 'a\x00\r\nb')
+
+    def test_inline_commands(self):
+        sock = self._raw_connection()
+        try:
+            # bare \n line endings are fine for inline commands
+            sock.sendall(('set %s "hello world"\n' % self.k1).encode())
+            time.sleep(0.2)
+            self.assertEqual(sock.recv(4096), b'+OK\r\n')
+            sock.sendall(('\r\nget %s\r\n\nping\n' % self.k1).encode())
+            time.sleep(0.2)
+            self.assertEqual(sock.recv(4096), b'$11\r\nhello world\r\n+PONG\r\n')
+        finally:
+            sock.close()
+
+        # the RESP framing stays strict
+        sock = self._raw_connection()
+        try:
+            sock.sendall(b'*1\n$4\r\nping\r\n')
+            time.sleep(0.2)
+            self.assertEqual(sock.recv(4096), b'')
+        finally:
+            sock.close()
+
+    def test_monitor(self):
+        with self.r.monitor() as m:
+            self.r.set(self.k1, 'value1')
+            self.r.get(self.k1)
+            self.assertEqual(m.next_command()['command'], 'SET %s value1' % self.k1)
+            self.assertEqual(m.next_command()['command'], 'GET %s' % self.k1)
+
     def test_client(self):
         client1 = self.r
         client1_id = client1.execute_command("client id")
@@ -67,13 +874,64 @@ class GenericTest(unittest.TestCase):
         random_name = random_string(6)
         self.assertTrue(client1.execute_command("client setname", random_name))
         self.assertEqual(client1.execute_command("client getname"), random_name)
+        with self.assertRaises(Exception) as cm:
+            client1.execute_command("client setname", "bad name")
+        self.assertIn("cannot contain spaces", str(cm.exception))
+        self.assertEqual(client1.execute_command("client getname"), random_name)
+        self.assertTrue(client1.execute_command("client setname", ""))
+        self.assertIsNone(client1.execute_command("client getname"))
+        self.assertTrue(client1.execute_command("client setname", random_name))
 
         client2 = RedisWrapper.clone()
         self.assertIsNotNone(client2.execute_command("client id"))
+        self.assertGreaterEqual(client2.info("clients")["connected_clients"], 2)
+        client1_line = client2.execute_command("client list id", client1_id)
+        self.assertIn("name={} ".format(random_name), client1_line)
+        self.assertIn(" cmd=client ", client1_line)
         self.assertIsNotNone(client2.execute_command("client list id", client1_id))
         self.assertEqual(client2.execute_command("client kill id", client1_id), 1)
         self.assertEqual(client2.execute_command("client list id", client1_id), "")
 
+    def test_client_kill(self):
+        client1 = RedisWrapper.clone()
+        client1_id = client1.execute_command("client id")
+        client1.execute_command("watch", self.k1)
+        client1.execute_command("multi")
+        self.assertEqual(client1.execute_command("set", self.k1, "v1"), "QUEUED")
+
+        # killed twice before it is closed
+        self.assertEqual(self.r.execute_command("client kill id", client1_id), 1)
+        self.r.execute_command("client kill id", client1_id)
+        for _ in range(50):
+            if self.r.execute_command("client list id", client1_id) == "":
+                break
+            time.sleep(0.1)
+        self.assertEqual(self.r.execute_command("client list id", client1_id), "")
+
+        # the client reconnects without the state of the killed connection
+        self.assertRaises(exceptions.ResponseError, client1.execute_command, "exec")
+        self.assertIsNone(self.r.get(self.k1))
+
+        # the legacy form
+        addr = client1.execute_command("client info").split(" ")[1][len("addr="):]
+        self.assertEqual(self.r.execute_command("client kill", addr), "OK")
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command("client kill", "127.0.0.1:1")
+        self.assertEqual(str(cm.exception), "No such client")
+
+    def test_client_info(self):
+        client = RedisWrapper.clone()
+        client_id = client.execute_command("client id")
+        info = client.execute_command("client info")
+        self.assertIn("id={} ".format(client_id), info)
+        self.assertIn(" multi=-1 ", info)
+        self.assertIn(" resp=2 ", info)
+        self.assertTrue(info.endswith("last-err=\n"))
+
+        self.assertTrue(client.set(self.k1, "v1"))
+        self.assertRaises(exceptions.ResponseError, client.lpush, self.k1, "v1")
+        self.assertTrue(client.execute_command("client info").endswith("last-err=WRONGTYPE\n"))
+
     def test_scan(self):
         # add some keys for scan test
         for i in range(0, 10):
@@ -97,6 +955,18 @@ class GenericTest(unittest.TestCase):
         match_scan = self.r.execute_command('xscan', '', 'count', 100, 'match', '^hash:*')
         self.assertEqual(match_scan[0], '')
         self.assertEqual(len(match_scan[1]), 10)
+        type_scan = self.r.execute_command('xscan', '', 'count', 100, 'type', 'zset')
+        self.assertEqual(sorted(type_scan[1]), sorted(['zset:' + str(i) for i in range(10)]))
+        # the filtered keys are still counted, the cursor advances over them
+        part_type_scan = self.r.execute_command('xscan', '', 'count', 10, 'type', 'set')
+        self.assertEqual(part_type_scan, ['hash:9', []])
+        type_match_scan = self.r.execute_command('xscan', '', 'count', 100, 'match', ':1$', 'type', 'LIST')
+        self.assertEqual(type_match_scan[1], ['list:1'])
+        # an unknown type matches no key
+        self.assertEqual(self.r.execute_command('xscan', '', 'count', 100, 'type', 'stream'), ['', []])
+        # a count less than 1 is rejected
+        self.assertRaises(Exception, self.r.execute_command, 'xscan', '', 'count', 0)
+        self.assertRaises(Exception, self.r.execute_command, 'xscan', '', 'count', -1)
 
         # clean up the keys
         keys = []
@@ -108,6 +978,23 @@ class GenericTest(unittest.TestCase):
             keys.append('zset:' + str(i))
         self.r.delete(*keys)
 
+    def test_randomkey(self):
+        self.assertIsNone(self.r.randomkey())
+        keys = ['random:' + str(i) for i in range(20)]
+        for key in keys:
+            self.r.set(key, 'value')
+        self.r.sadd('random:set', *['member' + str(i) for i in range(200)])
+        keys.append('random:set')
+        picked = set(self.r.randomkey() for _ in range(200))
+        self.assertTrue(picked <= set(keys))
+        self.assertGreater(len(picked), 1)
+        # expired keys are skipped
+        self.r.delete(*keys[1:])
+        self.r.pexpire(keys[0], 1)
+        time.sleep(0.01)
+        self.assertIsNone(self.r.randomkey())
+        self.r.delete(keys[0])
+
     def tearDown(self):
         pass
 
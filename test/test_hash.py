@@ -41,6 +41,14 @@ class HashTest(unittest.TestCase):
         self.assertEqual(self.v3, self.r.hget(self.k1, self.f3))
         self.assertEqual(self.v4, self.r.hget(self.k1, self.f4))
 
+        # only the new fields are counted, a repeated field once
+        self.assertEqual(self.r.execute_command('hset', self.k1, self.f1, self.v2, self.f2, self.v2,
+                                                self.f3, self.v1, self.f2, self.v3), 1)
+        self.assertEqual(self.r.hlen(self.k1), 4)
+        self.assertEqual(self.r.hget(self.k1, self.f2), self.v3)
+        self.assertEqual(self.r.hset(self.k1, self.f1, self.v1), 0)
+        self.assertEqual(self.r.hlen(self.k1), 4)
+
     def test_hsetnx(self):
         self.assertIsNone(self.r.hget(self.k1, self.f1))
         self.assertEqual(self.r.hsetnx(self.k1, self.f1, self.v1), 1)
@@ -81,6 +89,14 @@ class HashTest(unittest.TestCase):
         self.assertEqual(self.r.hdel(self.k1, self.f1, self.f2), 2)
         self.assertEqual(self.r.hlen(self.k1), 1)
 
+    def test_hgetdel(self):
+        self.assertTrue(self.r.hmset(self.k1, {self.f1: self.v1, self.f2: self.v2, self.f3: self.v3}))
+        self.assertListEqual(self.r.execute_command('HGETDEL', self.k1, 'FIELDS', 2, self.f1, self.f4), [self.v1, None])
+        self.assertEqual(self.r.hlen(self.k1), 2)
+        self.assertListEqual(self.r.execute_command('HGETDEL', self.k1, 'FIELDS', 2, self.f2, self.f3), [self.v2, self.v3])
+        self.assertEqual(self.r.exists(self.k1), 0)
+        self.assertListEqual(self.r.execute_command('HGETDEL', self.k1, 'FIELDS', 1, self.f1), [None])
+
     def test_hkeys(self):
         self.assertTrue(self.r.hmset(self.k1, {self.f1: self.v1, self.f2: self.v2, self.f3: self.v3}))
         self.assertListEqual(self.r.hkeys(self.k1), [self.f1, self.f2, self.f3])
@@ -104,6 +120,62 @@ class HashTest(unittest.TestCase):
         self.assertEqual(self.r.hincrby(self.k1, self.f1, 9), 10)
         self.assertEqual(self.r.hincrby(self.k1, self.f1, -15), -5)
 
+    def test_hincrbyfloat(self):
+        self.assertEqual(self.r.hincrbyfloat(self.k1, self.f1, 10.5), 10.5)
+        self.assertEqual(self.r.hincrbyfloat(self.k1, self.f1, 0.1), 10.6)
+        self.assertEqual(self.r.hget(self.k1, self.f1), '10.6')
+        self.assertEqual(self.r.hincrbyfloat(self.k1, self.f1, -5.6), 5)
+        self.assertEqual(self.r.hget(self.k1, self.f1), '5')
+        self.r.hset(self.k1, self.f2, self.v2)
+        with self.assertRaises(Exception) as cm:
+            self.r.hincrbyfloat(self.k1, self.f2, 1)
+        self.assertEqual(str(cm.exception), 'value is not a valid float')
+        self.assertRaises(Exception, self.r.hincrbyfloat, self.k1, self.f1, 'inf')
+
+    def test_hrandfield(self):
+        self.assertIsNone(self.r.execute_command('hrandfield', self.k1))
+        self.assertEqual(self.r.execute_command('hrandfield', self.k1, 3), [])
+        fields = {self.f1: self.v1, self.f2: self.v2, self.f3: self.v3}
+        self.assertTrue(self.r.hmset(self.k1, fields))
+        self.assertIn(self.r.execute_command('hrandfield', self.k1), fields)
+        # a positive count replies distinct fields, at most all of them
+        distinct = self.r.execute_command('hrandfield', self.k1, 2)
+        self.assertEqual(len(set(distinct)), 2)
+        self.assertEqual(sorted(self.r.execute_command('hrandfield', self.k1, 10)), sorted(fields))
+        # a negative count may repeat fields
+        repeated = self.r.execute_command('hrandfield', self.k1, -10)
+        self.assertEqual(len(repeated), 10)
+        self.assertTrue(set(repeated) <= set(fields))
+        pairs = self.r.execute_command('hrandfield', self.k1, 10, 'withvalues')
+        self.assertEqual(dict(zip(pairs[::2], pairs[1::2])), fields)
+        self.assertRaises(Exception, self.r.execute_command, 'hrandfield', self.k1, 1, 'withscores')
+        # a count out of range is rejected instead of allocating the reply
+        for args in [(-9223372036854775808,), (-9223372036854775807,), (-4611686018427387904, 'withvalues')]:
+            with self.assertRaises(Exception) as cm:
+                self.r.execute_command('hrandfield', self.k1, *args)
+            self.assertIn('value is out of range', str(cm.exception))
+
+    def test_hscan(self):
+        self.assertEqual(self.r.execute_command('hscan', self.k1, 0), ['0', []])
+        fields = {'f' + str(i): str(i) for i in range(100)}
+        self.assertTrue(self.r.hmset(self.k1, fields))
+        # the cursor is opaque, a full iteration returns every field once
+        cursor, scanned = '0', []
+        while True:
+            cursor, pairs = self.r.execute_command('hscan', self.k1, cursor, 'count', 7)
+            self.assertLessEqual(len(pairs), 14)
+            scanned += pairs
+            if cursor == '0':
+                break
+        self.assertEqual(len(scanned), 200)
+        self.assertEqual(dict(zip(scanned[::2], scanned[1::2])), fields)
+        # the pattern filters the fields after they are read
+        cursor, pairs = self.r.execute_command('hscan', self.k1, 0, 'match', 'f1?', 'count', 1000)
+        self.assertEqual(cursor, '0')
+        self.assertEqual(sorted(pairs[::2]), ['f' + str(i) for i in range(10, 20)])
+        self.assertRaises(Exception, self.r.execute_command, 'hscan', self.k1, 'cursor')
+        self.assertRaises(Exception, self.r.execute_command, 'hscan', self.k1, 0, 'count', 0)
+
     def test_del(self):
         self.assertTrue(self.r.hmset(self.k1, {self.f1: self.v1, self.f2: self.v2, self.f3: self.v3}))
         self.assertTrue(self.r.execute_command("del", self.k1))
@@ -234,7 +234,14 @@ class InvalidTest(unittest.TestCase):
 
     def test_unknown(self):
         arbitrary_unknown = "unknown_" + random_string(random.randint(3, 6)).lower()
-        self.assertError("unknown command '{}'".format(arbitrary_unknown), arbitrary_unknown)
+        self.assertError("unknown command '{}', with args beginning with: ".format(arbitrary_unknown), arbitrary_unknown)
+        self.assertError("unknown command '{}', with args beginning with: 'a' 'b c' '1' ".format(arbitrary_unknown),
+                         arbitrary_unknown, 'a', 'b c', 1)
+        # the preview of the arguments is cut at 128 bytes
+        self.assertError("unknown command '{}', with args beginning with: '{}' '{}' ".format(
+            arbitrary_unknown, 'x' * 100, 'y' * 25), arbitrary_unknown, 'x' * 100, 'y' * 100, 'z')
+        self.assertError("unknown command '{}', with args beginning with: 'a b' ".format(arbitrary_unknown),
+                         arbitrary_unknown, 'a\nb')
 
     def tearDown(self):
         pass
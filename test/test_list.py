@@ -1,3 +1,5 @@
+import socket
+import threading
 import time
 import unittest
 
@@ -45,6 +47,84 @@ class ListTest(unittest.TestCase):
         for i in range(200):
             self.assertEqual(self.r.lpop(self.k1), str(i))
 
+    def test_blpop(self):
+        self.assertTrue(self.r.rpush(self.k2, self.v1, self.v2))
+        # the first non-empty list is popped
+        self.assertEqual(self.r.blpop([self.k1, self.k2], 1), (self.k2, self.v1))
+        self.assertEqual(self.r.brpop([self.k1, self.k2], 1), (self.k2, self.v2))
+        # times out on empty lists
+        self.assertIsNone(self.r.blpop([self.k1, self.k2], 1))
+
+    def test_brpop_waiters(self):
+        n = 5
+        popped = []
+        lock = threading.Lock()
+
+        def waiter():
+            item = self.r.brpop(self.k1, 10)
+            with lock:
+                popped.append(item)
+
+        threads = [threading.Thread(target=waiter) for _ in range(n)]
+        for t in threads:
+            t.start()
+        time.sleep(0.5)
+
+        # each push is delivered to exactly one waiter
+        for i in range(n):
+            self.assertTrue(self.r.lpush(self.k1, str(i)))
+            time.sleep(0.3)
+            with lock:
+                self.assertEqual(len(popped), i + 1)
+        for t in threads:
+            t.join()
+
+        self.assertEqual(sorted(v for _, v in popped), [str(i) for i in range(n)])
+        self.assertEqual(self.r.llen(self.k1), 0)
+
+    def test_brpop_closed_waiter(self):
+        other = RedisWrapper.clone()
+        popped = []
+        kwargs = self.r.connection_pool.connection_kwargs
+        sock = socket.create_connection((kwargs['host'], int(kwargs['port'])))
+        sock.sendall(b'*3\r\n$5\r\nbrpop\r\n$%d\r\n%s\r\n$2\r\n10\r\n' % (len(self.k1), self.k1.encode()))
+        time.sleep(0.3)
+        thread = threading.Thread(target=lambda: popped.append(other.brpop(self.k1, 10)))
+        thread.start()
+        time.sleep(0.3)
+        # the first waiter goes away, the push is delivered to the next one
+        sock.close()
+        time.sleep(0.3)
+        self.assertTrue(self.r.lpush(self.k1, self.v1))
+        thread.join(0.5)
+        self.assertEqual(popped, [(self.k1, self.v1)])
+
+    def test_brpop_bulk_load(self):
+        other = RedisWrapper.clone()
+        popped = []
+        thread = threading.Thread(target=lambda: popped.append(other.brpop(self.k1, 10)))
+        thread.start()
+        time.sleep(0.3)
+        # the pop is woken once the batch of the push is committed
+        self.assertEqual(self.r.execute_command('bulkload begin batch', 1), 'OK')
+        self.assertEqual(self.r.rpush(self.k1, self.v1), 1)
+        thread.join(0.5)
+        self.assertEqual(popped, [(self.k1, self.v1)])
+        self.r.execute_command('bulkload end')
+
+    def test_lmpop(self):
+        self.assertIsNone(self.r.execute_command('lmpop', 2, self.k1, self.k2, 'left'))
+        self.r.rpush(self.k2, 'a', 'b', 'c')
+        self.assertEqual(self.r.execute_command('lmpop', 2, self.k1, self.k2, 'left'), [self.k2, ['a']])
+        self.r.rpush(self.k1, 'x')
+        self.assertEqual(self.r.execute_command('lmpop', 2, self.k1, self.k2, 'right', 'count', 5), [self.k1, ['x']])
+        # the count is clamped to the length of the list
+        self.assertEqual(self.r.execute_command('lmpop', 2, self.k1, self.k2, 'right', 'count', 5), [self.k2, ['c', 'b']])
+        self.assertEqual(self.r.exists(self.k2), 0)
+        self.assertRaises(Exception, self.r.execute_command, 'lmpop', 0, self.k1, 'left')
+        self.assertRaises(Exception, self.r.execute_command, 'lmpop', 1, self.k1, 'up')
+        self.assertRaises(Exception, self.r.execute_command, 'lmpop', 1, self.k1, 'left', 'count', 0)
+
     def test_type(self):
         self.assertEqual(self.r.type(self.k1), CmdType.NULL.value)
         self.assertTrue(self.r.lpush(self.k1, self.v1))
@@ -99,6 +179,29 @@ class ListTest(unittest.TestCase):
         # remove same elements at most 6 times from right
         self.assertEqual(self.r.lrem(self.k1, -6, 20), 6)
 
+    def test_lpos(self):
+        self.r.rpush(self.k1, 'a', 'b', 'c', '1', '2', '3', 'c', 'c')
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'c'), 2)
+        self.assertIsNone(self.r.execute_command('lpos', self.k1, 'x'))
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'c', 'rank', 2), 6)
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'c', 'rank', -1), 7)
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'c', 'count', 2), [2, 6])
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'c', 'count', 0), [2, 6, 7])
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'c', 'rank', -1, 'count', 2), [7, 6])
+        # maxlen bounds the elements compared
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'c', 'count', 0, 'maxlen', 3), [2])
+        self.assertEqual(self.r.execute_command('lpos', self.k1, 'x', 'count', 0), [])
+        self.assertIsNone(self.r.execute_command('lpos', self.k2, 'c'))
+        self.assertRaises(Exception, self.r.execute_command, 'lpos', self.k1, 'c', 'rank', 0)
+        self.assertRaises(Exception, self.r.execute_command, 'lpos', self.k1, 'c', 'count', -1)
+        self.assertRaises(Exception, self.r.execute_command, 'lpos', self.k1, 'c', 'maxlen')
+
+        # positions beyond a batch of the scan
+        self.r.delete(self.k1)
+        self.r.rpush(self.k1, *[str(i) for i in range(2500)])
+        self.assertEqual(self.r.execute_command('lpos', self.k1, '2400'), 2400)
+        self.assertEqual(self.r.execute_command('lpos', self.k1, '10', 'rank', -1), 10)
+
     def test_linsert(self):
         for i in range(100):
             self.assertTrue(self.r.rpush(self.k1, str(i)))
@@ -1,3 +1,4 @@
+import hashlib
 import operator
 import random
 import time
@@ -60,6 +61,18 @@ class LuaTest(unittest.TestCase):
         with self.assertRaisesRegex(Exception, "No matching script"):
             self.r.execute_command('evalsha', 'not-exist-sha', 0)
 
+    def test_script_exists_flush(self):
+        sha = self.r.execute_command('script', 'load', 'return 2')
+        self.assertEqual(sha, hashlib.sha1(b'return 2').hexdigest())
+        self.assertListEqual(self.r.execute_command('script', 'exists', sha, sha.upper(), 'not-exist-sha'), [1, 1, 0])
+        self.assertRaises(Exception, self.r.execute_command, 'script', 'exists')
+        self.assertRaises(Exception, self.r.execute_command, 'script', 'flush', 'later')
+        self.assertRaises(Exception, self.r.execute_command, 'script', 'nosuchsubcommand')
+        self.assertEqual(self.r.execute_command('script', 'flush', 'async'), 'OK')
+        self.assertListEqual(self.r.execute_command('script', 'exists', sha), [0])
+        with self.assertRaisesRegex(Exception, "No matching script"):
+            self.r.execute_command('evalsha', sha, 0)
+
     def test_integer_conversion(self):
         script = '''
         redis.call('set', KEYS[1], 0)
@@ -63,6 +63,10 @@ class SetTest(unittest.TestCase):
         self.assertEqual(self.r.sadd(self.k1, self.v2), 1)
         self.assertListEqual(self.r.execute_command('smismember', self.k1, self.v1, self.v2, NOT_EXISTS_LITERAL),
                              [1, 1, 0])
+        # the replies follow the order of the members, repeated ones included
+        self.assertListEqual(self.r.execute_command('smismember', self.k1, NOT_EXISTS_LITERAL, self.v2, self.v2),
+                             [0, 1, 1])
+        self.assertRaises(Exception, self.r.execute_command, 'smismember', self.k1)
 
     def test_smembers(self):
         for i in range(200):
@@ -77,6 +81,93 @@ class SetTest(unittest.TestCase):
         self.assertEqual(len(self.r.srandmember(self.k1, -10)), 10)
         self.assertEqual(len(self.r.srandmember(self.k1, 300)), 200)
         self.assertEqual(len(self.r.srandmember(self.k1, -300)), 300)
+        # a positive count replies distinct members
+        self.assertEqual(len(set(self.r.srandmember(self.k1, 50))), 50)
+        # a count out of range is rejected instead of allocating the reply
+        for count in [-9223372036854775808, -9223372036854775807]:
+            with self.assertRaises(Exception) as cm:
+                self.r.srandmember(self.k1, count)
+            self.assertIn('value is out of range', str(cm.exception))
+        self.r.delete(self.k1)
+        self.assertIsNone(self.r.srandmember(self.k1))
+        self.assertEqual(self.r.srandmember(self.k1, 5), [])
+
+    def test_sscan(self):
+        self.assertEqual(self.r.execute_command('sscan', self.k1, 0), ['0', []])
+        members = [str(i) for i in range(100)]
+        self.assertEqual(self.r.sadd(self.k1, *members), 100)
+        cursor, scanned = '0', []
+        while True:
+            cursor, batch = self.r.execute_command('sscan', self.k1, cursor, 'count', 9)
+            scanned += batch
+            # members added during the scan don't hide the members present
+            self.r.sadd(self.k1, 'added' + cursor)
+            if cursor == '0':
+                break
+        self.assertTrue(set(members) <= set(scanned))
+        self.assertEqual(len(scanned), len(set(scanned)))
+        cursor, batch = self.r.execute_command('sscan', self.k1, 0, 'match', '[1-2]', 'count', 1000)
+        self.assertEqual((cursor, sorted(batch)), ('0', ['1', '2']))
+        self.r.set(self.k2, 'string')
+        self.assertRaises(Exception, self.r.execute_command, 'sscan', self.k2, 0)
+        self.r.delete(self.k2)
+
+    def test_srandmember_large(self):
+        # a large set is sampled by windows, not by a scan of the whole set
+        members = [str(i) for i in range(3000)]
+        for i in range(0, len(members), 500):
+            self.r.sadd(self.k1, *members[i:i + 500])
+        sampled = self.r.srandmember(self.k1, 40)
+        self.assertEqual(len(set(sampled)), 40)
+        self.assertTrue(set(sampled) <= set(members))
+        repeated = self.r.srandmember(self.k1, -40)
+        self.assertEqual(len(repeated), 40)
+        self.assertTrue(set(repeated) <= set(members))
+        self.assertIn(self.r.srandmember(self.k1), set(members))
+        self.assertEqual(len(set(self.r.srandmember(self.k1, 2000))), 2000)
+
+    def test_srandmember_distribution(self):
+        members = [str(i) for i in range(10)]
+        self.assertEqual(self.r.sadd(self.k1, *members), 10)
+        trials = 2000
+        counts = dict.fromkeys(members, 0)
+        for _ in range(trials):
+            counts[self.r.srandmember(self.k1)] += 1
+        # every member is expected trials / 10 times
+        for member in members:
+            self.assertGreater(counts[member], trials / 10 / 2)
+            self.assertLess(counts[member], trials / 10 * 2)
+
+        counts = dict.fromkeys(members, 0)
+        for _ in range(trials // 5):
+            sampled = self.r.srandmember(self.k1, 5)
+            self.assertEqual(len(set(sampled)), 5)
+            for member in sampled:
+                counts[member] += 1
+        for member in members:
+            self.assertGreater(counts[member], trials / 10 / 2)
+            self.assertLess(counts[member], trials / 10 * 2)
+
+        # negative count samples with repeats
+        sampled = self.r.srandmember(self.k1, -trials)
+        self.assertEqual(len(sampled), trials)
+        self.assertEqual(set(sampled), set(members))
+
+    def test_sintercard(self):
+        self.r.sadd(self.k1, *[str(i) for i in range(3000)])
+        self.r.sadd(self.k2, *[str(i) for i in range(0, 3000, 2)])
+        self.assertEqual(self.r.execute_command('sintercard', 2, self.k1, self.k2), 1500)
+        self.assertEqual(self.r.execute_command('sintercard', 1, self.k1), 3000)
+        self.assertEqual(self.r.execute_command('sintercard', 2, self.k1, self.k2, 'limit', 10), 10)
+        self.assertEqual(self.r.execute_command('sintercard', 2, self.k1, self.k2, 'limit', 0), 1500)
+        # a missing set makes the intersection empty
+        self.assertEqual(self.r.execute_command('sintercard', 3, self.k1, self.k2, self.k3), 0)
+        self.r.set(self.k3, self.v1)
+        self.assertRaises(Exception, self.r.execute_command, 'sintercard', 2, self.k1, self.k3)
+        self.assertRaises(Exception, self.r.execute_command, 'sintercard', 0, self.k1)
+        self.assertRaises(Exception, self.r.execute_command, 'sintercard', 3, self.k1, self.k2)
+        self.assertRaises(Exception, self.r.execute_command, 'sintercard', 1, self.k1, 'limit', -1)
+        self.assertRaises(Exception, self.r.execute_command, 'sintercard', 1, self.k1, self.k2)
 
     def test_srem(self):
         for i in range(200):
@@ -1,3 +1,4 @@
+import threading
 import time
 import unittest
 
@@ -57,6 +58,17 @@ class StringTest(unittest.TestCase):
         self.assertTrue(self.r.set(self.k1, self.v1))
         self.assertEqual(self.r.type(self.k1), CmdType.STRING.value)
 
+    def test_get_missing_then_set(self):
+        # repeated misses may be served from the negative cache
+        for _ in range(10):
+            self.assertIsNone(self.r.get(self.k1))
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        self.assertEqual(self.r.get(self.k1), self.v1)
+        self.r.delete(self.k1)
+        self.assertIsNone(self.r.get(self.k1))
+        self.r.mset({self.k1: self.v2})
+        self.assertEqual(self.r.get(self.k1), self.v2)
+
     def test_setex(self):
         self.assertTrue(self.r.setex(self.k1, 5, self.v1))
         ttl = self.r.ttl(self.k1)
@@ -81,6 +93,53 @@ class StringTest(unittest.TestCase):
         v1 = self.r.get(self.k2)
         self.assertEqual(self.v1, v1, '{} != {}'.format(v1, self.v1))
 
+    def test_set_ifeq_get(self):
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        # the swap happened when the old value equals the expected token
+        self.assertEqual(self.r.execute_command('set', self.k1, self.v2, 'IFEQ', self.v1, 'GET'), self.v1)
+        self.assertEqual(self.r.get(self.k1), self.v2)
+        self.assertEqual(self.r.execute_command('set', self.k1, 'value3', 'IFEQ', self.v1, 'GET'), self.v2)
+        self.assertEqual(self.r.get(self.k1), self.v2)
+
+        self.assertEqual(self.r.execute_command('set', self.k1, self.v1, 'IFEQ', self.v2, 'PX', 5000), 'OK')
+        self.assertGreater(self.r.pttl(self.k1), 0)
+        self.assertIsNone(self.r.execute_command('set', self.k1, self.v2, 'IFEQ', self.v2))
+        self.assertEqual(self.r.get(self.k1), self.v1)
+
+        # a missing key is not created by IFEQ
+        self.assertIsNone(self.r.execute_command('set', self.k2, self.v1, 'IFEQ', self.v1, 'GET'))
+        self.assertIsNone(self.r.get(self.k2))
+        self.assertIsNone(self.r.set(self.k2, self.v1, get=True))
+        self.assertEqual(self.r.set(self.k2, self.v2, get=True), self.v1)
+        self.assertEqual(self.r.execute_command('set', self.k2, self.v1, 'NX', 'GET'), self.v2)
+        self.assertEqual(self.r.get(self.k2), self.v2)
+
+        self.r.delete(self.k2)
+        self.r.hset(self.k2, 'f1', self.v1)
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('set', self.k2, self.v1, 'GET')
+        self.assertEqual(str(cm.exception), 'WRONGTYPE Operation against a key holding the wrong kind of value')
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('set', self.k1, self.v1, 'NX', 'IFEQ', self.v1)
+        self.assertEqual(str(cm.exception), 'Invalid arguments')
+
+    def test_set_over_collection(self):
+        # a small set is freed in the transaction, a big one by the gc
+        for size in [10, 2000]:
+            self.r.sadd(self.k1, *['member%d' % i for i in range(size)])
+            t = time.time()
+            self.assertTrue(self.r.set(self.k1, self.v1))
+            self.assertLess(time.time() - t, 5)
+            self.assertEqual(self.r.type(self.k1), 'string')
+            self.assertEqual(self.r.get(self.k1), self.v1)
+
+            # the old members never show up again
+            self.r.delete(self.k1)
+            self.assertEqual(self.r.sadd(self.k1, 'member0'), 1)
+            self.assertEqual(self.r.scard(self.k1), 1)
+            self.assertEqual(self.r.smembers(self.k1), {'member0'})
+            self.r.delete(self.k1)
+
     def test_mget(self):
         self.assertTrue(self.r.mset({self.k1: self.v1, self.k2: self.v2}))
         self.assertListEqual(self.r.mget(self.k1, self.k2), [self.v1, self.v2])
@@ -111,6 +170,74 @@ class StringTest(unittest.TestCase):
         err = cm.exception
         self.assertEqual(str(err), 'value is not an integer or out of range')
 
+    def test_incr_overflow(self):
+        self.assertTrue(self.r.set(self.k1, 9223372036854775807))
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command("INCR", self.k1)
+        self.assertEqual(str(cm.exception), 'increment or decrement would overflow')
+        self.assertEqual(self.r.get(self.k1), '9223372036854775807')
+
+        self.assertTrue(self.r.set(self.k1, -9223372036854775808))
+        with self.assertRaises(Exception) as cm:
+            self.r.decrby(self.k1, 1)
+        self.assertEqual(str(cm.exception), 'increment or decrement would overflow')
+
+    def test_incr_concurrent(self):
+        # concurrent increments conflict and retry, none of them is lost
+        def worker():
+            for _ in range(50):
+                self.r.incr(self.k1)
+
+        threads = [threading.Thread(target=worker) for _ in range(8)]
+        for t in threads:
+            t.start()
+        for t in threads:
+            t.join()
+
+        self.assertEqual(self.r.get(self.k1), '400')
+
+    def test_string_mutations(self):
+        # every string command shares one value layout, switching between
+        # integer and raw values must keep it consistent
+        self.assertTrue(self.r.set(self.k1, '10'))
+        self.assertEqual(self.r.incr(self.k1), 11)
+        self.assertEqual(self.r.get(self.k1), '11')
+        self.assertEqual(self.r.strlen(self.k1), 2)
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        with self.assertRaises(Exception):
+            self.r.incr(self.k1)
+        self.assertEqual(self.r.get(self.k1), self.v1)
+        self.assertEqual(self.r.type(self.k1), 'string')
+        self.assertTrue(self.r.set(self.k1, '-5'))
+        self.assertEqual(self.r.decrby(self.k1, 5), -10)
+        self.assertEqual(self.r.get(self.k1), '-10')
+        self.assertEqual(self.r.object('encoding', self.k1), 'int')
+
+        # the bit and range commands rewrite the same value
+        self.assertEqual(self.r.append(self.k1, '0'), 4)
+        self.assertEqual(self.r.get(self.k1), '-100')
+        self.assertEqual(self.r.incr(self.k1), -99)
+        self.assertEqual(self.r.setrange(self.k1, 0, 'a'), 3)
+        self.assertEqual(self.r.get(self.k1), 'a99')
+        self.assertEqual(self.r.object('encoding', self.k1), 'embstr')
+        self.assertEqual(self.r.getrange(self.k1, 1, -1), '99')
+        # 'a' is 0x61, clearing bit 1 gives '!'
+        self.assertEqual(self.r.setbit(self.k1, 1, 0), 1)
+        self.assertEqual(self.r.get(self.k1), '!99')
+        self.assertEqual(self.r.bitfield(self.k1).set('u8', 0, ord('7')).execute(), [ord('!')])
+        self.assertEqual(self.r.get(self.k1), '799')
+        self.assertEqual(self.r.incrby(self.k1, 1), 800)
+        self.assertEqual(self.r.object('encoding', self.k1), 'int')
+        # a non canonical integer is a string
+        self.assertTrue(self.r.set(self.k1, '007'))
+        self.assertEqual(self.r.object('encoding', self.k1), 'embstr')
+        self.assertTrue(self.r.set(self.k1, 8))
+        self.assertEqual(self.r.append(self.k1, 'x' * 44), 45)
+        self.assertEqual(self.r.object('encoding', self.k1), 'raw')
+        self.assertEqual(self.r.strlen(self.k1), 45)
+        self.assertEqual(self.r.getrange(self.k1, 0, 1), '8x')
+        self.assertEqual(self.r.type(self.k1), 'string')
+
     def test_incrby(self):
         self.assertEqual(self.r.incrby(self.k1, 1), 1)
         self.assertEqual(self.r.incrby(self.k1, 9), 10)
@@ -151,11 +278,239 @@ class StringTest(unittest.TestCase):
         err = cm.exception
         self.assertEqual(str(err), 'value is not an integer or out of range')
 
+    def test_bitcount(self):
+        self.assertEqual(self.r.bitcount(self.k1), 0)
+        self.r.set(self.k1, 'foobar')
+        self.assertEqual(self.r.bitcount(self.k1), 26)
+        self.assertEqual(self.r.bitcount(self.k1, 0, 0), 4)
+        self.assertEqual(self.r.bitcount(self.k1, 1, 1), 6)
+        self.assertEqual(self.r.bitcount(self.k1, -2, -1), 7)
+        self.assertEqual(self.r.bitcount(self.k1, 4, 2), 0)
+        self.assertEqual(self.r.bitcount(self.k1, -100, 100), 26)
+        self.assertEqual(self.r.execute_command('bitcount', self.k1, 1, 1, 'byte'), 6)
+        self.assertEqual(self.r.execute_command('bitcount', self.k1, 5, 30, 'bit'), 17)
+        self.assertEqual(self.r.execute_command('bitcount', self.k1, 1, 1, 'bit'), 1)
+        self.assertRaises(Exception, self.r.execute_command, 'bitcount', self.k1, 0)
+        self.assertRaises(Exception, self.r.execute_command, 'bitcount', self.k1, 0, 1, 'word')
+
+    def test_bitpos(self):
+        self.assertEqual(self.r.bitpos(self.k1, 1), -1)
+        self.assertEqual(self.r.bitpos(self.k1, 0), 0)
+        self.r.set(self.k1, b'\xff\xf0\x00')
+        self.assertEqual(self.r.bitpos(self.k1, 0), 12)
+        self.r.set(self.k1, b'\x00\xff\xf0')
+        self.assertEqual(self.r.bitpos(self.k1, 1, 0), 8)
+        self.assertEqual(self.r.bitpos(self.k1, 1, 2), 16)
+        self.assertEqual(self.r.execute_command('bitpos', self.k1, 1, 2, -1, 'byte'), 16)
+        self.assertEqual(self.r.execute_command('bitpos', self.k1, 1, 7, 15, 'bit'), 8)
+        self.r.set(self.k1, b'\x00\x00\x00')
+        self.assertEqual(self.r.bitpos(self.k1, 1), -1)
+        self.assertEqual(self.r.execute_command('bitpos', self.k1, 1, 7, -3, 'bit'), -1)
+        # a clear bit is found in the padding only without an explicit end
+        self.r.set(self.k1, b'\xff\xff\xff')
+        self.assertEqual(self.r.bitpos(self.k1, 0), 24)
+        self.assertEqual(self.r.bitpos(self.k1, 0, 0), 24)
+        self.assertEqual(self.r.bitpos(self.k1, 0, 0, -1), -1)
+        self.assertRaises(Exception, self.r.execute_command, 'bitpos', self.k1, 2)
+
+    def test_bitop(self):
+        dst = '__string_bitop__'
+        self.r.set(self.k1, 'ab')
+        self.r.set(self.k2, 'c')
+        self.assertEqual(self.r.bitop('and', dst, self.k1, self.k2), 2)
+        self.assertEqual(self.r.get(dst), 'a\x00')
+        self.assertEqual(self.r.bitop('or', dst, self.k1, self.k2), 2)
+        self.assertEqual(self.r.get(dst), 'cb')
+        self.assertEqual(self.r.bitop('xor', dst, self.k1, self.k2), 2)
+        self.assertEqual(self.r.get(dst), '\x02b')
+        self.assertEqual(self.r.bitop('not', dst, self.k2), 1)
+        self.assertEqual(self.r.bitcount(dst), 4)
+        # a missing source is read as an empty string
+        self.assertEqual(self.r.bitop('or', dst, self.k2, '__string_missing__'), 1)
+        self.assertEqual(self.r.get(dst), 'c')
+        # dst is deleted when every source is missing
+        self.assertEqual(self.r.bitop('or', dst, '__string_missing__'), 0)
+        self.assertIsNone(self.r.get(dst))
+        with self.assertRaises(Exception) as cm:
+            self.r.bitop('not', dst, self.k1, self.k2)
+        self.assertEqual(str(cm.exception), 'BITOP NOT must be called with a single source key.')
+        self.assertRaises(Exception, self.r.bitop, 'nand', dst, self.k1)
+        self.r.hset(self.k2, 'f1', self.v1)
+        with self.assertRaises(Exception) as cm:
+            self.r.bitop('and', dst, self.k1, self.k2)
+        self.assertEqual(str(cm.exception), 'WRONGTYPE Operation against a key holding the wrong kind of value')
+        self.r.delete(dst)
+
+    def test_setbit_getbit(self):
+        self.assertEqual(self.r.getbit(self.k1, 7), 0)
+        self.assertEqual(self.r.setbit(self.k1, 1, 1), 0)
+        self.assertEqual(self.r.setbit(self.k1, 7, 1), 0)
+        self.assertEqual(self.r.get(self.k1), 'A')
+        self.assertEqual(self.r.setbit(self.k1, 7, 0), 1)
+        self.assertEqual(self.r.get(self.k1), '@')
+        self.assertEqual(self.r.getbit(self.k1, 1), 1)
+        self.assertEqual(self.r.getbit(self.k1, 100), 0)
+        # the value is grown with zero bytes
+        self.assertEqual(self.r.setbit(self.k1, 17, 1), 0)
+        self.assertEqual(self.r.get(self.k1), '@\x00@')
+        # the ttl is kept
+        self.r.expire(self.k1, 100)
+        self.r.setbit(self.k1, 0, 0)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        with self.assertRaises(Exception) as cm:
+            self.r.setbit(self.k1, 2 ** 32, 1)
+        self.assertEqual(str(cm.exception), 'bit offset is not an integer or out of range')
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('setbit', self.k1, 0, 2)
+        self.assertEqual(str(cm.exception), 'bit is not an integer or out of range')
+        self.assertRaises(Exception, self.r.getbit, self.k1, -1)
+
+    def test_bitfield(self):
+        self.assertEqual(self.r.execute_command('bitfield', self.k1, 'get', 'u8', 0), [0])
+        self.assertEqual(self.r.execute_command('bitfield', self.k1, 'set', 'u8', 0, 65, 'get', 'u4', 4), [0, 1])
+        self.assertEqual(self.r.get(self.k1), 'A')
+        self.assertEqual(self.r.execute_command('bitfield', self.k1, 'set', 'i8', '#1', -1, 'get', 'u8', 8), [0, 255])
+        self.assertEqual(self.r.execute_command('bitfield', self.k1, 'get', 'i8', 8), [-1])
+        # the default overflow wraps
+        self.assertEqual(self.r.execute_command('bitfield', self.k1, 'incrby', 'u8', 8, 2), [1])
+        self.assertEqual(self.r.execute_command('bitfield', self.k1, 'overflow', 'sat', 'incrby', 'i8', 8, 200,
+                                                'incrby', 'u8', 8, -300), [127, 0])
+        self.assertEqual(self.r.execute_command('bitfield', self.k1, 'overflow', 'fail', 'incrby', 'u8', 8, 256,
+                                                'incrby', 'u8', 8, 1), [None, 1])
+        self.assertEqual(self.r.execute_command('bitfield', self.k1, 'get', 'i64', 0, 'get', 'u63', 0),
+                         [0x4101000000000000, 0x4101000000000000 >> 1])
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('bitfield', self.k1, 'get', 'u64', 0)
+        self.assertEqual(str(cm.exception), 'Invalid bitfield type. Use something like i16 u8. Note that u64 is not supported but i64 is.')
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('bitfield', self.k1, 'overflow', 'none', 'get', 'u8', 0)
+        self.assertEqual(str(cm.exception), 'Invalid OVERFLOW type specified')
+        self.assertRaises(Exception, self.r.execute_command, 'bitfield', self.k1, 'get', 'u8', -1)
+        self.assertRaises(Exception, self.r.execute_command, 'bitfield', self.k1, 'set', 'u8', 0)
+
+    def test_setrange(self):
+        # an empty value does not create the key
+        self.assertEqual(self.r.setrange(self.k1, 0, ''), 0)
+        self.assertEqual(self.r.exists(self.k1), 0)
+        self.assertEqual(self.r.setrange(self.k1, 2, 'ab'), 4)
+        self.assertEqual(self.r.get(self.k1), '\x00\x00ab')
+        self.r.set(self.k1, 'hello world')
+        self.r.expire(self.k1, 100)
+        self.assertEqual(self.r.setrange(self.k1, 6, 'redis'), 11)
+        self.assertEqual(self.r.get(self.k1), 'hello redis')
+        self.assertEqual(self.r.setrange(self.k1, 10, 'sss'), 13)
+        self.assertEqual(self.r.get(self.k1), 'hello redisss')
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.assertEqual(self.r.setrange(self.k1, 0, ''), 13)
+        with self.assertRaises(Exception) as cm:
+            self.r.setrange(self.k1, -1, 'a')
+        self.assertEqual(str(cm.exception), 'offset is out of range')
+        with self.assertRaises(Exception) as cm:
+            self.r.setrange(self.k1, 512 * 1024 * 1024, 'a')
+        self.assertEqual(str(cm.exception), 'string exceeds maximum allowed size (proto-max-bulk-len)')
+        self.r.hset(self.k2, 'f1', self.v1)
+        with self.assertRaises(Exception) as cm:
+            self.r.setrange(self.k2, 0, 'a')
+        self.assertEqual(str(cm.exception), 'WRONGTYPE Operation against a key holding the wrong kind of value')
+
+    def test_getrange(self):
+        self.assertEqual(self.r.getrange(self.k1, 0, -1), '')
+        self.r.set(self.k1, 'This is a string')
+        self.assertEqual(self.r.getrange(self.k1, 0, 3), 'This')
+        self.assertEqual(self.r.getrange(self.k1, -3, -1), 'ing')
+        self.assertEqual(self.r.getrange(self.k1, 0, -1), 'This is a string')
+        self.assertEqual(self.r.getrange(self.k1, 10, 100), 'string')
+        self.assertEqual(self.r.getrange(self.k1, 100, 200), '')
+        self.assertEqual(self.r.getrange(self.k1, -1, -5), '')
+        self.assertEqual(self.r.getrange(self.k1, -100, 3), 'This')
+        self.assertEqual(self.r.substr(self.k1, 5, 6), 'is')
+        self.r.set(self.k1, '')
+        self.assertEqual(self.r.getrange(self.k1, 0, -1), '')
+        self.assertRaises(Exception, self.r.getrange, self.k1, 'a', 1)
+
+    def test_append(self):
+        self.assertEqual(self.r.append(self.k1, 'Hello'), 5)
+        self.assertEqual(self.r.append(self.k1, ' World'), 11)
+        self.assertEqual(self.r.get(self.k1), 'Hello World')
+        # the ttl is kept
+        self.r.expire(self.k1, 100)
+        self.assertEqual(self.r.append(self.k1, '!'), 12)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.r.hset(self.k2, 'f1', self.v1)
+        with self.assertRaises(Exception) as cm:
+            self.r.append(self.k2, 'a')
+        self.assertEqual(str(cm.exception), 'WRONGTYPE Operation against a key holding the wrong kind of value')
+
+    def test_incrbyfloat(self):
+        self.assertEqual(self.r.incrbyfloat(self.k1, 10.5), 10.5)
+        self.assertEqual(self.r.incrbyfloat(self.k1, 0.1), 10.6)
+        self.assertEqual(self.r.get(self.k1), '10.6')
+        self.r.set(self.k1, '5.0e3')
+        self.assertEqual(self.r.incrbyfloat(self.k1, 2.0e2), 5200)
+        self.assertEqual(self.r.get(self.k1), '5200')
+        # the ttl is kept
+        self.r.expire(self.k1, 100)
+        self.r.incrbyfloat(self.k1, 1)
+        self.assertGreater(self.r.ttl(self.k1), 0)
+        self.r.set(self.k1, self.v1)
+        with self.assertRaises(Exception) as cm:
+            self.r.incrbyfloat(self.k1, 1)
+        self.assertEqual(str(cm.exception), 'value is not a valid float')
+        self.r.set(self.k1, 1)
+        with self.assertRaises(Exception) as cm:
+            self.r.incrbyfloat(self.k1, 'nan')
+        self.assertEqual(str(cm.exception), 'value is not a valid float')
+        self.assertRaises(Exception, self.r.incrbyfloat, self.k1, 'inf')
+        self.r.set(self.k1, '1.7e308')
+        with self.assertRaises(Exception) as cm:
+            self.r.incrbyfloat(self.k1, '1.7e308')
+        self.assertEqual(str(cm.exception), 'increment would produce NaN or Infinity')
+
     def test_strlen(self):
         self.assertEqual(self.r.strlen(self.k1), 0)
         self.assertTrue(self.r.set(self.k1, self.v1))
         self.assertEqual(self.r.strlen(self.k1), len(self.v1))
 
+    def test_getdel(self):
+        self.assertIsNone(self.r.execute_command('getdel', self.k1))
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        self.assertEqual(self.r.execute_command('getdel', self.k1), self.v1)
+        self.assertIsNone(self.r.get(self.k1))
+        self.assertIsNone(self.r.execute_command('getdel', self.k1))
+
+        self.r.hset(self.k2, 'f1', self.v1)
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('getdel', self.k2)
+        self.assertEqual(str(cm.exception), 'WRONGTYPE Operation against a key holding the wrong kind of value')
+        self.assertEqual(self.r.hget(self.k2, 'f1'), self.v1)
+        self.r.delete(self.k2)
+
+    def test_getex(self):
+        self.assertIsNone(self.r.execute_command('getex', self.k1))
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        self.assertEqual(self.r.execute_command('getex', self.k1), self.v1)
+        self.assertEqual(self.r.ttl(self.k1), -1)
+
+        self.assertEqual(self.r.execute_command('getex', self.k1, 'EX', 5), self.v1)
+        self.assertGreater(self.r.pttl(self.k1), 0)
+        self.assertEqual(self.r.execute_command('getex', self.k1, 'PXAT', msec_ts_after_five_secs()), self.v1)
+        self.assertGreater(self.r.pttl(self.k1), 0)
+        self.assertEqual(self.r.execute_command('getex', self.k1, 'PERSIST'), self.v1)
+        self.assertEqual(self.r.ttl(self.k1), -1)
+
+        # conflicting options
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('getex', self.k1, 'EX', 5, 'PERSIST')
+        self.assertEqual(str(cm.exception), 'syntax error')
+        self.assertEqual(self.r.ttl(self.k1), -1)
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('getex', self.k1, 'PX', 0)
+        self.assertEqual(str(cm.exception), "invalid expire time in 'getex' command")
+
+        # a timestamp in the past deletes the key
+        self.assertIsNone(self.r.execute_command('getex', self.k1, 'EXAT', 1))
+        self.assertIsNone(self.r.get(self.k1))
+
     def test_del(self):
         self.assertTrue(self.r.set(self.k1, self.v1))
         v1 = self.r.get(self.k1)
@@ -237,6 +592,29 @@ class StringTest(unittest.TestCase):
         time.sleep(6)
         self.assertIsNone(self.r.get(self.k1))
 
+    def test_pexpire_conflict(self):
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        results = []
+
+        # concurrent expires of the key conflict and retry the transactions,
+        # each worker sets its own ttl
+        def worker(ms):
+            conn = RedisWrapper.clone()
+            for _ in range(20):
+                results.append(conn.pexpire(self.k1, ms))
+
+        threads = [threading.Thread(target=worker, args=(5000 + 1000 * i,)) for i in range(4)]
+        for t in threads:
+            t.start()
+        for t in threads:
+            t.join()
+
+        # every retried expire is applied, the ttl left is one of the workers'
+        self.assertEqual(results, [True] * 80)
+        pttl = self.r.execute_command('pttl', self.k1)
+        self.assertTrue(any(ms - 500 < pttl <= ms for ms in (5000, 6000, 7000, 8000)), pttl)
+        self.assertEqual(self.r.get(self.k1), self.v1)
+
     def test_expireat(self):
         self.assertTrue(self.r.set(self.k1, self.v1))
         # expire in 5s
@@ -248,6 +626,50 @@ class StringTest(unittest.TestCase):
         time.sleep(6)
         self.assertIsNone(self.r.get(self.k1))
 
+    def test_expiretime(self):
+        self.assertEqual(self.r.execute_command('expiretime', self.k1), -2)
+        self.assertEqual(self.r.execute_command('pexpiretime', self.k1), -2)
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        self.assertEqual(self.r.execute_command('expiretime', self.k1), -1)
+        self.assertEqual(self.r.execute_command('pexpiretime', self.k1), -1)
+        ts = msec_ts_after_five_secs()
+        self.assertTrue(self.r.pexpireat(self.k1, ts))
+        self.assertEqual(self.r.execute_command('pexpiretime', self.k1), ts)
+        self.assertEqual(self.r.execute_command('expiretime', self.k1), ts // 1000)
+        # the expire time of a collection is kept the same way
+        self.assertEqual(self.r.lpush(self.k2, self.v1), 1)
+        self.assertTrue(self.r.pexpireat(self.k2, ts))
+        self.assertEqual(self.r.execute_command('pexpiretime', self.k2), ts)
+        self.r.delete(self.k2)
+
+    def test_expire_time_validation(self):
+        huge = 2 ** 63 - 1
+        # relative ttls must be positive and must not overflow
+        for cmd, args in [('set', ('EX', 0)), ('set', ('EX', -1)), ('set', ('EX', huge)),
+                          ('set', ('PX', 0)), ('set', ('PX', -1)), ('set', ('PX', huge)),
+                          ('setex', (0,)), ('setex', (-1,)), ('setex', (huge,))]:
+            argv = (self.k1, self.v1) + args if cmd == 'set' else (self.k1,) + args + (self.v1,)
+            with self.assertRaises(Exception) as cm:
+                self.r.execute_command(cmd, *argv)
+            self.assertEqual(str(cm.exception), "invalid expire time in '{}' command".format(cmd), argv)
+            self.assertIsNone(self.r.get(self.k1))
+
+        # an expire time in the past deletes the key
+        for cmd, arg in [('expire', 0), ('expire', -1), ('pexpire', 0), ('pexpire', -1),
+                         ('expireat', 1), ('expireat', -1), ('pexpireat', 1), ('pexpireat', -1)]:
+            self.assertTrue(self.r.set(self.k1, self.v1))
+            self.assertEqual(self.r.execute_command(cmd, self.k1, arg), 1, cmd)
+            self.assertIsNone(self.r.get(self.k1))
+            self.assertEqual(self.r.execute_command(cmd, self.k1, arg), 0, cmd)
+
+        # an expire time overflowing is rejected and the key is kept
+        self.assertTrue(self.r.set(self.k1, self.v1))
+        for cmd in ['expire', 'pexpire', 'expireat']:
+            with self.assertRaises(Exception) as cm:
+                self.r.execute_command(cmd, self.k1, huge)
+            self.assertEqual(str(cm.exception), "invalid expire time in '{}' command".format(cmd))
+        self.assertEqual(self.r.ttl(self.k1), -1)
+
     def tearDown(self):
         pass
 
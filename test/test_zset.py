@@ -88,6 +88,37 @@ class ZsetTest(unittest.TestCase):
         #  range with scores
         self.assertListEqual(self.r.zrevrange(self.k1, 10, 20, True), [(str(i), 100 - i) for i in range(10, 21)])
 
+    def test_zmpop(self):
+        self.assertIsNone(self.r.execute_command('zmpop', 2, self.k1, self.k2, 'min'))
+        self.r.zadd(self.k2, {'a': 1, 'b': 2, 'c': 3})
+        self.assertEqual(self.r.execute_command('zmpop', 2, self.k1, self.k2, 'min'), [self.k2, [['a', '1']]])
+        # the count is clamped to the size of the sorted set
+        self.assertEqual(self.r.execute_command('zmpop', 2, self.k1, self.k2, 'max', 'count', 5),
+                         [self.k2, [['c', '3'], ['b', '2']]])
+        self.assertEqual(self.r.exists(self.k2), 0)
+        self.assertRaises(Exception, self.r.execute_command, 'zmpop', 2, self.k1, 'min')
+        self.assertRaises(Exception, self.r.execute_command, 'zmpop', 1, self.k1, 'left')
+
+    def test_zrangestore(self):
+        for i in range(10):
+            self.r.zadd(self.k1, {str(i): i})
+        self.assertEqual(self.r.execute_command('zrangestore', self.k2, self.k1, 0, 2), 3)
+        self.assertEqual(self.r.zrange(self.k2, 0, -1, withscores=True), [('0', 0), ('1', 1), ('2', 2)])
+        self.assertEqual(self.r.execute_command('zrangestore', self.k2, self.k1, 0, 1, 'rev'), 2)
+        self.assertEqual(self.r.zrange(self.k2, 0, -1), ['8', '9'])
+        self.assertEqual(self.r.execute_command('zrangestore', self.k2, self.k1, '(3', 6, 'byscore', 'limit', 1, 2), 2)
+        self.assertEqual(self.r.zrange(self.k2, 0, -1), ['5', '6'])
+        # an empty range deletes the destination
+        self.assertEqual(self.r.execute_command('zrangestore', self.k2, self.k1, 20, 30, 'byscore'), 0)
+        self.assertEqual(self.r.exists(self.k2), 0)
+        self.r.set(self.k2, self.v1)
+        self.assertEqual(self.r.execute_command('zrangestore', self.k2, self.k1, 0, 0), 1)
+        self.assertEqual(self.r.type(self.k2), 'zset')
+
+        self.assertRaises(Exception, self.r.execute_command, 'zrangestore', self.k2, self.k1, 0, 1, 'byscore', 'bylex')
+        self.assertRaises(Exception, self.r.execute_command, 'zrangestore', self.k2, self.k1, 0, 1, 'limit', 0, 1)
+        self.assertRaises(Exception, self.r.execute_command, 'zrangestore', self.k2, self.k1, 0, 1, 'withscores')
+
     def test_zrangebyscore(self):
         for i in range(100):
             self.assertEqual(self.r.zadd(self.k1, {str(i): 100 - i}), 1)
@@ -119,6 +150,22 @@ class ZsetTest(unittest.TestCase):
         for i in range(100):
             self.assertEqual(self.r.zadd(self.k1, {str(i): i}), 1)
         self.assertEqual(self.r.zcount(self.k1, 50, 100), 50)
+        self.assertEqual(self.r.zcount(self.k1, '(50', '(60'), 9)
+        self.assertEqual(self.r.zcount(self.k1, '-inf', '+inf'), 100)
+        self.assertEqual(self.r.zcount(self.k1, 10, 10), 1)
+        self.assertEqual(self.r.zcount(self.k1, '(10', 10), 0)
+        self.assertEqual(self.r.zcount(self.k1, 60, 50), 0)
+        self.assertEqual(self.r.zcount(self.k2, 0, 100), 0)
+        with self.assertRaises(Exception) as cm:
+            self.r.zcount(self.k1, 'abc', 100)
+        self.assertEqual(str(cm.exception), 'min or max is not a float')
+
+    def test_zcount_large(self):
+        # the count spans several scan batches
+        for i in range(0, 2500, 500):
+            self.assertEqual(self.r.zadd(self.k1, {str(j): j for j in range(i, i + 500)}), 500)
+        self.assertEqual(self.r.zcount(self.k1, '-inf', '+inf'), 2500)
+        self.assertEqual(self.r.zcount(self.k1, 1000, '(2000'), 1000)
 
     def test_zscore(self):
         self.assertIsNone(self.r.zscore(self.k1, self.v1))
@@ -145,6 +192,95 @@ class ZsetTest(unittest.TestCase):
         for i in range(100):
             self.assertEqual(self.r.zrank(self.k1, str(i)), i)
 
+    def test_zrandmember(self):
+        self.assertIsNone(self.r.execute_command('zrandmember', self.k1))
+        self.assertEqual(self.r.execute_command('zrandmember', self.k1, 5), [])
+        for i in range(100):
+            self.assertEqual(self.r.zadd(self.k1, {str(i): i}), 1)
+        self.assertIn(self.r.execute_command('zrandmember', self.k1), [str(i) for i in range(100)])
+        # a positive count replies distinct members, at most all of them
+        self.assertEqual(len(set(self.r.execute_command('zrandmember', self.k1, 10))), 10)
+        self.assertEqual(len(self.r.execute_command('zrandmember', self.k1, 300)), 100)
+        # a negative count may repeat members
+        self.assertEqual(len(self.r.execute_command('zrandmember', self.k1, -300)), 300)
+        # a count out of range is rejected instead of allocating the reply
+        for args in [(-9223372036854775808,), (-9223372036854775807,), (-4611686018427387904, 'withscores')]:
+            with self.assertRaises(Exception) as cm:
+                self.r.execute_command('zrandmember', self.k1, *args)
+            self.assertIn('value is out of range', str(cm.exception))
+        pairs = self.r.execute_command('zrandmember', self.k1, 10, 'withscores')
+        self.assertEqual(len(pairs), 20)
+        for member, score in zip(pairs[::2], pairs[1::2]):
+            self.assertEqual(float(score), float(member))
+        self.assertRaises(Exception, self.r.execute_command, 'zrandmember', self.k1, 1, 'withvalues')
+
+    def test_zscan(self):
+        self.assertEqual(self.r.execute_command('zscan', self.k1, 0), ['0', []])
+        for i in range(50):
+            self.assertEqual(self.r.zadd(self.k1, {'m' + str(i): i}), 1)
+        cursor, scanned = '0', []
+        while True:
+            cursor, pairs = self.r.execute_command('zscan', self.k1, cursor, 'count', 8)
+            scanned += pairs
+            if cursor == '0':
+                break
+        self.assertEqual(len(scanned), 100)
+        for member, score in zip(scanned[::2], scanned[1::2]):
+            self.assertEqual(float(score), float(member[1:]))
+        cursor, pairs = self.r.execute_command('zscan', self.k1, 0, 'match', '*9', 'count', 1000)
+        self.assertEqual(sorted(pairs[::2]), ['m19', 'm29', 'm39', 'm49', 'm9'])
+
+    def test_zdiff(self):
+        self.assertEqual(self.r.zadd(self.k1, {'a': 1, 'b': 2, 'c': 3, 'd': 0}), 4)
+        self.assertEqual(self.r.zadd(self.k2, {'a': 10, 'c': 30}), 2)
+        self.assertEqual(self.r.execute_command('zdiff', 2, self.k1, self.k2), ['d', 'b'])
+        self.assertEqual(self.r.execute_command('zdiff', 2, self.k1, self.k2, 'withscores'), ['d', '0', 'b', '2'])
+        self.assertEqual(self.r.execute_command('zdiff', 1, self.k1), ['d', 'a', 'b', 'c'])
+        self.assertEqual(self.r.execute_command('zdiff', 2, '__zset_missing__', self.k1), [])
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('zdiff', 3, self.k1, self.k2)
+        self.assertEqual(str(cm.exception), "Number of keys can't be greater than number of args")
+        self.assertRaises(Exception, self.r.execute_command, 'zdiff', 0, self.k1)
+
+    def test_zdiffstore(self):
+        dst = '__zset_dst__'
+        self.assertEqual(self.r.zadd(self.k1, {'a': 1, 'b': 2, 'c': 3}), 3)
+        self.assertEqual(self.r.zadd(self.k2, {'a': 10}), 1)
+        self.r.set(dst, 'value')
+        self.assertEqual(self.r.execute_command('zdiffstore', dst, 2, self.k1, self.k2), 2)
+        self.assertEqual(self.r.zrange(dst, 0, -1, withscores=True), [('b', 2), ('c', 3)])
+        # dst is deleted when the difference is empty
+        self.assertEqual(self.r.execute_command('zdiffstore', dst, 2, self.k1, self.k1), 0)
+        self.assertEqual(self.r.exists(dst), 0)
+        self.assertRaises(Exception, self.r.execute_command, 'zdiffstore', dst, 2, self.k1, self.k2, 'withscores')
+        self.r.set(self.k2, 'value')
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('zdiffstore', dst, 2, self.k1, self.k2)
+        self.assertEqual(str(cm.exception), 'WRONGTYPE Operation against a key holding the wrong kind of value')
+        self.r.delete(dst)
+
+    def test_zunion_zinter(self):
+        self.assertEqual(self.r.zadd(self.k1, {'a': 1, 'b': 2}), 2)
+        self.assertEqual(self.r.zadd(self.k2, {'b': 3, 'c': 4}), 2)
+        self.assertEqual(self.r.execute_command('zunion', 2, self.k1, self.k2), ['a', 'c', 'b'])
+        self.assertEqual(self.r.execute_command('zunion', 2, self.k1, self.k2, 'withscores'),
+                         ['a', '1', 'c', '4', 'b', '5'])
+        self.assertEqual(self.r.execute_command('zunion', 2, self.k1, self.k2, 'weights', 2, 1,
+                                                'aggregate', 'max', 'withscores'),
+                         ['a', '2', 'b', '4', 'c', '4'])
+        self.assertEqual(self.r.execute_command('zinter', 2, self.k1, self.k2, 'withscores'), ['b', '5'])
+        self.assertEqual(self.r.execute_command('zinter', 2, self.k1, self.k2, 'aggregate', 'min', 'withscores'),
+                         ['b', '2'])
+        self.assertEqual(self.r.execute_command('zinter', 2, self.k1, '__zset_missing__'), [])
+        self.assertEqual(self.r.execute_command('zunion', 2, self.k1, '__zset_missing__'), ['a', 'b'])
+        with self.assertRaises(Exception) as cm:
+            self.r.execute_command('zunion', 2, self.k1, self.k2, 'weights', 1, 'x')
+        self.assertEqual(str(cm.exception), 'weight value is not a float')
+        self.assertRaises(Exception, self.r.execute_command, 'zunion', 2, self.k1, self.k2, 'weights', 1)
+        self.assertRaises(Exception, self.r.execute_command, 'zinter', 2, self.k1, self.k2, 'aggregate', 'avg')
+        # nothing is stored
+        self.assertEqual(self.r.zcard(self.k1), 2)
+
     def test_zpopmin(self):
         self.assertEqual(self.r.zadd(self.k1, {self.v1: 1, self.v2: 2}), 2)
         self.assertListEqual(self.r.zpopmin(self.k1), [(self.v1, 1)])
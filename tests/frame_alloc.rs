@@ -0,0 +1,98 @@
+//! Count the allocations of parsing a pipeline of requests the way the
+//! connection does: each frame is split off the read buffer and its bulk
+//! strings are slices of it.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io::Cursor;
+
+use bytes::{Bytes, BytesMut};
+use tidis::Frame;
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const FRAMES: usize = 1000;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+/// A read buffer holding a pipeline of `FRAMES` small SETs.
+fn pipeline() -> BytesMut {
+    let mut buffer = BytesMut::new();
+    for i in 0..FRAMES {
+        let value = format!("value{}", i);
+        buffer.extend_from_slice(
+            format!(
+                "*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n${}\r\n{}\r\n",
+                value.len(),
+                value
+            )
+            .as_bytes(),
+        );
+    }
+    buffer
+}
+
+/// Parse all the frames of the buffer, `copy` copies every bulk string out of
+/// the frame as the parser did before it returned slices.
+fn parse_all(mut buffer: BytesMut, copy: bool) -> (usize, Vec<Frame>) {
+    let mut frames = Vec::with_capacity(FRAMES);
+    let before = allocations();
+    while !buffer.is_empty() {
+        let mut buf = Cursor::new(&buffer[..]);
+        Frame::check(&mut buf).unwrap();
+        let len = buf.position() as usize;
+        let data = buffer.split_to(len).freeze();
+        let frame = match Frame::parse(&mut Cursor::new(&data[..]), &data).unwrap() {
+            Frame::Array(args) if copy => Frame::Array(
+                args.into_iter()
+                    .map(|arg| match arg {
+                        Frame::Bulk(val) => Frame::Bulk(Bytes::copy_from_slice(&val)),
+                        arg => arg,
+                    })
+                    .collect(),
+            ),
+            frame => frame,
+        };
+        frames.push(frame);
+    }
+    (allocations() - before, frames)
+}
+
+#[test]
+fn parse_allocations() {
+    let (copied, frames) = parse_all(pipeline(), true);
+    assert_eq!(frames.len(), FRAMES);
+    let (sliced, frames) = parse_all(pipeline(), false);
+    assert_eq!(frames.len(), FRAMES);
+    assert!(matches!(&frames[1], Frame::Array(args) if args.len() == 3 && args[2] == "value1"));
+
+    println!(
+        "allocations per frame: {:.2} sliced, {:.2} copied",
+        sliced as f64 / FRAMES as f64,
+        copied as f64 / FRAMES as f64
+    );
+    // only the array of the arguments is allocated for each frame, the buffer
+    // is shared once by the first split
+    assert!(sliced <= FRAMES + 2, "{} allocations", sliced);
+    assert!(copied >= sliced + 3 * FRAMES, "{} allocations", copied);
+}